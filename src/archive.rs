@@ -1,14 +1,337 @@
+use crate::config::ReleaseConfig;
 use crate::error::{Error, Result};
+use crate::format_release::SIDECAR_PATTERN;
+use crate::{parse_comma_list, split_src_dst};
+use flate2::{Compression, GzBuilder};
+use lzma_rust2::{XzOptions, XzWriter};
 use regex::Regex;
-use std::env;
-use std::fs;
-use std::path::Path;
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::LazyLock;
+use std::sync::mpsc::{self, Receiver, SyncSender};
+use std::thread;
 
+/// Modification time recorded for every archive entry: 1980-01-01T00:00:00Z,
+/// the earliest timestamp the zip format can represent.
+pub const NORMALIZED_MTIME: u64 = 315_532_800;
+
+pub const FILE_MODE: u32 = 0o644;
+pub const EXECUTABLE_MODE: u32 = 0o755;
+pub const DIR_MODE: u32 = 0o755;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveFormat {
+    TarGz,
+    TarXz,
+    TarZst,
+    Zip,
+}
+
+impl ArchiveFormat {
+    pub fn extension(self) -> &'static str {
+        match self {
+            ArchiveFormat::TarGz => "tar.gz",
+            ArchiveFormat::TarXz => "tar.xz",
+            ArchiveFormat::TarZst => "tar.zst",
+            ArchiveFormat::Zip => "zip",
+        }
+    }
+
+    /// Zip on Windows, tar.gz everywhere else.
+    pub fn default_for_target(target: &str) -> Self {
+        if target.contains("windows") {
+            ArchiveFormat::Zip
+        } else {
+            ArchiveFormat::TarGz
+        }
+    }
+
+    /// Reads ARCHIVE_FORMAT, falling back to the platform default for the target.
+    pub fn from_env(config: &ReleaseConfig, target: &str) -> Result<Self> {
+        let value = config.get("ARCHIVE_FORMAT", "");
+        if value.is_empty() {
+            Ok(Self::default_for_target(target))
+        } else {
+            value.parse()
+        }
+    }
+}
+
+impl FromStr for ArchiveFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().trim_start_matches('.') {
+            "tar.gz" | "tgz" => Ok(ArchiveFormat::TarGz),
+            "tar.xz" | "txz" => Ok(ArchiveFormat::TarXz),
+            "tar.zst" | "tzst" => Ok(ArchiveFormat::TarZst),
+            "zip" => Ok(ArchiveFormat::Zip),
+            other => Err(Error::User(format!(
+                "unsupported archive format: {other} (expected tar.gz, tar.xz, tar.zst or zip)"
+            ))),
+        }
+    }
+}
+
+/// A file to store in an archive under `name`, a relative `/`-separated path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveEntry {
+    pub source: PathBuf,
+    pub name: String,
+    pub mode: u32,
+}
+
+impl ArchiveEntry {
+    pub fn file(source: impl Into<PathBuf>, name: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            name: name.into(),
+            mode: FILE_MODE,
+        }
+    }
+
+    pub fn executable(source: impl Into<PathBuf>, name: impl Into<String>) -> Self {
+        Self {
+            source: source.into(),
+            name: name.into(),
+            mode: EXECUTABLE_MODE,
+        }
+    }
+}
+
+enum Node<'a> {
+    Dir,
+    File(&'a ArchiveEntry),
+}
+
+/// Sorts entries by name and adds the parent directories they need.
+fn archive_layout(entries: &[ArchiveEntry]) -> Result<BTreeMap<String, Node<'_>>> {
+    let mut nodes = BTreeMap::new();
+    for entry in entries {
+        let name = entry.name.trim_start_matches("./");
+        if name.is_empty()
+            || name.starts_with('/')
+            || name.contains('\\')
+            || name
+                .split('/')
+                .any(|c| c.is_empty() || c == "." || c == "..")
+        {
+            return Err(Error::User(format!(
+                "invalid archive entry name: {}",
+                entry.name
+            )));
+        }
+        let mut prefix = String::new();
+        let components: Vec<&str> = name.split('/').collect();
+        for component in &components[..components.len() - 1] {
+            prefix.push_str(component);
+            prefix.push('/');
+            nodes.entry(prefix.clone()).or_insert(Node::Dir);
+        }
+        if nodes.insert(name.to_string(), Node::File(entry)).is_some() {
+            return Err(Error::User(format!("duplicate archive entry: {name}")));
+        }
+    }
+    Ok(nodes)
+}
+
+/// Writes `entries` to `dest` in the given format.
+///
+/// Entries are stored in name order with normalized timestamps and ownership
+/// and the mode from each entry, so the same inputs produce byte-identical
+/// archives on every runner.
+pub fn create_archive(dest: &Path, format: ArchiveFormat, entries: &[ArchiveEntry]) -> Result<()> {
+    let layout = archive_layout(entries)?;
+    let file = BufWriter::new(File::create(dest)?);
+    match format {
+        ArchiveFormat::TarGz => {
+            let encoder = GzBuilder::new().mtime(0).write(file, Compression::best());
+            let encoder = write_tar(encoder, &layout)?;
+            encoder.finish()?.flush()?;
+        }
+        ArchiveFormat::TarXz => {
+            let encoder = XzWriter::new(file, XzOptions::with_preset(6))?;
+            let encoder = write_tar(encoder, &layout)?;
+            encoder.finish()?.flush()?;
+        }
+        ArchiveFormat::TarZst => {
+            // ruzstd compresses from a reader, so the tar stream is produced
+            // on another thread and handed over in bounded chunks
+            let (sender, receiver) = mpsc::sync_channel(ZSTD_PIPE_CHUNKS);
+            let mut output = ErrorRecordingWriter::new(file);
+            thread::scope(|scope| {
+                let tar = scope.spawn(|| write_tar(ChunkWriter(sender), &layout).map(drop));
+                ruzstd::encoding::compress(
+                    ChunkReader::new(receiver),
+                    &mut output,
+                    ruzstd::encoding::CompressionLevel::Fastest,
+                );
+                tar.join()
+                    .map_err(|_| Error::User("tar writer thread panicked".into()))?
+            })?;
+            output.finish()?.flush()?;
+        }
+        ArchiveFormat::Zip => write_zip(file, &layout)?.flush()?,
+    }
+    Ok(())
+}
+
+/// Number of chunks buffered between the tar writer and the zstd encoder.
+const ZSTD_PIPE_CHUNKS: usize = 16;
+
+/// Sends everything written to it as chunks over a channel.
+struct ChunkWriter(SyncSender<Vec<u8>>);
+
+impl Write for ChunkWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0
+            .send(buf.to_vec())
+            .map_err(|_| io::Error::new(io::ErrorKind::BrokenPipe, "zstd encoder stopped"))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Passes writes through to the inner writer and records the first error
+/// instead of returning it: ruzstd panics when its output fails to write.
+struct ErrorRecordingWriter<W> {
+    inner: W,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> ErrorRecordingWriter<W> {
+    fn new(inner: W) -> Self {
+        ErrorRecordingWriter { inner, error: None }
+    }
+
+    /// Returns the inner writer, or the first error a write ran into.
+    fn finish(self) -> io::Result<W> {
+        match self.error {
+            Some(e) => Err(e),
+            None => Ok(self.inner),
+        }
+    }
+}
+
+impl<W: Write> Write for ErrorRecordingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.error.is_none() {
+            if let Err(e) = self.inner.write_all(buf) {
+                self.error = Some(e);
+            }
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        if self.error.is_none() {
+            if let Err(e) = self.inner.flush() {
+                self.error = Some(e);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reads the chunks sent by a [`ChunkWriter`] until it is dropped.
+struct ChunkReader {
+    receiver: Receiver<Vec<u8>>,
+    chunk: Vec<u8>,
+    pos: usize,
+}
+
+impl ChunkReader {
+    fn new(receiver: Receiver<Vec<u8>>) -> Self {
+        ChunkReader {
+            receiver,
+            chunk: Vec::new(),
+            pos: 0,
+        }
+    }
+}
+
+impl Read for ChunkReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        while self.pos == self.chunk.len() {
+            match self.receiver.recv() {
+                Ok(chunk) => {
+                    self.chunk = chunk;
+                    self.pos = 0;
+                }
+                Err(_) => return Ok(0),
+            }
+        }
+        let n = buf.len().min(self.chunk.len() - self.pos);
+        buf[..n].copy_from_slice(&self.chunk[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+fn write_tar<W: Write>(writer: W, layout: &BTreeMap<String, Node<'_>>) -> Result<W> {
+    let mut builder = tar::Builder::new(writer);
+    for (name, node) in layout {
+        let mut header = tar::Header::new_ustar();
+        header.set_mtime(NORMALIZED_MTIME);
+        header.set_uid(0);
+        header.set_gid(0);
+        match node {
+            Node::Dir => {
+                header.set_entry_type(tar::EntryType::Directory);
+                header.set_mode(DIR_MODE);
+                header.set_size(0);
+                builder.append_data(&mut header, name, io::empty())?;
+            }
+            Node::File(entry) => {
+                let source = File::open(&entry.source).map_err(|e| {
+                    Error::User(format!("cannot read {}: {e}", entry.source.display()))
+                })?;
+                header.set_entry_type(tar::EntryType::Regular);
+                header.set_mode(entry.mode);
+                header.set_size(source.metadata()?.len());
+                builder.append_data(&mut header, name, source)?;
+            }
+        }
+    }
+    Ok(builder.into_inner()?)
+}
+
+fn write_zip<W: Write + io::Seek>(writer: W, layout: &BTreeMap<String, Node<'_>>) -> Result<W> {
+    let mut zip = zip::ZipWriter::new(writer);
+    let base = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .last_modified_time(zip::DateTime::default());
+    for (name, node) in layout {
+        match node {
+            Node::Dir => zip.add_directory(name.as_str(), base.unix_permissions(DIR_MODE))?,
+            Node::File(entry) => {
+                let mut source = File::open(&entry.source).map_err(|e| {
+                    Error::User(format!("cannot read {}: {e}", entry.source.display()))
+                })?;
+                zip.start_file(name.as_str(), base.unix_permissions(entry.mode))?;
+                io::copy(&mut source, &mut zip)?;
+            }
+        }
+    }
+    Ok(zip.finish()?)
+}
+
+/// Archives and the sidecar files of [`SIDECAR_PATTERN`], which are never archived.
+static NOT_ARCHIVABLE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"\.(tar\.gz|tar\.xz|tar\.zst|zip)$|{SIDECAR_PATTERN}"
+    ))
+    .unwrap()
+});
+
+/// Lists the files in `dir` that can go into an archive, sorted.
 pub fn list_archivable_files(dir: &Path) -> Vec<String> {
-    let exclude = Regex::new(
-        r"\.(tar\.gz|zip|sha256|sha512|b2|sig|pem|sigstore\.json|spdx\.json|cdx\.json)$",
-    )
-    .unwrap();
     let mut files = Vec::new();
     if let Ok(entries) = fs::read_dir(dir) {
         for entry in entries.flatten() {
@@ -21,7 +344,7 @@ pub fn list_archivable_files(dir: &Path) -> Vec<String> {
                 .unwrap_or_default()
                 .to_string_lossy()
                 .to_string();
-            if !exclude.is_match(&name) {
+            if !NOT_ARCHIVABLE.is_match(&name) {
                 files.push(name);
             }
         }
@@ -30,37 +353,173 @@ pub fn list_archivable_files(dir: &Path) -> Vec<String> {
     files
 }
 
+/// Returns the LICENSE* and README.md files in the current directory as archive entries.
+pub fn doc_entries() -> Vec<ArchiveEntry> {
+    let mut entries: Vec<ArchiveEntry> = glob::glob("LICENSE*")
+        .unwrap()
+        .flatten()
+        .filter(|p| p.is_file())
+        .map(|p| {
+            let name = p
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string();
+            ArchiveEntry::file(p, name)
+        })
+        .collect();
+    if Path::new("README.md").is_file() {
+        entries.push(ArchiveEntry::file("README.md", "README.md"));
+    }
+    entries
+}
+
 /// Copies LICENSE* and README.md to the destination.
 pub fn copy_docs(dest: &Path) -> Result<()> {
-    for entry in glob::glob("LICENSE*").unwrap().flatten() {
-        let dest_file = dest.join(entry.file_name().unwrap_or_default());
-        fs::copy(&entry, &dest_file)?;
+    for entry in doc_entries() {
+        fs::copy(&entry.source, dest.join(&entry.name))?;
     }
-    if Path::new("README.md").exists() {
-        fs::copy("README.md", dest.join("README.md"))?;
+    Ok(())
+}
+
+/// Converts a relative path to a `/`-separated archive entry name.
+fn entry_name(path: &Path) -> String {
+    path.components()
+        .filter(|c| !matches!(c, std::path::Component::CurDir))
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Lists the files under `dir` recursively, sorted, as paths relative to `dir`.
+fn walk_files(dir: &Path, rel: &Path, out: &mut Vec<PathBuf>) -> Result<()> {
+    let mut children: Vec<_> = fs::read_dir(dir.join(rel))?
+        .flatten()
+        .map(|e| e.file_name())
+        .collect();
+    children.sort();
+    for child in children {
+        let child_rel = rel.join(child);
+        if dir.join(&child_rel).is_dir() {
+            walk_files(dir, &child_rel, out)?;
+        } else {
+            out.push(child_rel);
+        }
     }
     Ok(())
 }
 
-/// Copies additional include files to the destination.
-pub fn copy_includes(dest: &Path) -> Result<()> {
-    let includes = env::var("ARCHIVE_INCLUDE").unwrap_or_default();
-    if includes.is_empty() {
-        return Ok(());
+/// Resolves one include spec into archive entries.
+///
+/// A plain glob (`completions/*`) keeps each match at its path relative to the
+/// working directory. A `src:dst` spec remaps: a single file lands at `dst`
+/// unless `dst` ends with `/`; multiple matches and directories are placed
+/// under `dst`. Matched directories are included recursively. A spec that
+/// matches nothing is an error.
+pub fn include_entries(spec: &str) -> Result<Vec<ArchiveEntry>> {
+    let (pattern, dst) = split_src_dst(spec);
+    let dst = dst.map(|dst| dst.trim_matches('/'));
+
+    let matches: Vec<PathBuf> = glob::glob(pattern)
+        .map_err(|e| Error::User(format!("invalid glob pattern '{pattern}': {e}")))?
+        .flatten()
+        .collect();
+    if matches.is_empty() {
+        return Err(Error::User(format!("include '{spec}' matched no files")));
     }
-    for pattern in includes.split(',') {
-        let pattern = pattern.trim();
-        if pattern.is_empty() {
-            continue;
+
+    let mut entries = Vec::new();
+    for path in &matches {
+        if dst.is_none() && (path.is_absolute() || entry_name(path).split('/').any(|c| c == "..")) {
+            return Err(Error::User(format!(
+                "include '{}' is outside the project directory; use src:dst to place it in the archive",
+                path.display()
+            )));
         }
-        let matches = glob::glob(pattern)
-            .map_err(|e| Error::User(format!("invalid glob pattern '{pattern}': {e}")))?;
-        for entry in matches.flatten() {
-            if entry.is_file() {
-                let dest_file = dest.join(entry.file_name().unwrap_or_default());
-                fs::copy(&entry, &dest_file)?;
+        let base = match dst {
+            None => entry_name(path),
+            Some(dst) if matches.len() == 1 && !spec.ends_with('/') && path.is_file() => {
+                dst.to_string()
             }
+            Some(dst) => {
+                let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+                if dst.is_empty() {
+                    file_name.to_string()
+                } else {
+                    format!("{dst}/{file_name}")
+                }
+            }
+        };
+        if path.is_dir() {
+            let mut files = Vec::new();
+            walk_files(path, Path::new(""), &mut files)?;
+            for rel in files {
+                let name = if base.is_empty() {
+                    entry_name(&rel)
+                } else {
+                    format!("{base}/{}", entry_name(&rel))
+                };
+                entries.push(ArchiveEntry::file(path.join(&rel), name));
+            }
+        } else if path.is_file() {
+            entries.push(ArchiveEntry::file(path, base));
         }
     }
+    Ok(entries)
+}
+
+/// Resolves all ARCHIVE_INCLUDE specs.
+pub fn configured_include_entries(config: &ReleaseConfig) -> Result<Vec<ArchiveEntry>> {
+    let mut entries = Vec::new();
+    for spec in parse_comma_list(&config.get("ARCHIVE_INCLUDE", "")) {
+        entries.extend(include_entries(&spec)?);
+    }
+    Ok(entries)
+}
+
+/// Copies additional include files to the destination, preserving their layout.
+pub fn copy_includes(config: &ReleaseConfig, dest: &Path) -> Result<()> {
+    for entry in configured_include_entries(config)? {
+        let dest_file = dest.join(&entry.name);
+        if let Some(parent) = dest_file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(&entry.source, &dest_file)?;
+    }
     Ok(())
 }
+
+/// Builds the entries of a release archive: the binaries, LICENSE*/README.md
+/// and ARCHIVE_INCLUDE files, optionally nested under `top_level_dir`.
+pub fn release_entries_for(
+    config: &ReleaseConfig,
+    binary_paths: &[&Path],
+    top_level_dir: Option<&str>,
+) -> Result<Vec<ArchiveEntry>> {
+    let includes = configured_include_entries(config)?;
+    let mut entries: Vec<ArchiveEntry> = binary_paths
+        .iter()
+        .map(|path| {
+            let name = path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string();
+            ArchiveEntry::executable(path, name)
+        })
+        .collect();
+    // An explicitly included file replaces the default doc file of the same name
+    entries.extend(
+        doc_entries()
+            .into_iter()
+            .filter(|doc| !includes.iter().any(|inc| inc.name == doc.name)),
+    );
+    entries.extend(includes);
+
+    if let Some(top) = top_level_dir {
+        for entry in &mut entries {
+            entry.name = format!("{top}/{}", entry.name);
+        }
+    }
+    Ok(entries)
+}
@@ -1,10 +1,23 @@
 use crate::cargo_info::get_cargo_info;
-use crate::env_or;
+use crate::config::ReleaseConfig;
 use crate::error::{Error, Result};
 use crate::output::{output, output_multiline, print_hr};
-use crate::parse_comma_list;
+use crate::{parse_comma_list, single_line};
 use std::fs;
 
+/// Quotes `value` as a single-line bash double-quoted string.
+fn shell_string(value: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in single_line(value).chars() {
+        if matches!(c, '\\' | '"' | '$' | '`') {
+            quoted.push('\\');
+        }
+        quoted.push(c);
+    }
+    quoted.push('"');
+    quoted
+}
+
 /// Formats a PKGBUILD array field like `depends=('foo' 'bar')` if non-empty.
 fn pkgbuild_array(output: &mut String, key: &str, raw: &str) {
     let items = parse_comma_list(raw);
@@ -40,20 +53,26 @@ pub fn generate_pkgbuild(config: &PkgbuildConfig) -> String {
     let mut pkgbuild = String::new();
 
     if !config.maintainer.is_empty() {
-        pkgbuild.push_str(&format!("# Maintainer: {}\n", config.maintainer));
+        pkgbuild.push_str(&format!(
+            "# Maintainer: {}\n",
+            single_line(&config.maintainer)
+        ));
     }
 
     pkgbuild.push_str(&format!("pkgname={}\n", config.pkgname));
     pkgbuild.push_str(&format!("pkgver={}\n", config.pkgver));
     pkgbuild.push_str("pkgrel=1\n");
-    pkgbuild.push_str(&format!("pkgdesc=\"{}\"\n", config.pkgdesc));
+    pkgbuild.push_str(&format!("pkgdesc={}\n", shell_string(&config.pkgdesc)));
     pkgbuild.push_str("arch=('x86_64' 'aarch64')\n");
 
     if !config.url.is_empty() {
-        pkgbuild.push_str(&format!("url=\"{}\"\n", config.url));
+        pkgbuild.push_str(&format!("url={}\n", shell_string(&config.url)));
     }
 
-    pkgbuild.push_str(&format!("license=('{}')\n", config.license));
+    pkgbuild.push_str(&format!(
+        "license=('{}')\n",
+        single_line(&config.license).replace('\'', "'\\''")
+    ));
 
     pkgbuild_array(&mut pkgbuild, "depends", &config.depends);
     pkgbuild_array(&mut pkgbuild, "makedepends", &config.makedepends);
@@ -110,17 +129,17 @@ pub struct SrcinfoConfig {
 /// Generates .SRCINFO content.
 pub fn generate_srcinfo(config: &SrcinfoConfig) -> String {
     let mut srcinfo = format!("pkgbase = {}\n", config.pkgname);
-    srcinfo.push_str(&format!("\tpkgdesc = {}\n", config.pkgdesc));
+    srcinfo.push_str(&format!("\tpkgdesc = {}\n", single_line(&config.pkgdesc)));
     srcinfo.push_str(&format!("\tpkgver = {}\n", config.pkgver));
     srcinfo.push_str("\tpkgrel = 1\n");
 
     if !config.url.is_empty() {
-        srcinfo.push_str(&format!("\turl = {}\n", config.url));
+        srcinfo.push_str(&format!("\turl = {}\n", single_line(&config.url)));
     }
 
     srcinfo.push_str("\tarch = x86_64\n");
     srcinfo.push_str("\tarch = aarch64\n");
-    srcinfo.push_str(&format!("\tlicense = {}\n", config.license));
+    srcinfo.push_str(&format!("\tlicense = {}\n", single_line(&config.license)));
 
     for dep in parse_comma_list(&config.depends) {
         srcinfo.push_str(&format!("\tdepends = {dep}\n"));
@@ -151,11 +170,11 @@ pub fn generate_srcinfo(config: &SrcinfoConfig) -> String {
     srcinfo
 }
 
-pub fn run_generate_aur() -> Result<()> {
-    let info = get_cargo_info()?;
-    let default_name = env_or("BINARY_NAME", &info.name);
-    let pkg_name = env_or("AUR_PACKAGE_NAME", &default_name);
-    let version = env_or("VERSION", &info.version);
+pub fn run_generate_aur(config: &ReleaseConfig) -> Result<()> {
+    let info = get_cargo_info(config)?;
+    let default_name = config.get("BINARY_NAME", &info.name);
+    let pkg_name = config.get("AUR_PACKAGE_NAME", &default_name);
+    let version = config.get("VERSION", &info.version);
 
     if pkg_name.is_empty() {
         return Err(Error::User(
@@ -168,14 +187,12 @@ pub fn run_generate_aur() -> Result<()> {
         ));
     }
 
-    let description = env_or(
-        "PKG_DESCRIPTION",
-        &format!("{pkg_name} - built with rust-build-package-release-action"),
-    );
-    let license = env_or("PKG_LICENSE", "MIT");
-    let binary_name = env_or("BINARY_NAME", &pkg_name);
-    let source_url = env_or("AUR_SOURCE_URL", "");
-    let source_sha256 = env_or("AUR_SOURCE_SHA256", "");
+    let description = config.get("PKG_DESCRIPTION", &info.description_or_default(&pkg_name));
+    let license = config.get("PKG_LICENSE", info.license_or("MIT"));
+    let homepage = config.get("PKG_HOMEPAGE", info.homepage_or_repository());
+    let binary_name = config.get("BINARY_NAME", &pkg_name);
+    let source_url = config.get("AUR_SOURCE_URL", "");
+    let source_sha256 = config.get("AUR_SOURCE_SHA256", "");
 
     if !source_url.is_empty() && source_sha256.is_empty() {
         println!(
@@ -189,22 +206,22 @@ pub fn run_generate_aur() -> Result<()> {
         pkgname: pkg_name.clone(),
         pkgver: version.clone(),
         pkgdesc: description.clone(),
-        url: env_or("PKG_HOMEPAGE", ""),
+        url: homepage.clone(),
         license: license.clone(),
-        maintainer: env_or("AUR_MAINTAINER", ""),
+        maintainer: config.get("AUR_MAINTAINER", info.maintainer_or("")),
         source_url: source_url.clone(),
         source_sha256: source_sha256.clone(),
-        depends: env_or("PKG_DEPENDS", ""),
-        makedepends: env_or("AUR_MAKEDEPENDS", "cargo"),
-        optdepends: env_or("AUR_OPTDEPENDS", ""),
-        provides: env_or("PKG_PROVIDES", ""),
-        conflicts: env_or("PKG_CONFLICTS", ""),
+        depends: config.get("PKG_DEPENDS", ""),
+        makedepends: config.get("AUR_MAKEDEPENDS", "cargo"),
+        optdepends: config.get("AUR_OPTDEPENDS", ""),
+        provides: config.get("PKG_PROVIDES", ""),
+        conflicts: config.get("PKG_CONFLICTS", ""),
         binary_name,
     };
 
     let pkgbuild = generate_pkgbuild(&pkgbuild_config);
 
-    let output_dir = env_or("AUR_OUTPUT_DIR", "target/aur");
+    let output_dir = config.get("AUR_OUTPUT_DIR", "target/aur");
     fs::create_dir_all(&output_dir)?;
 
     let pkgbuild_path = format!("{output_dir}/PKGBUILD");
@@ -214,15 +231,15 @@ pub fn run_generate_aur() -> Result<()> {
         pkgname: pkg_name,
         pkgver: version,
         pkgdesc: description,
-        url: env_or("PKG_HOMEPAGE", ""),
+        url: homepage,
         license,
         source_url,
         source_sha256,
-        depends: env_or("PKG_DEPENDS", ""),
-        makedepends: env_or("AUR_MAKEDEPENDS", "cargo"),
-        optdepends: env_or("AUR_OPTDEPENDS", ""),
-        provides: env_or("PKG_PROVIDES", ""),
-        conflicts: env_or("PKG_CONFLICTS", ""),
+        depends: config.get("PKG_DEPENDS", ""),
+        makedepends: config.get("AUR_MAKEDEPENDS", "cargo"),
+        optdepends: config.get("AUR_OPTDEPENDS", ""),
+        provides: config.get("PKG_PROVIDES", ""),
+        conflicts: config.get("PKG_CONFLICTS", ""),
     };
 
     let srcinfo = generate_srcinfo(&srcinfo_config);
@@ -1,27 +1,255 @@
 use crate::checksum::Checksums;
-use crate::error::Result;
+use crate::config::ReleaseConfig;
+use crate::error::{Error, Result};
 use crate::output::output;
 use crate::output::output_multiline;
+use crate::parse_comma_list;
 use crate::tools;
+use serde::Serialize;
 use serde_json::Value;
 use std::env;
 use std::process::Command;
+use std::str::FromStr;
 
-/// Check if a Cargo feature exists in the project.
-fn has_cargo_feature(feature: &str) -> bool {
-    let package = env::var("PACKAGE").unwrap_or_default();
-    let mut args = vec![
-        "metadata".to_string(),
-        "--format-version".to_string(),
-        "1".to_string(),
-        "--no-deps".to_string(),
-    ];
-    if !package.is_empty() {
-        args.push("--package".to_string());
-        args.push(package.clone());
+/// A `[[bin]]` target of a workspace member.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BinaryTarget {
+    pub name: String,
+    pub package: String,
+    pub version: String,
+}
+
+/// How a release with several binaries is packaged.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BinaryPackaging {
+    /// One archive or package containing every binary.
+    #[default]
+    Combined,
+    /// One archive or package per binary.
+    Separate,
+}
+
+impl FromStr for BinaryPackaging {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim() {
+            "" | "combined" => Ok(BinaryPackaging::Combined),
+            "separate" => Ok(BinaryPackaging::Separate),
+            other => Err(Error::User(format!(
+                "unsupported binary packaging: {other} (expected combined or separate)"
+            ))),
+        }
+    }
+}
+
+/// The tool that compiles release binaries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum BuildTool {
+    /// `cargo rustc`, or `cargo build` for several binaries.
+    #[default]
+    Cargo,
+    /// `cargo zigbuild`, linking with Zig.
+    Zigbuild,
+    /// `cross build`, in a container with the target's toolchain (configured by `Cross.toml`).
+    Cross,
+}
+
+impl BuildTool {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BuildTool::Cargo => "cargo",
+            BuildTool::Zigbuild => "zigbuild",
+            BuildTool::Cross => "cross",
+        }
+    }
+}
+
+impl FromStr for BuildTool {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim() {
+            "" | "cargo" => Ok(BuildTool::Cargo),
+            "zigbuild" => Ok(BuildTool::Zigbuild),
+            "cross" => Ok(BuildTool::Cross),
+            other => Err(Error::User(format!(
+                "unsupported build tool: {other} (expected cargo, zigbuild or cross)"
+            ))),
+        }
     }
+}
+
+/// Architectures whose Linux targets have no cross toolchain on the runner,
+/// so they are built with `cross` unless another tool is configured.
+const CROSS_REQUIRED_ARCHS: &[&str] = &[
+    "powerpc64le",
+    "powerpc64",
+    "s390x",
+    "riscv64gc",
+    "loongarch64",
+];
+
+/// Whether `target` can only be built with `cross`.
+pub fn requires_cross(target: &str) -> bool {
+    target.contains("linux")
+        && target
+            .split('-')
+            .next()
+            .is_some_and(|arch| CROSS_REQUIRED_ARCHS.contains(&arch))
+}
+
+/// The build tool set with `BUILD_TOOL`, or `USE_ZIGBUILD`. `None` when
+/// neither is set.
+pub fn configured_build_tool(config: &ReleaseConfig) -> Result<Option<BuildTool>> {
+    let configured = config.get("BUILD_TOOL", "");
+    let use_zigbuild = config.get("USE_ZIGBUILD", "") == "true";
+    if configured.trim().is_empty() {
+        return Ok(use_zigbuild.then_some(BuildTool::Zigbuild));
+    }
+    let tool: BuildTool = configured.parse()?;
+    if use_zigbuild && tool != BuildTool::Zigbuild {
+        return Err(Error::User(format!(
+            "use-zigbuild conflicts with build-tool: {configured}"
+        )));
+    }
+    Ok(Some(tool))
+}
 
-    let result = Command::new("cargo").args(&args).output();
+/// The build tool for `target`: the configured one on every target, else
+/// `cross` for the Linux targets that require it and cargo otherwise.
+pub fn build_tool_for(target: &str, configured: Option<BuildTool>) -> BuildTool {
+    configured.unwrap_or(if requires_cross(target) {
+        BuildTool::Cross
+    } else {
+        BuildTool::Cargo
+    })
+}
+
+/// The build tool for `target` from the environment and release config.
+pub fn build_tool(config: &ReleaseConfig, target: &str) -> Result<BuildTool> {
+    Ok(build_tool_for(target, configured_build_tool(config)?))
+}
+
+/// Finds the binary targets of workspace members in `cargo metadata --no-deps`
+/// output. `packages` limits the members searched (all when empty);
+/// `binaries` names the binaries to release, or is `all`.
+pub fn workspace_binaries(
+    metadata: &Value,
+    packages: &[String],
+    binaries: &[String],
+) -> Result<Vec<BinaryTarget>> {
+    let members: Vec<&str> = metadata["workspace_members"]
+        .as_array()
+        .map(|m| m.iter().filter_map(|id| id.as_str()).collect())
+        .unwrap_or_default();
+    let workspace_packages: Vec<&Value> = metadata["packages"]
+        .as_array()
+        .map(|p| {
+            p.iter()
+                .filter(|p| p["id"].as_str().is_some_and(|id| members.contains(&id)))
+                .collect()
+        })
+        .unwrap_or_default();
+
+    for package in packages {
+        if !workspace_packages
+            .iter()
+            .any(|p| p["name"].as_str() == Some(package))
+        {
+            return Err(Error::User(format!(
+                "package {package} is not a workspace member"
+            )));
+        }
+    }
+
+    let mut found: Vec<BinaryTarget> = Vec::new();
+    for package in workspace_packages.iter().filter(|p| {
+        packages.is_empty() || packages.iter().any(|name| p["name"].as_str() == Some(name))
+    }) {
+        for target in package["targets"].as_array().into_iter().flatten() {
+            let is_bin = target["kind"]
+                .as_array()
+                .is_some_and(|kinds| kinds.iter().any(|k| k == "bin"));
+            if !is_bin {
+                continue;
+            }
+            let name = target["name"].as_str().unwrap_or_default().to_string();
+            if let Some(existing) = found.iter().find(|b| b.name == name) {
+                return Err(Error::User(format!(
+                    "binary {name} is defined by both {} and {}",
+                    existing.package,
+                    package["name"].as_str().unwrap_or_default()
+                )));
+            }
+            found.push(BinaryTarget {
+                name,
+                package: package["name"].as_str().unwrap_or_default().to_string(),
+                version: package["version"].as_str().unwrap_or_default().to_string(),
+            });
+        }
+    }
+
+    if binaries.is_empty() || binaries.iter().any(|b| b == "all") {
+        if found.is_empty() {
+            return Err(Error::User(
+                "no binary targets found in the selected packages".into(),
+            ));
+        }
+        return Ok(found);
+    }
+    binaries
+        .iter()
+        .map(|name| {
+            found
+                .iter()
+                .find(|b| &b.name == name)
+                .cloned()
+                .ok_or_else(|| {
+                    Error::User(format!("binary {name} not found in the selected packages"))
+                })
+        })
+        .collect()
+}
+
+/// Discovers the binaries selected by BINARIES and PACKAGES. Empty when
+/// BINARIES is not set, i.e. for single-binary releases.
+pub fn discover_binaries(config: &ReleaseConfig) -> Result<Vec<BinaryTarget>> {
+    let binaries = parse_comma_list(&config.get("BINARIES", ""));
+    if binaries.is_empty() {
+        return Ok(Vec::new());
+    }
+    let manifest_path = config.get("MANIFEST_PATH", "Cargo.toml");
+    let output = tools::run_command(
+        "cargo",
+        &[
+            "metadata",
+            "--format-version",
+            "1",
+            "--no-deps",
+            "--manifest-path",
+            &manifest_path,
+        ],
+    )?;
+    let metadata: Value = serde_json::from_slice(&output.stdout)?;
+    let packages = parse_comma_list(&config.get("PACKAGES", ""));
+    workspace_binaries(&metadata, &packages, &binaries)
+}
+
+/// Check if a Cargo feature exists in `package`, or in the first package of
+/// the manifest at MANIFEST_PATH when `package` is empty.
+fn has_cargo_feature(config: &ReleaseConfig, package: &str, feature: &str) -> bool {
+    let manifest_path = config.get("MANIFEST_PATH", "Cargo.toml");
+    let args = [
+        "metadata",
+        "--format-version",
+        "1",
+        "--no-deps",
+        "--manifest-path",
+        &manifest_path,
+    ];
+
+    let result = Command::new("cargo").args(args).output();
     let output = match result {
         Ok(o) if o.status.success() => o,
         _ => return false,
@@ -40,7 +268,7 @@ fn has_cargo_feature(feature: &str) -> bool {
     let pkg = if !package.is_empty() {
         packages
             .iter()
-            .find(|p| p["name"].as_str() == Some(&package))
+            .find(|p| p["name"].as_str() == Some(package))
     } else {
         packages.first()
     };
@@ -53,32 +281,79 @@ fn has_cargo_feature(feature: &str) -> bool {
     }
 }
 
-/// Build with cargo rustc or cargo-zigbuild using the environment configuration.
-pub fn cargo_build(target: &str, binary_name: &str) -> Result<()> {
-    let package = env::var("PACKAGE").unwrap_or_default();
-    let no_default_features = env::var("NO_DEFAULT_FEATURES").unwrap_or_default() == "true";
-    let mut features = env::var("FEATURES").unwrap_or_default();
-    let locked = env::var("LOCKED").unwrap_or_default() == "true";
-    let profile = env::var("PROFILE").unwrap_or_else(|_| "release".into());
-    let target_rustflags = env::var("TARGET_RUSTFLAGS").unwrap_or_default();
-    let use_zigbuild = env::var("USE_ZIGBUILD").unwrap_or_default() == "true";
+/// Build with cargo rustc, cargo-zigbuild or cross using the environment and release config.
+pub fn cargo_build(config: &ReleaseConfig, target: &str, binary_name: &str) -> Result<()> {
+    let package = config.get("PACKAGE", "");
+    let packages: Vec<String> = (!package.is_empty())
+        .then_some(package)
+        .into_iter()
+        .collect();
+    let binaries: Vec<String> = (!binary_name.is_empty())
+        .then(|| binary_name.to_string())
+        .into_iter()
+        .collect();
+    run_cargo_build(config, target, &packages, &binaries)
+}
 
-    if !target_rustflags.is_empty() {
-        // Safety: running single-threaded at this point during build setup
-        unsafe { env::set_var("RUSTFLAGS", &target_rustflags) };
+/// Builds several binaries, possibly from different packages, in one cargo invocation.
+pub fn cargo_build_binaries(
+    config: &ReleaseConfig,
+    target: &str,
+    binaries: &[BinaryTarget],
+) -> Result<()> {
+    let mut packages: Vec<String> = Vec::new();
+    for binary in binaries {
+        if !packages.contains(&binary.package) {
+            packages.push(binary.package.clone());
+        }
     }
+    let names: Vec<String> = binaries.iter().map(|b| b.name.clone()).collect();
+    run_cargo_build(config, target, &packages, &names)
+}
+
+fn run_cargo_build(
+    config: &ReleaseConfig,
+    target: &str,
+    packages: &[String],
+    binaries: &[String],
+) -> Result<()> {
+    let no_default_features = config.get("NO_DEFAULT_FEATURES", "") == "true";
+    let mut features = config.get("FEATURES", "");
+    let locked = config.get("LOCKED", "") == "true";
+    let profile = config.get("PROFILE", "release");
+    let target_rustflags = config.get("TARGET_RUSTFLAGS", "");
+    let tool = build_tool(config, target)?;
+
+    let mut rustflags = if target_rustflags.is_empty() {
+        env::var("RUSTFLAGS").unwrap_or_default()
+    } else {
+        target_rustflags
+    };
 
     // For musl targets without zigbuild, set static linking
-    if target.contains("musl")
-        && !use_zigbuild
-        && env::var("RUSTFLAGS").unwrap_or_default().is_empty()
-    {
-        // Safety: running single-threaded at this point during build setup
-        unsafe { env::set_var("RUSTFLAGS", "-C target-feature=+crt-static") };
+    if target.contains("musl") && tool != BuildTool::Zigbuild && rustflags.is_empty() {
+        rustflags = "-C target-feature=+crt-static".to_string();
+    }
+
+    let mut envs: Vec<(&str, &str)> = Vec::new();
+    if !rustflags.is_empty() {
+        envs.push(("RUSTFLAGS", &rustflags));
+    }
+    // cross provides the linker in its container
+    if target.contains("linux") && tool != BuildTool::Cross {
+        envs.extend(tools::host_cross_linker(target));
     }
 
-    // For musl targets, auto-enable mimalloc if available
-    if target.contains("musl") && !features.contains("mimalloc") && has_cargo_feature("mimalloc") {
+    // For musl targets, auto-enable mimalloc if the package being built has it
+    if target.contains("musl")
+        && packages.len() <= 1
+        && !features.contains("mimalloc")
+        && has_cargo_feature(
+            config,
+            packages.first().map(String::as_str).unwrap_or_default(),
+            "mimalloc",
+        )
+    {
         println!("\x1b[32mEnabling mimalloc feature for musl build\x1b[0m");
         features = if features.is_empty() {
             "mimalloc".to_string()
@@ -87,16 +362,33 @@ pub fn cargo_build(target: &str, binary_name: &str) -> Result<()> {
         };
     }
 
-    let mut args: Vec<String> = if use_zigbuild {
-        tools::check_zigbuild()?;
-        vec!["zigbuild".into(), "--target".into(), target.into()]
+    let program = if tool == BuildTool::Cross {
+        "cross"
     } else {
-        vec![
+        "cargo"
+    };
+    let mut args: Vec<String> = match tool {
+        BuildTool::Zigbuild => {
+            tools::check_zigbuild()?;
+            vec!["zigbuild".into(), "--target".into(), target.into()]
+        }
+        BuildTool::Cross => {
+            tools::check_cross()?;
+            vec!["build".into(), "--target".into(), target.into()]
+        }
+        // cargo rustc builds a single target
+        BuildTool::Cargo if packages.len() > 1 || binaries.len() > 1 => vec![
+            "build".into(),
+            "--target".into(),
+            target.into(),
+            "-q".into(),
+        ],
+        BuildTool::Cargo => vec![
             "rustc".into(),
             "--target".into(),
             target.into(),
             "-q".into(),
-        ]
+        ],
     };
 
     if profile == "release" {
@@ -106,14 +398,14 @@ pub fn cargo_build(target: &str, binary_name: &str) -> Result<()> {
         args.push(profile);
     }
 
-    if !package.is_empty() {
+    for package in packages {
         args.push("--package".into());
-        args.push(package);
+        args.push(package.clone());
     }
 
-    if !binary_name.is_empty() {
+    for binary in binaries {
         args.push("--bin".into());
-        args.push(binary_name.into());
+        args.push(binary.clone());
     }
 
     if no_default_features {
@@ -130,7 +422,7 @@ pub fn cargo_build(target: &str, binary_name: &str) -> Result<()> {
     }
 
     let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
-    tools::run_command_inherit("cargo", &args_refs)
+    tools::run_command_inherit_env(program, &args_refs, &envs)
 }
 
 /// Outputs build results to GITHUB_OUTPUT.
@@ -147,6 +439,8 @@ pub fn output_build_results(
     output("sha256", &checksums.sha256);
     output("sha512", &checksums.sha512);
     output("b2", &checksums.b2);
+    output("sha3_256", &checksums.sha3_256);
+    output("b3", &checksums.b3);
 
     if !checksums.sha256.is_empty() {
         output("checksum_file", &format!("{artifact_path}.sha256"));
@@ -181,6 +475,8 @@ pub fn build_summary(
         "sha256": checksums.sha256,
         "sha512": checksums.sha512,
         "b2": checksums.b2,
+        "sha3_256": checksums.sha3_256,
+        "b3": checksums.b3,
     });
     serde_json::to_string_pretty(&summary).unwrap_or_default()
 }
@@ -0,0 +1,313 @@
+use crate::cargo_info::get_cargo_info_from_path;
+use crate::config::ReleaseConfig;
+use crate::error::{Error, Result};
+use crate::output::output;
+use crate::tools::run_command;
+use crate::version::{BumpLevel, Version};
+use std::fs;
+use std::path::{Path, PathBuf};
+use toml_edit::{DocumentMut, Item, Value};
+
+const DEPENDENCY_TABLES: &[&str] = &["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// A manifest of a workspace (or single-package) project.
+#[derive(Debug, Clone)]
+pub struct Manifest {
+    pub path: PathBuf,
+    pub doc: DocumentMut,
+}
+
+impl Manifest {
+    pub fn read(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .map_err(|e| Error::User(format!("could not read {}: {e}", path.display())))?;
+        let doc = content
+            .parse::<DocumentMut>()
+            .map_err(|e| Error::User(format!("could not parse {}: {e}", path.display())))?;
+        Ok(Manifest {
+            path: path.to_path_buf(),
+            doc,
+        })
+    }
+
+    pub fn package_name(&self) -> Option<&str> {
+        self.doc.get("package")?.get("name")?.as_str()
+    }
+
+    /// The literal `[package].version`, `None` when it is inherited or absent.
+    pub fn package_version(&self) -> Option<&str> {
+        self.doc.get("package")?.get("version")?.as_str()
+    }
+
+    pub fn inherits_version(&self) -> bool {
+        self.doc
+            .get("package")
+            .and_then(|p| p.get("version"))
+            .and_then(|v| v.get("workspace"))
+            .and_then(|w| w.as_bool())
+            .unwrap_or(false)
+    }
+
+    pub fn workspace_version(&self) -> Option<&str> {
+        self.doc
+            .get("workspace")?
+            .get("package")?
+            .get("version")?
+            .as_str()
+    }
+}
+
+/// Replaces a string value, keeping its surrounding whitespace and comments.
+fn set_string(value: &mut Value, new: &str) {
+    let decor = value.decor().clone();
+    *value = Value::from(new);
+    *value.decor_mut() = decor;
+}
+
+/// Rewrites a single-comparator requirement such as `1.2.3`, `=1.2.3` or
+/// `^1.2` to require `version`, keeping the operator. Requirements with
+/// several comparators or wildcards are left to the user.
+pub fn update_requirement(requirement: &str, version: &Version) -> Option<String> {
+    let requirement = requirement.trim();
+    if requirement.contains([',', '*', 'x', 'X']) || requirement.is_empty() {
+        return None;
+    }
+    let operator_len = requirement
+        .find(|c: char| c.is_ascii_digit())
+        .unwrap_or(requirement.len());
+    let operator = requirement[..operator_len].trim();
+    if !["", "=", "^", "~", ">="].contains(&operator) {
+        return None;
+    }
+    let updated = format!("{operator}{version}");
+    (updated != requirement).then_some(updated)
+}
+
+/// Updates `version` requirements of dependencies on `packages` that are
+/// declared with a `path` (or through `workspace = true`, which is handled in
+/// `[workspace.dependencies]`). Returns whether anything changed.
+pub fn update_dependency_requirements(
+    doc: &mut DocumentMut,
+    packages: &[String],
+    version: &Version,
+) -> bool {
+    let mut changed = false;
+    let mut update_table = |table: &mut dyn toml_edit::TableLike| {
+        for (key, item) in table.iter_mut() {
+            let Some(dep) = item.as_table_like_mut() else {
+                continue;
+            };
+            let name = dep
+                .get("package")
+                .and_then(|p| p.as_str())
+                .unwrap_or(key.get())
+                .to_string();
+            if !packages.contains(&name) || dep.get("path").is_none() {
+                continue;
+            }
+            if let Some(value) = dep.get_mut("version").and_then(Item::as_value_mut)
+                && let Some(updated) = value
+                    .as_str()
+                    .and_then(|req| update_requirement(req, version))
+            {
+                set_string(value, &updated);
+                changed = true;
+            }
+        }
+    };
+
+    for name in DEPENDENCY_TABLES {
+        if let Some(table) = doc.get_mut(name).and_then(Item::as_table_like_mut) {
+            update_table(table);
+        }
+    }
+    if let Some(targets) = doc.get_mut("target").and_then(Item::as_table_like_mut) {
+        for (_, target) in targets.iter_mut() {
+            for name in DEPENDENCY_TABLES {
+                if let Some(table) = target.get_mut(name).and_then(Item::as_table_like_mut) {
+                    update_table(table);
+                }
+            }
+        }
+    }
+    if let Some(table) = doc
+        .get_mut("workspace")
+        .and_then(|w| w.get_mut("dependencies"))
+        .and_then(Item::as_table_like_mut)
+    {
+        update_table(table);
+    }
+    changed
+}
+
+/// Paths of workspace member manifests listed in `[workspace].members`
+/// (glob patterns relative to the root), minus `[workspace].exclude`.
+pub fn workspace_members(root: &Manifest) -> Vec<PathBuf> {
+    let dir = root.path.parent().unwrap_or(Path::new(""));
+    let patterns = |key: &str| -> Vec<String> {
+        root.doc
+            .get("workspace")
+            .and_then(|w| w.get(key))
+            .and_then(|m| m.as_array())
+            .map(|a| {
+                a.iter()
+                    .filter_map(|v| v.as_str().map(String::from))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let expand = |pattern: &String| -> Vec<PathBuf> {
+        let full = dir.join(pattern);
+        glob::glob(&full.to_string_lossy())
+            .map(|paths| paths.filter_map(|p| p.ok()).collect())
+            .unwrap_or_default()
+    };
+    let excluded: Vec<PathBuf> = patterns("exclude").iter().flat_map(expand).collect();
+
+    let mut members: Vec<PathBuf> = patterns("members")
+        .iter()
+        .flat_map(expand)
+        .filter(|p| !excluded.contains(p))
+        .map(|p| p.join("Cargo.toml"))
+        .filter(|p| p.is_file() && *p != root.path)
+        .collect();
+    members.sort();
+    members.dedup();
+    members
+}
+
+/// The outcome of bumping a project's version.
+#[derive(Debug, Clone)]
+pub struct VersionBump {
+    pub previous: Version,
+    pub version: Version,
+    /// Packages whose version changed.
+    pub packages: Vec<String>,
+    /// Manifests that were modified, root first.
+    pub manifests: Vec<Manifest>,
+}
+
+/// Computes the new version and edits the root manifest and workspace members
+/// in memory. `[workspace.package].version` is bumped when the root defines it,
+/// otherwise `[package].version`. Members with a literal version equal to the
+/// previous one are bumped along with it, and path dependencies on bumped
+/// packages get their version requirements updated.
+pub fn bump_manifests(
+    manifest_path: &Path,
+    level: &BumpLevel,
+    pre_id: Option<&str>,
+) -> Result<VersionBump> {
+    let mut root = Manifest::read(manifest_path)?;
+    let current = get_cargo_info_from_path(&manifest_path.to_string_lossy())?.version;
+    if root.workspace_version().is_none() && root.inherits_version() {
+        return Err(Error::User(format!(
+            "{} inherits its version from the workspace: run bump-version against the workspace root manifest",
+            manifest_path.display()
+        )));
+    }
+    if current.is_empty() {
+        return Err(Error::User(format!(
+            "no version found in {}: bump-version needs [package].version or [workspace.package].version",
+            manifest_path.display()
+        )));
+    }
+    let previous = Version::parse(&current)?;
+    let version = previous.bump(level, pre_id)?;
+    let new = version.to_string();
+
+    let mut packages = Vec::new();
+    if root.workspace_version().is_some() {
+        let value = root.doc["workspace"]["package"]["version"]
+            .as_value_mut()
+            .expect("workspace version is a string");
+        set_string(value, &new);
+        if let Some(name) = root.package_name().filter(|_| root.inherits_version()) {
+            packages.push(name.to_string());
+        }
+    }
+    if root.package_version() == Some(current.as_str()) {
+        let value = root.doc["package"]["version"]
+            .as_value_mut()
+            .expect("package version is a string");
+        set_string(value, &new);
+        packages.extend(root.package_name().map(String::from));
+    }
+
+    let mut members = Vec::new();
+    for path in workspace_members(&root) {
+        let mut member = Manifest::read(&path)?;
+        let inherited = member.inherits_version() && root.workspace_version().is_some();
+        let literal = member.package_version() == Some(current.as_str());
+        if literal {
+            let value = member.doc["package"]["version"]
+                .as_value_mut()
+                .expect("package version is a string");
+            set_string(value, &new);
+        }
+        if inherited || literal {
+            packages.extend(member.package_name().map(String::from));
+        }
+        members.push((member, literal));
+    }
+
+    let mut manifests = Vec::new();
+    update_dependency_requirements(&mut root.doc, &packages, &version);
+    manifests.push(root);
+    for (mut member, version_changed) in members {
+        let deps_changed = update_dependency_requirements(&mut member.doc, &packages, &version);
+        if version_changed || deps_changed {
+            manifests.push(member);
+        }
+    }
+
+    Ok(VersionBump {
+        previous,
+        version,
+        packages,
+        manifests,
+    })
+}
+
+pub fn run_bump_version(config: &ReleaseConfig) -> Result<()> {
+    let level = config.get("BUMP_LEVEL", "");
+    if level.is_empty() {
+        return Err(Error::User(
+            "BUMP_LEVEL is required: major, minor, patch, prerelease or a version".to_string(),
+        ));
+    }
+    let level: BumpLevel = level.parse()?;
+    let pre_id = config.get("BUMP_PRE_ID", "");
+    let manifest_path = config.get("MANIFEST_PATH", "Cargo.toml");
+
+    let bump = bump_manifests(
+        Path::new(&manifest_path),
+        &level,
+        (!pre_id.is_empty()).then_some(pre_id.as_str()),
+    )?;
+    let mut files = Vec::new();
+    for manifest in &bump.manifests {
+        fs::write(&manifest.path, manifest.doc.to_string())?;
+        println!("\x1b[32mUpdated\x1b[0m {}", manifest.path.display());
+        files.push(manifest.path.display().to_string());
+    }
+
+    let lockfile = Path::new(&manifest_path).with_file_name("Cargo.lock");
+    if lockfile.exists() {
+        run_command(
+            "cargo",
+            &["update", "--workspace", "--manifest-path", &manifest_path],
+        )?;
+        println!("\x1b[32mUpdated\x1b[0m {}", lockfile.display());
+        files.push(lockfile.display().to_string());
+    }
+
+    println!(
+        "\x1b[32mBumped version:\x1b[0m {} -> {}",
+        bump.previous, bump.version
+    );
+    output("previous_version", &bump.previous.to_string());
+    output("version", &bump.version.to_string());
+    output("prerelease", &bump.version.is_prerelease().to_string());
+    output("updated_files", &files.join(","));
+    Ok(())
+}
@@ -1,63 +1,189 @@
+use crate::config::ReleaseConfig;
 use crate::error::{Error, Result};
-use serde::Deserialize;
+use std::path::{Path, PathBuf};
 use std::{env, fs};
+use toml::{Table, Value};
 
-#[derive(Debug, Clone)]
+/// Package metadata read from Cargo.toml. Fields inherited with
+/// `workspace = true` are resolved from the workspace's `[workspace.package]`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct CargoInfo {
     pub name: String,
     pub version: String,
+    pub description: String,
+    pub license: String,
+    /// Relative to the current directory, like the manifest path.
+    pub license_file: String,
+    pub homepage: String,
+    pub repository: String,
+    pub authors: Vec<String>,
+    pub keywords: Vec<String>,
+    /// Relative to the current directory; empty when unset or `readme = false`.
+    pub readme: String,
 }
 
-#[derive(Deserialize)]
-struct CargoToml {
-    package: Option<PackageSection>,
-    workspace: Option<WorkspaceSection>,
-}
+impl CargoInfo {
+    /// The homepage, falling back to the repository URL.
+    pub fn homepage_or_repository(&self) -> &str {
+        if self.homepage.is_empty() {
+            &self.repository
+        } else {
+            &self.homepage
+        }
+    }
 
-#[derive(Deserialize)]
-struct PackageSection {
-    name: Option<String>,
-    version: Option<String>,
-}
+    /// The first author (e.g. `Jane Doe <jane@example.com>`), or `default`.
+    pub fn maintainer_or<'a>(&'a self, default: &'a str) -> &'a str {
+        self.authors.first().map_or(default, String::as_str)
+    }
 
-#[derive(Deserialize)]
-struct WorkspaceSection {
-    package: Option<WorkspacePackage>,
-}
+    /// The license expression, or `default` when the manifest has none.
+    pub fn license_or<'a>(&'a self, default: &'a str) -> &'a str {
+        if self.license.is_empty() {
+            default
+        } else {
+            &self.license
+        }
+    }
 
-#[derive(Deserialize)]
-struct WorkspacePackage {
-    version: Option<String>,
+    /// The description, falling back to a generated one for `name`.
+    pub fn description_or_default(&self, name: &str) -> String {
+        if self.description.is_empty() {
+            format!("{name} - built with rust-build-package-release-action")
+        } else {
+            self.description.clone()
+        }
+    }
 }
 
-pub fn get_cargo_info() -> Result<CargoInfo> {
-    let manifest = env::var("MANIFEST_PATH").unwrap_or_else(|_| "Cargo.toml".to_string());
-    get_cargo_info_from_path(&manifest)
+pub fn get_cargo_info(config: &ReleaseConfig) -> Result<CargoInfo> {
+    get_cargo_info_from_path(&config.get("MANIFEST_PATH", "Cargo.toml"))
 }
 
 pub fn get_cargo_info_from_path(manifest_path: &str) -> Result<CargoInfo> {
     let content = fs::read_to_string(manifest_path)
         .map_err(|e| Error::User(format!("could not read {manifest_path}: {e}")))?;
-    let cargo: CargoToml = toml::from_str(&content)?;
+    let manifest: Table = toml::from_str(&content)?;
+    let manifest_dir = Path::new(manifest_path)
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .to_path_buf();
 
-    let name = cargo
-        .package
-        .as_ref()
-        .and_then(|p| p.name.clone())
-        .unwrap_or_default();
+    let package = manifest.get("package").and_then(Value::as_table);
+    let own_workspace = workspace_package(&manifest).map(|ws| (ws.clone(), manifest_dir.clone()));
+    let mut inherited: Option<Option<(Table, PathBuf)>> = None;
 
-    let version = cargo
-        .package
-        .as_ref()
-        .and_then(|p| p.version.clone())
-        .or_else(|| {
-            cargo
-                .workspace
+    let mut field = |key: &str| -> Option<(Value, PathBuf)> {
+        match package.and_then(|p| p.get(key)) {
+            Some(value) if is_inherited(value) => {
+                let workspace = inherited.get_or_insert_with(|| {
+                    own_workspace
+                        .clone()
+                        .or_else(|| find_workspace_package(&manifest_dir))
+                });
+                let (table, dir) = workspace.as_ref()?;
+                table.get(key).map(|v| (v.clone(), dir.clone()))
+            }
+            Some(value) => Some((value.clone(), manifest_dir.clone())),
+            // A virtual workspace manifest describes its members
+            None if package.is_none() => own_workspace
                 .as_ref()
-                .and_then(|w| w.package.as_ref())
-                .and_then(|p| p.version.clone())
-        })
+                .and_then(|(table, dir)| table.get(key).map(|v| (v.clone(), dir.clone()))),
+            None => None,
+        }
+    };
+
+    let name = package
+        .and_then(|p| p.get("name"))
+        .and_then(Value::as_str)
+        .map(String::from)
         .unwrap_or_default();
+    let version = match package.and_then(|p| p.get("version")) {
+        Some(Value::String(v)) => v.clone(),
+        _ => workspace_package(&manifest)
+            .and_then(|ws| ws.get("version"))
+            .and_then(Value::as_str)
+            .map(String::from)
+            .or_else(|| as_string(field("version")))
+            .unwrap_or_default(),
+    };
+
+    Ok(CargoInfo {
+        name,
+        version,
+        description: as_string(field("description")).unwrap_or_default(),
+        license: as_string(field("license")).unwrap_or_default(),
+        license_file: as_path(field("license-file")),
+        homepage: as_string(field("homepage")).unwrap_or_default(),
+        repository: as_string(field("repository")).unwrap_or_default(),
+        authors: as_list(field("authors")),
+        keywords: as_list(field("keywords")),
+        readme: as_path(field("readme")),
+    })
+}
+
+fn as_string(value: Option<(Value, PathBuf)>) -> Option<String> {
+    value.and_then(|(v, _)| v.as_str().map(String::from))
+}
+
+fn as_list(value: Option<(Value, PathBuf)>) -> Vec<String> {
+    match value {
+        Some((Value::Array(items), _)) => items
+            .iter()
+            .filter_map(|i| i.as_str().map(String::from))
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// A path field (or `readme = true`) relative to the manifest that declared it.
+fn as_path(value: Option<(Value, PathBuf)>) -> String {
+    match value {
+        Some((Value::String(p), dir)) => dir.join(p).to_string_lossy().to_string(),
+        Some((Value::Boolean(true), dir)) => dir.join("README.md").to_string_lossy().to_string(),
+        _ => String::new(),
+    }
+}
+
+/// `{ workspace = true }`
+fn is_inherited(value: &Value) -> bool {
+    value.get("workspace").and_then(Value::as_bool) == Some(true)
+}
+
+fn workspace_package(manifest: &Table) -> Option<&Table> {
+    manifest
+        .get("workspace")
+        .and_then(|w| w.get("package"))
+        .and_then(Value::as_table)
+}
 
-    Ok(CargoInfo { name, version })
+/// Finds `[workspace.package]` of the nearest ancestor manifest with a
+/// `[workspace]` table, along with that manifest's directory.
+fn find_workspace_package(manifest_dir: &Path) -> Option<(Table, PathBuf)> {
+    let start = fs::canonicalize(if manifest_dir.as_os_str().is_empty() {
+        Path::new(".")
+    } else {
+        manifest_dir
+    })
+    .ok()?;
+    let cwd = env::current_dir().ok();
+    for dir in start.ancestors().skip(1) {
+        let Ok(content) = fs::read_to_string(dir.join("Cargo.toml")) else {
+            continue;
+        };
+        let Ok(manifest) = toml::from_str::<Table>(&content) else {
+            continue;
+        };
+        if !manifest.contains_key("workspace") {
+            continue;
+        }
+        let table = workspace_package(&manifest).cloned().unwrap_or_default();
+        // Keep paths relative to the current directory when possible
+        let dir = cwd
+            .as_ref()
+            .and_then(|cwd| dir.strip_prefix(cwd).ok())
+            .map_or_else(|| dir.to_path_buf(), Path::to_path_buf);
+        return Some((table, dir));
+    }
+    None
 }
@@ -1,58 +1,748 @@
+use crate::config::ReleaseConfig;
 use crate::error::{Error, Result};
-use crate::output::{output, output_multiline};
+use crate::output::{annotation, output, output_multiline};
+use crate::timestamp::today;
+use crate::version::Version;
 use regex::Regex;
+use std::cmp::Ordering;
+use std::fs;
 use std::path::Path;
-use std::{env, fs};
+use std::sync::LazyLock;
 
-/// Extracts a version's section from a changelog.
-pub fn extract_changelog_section(content: &str, version: &str) -> Result<String> {
-    let lines: Vec<&str> = content.lines().collect();
-    let version_header = format!("## v{version}");
-    let alt_header = format!("## {version}");
+/// Title of the section that collects changes not yet released.
+pub const UNRELEASED: &str = "Unreleased";
 
-    let start_idx = lines
-        .iter()
-        .position(|line| line.starts_with(&version_header) || line.starts_with(&alt_header))
-        .ok_or_else(|| Error::User(format!("version {version} not found in changelog")))?;
+static VERSION_HEADING: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?i)^\[?(?:v?(?P<version>\d+\.\d+\.\d+(?:-[0-9A-Za-z.\-]+)?(?:\+[0-9A-Za-z.\-]+)?)|(?P<unreleased>unreleased))\]?(?P<rest>.*)$",
+    )
+    .unwrap()
+});
+static ATX_HEADING: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s{0,3}##\s+(.*?)\s*#*\s*$").unwrap());
+static SETEXT_UNDERLINE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s{0,3}-{2,}\s*$").unwrap());
+static LINK_DEFINITION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s{0,3}\[([^\]]+)\]:\s*(\S+)").unwrap());
+static SUBSECTION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^#{3,6}\s+(.*?)\s*#*\s*$").unwrap());
+static BULLET: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\s{0,3}[-*+]\s+(.*)$").unwrap());
+static FIRST_BULLET: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^([ \t]{0,3}[-*+][ \t]+)\S").unwrap());
+static UNRELEASED_LINK: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?i)^(\s{0,3}\[unreleased\]:\s*)(\S+)").unwrap());
 
-    let remaining = &lines[start_idx + 1..];
-    let next_version_re = Regex::new(r"^## v?\d+\.\d+\.\d+").unwrap();
-    let end_offset = remaining
-        .iter()
-        .position(|line| next_version_re.is_match(line))
-        .unwrap_or(remaining.len());
+/// The kind of change a changelog subsection (or a conventional commit) describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ChangeKind {
+    Breaking,
+    Added,
+    Changed,
+    Deprecated,
+    Removed,
+    Fixed,
+    Security,
+    Performance,
+    Documentation,
+    Other,
+}
 
-    let section = &lines[start_idx..start_idx + 1 + end_offset];
-    let notes = section.join("\n");
+impl ChangeKind {
+    /// Maps a subsection title, in Keep a Changelog style (`Added`, `Fixed`, …)
+    /// or the conventional-changelog style (`Features`, `Bug Fixes`, …).
+    pub fn from_heading(title: &str) -> Self {
+        let title = title.trim().to_lowercase();
+        match title.as_str() {
+            t if t.contains("breaking") => ChangeKind::Breaking,
+            "added" | "features" | "new features" | "enhancements" => ChangeKind::Added,
+            "changed" | "changes" | "refactoring" | "code refactoring" => ChangeKind::Changed,
+            "deprecated" | "deprecations" => ChangeKind::Deprecated,
+            "removed" | "removals" => ChangeKind::Removed,
+            "fixed" | "fixes" | "bug fixes" | "bugfixes" => ChangeKind::Fixed,
+            "security" => ChangeKind::Security,
+            "performance" | "performance improvements" => ChangeKind::Performance,
+            "documentation" | "docs" => ChangeKind::Documentation,
+            _ => ChangeKind::Other,
+        }
+    }
+
+    /// Maps a conventional commit type such as `feat` or `fix`.
+    pub fn from_commit_type(kind: &str) -> Self {
+        match kind.trim().to_lowercase().as_str() {
+            "feat" | "feature" => ChangeKind::Added,
+            "fix" | "bugfix" => ChangeKind::Fixed,
+            "perf" => ChangeKind::Performance,
+            "refactor" => ChangeKind::Changed,
+            "revert" => ChangeKind::Removed,
+            "deprecate" => ChangeKind::Deprecated,
+            "security" | "sec" => ChangeKind::Security,
+            "docs" | "doc" => ChangeKind::Documentation,
+            _ => ChangeKind::Other,
+        }
+    }
+
+    /// The subsection title used when writing a changelog.
+    pub fn heading(self) -> &'static str {
+        match self {
+            ChangeKind::Breaking => "Breaking Changes",
+            ChangeKind::Added => "Added",
+            ChangeKind::Changed => "Changed",
+            ChangeKind::Deprecated => "Deprecated",
+            ChangeKind::Removed => "Removed",
+            ChangeKind::Fixed => "Fixed",
+            ChangeKind::Security => "Security",
+            ChangeKind::Performance => "Performance",
+            ChangeKind::Documentation => "Documentation",
+            ChangeKind::Other => "Other",
+        }
+    }
+}
+
+/// A `### Title` subsection of a release section and its list entries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangelogCategory {
+    /// The subsection title, empty for entries listed before any subsection.
+    pub title: String,
+    pub kind: ChangeKind,
+    /// 1-based line of the subsection heading (or of its first entry).
+    pub line: usize,
+    pub entries: Vec<String>,
+}
+
+/// A release (or `Unreleased`) section of a changelog.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangelogSection {
+    /// The version without a `v` prefix, or [`UNRELEASED`].
+    pub version: String,
+    /// Text after the version in the heading, e.g. `2024-01-01` in
+    /// `## [1.2.3] - 2024-01-01` or `Jul 11, 2026` in `## v1.2.3 (Jul 11, 2026)`.
+    pub date: Option<String>,
+    /// 1-based line of the heading.
+    pub line: usize,
+    /// The heading as written: one line, or two for setext headings.
+    pub heading: Vec<String>,
+    /// Lines after the heading with their 1-based line numbers,
+    /// excluding link reference definitions and trailing blank lines.
+    pub body: Vec<(usize, String)>,
+}
+
+impl ChangelogSection {
+    pub fn is_unreleased(&self) -> bool {
+        self.version == UNRELEASED
+    }
+
+    /// Whether the section has no content besides its heading.
+    pub fn is_empty(&self) -> bool {
+        self.body.iter().all(|(_, line)| line.trim().is_empty())
+    }
+
+    /// The heading and body as Markdown.
+    pub fn text(&self) -> String {
+        self.heading
+            .iter()
+            .cloned()
+            .chain(self.body.iter().map(|(_, line)| line.clone()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Groups list entries by `###` subsection. Continuation lines are joined
+    /// to their entry.
+    pub fn categories(&self) -> Vec<ChangelogCategory> {
+        let mut categories: Vec<ChangelogCategory> = Vec::new();
+        let mut in_entry = false;
+        for (line_no, line) in &self.body {
+            if let Some(caps) = SUBSECTION.captures(line) {
+                let title = caps[1].to_string();
+                categories.push(ChangelogCategory {
+                    kind: ChangeKind::from_heading(&title),
+                    title,
+                    line: *line_no,
+                    entries: Vec::new(),
+                });
+                in_entry = false;
+            } else if let Some(caps) = BULLET.captures(line) {
+                if categories.is_empty() {
+                    categories.push(ChangelogCategory {
+                        title: String::new(),
+                        kind: ChangeKind::Other,
+                        line: *line_no,
+                        entries: Vec::new(),
+                    });
+                }
+                if let Some(category) = categories.last_mut() {
+                    category.entries.push(caps[1].trim().to_string());
+                }
+                in_entry = true;
+            } else if line.trim().is_empty() {
+                in_entry = false;
+            } else if in_entry {
+                if let Some(entry) = categories.last_mut().and_then(|c| c.entries.last_mut()) {
+                    entry.push(' ');
+                    entry.push_str(line.trim());
+                }
+            }
+        }
+        categories
+    }
+}
+
+/// A parsed changelog: release sections in document order and the link
+/// reference definitions (`[1.2.3]: https://…`) found anywhere in the file.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Changelog {
+    pub sections: Vec<ChangelogSection>,
+    pub links: Vec<(String, String)>,
+}
+
+/// Parses a heading title such as `v1.2.3`, `[1.2.3] - 2024-01-01`,
+/// `1.2.3 (Jul 11, 2026)` or `[Unreleased]` into a version and date.
+pub fn parse_version_heading(title: &str) -> Option<(String, Option<String>)> {
+    let caps = VERSION_HEADING.captures(title.trim())?;
+    let version = match caps.name("version") {
+        Some(v) => v.as_str().to_string(),
+        None => UNRELEASED.to_string(),
+    };
+    let rest = caps.name("rest").map_or("", |r| r.as_str());
+    // The version must end at a word boundary: `1.2.3x` is not a version heading
+    if rest.chars().next().is_some_and(|c| c.is_alphanumeric()) {
+        return None;
+    }
+    let date = rest
+        .trim()
+        .trim_start_matches(['-', '–', '—', ':'])
+        .trim()
+        .trim_start_matches('(')
+        .trim_end_matches(')')
+        .trim();
+    Some((version, (!date.is_empty()).then(|| date.to_string())))
+}
+
+/// Strips a leading `v` so `v1.2.3` and `1.2.3` match the same section.
+fn normalize_version(version: &str) -> &str {
+    let version = version.trim();
+    version.strip_prefix('v').unwrap_or(version)
+}
+
+impl Changelog {
+    /// Parses release sections from ATX (`## v1.2.3`) and setext
+    /// (`1.2.3` underlined with `---`) level-2 headings. Headings inside
+    /// fenced code blocks are ignored.
+    pub fn parse(content: &str) -> Self {
+        let lines: Vec<&str> = content.lines().collect();
+        let mut changelog = Changelog::default();
+        let mut current: Option<ChangelogSection> = None;
+        let mut in_fence = false;
+        let mut i = 0;
+        while i < lines.len() {
+            let line = lines[i];
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                in_fence = !in_fence;
+            }
+
+            let heading = if in_fence {
+                None
+            } else if let Some(caps) = ATX_HEADING.captures(line) {
+                parse_version_heading(&caps[1]).map(|h| (h, 1))
+            } else if !line.trim().is_empty()
+                && !line.trim_start().starts_with('#')
+                && lines
+                    .get(i + 1)
+                    .is_some_and(|next| SETEXT_UNDERLINE.is_match(next))
+            {
+                parse_version_heading(line).map(|h| (h, 2))
+            } else {
+                None
+            };
+
+            if let Some(((version, date), heading_lines)) = heading {
+                if let Some(section) = current.take() {
+                    changelog.sections.push(section);
+                }
+                current = Some(ChangelogSection {
+                    version,
+                    date,
+                    line: i + 1,
+                    heading: lines[i..i + heading_lines]
+                        .iter()
+                        .map(|l| l.to_string())
+                        .collect(),
+                    body: Vec::new(),
+                });
+                i += heading_lines;
+                continue;
+            }
+
+            if let Some(caps) = LINK_DEFINITION.captures(line).filter(|_| !in_fence) {
+                changelog
+                    .links
+                    .push((caps[1].to_string(), caps[2].to_string()));
+            } else if let Some(section) = current.as_mut() {
+                section.body.push((i + 1, line.to_string()));
+            }
+            i += 1;
+        }
+        if let Some(section) = current {
+            changelog.sections.push(section);
+        }
+
+        for section in &mut changelog.sections {
+            while section
+                .body
+                .last()
+                .is_some_and(|(_, l)| l.trim().is_empty())
+            {
+                section.body.pop();
+            }
+        }
+        changelog
+    }
 
-    if notes.trim().is_empty() {
+    /// Finds the section for `version` (with or without a `v` prefix), or the
+    /// `Unreleased` section.
+    pub fn find(&self, version: &str) -> Option<&ChangelogSection> {
+        let version = normalize_version(version);
+        self.sections.iter().find(|s| {
+            if s.is_unreleased() {
+                version.eq_ignore_ascii_case(UNRELEASED)
+            } else {
+                s.version == version
+            }
+        })
+    }
+
+    pub fn unreleased(&self) -> Option<&ChangelogSection> {
+        self.sections.iter().find(|s| s.is_unreleased())
+    }
+
+    /// Link reference definitions used by `text`, e.g. `[1.2.3]` in a heading.
+    pub fn links_used_in(&self, text: &str) -> Vec<String> {
+        let text = text.to_lowercase();
+        self.links
+            .iter()
+            .filter(|(label, _)| text.contains(&format!("[{}]", label.to_lowercase())))
+            .map(|(label, url)| format!("[{label}]: {url}"))
+            .collect()
+    }
+}
+
+/// How an existing changelog writes headings and list items, so that
+/// generated sections look like the rest of the file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangelogStyle {
+    /// `## [1.2.3] - 2024-01-01` instead of `## v1.2.3 (Jan 1, 2024)`.
+    pub keep_a_changelog: bool,
+    /// List item prefix, e.g. `- ` or ` * `.
+    pub bullet: String,
+}
+
+impl Default for ChangelogStyle {
+    fn default() -> Self {
+        ChangelogStyle {
+            keep_a_changelog: false,
+            bullet: " * ".to_string(),
+        }
+    }
+}
+
+impl ChangelogStyle {
+    pub fn detect(content: &str) -> Self {
+        let changelog = Changelog::parse(content);
+        let keep_a_changelog = changelog
+            .sections
+            .first()
+            .and_then(|s| s.heading.first())
+            .is_some_and(|h| h.trim_start_matches('#').trim_start().starts_with('['));
+        let bullet = FIRST_BULLET
+            .captures(content)
+            .map(|caps| caps[1].to_string())
+            .unwrap_or_else(|| {
+                if keep_a_changelog {
+                    "- ".to_string()
+                } else {
+                    ChangelogStyle::default().bullet
+                }
+            });
+        ChangelogStyle {
+            keep_a_changelog,
+            bullet,
+        }
+    }
+
+    /// The section heading for `version` released on `date` (`YYYY-MM-DD`).
+    pub fn heading(&self, version: &str, date: &str) -> String {
+        if self.keep_a_changelog {
+            format!("## [{version}] - {date}")
+        } else {
+            format!("## v{version} ({})", long_date(date))
+        }
+    }
+
+    pub fn unreleased_heading(&self) -> String {
+        if self.keep_a_changelog {
+            format!("## [{UNRELEASED}]")
+        } else {
+            format!("## {UNRELEASED}")
+        }
+    }
+}
+
+/// Formats `YYYY-MM-DD` as `Jan 1, 2024`.
+pub fn long_date(date: &str) -> String {
+    const MONTHS: [&str; 12] = [
+        "Jan", "Feb", "Mar", "Apr", "May", "Jun", "Jul", "Aug", "Sep", "Oct", "Nov", "Dec",
+    ];
+    let parts: Vec<&str> = date.split('-').collect();
+    match parts.as_slice() {
+        [year, month, day] => {
+            let month = month
+                .parse::<usize>()
+                .ok()
+                .and_then(|m| MONTHS.get(m.wrapping_sub(1)));
+            match (month, day.parse::<u32>()) {
+                (Some(month), Ok(day)) => format!("{month} {day}, {year}"),
+                _ => date.to_string(),
+            }
+        }
+        _ => date.to_string(),
+    }
+}
+
+/// Extracts a version's section from a changelog, including its heading and
+/// the link reference definitions it refers to.
+pub fn extract_changelog_section(content: &str, version: &str) -> Result<String> {
+    let changelog = Changelog::parse(content);
+    let section = changelog
+        .find(version)
+        .ok_or_else(|| Error::User(format!("version {version} not found in changelog")))?;
+
+    if section.is_empty() {
         return Err(Error::User(format!(
             "no content found for version {version}"
         )));
     }
 
+    let mut notes = section.text();
+    let links = changelog.links_used_in(&notes);
+    if !links.is_empty() {
+        notes.push_str("\n\n");
+        notes.push_str(&links.join("\n"));
+    }
     Ok(notes)
 }
 
 /// Validates that a changelog contains an entry for the given version.
 pub fn validate_changelog_entry(content: &str, version: &str) -> bool {
-    let version_header = format!("## v{version}");
-    let alt_header = format!("## {version}");
-    content
-        .lines()
-        .any(|line| line.starts_with(&version_header) || line.starts_with(&alt_header))
+    Changelog::parse(content).find(version).is_some()
+}
+
+/// How serious a changelog lint finding is. Errors fail `lint-changelog`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
 }
 
-pub fn run_extract_changelog() -> Result<()> {
-    let version = env::var("VERSION").unwrap_or_default();
+impl Severity {
+    pub fn name(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+}
+
+/// A changelog lint finding at a 1-based line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub line: usize,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn error(line: usize, message: String) -> Self {
+        Diagnostic {
+            severity: Severity::Error,
+            line,
+            message,
+        }
+    }
+
+    fn warning(line: usize, message: String) -> Self {
+        Diagnostic {
+            severity: Severity::Warning,
+            line,
+            message,
+        }
+    }
+
+    /// The diagnostic as a GitHub Actions annotation on `file`.
+    pub fn annotation(&self, file: &str) -> String {
+        annotation(self.severity.name(), file, self.line, &self.message)
+    }
+}
+
+static ISO_DATE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\d{4})-(\d{2})-(\d{2})$").unwrap());
+static LONG_DATE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^([A-Za-z]+)\.?\s+(\d{1,2}),?\s+(\d{4})$").unwrap());
+
+/// Parses a release date written as `2024-01-01`, `Jan 1, 2024` or
+/// `January 1, 2024` into `(year, month, day)`. A trailing `[YANKED]` marker
+/// is ignored.
+pub fn parse_changelog_date(date: &str) -> Option<(u32, u32, u32)> {
+    const MONTHS: [&str; 12] = [
+        "january",
+        "february",
+        "march",
+        "april",
+        "may",
+        "june",
+        "july",
+        "august",
+        "september",
+        "october",
+        "november",
+        "december",
+    ];
+    let date = date.trim();
+    let date = date.strip_suffix("[YANKED]").map_or(date, |d| d.trim_end());
+    let (year, month, day) = if let Some(caps) = ISO_DATE.captures(date) {
+        (
+            caps[1].parse().ok()?,
+            caps[2].parse().ok()?,
+            caps[3].parse().ok()?,
+        )
+    } else {
+        let caps = LONG_DATE.captures(date)?;
+        let name = caps[1].to_lowercase();
+        let month = MONTHS
+            .iter()
+            .position(|m| *m == name || m[..3] == name || (name == "sept" && *m == "september"))?;
+        (
+            caps[3].parse().ok()?,
+            month as u32 + 1,
+            caps[2].parse().ok()?,
+        )
+    };
+
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days = match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if leap => 29,
+        2 => 28,
+        _ => return None,
+    };
+    (1..=days).contains(&day).then_some((year, month, day))
+}
+
+/// Whether a section lists any changes. Subsection headings alone,
+/// such as an empty `### Added`, do not count.
+fn has_entries(section: &ChangelogSection) -> bool {
+    section.body.iter().any(|(_, line)| {
+        let line = line.trim();
+        !line.is_empty() && !line.starts_with('#')
+    })
+}
+
+/// Checks that release sections are in descending version order with
+/// valid, non-increasing dates and no duplicates. With `release_version`,
+/// also checks that its section lists changes and that `Unreleased` is empty.
+pub fn lint_changelog(content: &str, release_version: Option<&str>) -> Vec<Diagnostic> {
+    let changelog = Changelog::parse(content);
+    let mut diagnostics = Vec::new();
+    let mut seen: Vec<&ChangelogSection> = Vec::new();
+    let mut previous: Option<&ChangelogSection> = None;
+    let mut previous_date: Option<(&ChangelogSection, (u32, u32, u32))> = None;
+
+    for (index, section) in changelog.sections.iter().enumerate() {
+        if section.is_unreleased() {
+            if index > 0 {
+                diagnostics.push(Diagnostic::error(
+                    section.line,
+                    "the Unreleased section must come before all releases".to_string(),
+                ));
+            }
+            continue;
+        }
+
+        if let Err(e) = Version::parse(&section.version) {
+            diagnostics.push(Diagnostic::error(section.line, e.to_string()));
+        } else if let Some(first) = seen.iter().find(|s| s.version == section.version) {
+            diagnostics.push(Diagnostic::error(
+                section.line,
+                format!(
+                    "duplicate section for version {} (first defined on line {})",
+                    section.version, first.line
+                ),
+            ));
+        } else if let Some(prev) = previous
+            && let (Ok(prev_version), Ok(version)) = (
+                Version::parse(&prev.version),
+                Version::parse(&section.version),
+            )
+            && prev_version.precedence(&version) == Ordering::Less
+        {
+            diagnostics.push(Diagnostic::error(
+                section.line,
+                format!(
+                    "version {} is listed below {} (line {}); releases must be in descending order",
+                    section.version, prev.version, prev.line
+                ),
+            ));
+        }
+
+        // Dates without digits, such as `(in development)`, are status notes
+        match section.date.as_deref() {
+            Some(text) if text.chars().any(|c| c.is_ascii_digit()) => {
+                match parse_changelog_date(text) {
+                    Some(date) => {
+                        if let Some((prev, prev_date)) = previous_date
+                            && date > prev_date
+                        {
+                            diagnostics.push(Diagnostic::error(
+                                section.line,
+                                format!(
+                                    "{} is dated {text}, after {} (line {}); dates must not decrease from older to newer releases",
+                                    section.version,
+                                    prev.version,
+                                    prev.line
+                                ),
+                            ));
+                        }
+                        previous_date = Some((section, date));
+                    }
+                    None => diagnostics.push(Diagnostic::error(
+                        section.line,
+                        format!(
+                            "invalid date '{text}' for version {}; expected YYYY-MM-DD or e.g. Jan 1, 2024",
+                            section.version
+                        ),
+                    )),
+                }
+            }
+            _ => {}
+        }
+
+        if !has_entries(section) && Some(section.version.as_str()) != release_version {
+            diagnostics.push(Diagnostic::warning(
+                section.line,
+                format!("section for version {} is empty", section.version),
+            ));
+        }
+
+        seen.push(section);
+        previous = Some(section);
+    }
+
+    if let Some(version) = release_version {
+        match changelog.find(version) {
+            Some(section) if !has_entries(section) => diagnostics.push(Diagnostic::error(
+                section.line,
+                format!("section for released version {} is empty", section.version),
+            )),
+            Some(_) => {}
+            None => diagnostics.push(Diagnostic::error(
+                1,
+                format!("no section for released version {version}"),
+            )),
+        }
+        if let Some(unreleased) = changelog.unreleased()
+            && has_entries(unreleased)
+        {
+            diagnostics.push(Diagnostic::error(
+                unreleased.line,
+                format!(
+                    "the Unreleased section still lists changes; move them into the {version} section"
+                ),
+            ));
+        }
+    }
+
+    diagnostics.sort_by_key(|d| d.line);
+    diagnostics
+}
+
+/// Returns the tag of `version`, named like the `previous` release tag:
+/// whatever precedes the version in it (`v`, `tool-v` or nothing) is kept.
+/// Falls back to `v<version>` when `previous` does not end with a version.
+pub fn release_tag(previous: &str, version: &str) -> String {
+    let prefix = previous
+        .char_indices()
+        .map(|(i, _)| i)
+        .find(|&i| Version::parse(&previous[i..]).is_ok())
+        .map_or("v", |i| &previous[..i]);
+    format!("{prefix}{version}")
+}
+
+/// Turns the `Unreleased` section into the section for `version` released on
+/// `date` (`YYYY-MM-DD`) and adds a fresh, empty `Unreleased` section above it.
+///
+/// When the file has an `[Unreleased]: …/compare/v1.2.2...HEAD` link, it is
+/// moved to compare from the new release and a `[1.2.3]: …/compare/v1.2.2...v1.2.3`
+/// link is added below it.
+pub fn promote_unreleased(content: &str, version: &str, date: &str) -> Result<String> {
+    let version = normalize_version(version);
+    let changelog = Changelog::parse(content);
+    let unreleased = changelog
+        .unreleased()
+        .ok_or_else(|| Error::User("changelog has no Unreleased section".to_string()))?;
+    if changelog.find(version).is_some() {
+        return Err(Error::User(format!(
+            "changelog already has a section for version {version}"
+        )));
+    }
+    if !has_entries(unreleased) {
+        return Err(Error::User(format!(
+            "the Unreleased section is empty, nothing to release as {version}"
+        )));
+    }
+
+    let style = ChangelogStyle::detect(content);
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+    let start = unreleased.line - 1;
+    lines.splice(
+        start..start + unreleased.heading.len(),
+        [
+            style.unreleased_heading(),
+            String::new(),
+            style.heading(version, date),
+        ],
+    );
+
+    if let Some(index) = lines.iter().position(|l| UNRELEASED_LINK.is_match(l)) {
+        let caps = UNRELEASED_LINK.captures(&lines[index]).unwrap();
+        let (prefix, url) = (caps[1].to_string(), caps[2].to_string());
+        if let Some((base, range)) = url.rsplit_once("/compare/")
+            && let Some((from, to)) = range.split_once("...")
+        {
+            let tag = release_tag(from, version);
+            lines[index] = format!("{prefix}{base}/compare/{tag}...{to}");
+            lines.insert(
+                index + 1,
+                format!("[{version}]: {base}/compare/{from}...{tag}"),
+            );
+        }
+    }
+
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+    Ok(updated)
+}
+
+pub fn run_extract_changelog(config: &ReleaseConfig) -> Result<()> {
+    let version = config.get("VERSION", "");
     if version.is_empty() {
         return Err(Error::User(
             "VERSION environment variable is required".to_string(),
         ));
     }
 
-    let changelog_path = env::var("CHANGELOG_PATH").unwrap_or_else(|_| "CHANGELOG.md".into());
-    let output_path = env::var("OUTPUT_PATH").unwrap_or_else(|_| "release_notes.md".into());
+    let changelog_path = config.get("CHANGELOG_PATH", "CHANGELOG.md");
+    let output_path = config.get("OUTPUT_PATH", "release_notes.md");
 
     if !Path::new(&changelog_path).exists() {
         return Err(Error::User(format!(
@@ -71,15 +761,15 @@ pub fn run_extract_changelog() -> Result<()> {
     Ok(())
 }
 
-pub fn run_validate_changelog() -> Result<()> {
-    let version = env::var("VERSION").unwrap_or_default();
+pub fn run_validate_changelog(config: &ReleaseConfig) -> Result<()> {
+    let version = config.get("VERSION", "");
     if version.is_empty() {
         return Err(Error::User(
             "VERSION environment variable is required".to_string(),
         ));
     }
 
-    let changelog_path = env::var("CHANGELOG_PATH").unwrap_or_else(|_| "CHANGELOG.md".into());
+    let changelog_path = config.get("CHANGELOG_PATH", "CHANGELOG.md");
 
     if !Path::new(&changelog_path).exists() {
         return Err(Error::User(format!(
@@ -100,3 +790,63 @@ pub fn run_validate_changelog() -> Result<()> {
         )))
     }
 }
+
+pub fn run_lint_changelog(config: &ReleaseConfig) -> Result<()> {
+    let changelog_path = config.get("CHANGELOG_PATH", "CHANGELOG.md");
+    if !Path::new(&changelog_path).exists() {
+        return Err(Error::User(format!(
+            "changelog not found: {changelog_path}"
+        )));
+    }
+
+    let version = config.get("VERSION", "");
+    let content = fs::read_to_string(&changelog_path)?;
+    let diagnostics = lint_changelog(&content, (!version.is_empty()).then_some(version.as_str()));
+
+    for diagnostic in &diagnostics {
+        println!("{}", diagnostic.annotation(&changelog_path));
+    }
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .count();
+    let warnings = diagnostics.len() - errors;
+    output("error_count", &errors.to_string());
+    output("warning_count", &warnings.to_string());
+
+    if errors > 0 {
+        return Err(Error::User(format!(
+            "{changelog_path} has {errors} error(s) and {warnings} warning(s)"
+        )));
+    }
+    println!("\x1b[32mChangelog lint passed:\x1b[0m {changelog_path} ({warnings} warning(s))");
+    Ok(())
+}
+
+pub fn run_prepare_changelog(config: &ReleaseConfig) -> Result<()> {
+    let version = config.get("VERSION", "");
+    if version.is_empty() {
+        return Err(Error::User(
+            "VERSION environment variable is required".to_string(),
+        ));
+    }
+
+    let changelog_path = config.get("CHANGELOG_PATH", "CHANGELOG.md");
+    if !Path::new(&changelog_path).exists() {
+        return Err(Error::User(format!(
+            "changelog not found: {changelog_path}"
+        )));
+    }
+
+    let content = fs::read_to_string(&changelog_path)?;
+    let date = &today();
+    let updated = promote_unreleased(&content, &version, date)?;
+    fs::write(&changelog_path, &updated)?;
+    let notes = extract_changelog_section(&updated, &version)?;
+
+    println!("\x1b[32mPromoted\x1b[0m Unreleased to v{version} in {changelog_path}");
+    output("version", &version);
+    output("changelog_file", &changelog_path);
+    output_multiline("release_notes", &notes);
+    Ok(())
+}
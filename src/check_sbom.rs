@@ -0,0 +1,1062 @@
+use crate::config::ReleaseConfig;
+use crate::error::{Error, Result};
+use crate::output::{output, print_hr, step_summary};
+use crate::parse_comma_list;
+use regex::Regex;
+use serde_json::Value;
+use std::collections::BTreeSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SbomKind {
+    Spdx,
+    CycloneDx,
+}
+
+impl SbomKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            SbomKind::Spdx => "SPDX",
+            SbomKind::CycloneDx => "CycloneDX",
+        }
+    }
+
+    /// Detects the format from the document's version marker.
+    pub fn detect(doc: &Value) -> Option<Self> {
+        if doc.get("spdxVersion").is_some() {
+            Some(SbomKind::Spdx)
+        } else if doc.get("bomFormat").is_some() {
+            Some(SbomKind::CycloneDx)
+        } else {
+            None
+        }
+    }
+}
+
+const SPDX_CHECKSUM_ALGORITHMS: &[&str] = &[
+    "SHA1",
+    "SHA224",
+    "SHA256",
+    "SHA384",
+    "SHA512",
+    "SHA3-256",
+    "SHA3-384",
+    "SHA3-512",
+    "BLAKE2b-256",
+    "BLAKE2b-384",
+    "BLAKE2b-512",
+    "BLAKE3",
+    "MD2",
+    "MD4",
+    "MD5",
+    "MD6",
+    "ADLER32",
+];
+
+const SPDX_REFERENCE_CATEGORIES: &[&str] = &[
+    "OTHER",
+    "PERSISTENT-ID",
+    "PERSISTENT_ID",
+    "SECURITY",
+    "PACKAGE-MANAGER",
+    "PACKAGE_MANAGER",
+];
+
+const SPDX_PURPOSES: &[&str] = &[
+    "APPLICATION",
+    "FRAMEWORK",
+    "LIBRARY",
+    "CONTAINER",
+    "OPERATING-SYSTEM",
+    "DEVICE",
+    "FIRMWARE",
+    "SOURCE",
+    "ARCHIVE",
+    "FILE",
+    "INSTALL",
+    "OTHER",
+];
+
+const SPDX_RELATIONSHIP_TYPES: &[&str] = &[
+    "VARIANT_OF",
+    "COPY_OF",
+    "PATCH_FOR",
+    "TEST_DEPENDENCY_OF",
+    "CONTAINED_BY",
+    "DATA_FILE_OF",
+    "OPTIONAL_COMPONENT_OF",
+    "ANCESTOR_OF",
+    "GENERATES",
+    "CONTAINS",
+    "OPTIONAL_DEPENDENCY_OF",
+    "FILE_ADDED",
+    "REQUIREMENT_DESCRIPTION_FOR",
+    "DEV_DEPENDENCY_OF",
+    "DEPENDENCY_OF",
+    "BUILD_DEPENDENCY_OF",
+    "DESCRIBES",
+    "PREREQUISITE_FOR",
+    "HAS_PREREQUISITE",
+    "PROVIDED_DEPENDENCY_OF",
+    "DYNAMIC_LINK",
+    "DESCRIBED_BY",
+    "METAFILE_OF",
+    "DEPENDENCY_MANIFEST_OF",
+    "PATCH_APPLIED",
+    "RUNTIME_DEPENDENCY_OF",
+    "TEST_OF",
+    "TEST_TOOL_OF",
+    "DEPENDS_ON",
+    "SPECIFICATION_FOR",
+    "FILE_MODIFIED",
+    "DISTRIBUTION_ARTIFACT",
+    "AMENDS",
+    "DOCUMENTATION_OF",
+    "GENERATED_FROM",
+    "STATIC_LINK",
+    "OTHER",
+    "BUILD_TOOL_OF",
+    "TEST_CASE_OF",
+    "PACKAGE_OF",
+    "DESCENDANT_OF",
+    "FILE_DELETED",
+    "EXPANDED_FROM_ARCHIVE",
+    "DEV_TOOL_OF",
+    "EXAMPLE_OF",
+];
+
+const CYCLONEDX_COMPONENT_TYPES: &[&str] = &[
+    "application",
+    "framework",
+    "library",
+    "container",
+    "platform",
+    "operating-system",
+    "device",
+    "device-driver",
+    "firmware",
+    "file",
+    "machine-learning-model",
+    "data",
+    "cryptographic-asset",
+];
+
+const CYCLONEDX_HASH_ALGORITHMS: &[&str] = &[
+    "MD5",
+    "SHA-1",
+    "SHA-256",
+    "SHA-384",
+    "SHA-512",
+    "SHA3-256",
+    "SHA3-384",
+    "SHA3-512",
+    "BLAKE2b-256",
+    "BLAKE2b-384",
+    "BLAKE2b-512",
+    "BLAKE3",
+    "Streebog-256",
+    "Streebog-512",
+];
+
+static DATE_TIME: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(\.\d+)?(Z|[+-]\d{2}:\d{2})$").unwrap()
+});
+static SPDX_CREATOR: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(Person|Organization|Tool): .+").unwrap());
+static SPDX_ID: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^SPDXRef-[A-Za-z0-9.\-]+$").unwrap());
+static SPDX_CHECKSUM: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^[0-9a-f]+$").unwrap());
+static CYCLONEDX_HASH: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^([a-fA-F0-9]{32}|[a-fA-F0-9]{40}|[a-fA-F0-9]{64}|[a-fA-F0-9]{96}|[a-fA-F0-9]{128})$",
+    )
+    .unwrap()
+});
+static CYCLONEDX_SERIAL: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^urn:uuid:[0-9a-f]{8}-[0-9a-f]{4}-[1-5][0-9a-f]{3}-[89ab][0-9a-f]{3}-[0-9a-f]{12}$",
+    )
+    .unwrap()
+});
+
+/// Collects structural problems as `<json path>: <problem>` messages.
+struct Validator {
+    errors: Vec<String>,
+}
+
+impl Validator {
+    fn error(&mut self, path: &str, message: &str) {
+        self.errors.push(format!("{path}: {message}"));
+    }
+
+    fn string<'a>(&mut self, value: &'a Value, key: &str, path: &str) -> Option<&'a str> {
+        match value.get(key) {
+            None => {
+                self.error(path, &format!("missing required property '{key}'"));
+                None
+            }
+            Some(Value::String(s)) => Some(s),
+            Some(_) => {
+                self.error(&format!("{path}.{key}"), "must be a string");
+                None
+            }
+        }
+    }
+
+    fn optional_string<'a>(&mut self, value: &'a Value, key: &str, path: &str) -> Option<&'a str> {
+        value.get(key)?;
+        self.string(value, key, path)
+    }
+
+    fn array<'a>(&mut self, value: &'a Value, key: &str, path: &str) -> &'a [Value] {
+        match value.get(key) {
+            None => &[],
+            Some(Value::Array(items)) => items,
+            Some(_) => {
+                self.error(&format!("{path}.{key}"), "must be an array");
+                &[]
+            }
+        }
+    }
+
+    fn one_of(&mut self, value: Option<&str>, allowed: &[&str], path: &str) {
+        if let Some(value) = value {
+            if !allowed.contains(&value) {
+                self.error(path, &format!("'{value}' is not an allowed value"));
+            }
+        }
+    }
+
+    fn matches(&mut self, value: Option<&str>, pattern: &Regex, path: &str) {
+        if let Some(value) = value {
+            if !pattern.is_match(value) {
+                self.error(
+                    path,
+                    &format!("'{value}' does not match {}", pattern.as_str()),
+                );
+            }
+        }
+    }
+}
+
+/// Sanity-checks the structure of an SPDX 2.3 JSON document: the required
+/// properties, types, enumerations and patterns that matter to consumers,
+/// plus the integrity of SPDX identifier references. This is a subset of the
+/// official JSON schema, not a full schema validation.
+pub fn validate_spdx(doc: &Value) -> Vec<String> {
+    let mut v = Validator { errors: Vec::new() };
+    if !doc.is_object() {
+        v.error("$", "document must be a JSON object");
+        return v.errors;
+    }
+
+    let version = v.string(doc, "spdxVersion", "$");
+    if version.is_some_and(|s| s != "SPDX-2.3") {
+        v.error("$.spdxVersion", "only SPDX-2.3 is supported");
+    }
+    if v.string(doc, "dataLicense", "$")
+        .is_some_and(|s| s != "CC0-1.0")
+    {
+        v.error("$.dataLicense", "must be CC0-1.0");
+    }
+    if v.string(doc, "SPDXID", "$")
+        .is_some_and(|s| s != "SPDXRef-DOCUMENT")
+    {
+        v.error("$.SPDXID", "must be SPDXRef-DOCUMENT");
+    }
+    v.string(doc, "name", "$");
+    let namespace = v.string(doc, "documentNamespace", "$");
+    if namespace.is_some_and(|ns| ns.contains('#') || !ns.contains("://")) {
+        v.error(
+            "$.documentNamespace",
+            "must be an absolute URI without a fragment",
+        );
+    }
+
+    match doc.get("creationInfo") {
+        Some(info) if info.is_object() => {
+            let created = v.string(info, "created", "$.creationInfo");
+            v.matches(created, &DATE_TIME, "$.creationInfo.created");
+            match info.get("creators") {
+                Some(Value::Array(creators)) if !creators.is_empty() => {
+                    for (i, creator) in creators.iter().enumerate() {
+                        v.matches(
+                            Some(creator.as_str().unwrap_or_default()),
+                            &SPDX_CREATOR,
+                            &format!("$.creationInfo.creators[{i}]"),
+                        );
+                    }
+                }
+                _ => v.error("$.creationInfo", "creators must be a non-empty array"),
+            }
+        }
+        _ => v.error("$", "missing required property 'creationInfo'"),
+    }
+
+    let mut ids = BTreeSet::new();
+    for (i, package) in v.array(doc, "packages", "$").iter().enumerate() {
+        let path = format!("$.packages[{i}]");
+        let id = v.string(package, "SPDXID", &path);
+        v.matches(id, &SPDX_ID, &format!("{path}.SPDXID"));
+        if let Some(id) = id {
+            if !ids.insert(id.to_string()) {
+                v.error(&format!("{path}.SPDXID"), &format!("duplicate SPDXID {id}"));
+            }
+        }
+        v.string(package, "name", &path);
+        v.string(package, "downloadLocation", &path);
+        if package
+            .get("filesAnalyzed")
+            .is_some_and(|f| !f.is_boolean())
+        {
+            v.error(&format!("{path}.filesAnalyzed"), "must be a boolean");
+        }
+        let purpose = v.optional_string(package, "primaryPackagePurpose", &path);
+        v.one_of(
+            purpose,
+            SPDX_PURPOSES,
+            &format!("{path}.primaryPackagePurpose"),
+        );
+        for (j, checksum) in v.array(package, "checksums", &path).iter().enumerate() {
+            let cpath = format!("{path}.checksums[{j}]");
+            let algorithm = v.string(checksum, "algorithm", &cpath);
+            v.one_of(
+                algorithm,
+                SPDX_CHECKSUM_ALGORITHMS,
+                &format!("{cpath}.algorithm"),
+            );
+            let value = v.string(checksum, "checksumValue", &cpath);
+            v.matches(value, &SPDX_CHECKSUM, &format!("{cpath}.checksumValue"));
+        }
+        for (j, reference) in v.array(package, "externalRefs", &path).iter().enumerate() {
+            let rpath = format!("{path}.externalRefs[{j}]");
+            let category = v.string(reference, "referenceCategory", &rpath);
+            v.one_of(
+                category,
+                SPDX_REFERENCE_CATEGORIES,
+                &format!("{rpath}.referenceCategory"),
+            );
+            v.string(reference, "referenceType", &rpath);
+            v.string(reference, "referenceLocator", &rpath);
+        }
+    }
+
+    let known = |id: &str| {
+        id == "SPDXRef-DOCUMENT"
+            || id == "NONE"
+            || id == "NOASSERTION"
+            || id.starts_with("DocumentRef-")
+            || ids.contains(id)
+    };
+    let relationships = v.array(doc, "relationships", "$");
+    for (i, relationship) in relationships.iter().enumerate() {
+        let path = format!("$.relationships[{i}]");
+        let kind = v.string(relationship, "relationshipType", &path);
+        v.one_of(
+            kind,
+            SPDX_RELATIONSHIP_TYPES,
+            &format!("{path}.relationshipType"),
+        );
+        for key in ["spdxElementId", "relatedSpdxElement"] {
+            if let Some(id) = v.string(relationship, key, &path) {
+                if !known(id) {
+                    v.error(
+                        &format!("{path}.{key}"),
+                        &format!("unknown SPDX element {id}"),
+                    );
+                }
+            }
+        }
+    }
+    v.errors
+}
+
+fn validate_cyclonedx_component(
+    v: &mut Validator,
+    component: &Value,
+    path: &str,
+    refs: &mut BTreeSet<String>,
+) {
+    if !component.is_object() {
+        v.error(path, "must be an object");
+        return;
+    }
+    let kind = v.string(component, "type", path);
+    v.one_of(kind, CYCLONEDX_COMPONENT_TYPES, &format!("{path}.type"));
+    v.string(component, "name", path);
+    v.optional_string(component, "version", path);
+    if let Some(bom_ref) = v.optional_string(component, "bom-ref", path) {
+        if !refs.insert(bom_ref.to_string()) {
+            v.error(
+                &format!("{path}.bom-ref"),
+                &format!("duplicate bom-ref {bom_ref}"),
+            );
+        }
+    }
+    for (i, hash) in v.array(component, "hashes", path).iter().enumerate() {
+        let hpath = format!("{path}.hashes[{i}]");
+        let alg = v.string(hash, "alg", &hpath);
+        v.one_of(alg, CYCLONEDX_HASH_ALGORITHMS, &format!("{hpath}.alg"));
+        let content = v.string(hash, "content", &hpath);
+        v.matches(content, &CYCLONEDX_HASH, &format!("{hpath}.content"));
+    }
+    for (i, license) in v.array(component, "licenses", path).iter().enumerate() {
+        let lpath = format!("{path}.licenses[{i}]");
+        match (license.get("license"), license.get("expression")) {
+            (Some(inner), None) => {
+                if inner.get("id").is_none() && inner.get("name").is_none() {
+                    v.error(&format!("{lpath}.license"), "requires 'id' or 'name'");
+                }
+            }
+            (None, Some(Value::String(_))) => {}
+            (None, Some(_)) => v.error(&format!("{lpath}.expression"), "must be a string"),
+            _ => v.error(&lpath, "requires exactly one of 'license' or 'expression'"),
+        }
+    }
+    for (i, property) in v.array(component, "properties", path).iter().enumerate() {
+        v.string(property, "name", &format!("{path}.properties[{i}]"));
+    }
+    for (i, child) in v.array(component, "components", path).iter().enumerate() {
+        validate_cyclonedx_component(v, child, &format!("{path}.components[{i}]"), refs);
+    }
+}
+
+/// Sanity-checks the structure of a CycloneDX 1.5 or 1.6 JSON document: the
+/// required properties, types, enumerations and patterns that matter to
+/// consumers, plus the integrity of `bom-ref` references in the dependency
+/// graph. This is a subset of the official JSON schema, not a full schema
+/// validation.
+pub fn validate_cyclonedx(doc: &Value) -> Vec<String> {
+    let mut v = Validator { errors: Vec::new() };
+    if !doc.is_object() {
+        v.error("$", "document must be a JSON object");
+        return v.errors;
+    }
+
+    if v.string(doc, "bomFormat", "$")
+        .is_some_and(|s| s != "CycloneDX")
+    {
+        v.error("$.bomFormat", "must be CycloneDX");
+    }
+    let spec = v.string(doc, "specVersion", "$");
+    v.one_of(spec, &["1.5", "1.6"], "$.specVersion");
+    let serial = v.optional_string(doc, "serialNumber", "$");
+    v.matches(serial, &CYCLONEDX_SERIAL, "$.serialNumber");
+    if let Some(version) = doc.get("version") {
+        if version.as_u64().is_none_or(|n| n < 1) {
+            v.error("$.version", "must be an integer >= 1");
+        }
+    }
+
+    let mut refs = BTreeSet::new();
+    if let Some(metadata) = doc.get("metadata") {
+        let timestamp = v.optional_string(metadata, "timestamp", "$.metadata");
+        v.matches(timestamp, &DATE_TIME, "$.metadata.timestamp");
+        if let Some(component) = metadata.get("component") {
+            validate_cyclonedx_component(&mut v, component, "$.metadata.component", &mut refs);
+        }
+    }
+    for (i, component) in v.array(doc, "components", "$").iter().enumerate() {
+        validate_cyclonedx_component(&mut v, component, &format!("$.components[{i}]"), &mut refs);
+    }
+
+    for (i, dependency) in v.array(doc, "dependencies", "$").iter().enumerate() {
+        let path = format!("$.dependencies[{i}]");
+        if let Some(r) = v.string(dependency, "ref", &path) {
+            if !refs.contains(r) {
+                v.error(&format!("{path}.ref"), &format!("unknown bom-ref {r}"));
+            }
+        }
+        for (j, target) in v.array(dependency, "dependsOn", &path).iter().enumerate() {
+            match target.as_str() {
+                Some(r) if refs.contains(r) => {}
+                Some(r) => v.error(
+                    &format!("{path}.dependsOn[{j}]"),
+                    &format!("unknown bom-ref {r}"),
+                ),
+                None => v.error(&format!("{path}.dependsOn[{j}]"), "must be a string"),
+            }
+        }
+    }
+    v.errors
+}
+
+/// Runs the structural checks for the format of `doc`.
+pub fn validate_sbom(doc: &Value) -> Result<(SbomKind, Vec<String>)> {
+    let kind = SbomKind::detect(doc).ok_or_else(|| {
+        Error::User("not an SBOM: neither spdxVersion nor bomFormat is set".into())
+    })?;
+    let errors = match kind {
+        SbomKind::Spdx => validate_spdx(doc),
+        SbomKind::CycloneDx => validate_cyclonedx(doc),
+    };
+    Ok((kind, errors))
+}
+
+/// A parsed SPDX license expression.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LicenseExpr {
+    /// A license identifier, with an optional `WITH` exception.
+    License {
+        id: String,
+        exception: Option<String>,
+    },
+    And(Box<LicenseExpr>, Box<LicenseExpr>),
+    Or(Box<LicenseExpr>, Box<LicenseExpr>),
+}
+
+struct ExprParser {
+    tokens: Vec<String>,
+    pos: usize,
+}
+
+impl ExprParser {
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        self.tokens
+            .get(self.pos)
+            .is_some_and(|t| t.eq_ignore_ascii_case(keyword))
+    }
+
+    fn next(&mut self) -> Option<String> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn or(&mut self) -> Result<LicenseExpr> {
+        let mut expr = self.and()?;
+        while self.peek_keyword("OR") {
+            self.pos += 1;
+            expr = LicenseExpr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<LicenseExpr> {
+        let mut expr = self.with()?;
+        while self.peek_keyword("AND") {
+            self.pos += 1;
+            expr = LicenseExpr::And(Box::new(expr), Box::new(self.with()?));
+        }
+        Ok(expr)
+    }
+
+    fn with(&mut self) -> Result<LicenseExpr> {
+        let expr = self.atom()?;
+        if self.peek_keyword("WITH") {
+            self.pos += 1;
+            let LicenseExpr::License { id, .. } = expr else {
+                return Err(Error::User("WITH must follow a license identifier".into()));
+            };
+            let exception = self
+                .next()
+                .filter(|t| t != "(" && t != ")")
+                .ok_or_else(|| Error::User("WITH must be followed by an exception".into()))?;
+            return Ok(LicenseExpr::License {
+                id,
+                exception: Some(exception),
+            });
+        }
+        Ok(expr)
+    }
+
+    fn atom(&mut self) -> Result<LicenseExpr> {
+        match self.next() {
+            Some(t) if t == "(" => {
+                let expr = self.or()?;
+                if self.next().as_deref() != Some(")") {
+                    return Err(Error::User("unbalanced parentheses".into()));
+                }
+                Ok(expr)
+            }
+            Some(t)
+                if t != ")"
+                    && !["AND", "OR", "WITH"]
+                        .iter()
+                        .any(|k| t.eq_ignore_ascii_case(k)) =>
+            {
+                Ok(LicenseExpr::License {
+                    id: t,
+                    exception: None,
+                })
+            }
+            Some(t) => Err(Error::User(format!("unexpected '{t}'"))),
+            None => Err(Error::User("unexpected end of expression".into())),
+        }
+    }
+}
+
+/// Parses an SPDX license expression. Cargo's legacy `MIT/Apache-2.0` syntax
+/// is read as `MIT OR Apache-2.0`.
+pub fn parse_license_expression(expression: &str) -> Result<LicenseExpr> {
+    let normalized = expression
+        .replace('/', " OR ")
+        .replace('(', " ( ")
+        .replace(')', " ) ");
+    let mut parser = ExprParser {
+        tokens: normalized.split_whitespace().map(String::from).collect(),
+        pos: 0,
+    };
+    let expr = parser
+        .or()
+        .map_err(|e| Error::User(format!("invalid license expression '{expression}': {e}")))?;
+    if parser.pos < parser.tokens.len() {
+        return Err(Error::User(format!(
+            "invalid license expression '{expression}': unexpected '{}'",
+            parser.tokens[parser.pos]
+        )));
+    }
+    Ok(expr)
+}
+
+/// Lowercases an SPDX license identifier and strips its `+`, `-only` or
+/// `-or-later` suffix.
+fn license_base(id: &str) -> String {
+    let id = id.trim().to_ascii_lowercase();
+    let base = id.trim_end_matches('+');
+    base.strip_suffix("-or-later")
+        .or_else(|| base.strip_suffix("-only"))
+        .unwrap_or(base)
+        .to_string()
+}
+
+/// License identifiers that dependencies may (allow) or must not (deny) use.
+#[derive(Debug, Clone, Default)]
+pub struct LicensePolicy {
+    pub allow: Vec<String>,
+    pub deny: Vec<String>,
+}
+
+impl LicensePolicy {
+    /// Reads SBOM_LICENSE_ALLOW and SBOM_LICENSE_DENY.
+    pub fn from_env(config: &ReleaseConfig) -> Self {
+        LicensePolicy {
+            allow: parse_comma_list(&config.get("SBOM_LICENSE_ALLOW", "")),
+            deny: parse_comma_list(&config.get("SBOM_LICENSE_DENY", "")),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.allow.is_empty() && self.deny.is_empty()
+    }
+
+    /// Matches identifiers case-insensitively and without the `+`, `-only` and
+    /// `-or-later` suffixes, so `GPL-3.0` matches `GPL-3.0-only`, `GPL-3.0+`
+    /// and `GPL-3.0-or-later`. A `WITH` exception matches either
+    /// `<id> WITH <exception>` or `<id>`.
+    fn listed(list: &[String], id: &str, exception: Option<&str>) -> bool {
+        let id = license_base(id);
+        list.iter()
+            .any(|entry| match parse_license_expression(entry) {
+                Ok(LicenseExpr::License {
+                    id: listed,
+                    exception: listed_exception,
+                }) => {
+                    license_base(&listed) == id
+                        && listed_exception.is_none_or(|e| {
+                            exception.is_some_and(|exception| e.eq_ignore_ascii_case(exception))
+                        })
+                }
+                _ => false,
+            })
+    }
+
+    fn permits_license(&self, id: &str, exception: Option<&str>) -> bool {
+        if Self::listed(&self.deny, id, exception) {
+            return false;
+        }
+        self.allow.is_empty() || Self::listed(&self.allow, id, exception)
+    }
+
+    /// Whether the expression can be satisfied: for `OR` either side may be
+    /// chosen, for `AND` both sides must be acceptable.
+    pub fn permits(&self, expr: &LicenseExpr) -> bool {
+        match expr {
+            LicenseExpr::License { id, exception } => {
+                self.permits_license(id, exception.as_deref())
+            }
+            LicenseExpr::And(a, b) => self.permits(a) && self.permits(b),
+            LicenseExpr::Or(a, b) => self.permits(a) || self.permits(b),
+        }
+    }
+
+    /// Returns why a package with `license` violates the policy, if it does.
+    pub fn check(&self, license: Option<&str>) -> Option<String> {
+        if self.is_empty() {
+            return None;
+        }
+        let license = license
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && *l != "NOASSERTION" && *l != "NONE");
+        let Some(license) = license else {
+            return (!self.allow.is_empty()).then(|| "no license declared".to_string());
+        };
+        match parse_license_expression(license) {
+            Ok(expr) if self.permits(&expr) => None,
+            Ok(_) => Some("license not permitted by policy".to_string()),
+            Err(e) => Some(e.to_string()),
+        }
+    }
+}
+
+/// A package listed in an SBOM and its declared license.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SbomComponent {
+    pub name: String,
+    pub version: String,
+    pub license: Option<String>,
+}
+
+fn cyclonedx_license(component: &Value) -> Option<String> {
+    let licenses = component.get("licenses")?.as_array()?;
+    let parts: Vec<String> = licenses
+        .iter()
+        .filter_map(|l| {
+            l.get("expression")
+                .and_then(Value::as_str)
+                .or_else(|| l.pointer("/license/id").and_then(Value::as_str))
+                .or_else(|| l.pointer("/license/name").and_then(Value::as_str))
+                .map(|s| {
+                    if licenses.len() > 1 {
+                        format!("({s})")
+                    } else {
+                        s.to_string()
+                    }
+                })
+        })
+        .collect();
+    (!parts.is_empty()).then(|| parts.join(" AND "))
+}
+
+fn collect_cyclonedx_components(
+    component: &Value,
+    roots: &BTreeSet<&str>,
+    out: &mut Vec<SbomComponent>,
+) {
+    let str_of = |key: &str| {
+        component
+            .get(key)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string()
+    };
+    let bom_ref = component.get("bom-ref").and_then(Value::as_str);
+    // Release files described by per-artifact SBOMs are not packages
+    if component.get("type").and_then(Value::as_str) != Some("file")
+        && !bom_ref.is_some_and(|r| roots.contains(r))
+    {
+        out.push(SbomComponent {
+            name: str_of("name"),
+            version: str_of("version"),
+            license: cyclonedx_license(component),
+        });
+    }
+    for child in component
+        .get("components")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+    {
+        collect_cyclonedx_components(child, roots, out);
+    }
+}
+
+/// The SPDX IDs of the packages a document describes, and of the packages
+/// contained in a described release file.
+fn spdx_roots(doc: &Value) -> BTreeSet<&str> {
+    let relationships: Vec<(&str, &str, &str)> = doc
+        .get("relationships")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|r| {
+            Some((
+                r.get("spdxElementId")?.as_str()?,
+                r.get("relationshipType")?.as_str()?,
+                r.get("relatedSpdxElement")?.as_str()?,
+            ))
+        })
+        .collect();
+    let mut roots: BTreeSet<&str> = doc
+        .get("documentDescribes")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect();
+    roots.extend(
+        relationships
+            .iter()
+            .filter(|(from, kind, _)| *from == "SPDXRef-DOCUMENT" && *kind == "DESCRIBES")
+            .map(|(_, _, to)| *to),
+    );
+    let described = roots.clone();
+    roots.extend(
+        relationships
+            .iter()
+            .filter(|(from, kind, _)| described.contains(from) && *kind == "CONTAINS")
+            .map(|(_, _, to)| *to),
+    );
+    roots
+}
+
+/// The `bom-ref`s of the component a CycloneDX document describes and, when
+/// that is a release file, of the components it depends on.
+fn cyclonedx_roots(doc: &Value) -> BTreeSet<&str> {
+    let mut roots = BTreeSet::new();
+    let Some(subject) = doc
+        .pointer("/metadata/component/bom-ref")
+        .and_then(Value::as_str)
+    else {
+        return roots;
+    };
+    roots.insert(subject);
+    if doc
+        .pointer("/metadata/component/type")
+        .and_then(Value::as_str)
+        != Some("file")
+    {
+        return roots;
+    }
+    for dependency in doc
+        .get("dependencies")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter(|d| d.get("ref").and_then(Value::as_str) == Some(subject))
+    {
+        roots.extend(
+            dependency
+                .get("dependsOn")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str),
+        );
+    }
+    roots
+}
+
+/// Lists the dependency packages an SBOM declares, with their licenses. The
+/// package the SBOM describes and release files described by per-artifact
+/// SBOMs are skipped.
+pub fn sbom_components(doc: &Value, kind: SbomKind) -> Vec<SbomComponent> {
+    let mut components = Vec::new();
+    match kind {
+        SbomKind::Spdx => {
+            let roots = spdx_roots(doc);
+            for package in doc
+                .get("packages")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                let id = package.get("SPDXID").and_then(Value::as_str);
+                if package.get("packageFileName").is_some()
+                    || id.is_some_and(|id| roots.contains(id))
+                {
+                    continue;
+                }
+                let str_of = |key: &str| package.get(key).and_then(Value::as_str);
+                let license = [str_of("licenseDeclared"), str_of("licenseConcluded")]
+                    .into_iter()
+                    .flatten()
+                    .find(|l| *l != "NOASSERTION" && *l != "NONE");
+                components.push(SbomComponent {
+                    name: str_of("name").unwrap_or_default().to_string(),
+                    version: str_of("versionInfo").unwrap_or_default().to_string(),
+                    license: license.map(String::from),
+                });
+            }
+        }
+        SbomKind::CycloneDx => {
+            let roots = cyclonedx_roots(doc);
+            if let Some(component) = doc.pointer("/metadata/component") {
+                collect_cyclonedx_components(component, &roots, &mut components);
+            }
+            for component in doc
+                .get("components")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+            {
+                collect_cyclonedx_components(component, &roots, &mut components);
+            }
+        }
+    }
+    components
+}
+
+/// A structural problem or license policy violation found in an SBOM.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SbomFinding {
+    pub file: String,
+    /// `name@version` for license violations, empty for structural problems.
+    pub package: String,
+    pub license: String,
+    pub problem: String,
+}
+
+/// Checks the structure of one SBOM file and its dependencies' licenses
+/// against `policy`. Files that are not JSON or not an SBOM are reported as
+/// a structural finding.
+pub fn check_sbom_file(path: &Path, policy: &LicensePolicy) -> Result<Vec<SbomFinding>> {
+    let file = path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    let structural = |problem: String| SbomFinding {
+        file: file.clone(),
+        package: String::new(),
+        license: String::new(),
+        problem,
+    };
+    let doc: Value = match serde_json::from_str(&fs::read_to_string(path)?) {
+        Ok(doc) => doc,
+        Err(e) => return Ok(vec![structural(format!("invalid JSON: {e}"))]),
+    };
+    let (kind, errors) = match validate_sbom(&doc) {
+        Ok(validated) => validated,
+        Err(e) => return Ok(vec![structural(e.to_string())]),
+    };
+
+    let mut findings: Vec<SbomFinding> = errors
+        .into_iter()
+        .map(|problem| structural(format!("{} structural check: {problem}", kind.name())))
+        .collect();
+    for component in sbom_components(&doc, kind) {
+        if let Some(problem) = policy.check(component.license.as_deref()) {
+            findings.push(SbomFinding {
+                file: file.clone(),
+                package: format!("{}@{}", component.name, component.version),
+                license: component.license.unwrap_or_default(),
+                problem,
+            });
+        }
+    }
+    Ok(findings)
+}
+
+/// Lists the `*.spdx.json` and `*.cdx.json` files in `dir`, sorted.
+pub fn find_sbom_files(dir: &Path) -> Vec<PathBuf> {
+    let mut files: Vec<PathBuf> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| e.path())
+        .filter(|p| {
+            p.is_file()
+                && p.file_name()
+                    .map(|n| n.to_string_lossy())
+                    .is_some_and(|n| n.ends_with(".spdx.json") || n.ends_with(".cdx.json"))
+        })
+        .collect();
+    files.sort();
+    files
+}
+
+fn escape_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace('\n', " ")
+}
+
+/// Renders findings as a Markdown table for the GitHub step summary.
+pub fn findings_summary(checked: usize, findings: &[SbomFinding]) -> String {
+    let mut summary = String::from("### SBOM check\n\n");
+    if findings.is_empty() {
+        summary.push_str(&format!(
+            "{checked} SBOM file(s) passed the structural checks and the license policy.\n"
+        ));
+        return summary;
+    }
+    summary.push_str(&format!(
+        "{} finding(s) in {checked} SBOM file(s):\n\n",
+        findings.len()
+    ));
+    summary.push_str("| SBOM | Crate | License | Problem |\n");
+    summary.push_str("|------|-------|---------|---------|\n");
+    for f in findings {
+        summary.push_str(&format!(
+            "| {} | {} | {} | {} |\n",
+            escape_cell(&f.file),
+            escape_cell(&f.package),
+            escape_cell(&f.license),
+            escape_cell(&f.problem)
+        ));
+    }
+    summary
+}
+
+pub fn run_check_sbom(config: &ReleaseConfig) -> Result<()> {
+    let configured = parse_comma_list(&config.get("SBOM_FILES", ""));
+    let sbom_dir = config.get("SBOM_OUTPUT_DIR", "target/sbom");
+    let files: Vec<PathBuf> = if configured.is_empty() {
+        let dir = Path::new(&sbom_dir);
+        if !dir.is_dir() {
+            return Err(Error::User(format!("SBOM directory not found: {sbom_dir}")));
+        }
+        find_sbom_files(dir)
+    } else {
+        configured.iter().map(PathBuf::from).collect()
+    };
+    if files.is_empty() {
+        return Err(Error::User(format!("no SBOM files found in {sbom_dir}")));
+    }
+
+    let policy = LicensePolicy::from_env(config);
+    for list in [&policy.allow, &policy.deny] {
+        for id in list {
+            parse_license_expression(id)?;
+        }
+    }
+
+    println!("\x1b[32mChecking SBOMs:\x1b[0m");
+    print_hr();
+    let mut findings = Vec::new();
+    for file in &files {
+        let file_findings = check_sbom_file(file, &policy)?;
+        if file_findings.is_empty() {
+            println!("  {}: OK", file.display());
+        } else {
+            println!(
+                "  \x1b[31m{}: {} finding(s)\x1b[0m",
+                file.display(),
+                file_findings.len()
+            );
+        }
+        for f in &file_findings {
+            if f.package.is_empty() {
+                println!("    {}", f.problem);
+            } else {
+                println!("    {} ({}): {}", f.package, f.license, f.problem);
+            }
+        }
+        findings.extend(file_findings);
+    }
+
+    step_summary(&findings_summary(files.len(), &findings));
+
+    let offending: BTreeSet<&str> = findings
+        .iter()
+        .filter(|f| !f.package.is_empty())
+        .map(|f| f.package.as_str())
+        .collect();
+    let structure_errors = findings.iter().filter(|f| f.package.is_empty()).count();
+    output("checked_count", &files.len().to_string());
+    output("structure_error_count", &structure_errors.to_string());
+    output(
+        "offending_crates",
+        &offending.into_iter().collect::<Vec<_>>().join(","),
+    );
+
+    if !findings.is_empty() {
+        return Err(Error::User(format!(
+            "SBOM check failed: {structure_errors} structural error(s), {} license violation(s)",
+            findings.len() - structure_errors
+        )));
+    }
+    println!("\x1b[32mChecked:\x1b[0m {} SBOM files", files.len());
+    Ok(())
+}
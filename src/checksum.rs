@@ -1,41 +1,256 @@
+use crate::config::ReleaseConfig;
 use crate::error::{Error, Result};
 use blake2::{Blake2b512, Digest};
+use regex::Regex;
+use serde::Serialize;
 use sha2::{Sha256, Sha512};
-use std::env;
+use sha3::Sha3_256;
+use std::fmt;
 use std::fs;
-use std::io::Write;
+use std::io::{Read, Write};
 use std::path::Path;
+use std::str::FromStr;
 
-/// Checksums produced for a file.
-#[derive(Debug, Clone, Default)]
+const READ_BUFFER_SIZE: usize = 64 * 1024;
+
+/// Checksum algorithms that can be selected via the `checksum` input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChecksumAlgorithm {
+    Sha256,
+    Sha512,
+    B2,
+    Sha3_256,
+    B3,
+}
+
+impl ChecksumAlgorithm {
+    pub const ALL: [ChecksumAlgorithm; 5] = [
+        ChecksumAlgorithm::Sha256,
+        ChecksumAlgorithm::Sha512,
+        ChecksumAlgorithm::B2,
+        ChecksumAlgorithm::Sha3_256,
+        ChecksumAlgorithm::B3,
+    ];
+
+    /// Name used in inputs, outputs and as the sidecar file extension.
+    pub fn name(self) -> &'static str {
+        match self {
+            ChecksumAlgorithm::Sha256 => "sha256",
+            ChecksumAlgorithm::Sha512 => "sha512",
+            ChecksumAlgorithm::B2 => "b2",
+            ChecksumAlgorithm::Sha3_256 => "sha3-256",
+            ChecksumAlgorithm::B3 => "b3",
+        }
+    }
+
+    /// Key used for action outputs and JSON fields.
+    pub fn output_key(self) -> &'static str {
+        match self {
+            ChecksumAlgorithm::Sha3_256 => "sha3_256",
+            other => other.name(),
+        }
+    }
+
+    /// Name of the consolidated manifest, e.g. `SHA256SUMS`.
+    pub fn manifest_name(self) -> String {
+        format!("{}SUMS", self.name().to_ascii_uppercase())
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ChecksumAlgorithm::Sha256 => "SHA256",
+            ChecksumAlgorithm::Sha512 => "SHA512",
+            ChecksumAlgorithm::B2 => "BLAKE2",
+            ChecksumAlgorithm::Sha3_256 => "SHA3-256",
+            ChecksumAlgorithm::B3 => "BLAKE3",
+        }
+    }
+
+    /// Detects the algorithm from a sidecar file name such as `app.tar.gz.sha512`.
+    pub fn from_extension(path: &str) -> Option<Self> {
+        let (_, ext) = path.rsplit_once('.')?;
+        Self::ALL.into_iter().find(|a| a.name() == ext)
+    }
+}
+
+impl fmt::Display for ChecksumAlgorithm {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for ChecksumAlgorithm {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "sha256" => Ok(ChecksumAlgorithm::Sha256),
+            "sha512" => Ok(ChecksumAlgorithm::Sha512),
+            "b2" | "blake2" | "blake2b" => Ok(ChecksumAlgorithm::B2),
+            "sha3-256" | "sha3_256" | "sha3" => Ok(ChecksumAlgorithm::Sha3_256),
+            "b3" | "blake3" => Ok(ChecksumAlgorithm::B3),
+            other => Err(Error::User(format!(
+                "unsupported checksum algorithm: {other} (expected one of: sha256, sha512, b2, sha3-256, b3)"
+            ))),
+        }
+    }
+}
+
+/// Parses a comma-separated algorithm list, defaulting to SHA-256 when empty.
+pub fn parse_checksum_algorithms(raw: &str) -> Result<Vec<ChecksumAlgorithm>> {
+    let mut algorithms = Vec::new();
+    for name in raw.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+        let algorithm: ChecksumAlgorithm = name.parse()?;
+        if !algorithms.contains(&algorithm) {
+            algorithms.push(algorithm);
+        }
+    }
+    if algorithms.is_empty() {
+        algorithms.push(ChecksumAlgorithm::Sha256);
+    }
+    Ok(algorithms)
+}
+
+enum Hasher {
+    Sha256(Sha256),
+    Sha512(Sha512),
+    B2(Blake2b512),
+    Sha3_256(Sha3_256),
+    B3(Box<blake3::Hasher>),
+}
+
+impl Hasher {
+    fn new(algorithm: ChecksumAlgorithm) -> Self {
+        match algorithm {
+            ChecksumAlgorithm::Sha256 => Hasher::Sha256(Sha256::new()),
+            ChecksumAlgorithm::Sha512 => Hasher::Sha512(Sha512::new()),
+            ChecksumAlgorithm::B2 => Hasher::B2(Blake2b512::new()),
+            ChecksumAlgorithm::Sha3_256 => Hasher::Sha3_256(Sha3_256::new()),
+            ChecksumAlgorithm::B3 => Hasher::B3(Box::new(blake3::Hasher::new())),
+        }
+    }
+
+    fn update(&mut self, data: &[u8]) {
+        match self {
+            Hasher::Sha256(h) => h.update(data),
+            Hasher::Sha512(h) => h.update(data),
+            Hasher::B2(h) => h.update(data),
+            Hasher::Sha3_256(h) => h.update(data),
+            Hasher::B3(h) => {
+                h.update(data);
+            }
+        }
+    }
+
+    fn finalize(self) -> String {
+        match self {
+            Hasher::Sha256(h) => format!("{:x}", h.finalize()),
+            Hasher::Sha512(h) => format!("{:x}", h.finalize()),
+            Hasher::B2(h) => format!("{:x}", h.finalize()),
+            Hasher::Sha3_256(h) => format!("{:x}", h.finalize()),
+            Hasher::B3(h) => h.finalize().to_hex().to_string(),
+        }
+    }
+}
+
+/// Hashes everything `reader` yields with all `algorithms` in a single pass.
+pub fn hash_reader<R: Read>(
+    mut reader: R,
+    algorithms: &[ChecksumAlgorithm],
+) -> Result<Vec<(ChecksumAlgorithm, String)>> {
+    let mut hashers: Vec<(ChecksumAlgorithm, Hasher)> =
+        algorithms.iter().map(|a| (*a, Hasher::new(*a))).collect();
+    let mut buf = vec![0u8; READ_BUFFER_SIZE];
+    loop {
+        let n = match reader.read(&mut buf) {
+            Ok(0) => break,
+            Ok(n) => n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e.into()),
+        };
+        for (_, hasher) in &mut hashers {
+            hasher.update(&buf[..n]);
+        }
+    }
+    Ok(hashers
+        .into_iter()
+        .map(|(algorithm, hasher)| (algorithm, hasher.finalize()))
+        .collect())
+}
+
+/// Streams a file once, computing all `algorithms`.
+pub fn hash_file(
+    path: &Path,
+    algorithms: &[ChecksumAlgorithm],
+) -> Result<Vec<(ChecksumAlgorithm, String)>> {
+    hash_reader(fs::File::open(path)?, algorithms)
+}
+
+fn hash_file_single(path: &Path, algorithm: ChecksumAlgorithm) -> Result<String> {
+    let mut hashes = hash_file(path, &[algorithm])?;
+    Ok(hashes.pop().map(|(_, hash)| hash).unwrap_or_default())
+}
+
+/// Checksums produced for a file. Serialized without the digests that were
+/// not computed, except `sha256`.
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct Checksums {
     pub sha256: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
     pub sha512: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
     pub b2: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub sha3_256: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub b3: String,
+}
+
+impl Checksums {
+    pub fn get(&self, algorithm: ChecksumAlgorithm) -> &str {
+        match algorithm {
+            ChecksumAlgorithm::Sha256 => &self.sha256,
+            ChecksumAlgorithm::Sha512 => &self.sha512,
+            ChecksumAlgorithm::B2 => &self.b2,
+            ChecksumAlgorithm::Sha3_256 => &self.sha3_256,
+            ChecksumAlgorithm::B3 => &self.b3,
+        }
+    }
+
+    pub fn set(&mut self, algorithm: ChecksumAlgorithm, hash: String) {
+        match algorithm {
+            ChecksumAlgorithm::Sha256 => self.sha256 = hash,
+            ChecksumAlgorithm::Sha512 => self.sha512 = hash,
+            ChecksumAlgorithm::B2 => self.b2 = hash,
+            ChecksumAlgorithm::Sha3_256 => self.sha3_256 = hash,
+            ChecksumAlgorithm::B3 => self.b3 = hash,
+        }
+    }
 }
 
 /// Compute SHA-256 of a file.
 pub fn sha256_file(path: &Path) -> Result<String> {
-    let data = fs::read(path)?;
-    let mut hasher = Sha256::new();
-    hasher.update(&data);
-    Ok(format!("{:x}", hasher.finalize()))
+    hash_file_single(path, ChecksumAlgorithm::Sha256)
 }
 
 /// Compute SHA-512 of a file.
 pub fn sha512_file(path: &Path) -> Result<String> {
-    let data = fs::read(path)?;
-    let mut hasher = Sha512::new();
-    hasher.update(&data);
-    Ok(format!("{:x}", hasher.finalize()))
+    hash_file_single(path, ChecksumAlgorithm::Sha512)
 }
 
 /// Compute BLAKE2b-512 of a file.
 pub fn blake2_file(path: &Path) -> Result<String> {
-    let data = fs::read(path)?;
-    let mut hasher = Blake2b512::new();
-    hasher.update(&data);
-    Ok(format!("{:x}", hasher.finalize()))
+    hash_file_single(path, ChecksumAlgorithm::B2)
+}
+
+/// Compute SHA3-256 of a file.
+pub fn sha3_256_file(path: &Path) -> Result<String> {
+    hash_file_single(path, ChecksumAlgorithm::Sha3_256)
+}
+
+/// Compute BLAKE3 of a file.
+pub fn blake3_file(path: &Path) -> Result<String> {
+    hash_file_single(path, ChecksumAlgorithm::B3)
 }
 
 /// Compute SHA-256 of a byte slice.
@@ -46,38 +261,19 @@ pub fn sha256_bytes(data: &[u8]) -> String {
 }
 
 /// Generate checksums for a file based on the CHECKSUM env var.
-/// Returns the computed checksums and writes .sha256/.sha512/.b2 sidecar files.
-pub fn generate_checksums(file_path: &Path) -> Result<Checksums> {
-    let checksum_types = env::var("CHECKSUM").unwrap_or_else(|_| "sha256".to_string());
+/// The file is read once for all requested algorithms; a `.<algorithm>`
+/// sidecar file is written for each.
+pub fn generate_checksums(config: &ReleaseConfig, file_path: &Path) -> Result<Checksums> {
+    let algorithms = parse_checksum_algorithms(&config.get("CHECKSUM", "sha256"))?;
     let basename = file_path.file_name().unwrap_or_default().to_string_lossy();
 
     let mut checksums = Checksums::default();
-
-    if checksum_types.contains("sha256") || checksum_types.is_empty() {
-        let hash = sha256_file(file_path)?;
-        let checksum_file = format!("{}.sha256", file_path.display());
-        let mut f = fs::File::create(&checksum_file)?;
-        writeln!(f, "{hash}  {basename}")?;
-        println!("\x1b[32mSHA256:\x1b[0m {hash}");
-        checksums.sha256 = hash;
-    }
-
-    if checksum_types.contains("sha512") {
-        let hash = sha512_file(file_path)?;
-        let checksum_file = format!("{}.sha512", file_path.display());
-        let mut f = fs::File::create(&checksum_file)?;
-        writeln!(f, "{hash}  {basename}")?;
-        println!("\x1b[32mSHA512:\x1b[0m {hash}");
-        checksums.sha512 = hash;
-    }
-
-    if checksum_types.contains("b2") {
-        let hash = blake2_file(file_path)?;
-        let checksum_file = format!("{}.b2", file_path.display());
+    for (algorithm, hash) in hash_file(file_path, &algorithms)? {
+        let checksum_file = format!("{}.{}", file_path.display(), algorithm.name());
         let mut f = fs::File::create(&checksum_file)?;
         writeln!(f, "{hash}  {basename}")?;
-        println!("\x1b[32mBLAKE2:\x1b[0m {hash}");
-        checksums.b2 = hash;
+        println!("\x1b[32m{}:\x1b[0m {hash}", algorithm.label());
+        checksums.set(algorithm, hash);
     }
 
     Ok(checksums)
@@ -85,13 +281,9 @@ pub fn generate_checksums(file_path: &Path) -> Result<Checksums> {
 
 /// Detect checksum type from file extension.
 pub fn detect_checksum_type(checksum_file: &str) -> &'static str {
-    if checksum_file.ends_with(".sha512") {
-        "sha512"
-    } else if checksum_file.ends_with(".b2") {
-        "b2"
-    } else {
-        "sha256"
-    }
+    ChecksumAlgorithm::from_extension(checksum_file)
+        .unwrap_or(ChecksumAlgorithm::Sha256)
+        .name()
 }
 
 /// Parse a checksum file and return the hash value (first token of first line).
@@ -112,15 +304,151 @@ pub fn parse_checksum_file(path: &Path) -> Result<String> {
 
 /// Compute checksum of a file using the specified type.
 pub fn compute_checksum(path: &Path, checksum_type: &str) -> Result<String> {
-    match checksum_type {
-        "sha256" => sha256_file(path),
-        "sha512" => sha512_file(path),
-        "b2" => blake2_file(path),
-        other => Err(Error::User(format!("unsupported checksum type: {other}"))),
+    let algorithm: ChecksumAlgorithm = checksum_type
+        .parse()
+        .map_err(|_| Error::User(format!("unsupported checksum type: {checksum_type}")))?;
+    hash_file_single(path, algorithm)
+}
+
+/// One entry of a checksum manifest such as `SHA256SUMS`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManifestEntry {
+    pub algorithm: ChecksumAlgorithm,
+    pub file: String,
+    pub hash: String,
+}
+
+/// Detects the algorithm of a manifest or sidecar from its file name:
+/// `SHA512SUMS`, `b2sums.txt`, `app.tar.gz.sha256` and so on.
+pub fn algorithm_for_manifest(file_name: &str) -> Option<ChecksumAlgorithm> {
+    let upper = file_name.to_ascii_uppercase();
+    let stem = upper
+        .strip_suffix(".TXT")
+        .unwrap_or(&upper)
+        .strip_suffix("SUMS")
+        .unwrap_or("");
+    if !stem.is_empty() {
+        if let Some(a) = ChecksumAlgorithm::ALL
+            .into_iter()
+            .find(|a| a.name().to_ascii_uppercase() == stem)
+        {
+            return Some(a);
+        }
+    }
+    ChecksumAlgorithm::from_extension(file_name)
+}
+
+/// Maps a BSD-style tag (`SHA256 (file) = hash`) to an algorithm.
+fn algorithm_from_tag(tag: &str) -> Option<ChecksumAlgorithm> {
+    match tag.to_ascii_uppercase().as_str() {
+        "SHA256" | "SHA2-256" => Some(ChecksumAlgorithm::Sha256),
+        "SHA512" | "SHA2-512" => Some(ChecksumAlgorithm::Sha512),
+        "BLAKE2B" | "BLAKE2B-512" | "BLAKE2" => Some(ChecksumAlgorithm::B2),
+        "SHA3-256" => Some(ChecksumAlgorithm::Sha3_256),
+        "BLAKE3" => Some(ChecksumAlgorithm::B3),
+        _ => None,
+    }
+}
+
+/// Undoes the escaping GNU coreutils applies to names containing `\` or newlines.
+fn unescape_gnu_name(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut chars = name.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') => out.push('\n'),
+                Some('r') => out.push('\r'),
+                Some(other) => out.push(other),
+                None => out.push('\\'),
+            }
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Parses a multi-line checksum manifest.
+///
+/// Accepts GNU lines (`<hash>  <file>`, `<hash> *<file>`, with `\`-escaped
+/// names) and BSD tagged lines (`SHA256 (<file>) = <hash>`). GNU lines use
+/// `default` as their algorithm; without it the algorithm is inferred from
+/// the digest length (64 hex digits for SHA-256, 128 for SHA-512).
+pub fn parse_checksum_manifest(
+    content: &str,
+    default: Option<ChecksumAlgorithm>,
+) -> Result<Vec<ManifestEntry>> {
+    let bsd = Regex::new(r"^([A-Za-z0-9-]+) ?\((.*)\) ?= ?([0-9A-Fa-f]+)$").unwrap();
+    let mut entries = Vec::new();
+    for (index, raw) in content.lines().enumerate() {
+        let line = raw.trim_end_matches('\r');
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let invalid = || Error::User(format!("invalid checksum line {}: {line}", index + 1));
+
+        if let Some(caps) = bsd.captures(line) {
+            let algorithm = algorithm_from_tag(&caps[1]).ok_or_else(|| {
+                Error::User(format!(
+                    "unsupported checksum algorithm on line {}: {}",
+                    index + 1,
+                    &caps[1]
+                ))
+            })?;
+            entries.push(ManifestEntry {
+                algorithm,
+                file: caps[2].to_string(),
+                hash: caps[3].to_ascii_lowercase(),
+            });
+            continue;
+        }
+
+        let (escaped, line) = match line.strip_prefix('\\') {
+            Some(rest) => (true, rest),
+            None => (false, line),
+        };
+        let (hash, rest) = line.split_once(' ').ok_or_else(invalid)?;
+        if hash.is_empty() || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid());
+        }
+        let name = rest
+            .strip_prefix(' ')
+            .or_else(|| rest.strip_prefix('*'))
+            .ok_or_else(invalid)?;
+        if name.is_empty() {
+            return Err(invalid());
+        }
+        let algorithm = match default {
+            Some(a) => a,
+            None => match hash.len() {
+                64 => ChecksumAlgorithm::Sha256,
+                128 => ChecksumAlgorithm::Sha512,
+                _ => {
+                    return Err(Error::User(format!(
+                        "cannot determine the checksum algorithm on line {}",
+                        index + 1
+                    )));
+                }
+            },
+        };
+        entries.push(ManifestEntry {
+            algorithm,
+            file: if escaped {
+                unescape_gnu_name(name)
+            } else {
+                name.to_string()
+            },
+            hash: hash.to_ascii_lowercase(),
+        });
     }
+    Ok(entries)
 }
 
 /// Verify a checksum file against an artifact.
+///
+/// The checksum file can be a single-entry sidecar or a manifest listing
+/// several files, in which case the entry for the artifact's name is used.
 pub fn verify_checksum(artifact_path: &Path, checksum_file_path: &Path) -> Result<()> {
     println!("\x1b[32mVerifying checksum...\x1b[0m");
 
@@ -131,16 +459,38 @@ pub fn verify_checksum(artifact_path: &Path, checksum_file_path: &Path) -> Resul
         )));
     }
 
-    let expected = parse_checksum_file(checksum_file_path)?;
-    let checksum_type = detect_checksum_type(&checksum_file_path.to_string_lossy());
-    let actual = compute_checksum(artifact_path, checksum_type)?;
+    let checksum_name = checksum_file_path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    let artifact_name = artifact_path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy()
+        .to_string();
+    let content = fs::read_to_string(checksum_file_path)?;
+    let listed = parse_checksum_manifest(&content, algorithm_for_manifest(&checksum_name))
+        .unwrap_or_default()
+        .into_iter()
+        .find(|e| e.file.trim_start_matches("./") == artifact_name);
+
+    let (algorithm, expected) = match listed {
+        Some(entry) => (entry.algorithm, entry.hash),
+        None => {
+            let expected = parse_checksum_file(checksum_file_path)?;
+            let algorithm: ChecksumAlgorithm = detect_checksum_type(&checksum_name).parse()?;
+            (algorithm, expected)
+        }
+    };
+    let actual = hash_file_single(artifact_path, algorithm)?;
 
-    if actual != expected {
+    if actual != expected.to_ascii_lowercase() {
         return Err(Error::User(format!(
             "checksum mismatch: expected {expected}, got {actual}"
         )));
     }
 
-    println!("  {}: {actual} \u{2713}", checksum_type.to_uppercase());
+    println!("  {}: {actual} \u{2713}", algorithm.label());
     Ok(())
 }
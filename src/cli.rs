@@ -0,0 +1,1170 @@
+use clap::{Args, Parser, Subcommand};
+
+/// Settings produced by command-line flags, keyed by the environment variable
+/// names the command handlers read.
+pub type Settings = Vec<(&'static str, String)>;
+
+/// Implemented by argument groups that map onto command handler settings.
+pub trait ToSettings {
+    fn push_settings(&self, settings: &mut Settings);
+
+    fn settings(&self) -> Settings {
+        let mut settings = Vec::new();
+        self.push_settings(&mut settings);
+        settings
+    }
+}
+
+fn push(settings: &mut Settings, key: &'static str, value: &Option<String>) {
+    if let Some(v) = value {
+        settings.push((key, v.clone()));
+    }
+}
+
+fn push_flag(settings: &mut Settings, key: &'static str, value: bool) {
+    if value {
+        settings.push((key, "true".to_string()));
+    }
+}
+
+fn push_bool(settings: &mut Settings, key: &'static str, value: Option<bool>) {
+    if let Some(v) = value {
+        settings.push((key, v.to_string()));
+    }
+}
+
+#[derive(Parser, Debug)]
+#[command(name = "rust-release-action")]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Extract release notes for a version from the changelog
+    ExtractChangelog(ChangelogArgs),
+    /// Validate that the changelog has an entry for a version
+    ValidateChangelog(ChangelogArgs),
+    /// Check changelog structure: version order, dates, duplicates and empty sections
+    LintChangelog(ChangelogArgs),
+    /// Promote the Unreleased changelog section to a version
+    PrepareChangelog(ChangelogArgs),
+    /// Generate a changelog section from conventional commits
+    GenerateChangelog(GenerateChangelogArgs),
+    /// Extract and validate the version from a git tag
+    ValidateVersion(ValidateVersionArgs),
+    /// Print the version from Cargo.toml
+    GetVersion(ProjectArgs),
+    /// Bump the version in Cargo.toml and workspace members
+    BumpVersion(BumpVersionArgs),
+    /// Get the latest release version from GitHub
+    GetReleaseVersion(VersionArgs),
+    /// Generate SPDX and CycloneDX SBOMs
+    GenerateSbom(SbomArgs),
+    /// Check the structure of SBOMs and dependency licenses against a policy
+    ///
+    /// The structural check covers the subset of the SPDX 2.3 and CycloneDX 1.5/1.6
+    /// JSON schemas that consumers rely on; it is not a full schema validation.
+    CheckSbom(CheckSbomArgs),
+    /// Generate SLSA build provenance for release artifacts
+    GenerateProvenance(ProvenanceArgs),
+    /// Generate a Homebrew formula
+    GenerateHomebrew(HomebrewArgs),
+    /// Generate an AUR PKGBUILD and .SRCINFO
+    GenerateAur(AurArgs),
+    /// Generate Winget manifests
+    GenerateWinget(WingetArgs),
+    /// Sign an artifact with cosign, GPG or minisign
+    SignArtifact(SignArgs),
+    /// Sign every release artifact in a directory
+    SignArtifacts(SignArtifactsArgs),
+    /// Verify an artifact's cosign, GPG or minisign signature
+    VerifySignature(VerifySignatureArgs),
+    /// Format a GitHub Release body
+    FormatRelease(FormatReleaseArgs),
+    /// Collect artifacts, compute checksums and write SHA256SUMS
+    CollectArtifacts(CollectArgs),
+    /// Verify artifacts against SHA256SUMS-style checksum manifests
+    VerifyChecksums(VerifyChecksumsArgs),
+    /// Plan a GitHub Actions build matrix for targets and package formats
+    Plan(PlanArgs),
+    /// Build a release binary, selecting the platform from the target
+    Release(ReleaseArgs),
+    /// Build a Linux binary or tarball
+    ReleaseLinux(ReleaseArgs),
+    /// Build a Debian package
+    ReleaseLinuxDeb(PackageArgs),
+    /// Build an RPM package
+    ReleaseLinuxRpm(PackageArgs),
+    /// Build an Alpine APK package
+    ReleaseLinuxApk(PackageArgs),
+    /// Build a macOS binary or tarball
+    ReleaseMacos(ReleaseArgs),
+    /// Build a universal (arm64 + x86_64) macOS binary or tarball
+    ReleaseMacosUniversal(UniversalArgs),
+    /// Build a macOS DMG installer
+    ReleaseMacosDmg(ReleaseArgs),
+    /// Build a Windows binary or zip
+    ReleaseWindows(ReleaseArgs),
+    /// Build a Windows MSI installer
+    ReleaseWindowsMsi(ReleaseArgs),
+    /// Publish the crate to crates.io
+    PublishCrate(PublishArgs),
+    /// Install, verify and remove a Debian package
+    TestDeb(TestPackageArgs),
+    /// Install, verify and remove an RPM package
+    TestRpm(TestPackageArgs),
+    /// Verify a Windows binary and MSI installer
+    TestWindows(TestWindowsArgs),
+}
+
+impl ToSettings for Command {
+    fn push_settings(&self, settings: &mut Settings) {
+        match self {
+            Command::ExtractChangelog(a)
+            | Command::ValidateChangelog(a)
+            | Command::LintChangelog(a)
+            | Command::PrepareChangelog(a) => a.push_settings(settings),
+            Command::GenerateChangelog(a) => a.push_settings(settings),
+            Command::ValidateVersion(a) => a.push_settings(settings),
+            Command::GetVersion(a) => a.push_settings(settings),
+            Command::BumpVersion(a) => a.push_settings(settings),
+            Command::GetReleaseVersion(a) => a.push_settings(settings),
+            Command::GenerateSbom(a) => a.push_settings(settings),
+            Command::CheckSbom(a) => a.push_settings(settings),
+            Command::GenerateProvenance(a) => a.push_settings(settings),
+            Command::GenerateHomebrew(a) => a.push_settings(settings),
+            Command::GenerateAur(a) => a.push_settings(settings),
+            Command::GenerateWinget(a) => a.push_settings(settings),
+            Command::SignArtifact(a) => a.push_settings(settings),
+            Command::SignArtifacts(a) => a.push_settings(settings),
+            Command::VerifySignature(a) => a.push_settings(settings),
+            Command::FormatRelease(a) => a.push_settings(settings),
+            Command::CollectArtifacts(a) => a.push_settings(settings),
+            Command::VerifyChecksums(a) => a.push_settings(settings),
+            Command::Plan(a) => a.push_settings(settings),
+            Command::Release(a)
+            | Command::ReleaseLinux(a)
+            | Command::ReleaseMacos(a)
+            | Command::ReleaseMacosDmg(a)
+            | Command::ReleaseWindows(a)
+            | Command::ReleaseWindowsMsi(a) => a.push_settings(settings),
+            Command::ReleaseMacosUniversal(a) => a.push_settings(settings),
+            Command::ReleaseLinuxDeb(a)
+            | Command::ReleaseLinuxRpm(a)
+            | Command::ReleaseLinuxApk(a) => a.push_settings(settings),
+            Command::PublishCrate(a) => a.push_settings(settings),
+            Command::TestDeb(a) | Command::TestRpm(a) => a.push_settings(settings),
+            Command::TestWindows(a) => a.push_settings(settings),
+        }
+    }
+}
+
+/// Cargo project selection.
+#[derive(Args, Debug, Default)]
+pub struct ProjectArgs {
+    /// Path to Cargo.toml
+    #[arg(long)]
+    pub manifest: Option<String>,
+    /// Path to a release config file
+    #[arg(long)]
+    pub config: Option<String>,
+    /// Cargo package name (for workspace builds)
+    #[arg(long)]
+    pub package: Option<String>,
+    /// Binary name (defaults to the package name)
+    #[arg(long)]
+    pub binary_name: Option<String>,
+    /// Binaries to release together (comma-separated, or "all")
+    #[arg(long)]
+    pub binaries: Option<String>,
+    /// Workspace members to search for binaries (comma-separated)
+    #[arg(long)]
+    pub packages: Option<String>,
+    /// Package several binaries "combined" (default) or "separate"
+    #[arg(long)]
+    pub binary_packaging: Option<String>,
+}
+
+impl ToSettings for ProjectArgs {
+    fn push_settings(&self, settings: &mut Settings) {
+        push(settings, "MANIFEST_PATH", &self.manifest);
+        push(settings, "RELEASE_CONFIG", &self.config);
+        push(settings, "PACKAGE", &self.package);
+        push(settings, "BINARY_NAME", &self.binary_name);
+        push(settings, "BINARIES", &self.binaries);
+        push(settings, "PACKAGES", &self.packages);
+        push(settings, "BINARY_PACKAGING", &self.binary_packaging);
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct BumpVersionArgs {
+    #[command(flatten)]
+    pub project: ProjectArgs,
+    /// major, minor, patch, prerelease, or an explicit version
+    #[arg(long)]
+    pub level: Option<String>,
+    /// Pre-release identifier for prerelease bumps (e.g. alpha, beta, rc)
+    #[arg(long)]
+    pub pre_id: Option<String>,
+}
+
+impl ToSettings for BumpVersionArgs {
+    fn push_settings(&self, settings: &mut Settings) {
+        self.project.push_settings(settings);
+        push(settings, "BUMP_LEVEL", &self.level);
+        push(settings, "BUMP_PRE_ID", &self.pre_id);
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct VersionArgs {
+    /// Version (without the v prefix)
+    #[arg(long)]
+    pub version: Option<String>,
+}
+
+impl ToSettings for VersionArgs {
+    fn push_settings(&self, settings: &mut Settings) {
+        push(settings, "VERSION", &self.version);
+    }
+}
+
+/// Cargo build options.
+#[derive(Args, Debug, Default)]
+pub struct BuildArgs {
+    /// Rust target triple
+    #[arg(long)]
+    pub target: Option<String>,
+    /// Cargo features to enable (comma-separated)
+    #[arg(long)]
+    pub features: Option<String>,
+    /// Build with --no-default-features
+    #[arg(long)]
+    pub no_default_features: bool,
+    /// Cargo build profile
+    #[arg(long)]
+    pub profile: Option<String>,
+    /// Build with --locked
+    #[arg(long)]
+    pub locked: bool,
+    /// Extra RUSTFLAGS for the build
+    #[arg(long)]
+    pub rustflags: Option<String>,
+    /// Use cargo-zigbuild for cross-compilation
+    #[arg(long)]
+    pub use_zigbuild: bool,
+    /// Build tool: cargo, zigbuild or cross (default: cross for targets that require it, cargo otherwise)
+    #[arg(long)]
+    pub build_tool: Option<String>,
+    /// Shell command to run before cargo build
+    #[arg(long)]
+    pub pre_build: Option<String>,
+    /// Skip cargo build and package an existing binary
+    #[arg(long)]
+    pub skip_build: bool,
+    /// Path to an existing binary (with --skip-build)
+    #[arg(long)]
+    pub binary_path: Option<String>,
+}
+
+impl ToSettings for BuildArgs {
+    fn push_settings(&self, settings: &mut Settings) {
+        push(settings, "TARGET", &self.target);
+        push(settings, "FEATURES", &self.features);
+        push_flag(settings, "NO_DEFAULT_FEATURES", self.no_default_features);
+        push(settings, "PROFILE", &self.profile);
+        push_flag(settings, "LOCKED", self.locked);
+        push(settings, "TARGET_RUSTFLAGS", &self.rustflags);
+        push_flag(settings, "USE_ZIGBUILD", self.use_zigbuild);
+        push(settings, "BUILD_TOOL", &self.build_tool);
+        push(settings, "PRE_BUILD", &self.pre_build);
+        push_flag(settings, "SKIP_BUILD", self.skip_build);
+        push(settings, "BINARY_PATH", &self.binary_path);
+    }
+}
+
+/// Artifact output options.
+#[derive(Args, Debug, Default)]
+pub struct OutputArgs {
+    /// Create an archive (.tar.gz on Linux/macOS, .zip on Windows)
+    #[arg(long)]
+    pub archive: bool,
+    /// Archive format: tar.gz, tar.xz, tar.zst or zip
+    #[arg(long)]
+    pub archive_format: Option<String>,
+    /// Wrap archive contents in a <name>-<version>-<target>/ directory
+    #[arg(long)]
+    pub archive_top_level_dir: bool,
+    /// Checksum algorithms (comma-separated: sha256, sha512, b2, sha3-256, b3)
+    #[arg(long)]
+    pub checksum: Option<String>,
+    /// Extra files to include in the archive (comma-separated globs or src:dst)
+    #[arg(long)]
+    pub include: Option<String>,
+}
+
+impl ToSettings for OutputArgs {
+    fn push_settings(&self, settings: &mut Settings) {
+        push_flag(settings, "ARCHIVE", self.archive);
+        push(settings, "ARCHIVE_FORMAT", &self.archive_format);
+        push_flag(
+            settings,
+            "ARCHIVE_TOP_LEVEL_DIR",
+            self.archive_top_level_dir,
+        );
+        push(settings, "CHECKSUM", &self.checksum);
+        push(settings, "ARCHIVE_INCLUDE", &self.include);
+    }
+}
+
+/// Package metadata shared by deb/rpm/apk and package manager manifests.
+#[derive(Args, Debug, Default)]
+pub struct PkgMetadataArgs {
+    /// Package description
+    #[arg(long)]
+    pub pkg_description: Option<String>,
+    /// Package maintainer (Name <email>)
+    #[arg(long)]
+    pub pkg_maintainer: Option<String>,
+    /// Project homepage URL
+    #[arg(long)]
+    pub pkg_homepage: Option<String>,
+    /// License identifier
+    #[arg(long)]
+    pub pkg_license: Option<String>,
+}
+
+impl ToSettings for PkgMetadataArgs {
+    fn push_settings(&self, settings: &mut Settings) {
+        push(settings, "PKG_DESCRIPTION", &self.pkg_description);
+        push(settings, "PKG_MAINTAINER", &self.pkg_maintainer);
+        push(settings, "PKG_HOMEPAGE", &self.pkg_homepage);
+        push(settings, "PKG_LICENSE", &self.pkg_license);
+    }
+}
+
+/// Linux package options (deb/rpm/apk).
+#[derive(Args, Debug, Default)]
+pub struct PkgArgs {
+    #[command(flatten)]
+    pub metadata: PkgMetadataArgs,
+    /// Vendor or organization name
+    #[arg(long)]
+    pub pkg_vendor: Option<String>,
+    /// Runtime dependencies (comma-separated)
+    #[arg(long)]
+    pub pkg_depends: Option<String>,
+    /// Recommended packages (comma-separated)
+    #[arg(long)]
+    pub pkg_recommends: Option<String>,
+    /// Suggested packages (comma-separated)
+    #[arg(long)]
+    pub pkg_suggests: Option<String>,
+    /// Conflicting packages (comma-separated)
+    #[arg(long)]
+    pub pkg_conflicts: Option<String>,
+    /// Packages this replaces (comma-separated)
+    #[arg(long)]
+    pub pkg_replaces: Option<String>,
+    /// Virtual packages provided (comma-separated)
+    #[arg(long)]
+    pub pkg_provides: Option<String>,
+    /// Extra files to include (src:dst, comma-separated)
+    #[arg(long)]
+    pub pkg_contents: Option<String>,
+    /// Debian package section
+    #[arg(long)]
+    pub pkg_section: Option<String>,
+    /// Debian package priority
+    #[arg(long)]
+    pub pkg_priority: Option<String>,
+    /// RPM package group
+    #[arg(long)]
+    pub pkg_group: Option<String>,
+    /// Package release/revision number
+    #[arg(long)]
+    pub pkg_release: Option<String>,
+}
+
+impl ToSettings for PkgArgs {
+    fn push_settings(&self, settings: &mut Settings) {
+        self.metadata.push_settings(settings);
+        push(settings, "PKG_VENDOR", &self.pkg_vendor);
+        push(settings, "PKG_DEPENDS", &self.pkg_depends);
+        push(settings, "PKG_RECOMMENDS", &self.pkg_recommends);
+        push(settings, "PKG_SUGGESTS", &self.pkg_suggests);
+        push(settings, "PKG_CONFLICTS", &self.pkg_conflicts);
+        push(settings, "PKG_REPLACES", &self.pkg_replaces);
+        push(settings, "PKG_PROVIDES", &self.pkg_provides);
+        push(settings, "PKG_CONTENTS", &self.pkg_contents);
+        push(settings, "PKG_SECTION", &self.pkg_section);
+        push(settings, "PKG_PRIORITY", &self.pkg_priority);
+        push(settings, "PKG_GROUP", &self.pkg_group);
+        push(settings, "PKG_RELEASE", &self.pkg_release);
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct ReleaseArgs {
+    #[command(flatten)]
+    pub project: ProjectArgs,
+    #[command(flatten)]
+    pub build: BuildArgs,
+    #[command(flatten)]
+    pub output: OutputArgs,
+}
+
+impl ToSettings for ReleaseArgs {
+    fn push_settings(&self, settings: &mut Settings) {
+        self.project.push_settings(settings);
+        self.build.push_settings(settings);
+        self.output.push_settings(settings);
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct UniversalArgs {
+    #[command(flatten)]
+    pub release: ReleaseArgs,
+    /// Pre-built aarch64-apple-darwin binary (with --skip-build)
+    #[arg(long)]
+    pub binary_path_arm64: Option<String>,
+    /// Pre-built x86_64-apple-darwin binary (with --skip-build)
+    #[arg(long)]
+    pub binary_path_x64: Option<String>,
+}
+
+impl ToSettings for UniversalArgs {
+    fn push_settings(&self, settings: &mut Settings) {
+        self.release.push_settings(settings);
+        push(settings, "BINARY_PATH_ARM64", &self.binary_path_arm64);
+        push(settings, "BINARY_PATH_X64", &self.binary_path_x64);
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct PackageArgs {
+    #[command(flatten)]
+    pub project: ProjectArgs,
+    #[command(flatten)]
+    pub build: BuildArgs,
+    /// Checksum algorithms (comma-separated: sha256, sha512, b2, sha3-256, b3)
+    #[arg(long)]
+    pub checksum: Option<String>,
+    #[command(flatten)]
+    pub pkg: PkgArgs,
+}
+
+impl ToSettings for PackageArgs {
+    fn push_settings(&self, settings: &mut Settings) {
+        self.project.push_settings(settings);
+        self.build.push_settings(settings);
+        push(settings, "CHECKSUM", &self.checksum);
+        self.pkg.push_settings(settings);
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct ChangelogArgs {
+    #[command(flatten)]
+    pub version: VersionArgs,
+    /// Path to CHANGELOG.md
+    #[arg(long)]
+    pub changelog: Option<String>,
+    /// Output file for extracted release notes
+    #[arg(long)]
+    pub notes_output: Option<String>,
+}
+
+impl ToSettings for ChangelogArgs {
+    fn push_settings(&self, settings: &mut Settings) {
+        self.version.push_settings(settings);
+        push(settings, "CHANGELOG_PATH", &self.changelog);
+        push(settings, "OUTPUT_PATH", &self.notes_output);
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct GenerateChangelogArgs {
+    #[command(flatten)]
+    pub changelog: ChangelogArgs,
+    /// Start of the commit range (defaults to the previous tag)
+    #[arg(long)]
+    pub from_ref: Option<String>,
+    /// End of the commit range
+    #[arg(long)]
+    pub to_ref: Option<String>,
+    /// Insert the section into the changelog instead of writing release notes
+    #[arg(long)]
+    pub write: bool,
+}
+
+impl ToSettings for GenerateChangelogArgs {
+    fn push_settings(&self, settings: &mut Settings) {
+        self.changelog.push_settings(settings);
+        push(settings, "CHANGELOG_FROM_REF", &self.from_ref);
+        push(settings, "CHANGELOG_TO_REF", &self.to_ref);
+        push_flag(settings, "CHANGELOG_WRITE", self.write);
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct ValidateVersionArgs {
+    #[command(flatten)]
+    pub project: ProjectArgs,
+    /// Git tag to validate (defaults to GITHUB_REF_NAME)
+    #[arg(long)]
+    pub tag: Option<String>,
+    /// Expected version to validate against the tag
+    #[arg(long)]
+    pub expected_version: Option<String>,
+    /// Also validate that the Cargo.toml version matches the tag
+    #[arg(long)]
+    pub validate_cargo_toml: bool,
+}
+
+impl ToSettings for ValidateVersionArgs {
+    fn push_settings(&self, settings: &mut Settings) {
+        self.project.push_settings(settings);
+        push(settings, "TAG", &self.tag);
+        push(settings, "EXPECTED_VERSION", &self.expected_version);
+        push_flag(settings, "VALIDATE_CARGO_TOML", self.validate_cargo_toml);
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct SbomArgs {
+    #[command(flatten)]
+    pub project: ProjectArgs,
+    /// SBOM formats to generate (spdx, cyclonedx, cyclonedx-1.5, cyclonedx-1.6)
+    #[arg(long)]
+    pub sbom_format: Option<String>,
+    /// Rust target triple to resolve platform-specific dependencies for
+    #[arg(long)]
+    pub target: Option<String>,
+    /// Cargo features enabled for the build (comma-separated)
+    #[arg(long)]
+    pub features: Option<String>,
+    /// Resolve dependencies as if built with --no-default-features
+    #[arg(long)]
+    pub no_default_features: bool,
+    /// Generate one SBOM per release artifact in --artifacts-dir
+    #[arg(long)]
+    pub per_artifact: bool,
+    /// Directory containing release artifacts (with --per-artifact)
+    #[arg(long)]
+    pub artifacts_dir: Option<String>,
+    /// Output directory for SBOM files
+    #[arg(long)]
+    pub sbom_dir: Option<String>,
+}
+
+impl ToSettings for SbomArgs {
+    fn push_settings(&self, settings: &mut Settings) {
+        self.project.push_settings(settings);
+        push(settings, "SBOM_FORMAT", &self.sbom_format);
+        push(settings, "SBOM_OUTPUT_DIR", &self.sbom_dir);
+        push(settings, "TARGET", &self.target);
+        push(settings, "FEATURES", &self.features);
+        push_flag(settings, "NO_DEFAULT_FEATURES", self.no_default_features);
+        push_flag(settings, "SBOM_PER_ARTIFACT", self.per_artifact);
+        push(settings, "ARTIFACTS_DIR", &self.artifacts_dir);
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct CheckSbomArgs {
+    /// Directory containing the SBOMs to check
+    #[arg(long)]
+    pub sbom_dir: Option<String>,
+    /// SBOM files to check (comma-separated, defaults to all SBOMs in --sbom-dir)
+    #[arg(long)]
+    pub sbom_files: Option<String>,
+    /// License identifiers dependencies may use (comma-separated)
+    #[arg(long)]
+    pub license_allow: Option<String>,
+    /// License identifiers dependencies must not use (comma-separated)
+    #[arg(long)]
+    pub license_deny: Option<String>,
+}
+
+impl ToSettings for CheckSbomArgs {
+    fn push_settings(&self, settings: &mut Settings) {
+        push(settings, "SBOM_OUTPUT_DIR", &self.sbom_dir);
+        push(settings, "SBOM_FILES", &self.sbom_files);
+        push(settings, "SBOM_LICENSE_ALLOW", &self.license_allow);
+        push(settings, "SBOM_LICENSE_DENY", &self.license_deny);
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct HomebrewArgs {
+    #[command(flatten)]
+    pub project: ProjectArgs,
+    #[command(flatten)]
+    pub version: VersionArgs,
+    #[command(flatten)]
+    pub metadata: PkgMetadataArgs,
+    /// Ruby class name for the formula
+    #[arg(long)]
+    pub brew_class: Option<String>,
+    /// Copyright holder for the MIT license header
+    #[arg(long)]
+    pub brew_copyright: Option<String>,
+    /// Download URL for the macOS ARM64 artifact
+    #[arg(long)]
+    pub brew_macos_arm64_url: Option<String>,
+    /// SHA256 of the macOS ARM64 artifact
+    #[arg(long)]
+    pub brew_macos_arm64_sha256: Option<String>,
+    /// Download URL for the macOS x64 artifact
+    #[arg(long)]
+    pub brew_macos_x64_url: Option<String>,
+    /// SHA256 of the macOS x64 artifact
+    #[arg(long)]
+    pub brew_macos_x64_sha256: Option<String>,
+    /// Download URL for the Linux ARM64 artifact
+    #[arg(long)]
+    pub brew_linux_arm64_url: Option<String>,
+    /// SHA256 of the Linux ARM64 artifact
+    #[arg(long)]
+    pub brew_linux_arm64_sha256: Option<String>,
+    /// Download URL for the Linux x64 artifact
+    #[arg(long)]
+    pub brew_linux_x64_url: Option<String>,
+    /// SHA256 of the Linux x64 artifact
+    #[arg(long)]
+    pub brew_linux_x64_sha256: Option<String>,
+    /// Output directory for the formula
+    #[arg(long)]
+    pub brew_dir: Option<String>,
+}
+
+impl ToSettings for HomebrewArgs {
+    fn push_settings(&self, settings: &mut Settings) {
+        self.project.push_settings(settings);
+        self.version.push_settings(settings);
+        self.metadata.push_settings(settings);
+        push(settings, "HOMEBREW_FORMULA_CLASS", &self.brew_class);
+        push(settings, "HOMEBREW_COPYRIGHT", &self.brew_copyright);
+        push(
+            settings,
+            "HOMEBREW_MACOS_ARM64_URL",
+            &self.brew_macos_arm64_url,
+        );
+        push(
+            settings,
+            "HOMEBREW_MACOS_ARM64_SHA256",
+            &self.brew_macos_arm64_sha256,
+        );
+        push(settings, "HOMEBREW_MACOS_X64_URL", &self.brew_macos_x64_url);
+        push(
+            settings,
+            "HOMEBREW_MACOS_X64_SHA256",
+            &self.brew_macos_x64_sha256,
+        );
+        push(
+            settings,
+            "HOMEBREW_LINUX_ARM64_URL",
+            &self.brew_linux_arm64_url,
+        );
+        push(
+            settings,
+            "HOMEBREW_LINUX_ARM64_SHA256",
+            &self.brew_linux_arm64_sha256,
+        );
+        push(settings, "HOMEBREW_LINUX_X64_URL", &self.brew_linux_x64_url);
+        push(
+            settings,
+            "HOMEBREW_LINUX_X64_SHA256",
+            &self.brew_linux_x64_sha256,
+        );
+        push(settings, "HOMEBREW_OUTPUT_DIR", &self.brew_dir);
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct AurArgs {
+    #[command(flatten)]
+    pub project: ProjectArgs,
+    #[command(flatten)]
+    pub version: VersionArgs,
+    #[command(flatten)]
+    pub metadata: PkgMetadataArgs,
+    /// Runtime dependencies (comma-separated)
+    #[arg(long)]
+    pub pkg_depends: Option<String>,
+    /// Virtual packages provided (comma-separated)
+    #[arg(long)]
+    pub pkg_provides: Option<String>,
+    /// Conflicting packages (comma-separated)
+    #[arg(long)]
+    pub pkg_conflicts: Option<String>,
+    /// AUR package name
+    #[arg(long)]
+    pub aur_name: Option<String>,
+    /// AUR maintainer (Name <email>)
+    #[arg(long)]
+    pub aur_maintainer: Option<String>,
+    /// Source tarball URL
+    #[arg(long)]
+    pub aur_source_url: Option<String>,
+    /// SHA256 of the source tarball
+    #[arg(long)]
+    pub aur_source_sha256: Option<String>,
+    /// Build-time dependencies (comma-separated)
+    #[arg(long)]
+    pub aur_makedepends: Option<String>,
+    /// Optional dependencies (comma-separated)
+    #[arg(long)]
+    pub aur_optdepends: Option<String>,
+    /// Output directory for PKGBUILD and .SRCINFO
+    #[arg(long)]
+    pub aur_dir: Option<String>,
+}
+
+impl ToSettings for AurArgs {
+    fn push_settings(&self, settings: &mut Settings) {
+        self.project.push_settings(settings);
+        self.version.push_settings(settings);
+        self.metadata.push_settings(settings);
+        push(settings, "PKG_DEPENDS", &self.pkg_depends);
+        push(settings, "PKG_PROVIDES", &self.pkg_provides);
+        push(settings, "PKG_CONFLICTS", &self.pkg_conflicts);
+        push(settings, "AUR_PACKAGE_NAME", &self.aur_name);
+        push(settings, "AUR_MAINTAINER", &self.aur_maintainer);
+        push(settings, "AUR_SOURCE_URL", &self.aur_source_url);
+        push(settings, "AUR_SOURCE_SHA256", &self.aur_source_sha256);
+        push(settings, "AUR_MAKEDEPENDS", &self.aur_makedepends);
+        push(settings, "AUR_OPTDEPENDS", &self.aur_optdepends);
+        push(settings, "AUR_OUTPUT_DIR", &self.aur_dir);
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct WingetArgs {
+    #[command(flatten)]
+    pub project: ProjectArgs,
+    #[command(flatten)]
+    pub version: VersionArgs,
+    #[command(flatten)]
+    pub metadata: PkgMetadataArgs,
+    /// Publisher display name
+    #[arg(long)]
+    pub winget_publisher: Option<String>,
+    /// Publisher ID
+    #[arg(long)]
+    pub winget_publisher_id: Option<String>,
+    /// Package ID
+    #[arg(long)]
+    pub winget_package_id: Option<String>,
+    /// URL to the license file
+    #[arg(long)]
+    pub winget_license_url: Option<String>,
+    /// Copyright notice
+    #[arg(long)]
+    pub winget_copyright: Option<String>,
+    /// Package tags (comma-separated)
+    #[arg(long)]
+    pub winget_tags: Option<String>,
+    /// Download URL for the Windows x64 artifact
+    #[arg(long)]
+    pub winget_x64_url: Option<String>,
+    /// SHA256 of the Windows x64 artifact
+    #[arg(long)]
+    pub winget_x64_sha256: Option<String>,
+    /// Download URL for the Windows ARM64 artifact
+    #[arg(long)]
+    pub winget_arm64_url: Option<String>,
+    /// SHA256 of the Windows ARM64 artifact
+    #[arg(long)]
+    pub winget_arm64_sha256: Option<String>,
+    /// Output directory for manifest files
+    #[arg(long)]
+    pub winget_dir: Option<String>,
+}
+
+impl ToSettings for WingetArgs {
+    fn push_settings(&self, settings: &mut Settings) {
+        self.project.push_settings(settings);
+        self.version.push_settings(settings);
+        self.metadata.push_settings(settings);
+        push(settings, "WINGET_PUBLISHER", &self.winget_publisher);
+        push(settings, "WINGET_PUBLISHER_ID", &self.winget_publisher_id);
+        push(settings, "WINGET_PACKAGE_ID", &self.winget_package_id);
+        push(settings, "WINGET_LICENSE_URL", &self.winget_license_url);
+        push(settings, "WINGET_COPYRIGHT", &self.winget_copyright);
+        push(settings, "WINGET_TAGS", &self.winget_tags);
+        push(settings, "WINGET_X64_URL", &self.winget_x64_url);
+        push(settings, "WINGET_X64_SHA256", &self.winget_x64_sha256);
+        push(settings, "WINGET_ARM64_URL", &self.winget_arm64_url);
+        push(settings, "WINGET_ARM64_SHA256", &self.winget_arm64_sha256);
+        push(settings, "WINGET_OUTPUT_DIR", &self.winget_dir);
+    }
+}
+
+/// Signing backend selection shared by the signing commands.
+#[derive(Args, Debug, Default)]
+pub struct SignerArgs {
+    /// Signing backend: cosign, gpg or minisign
+    #[arg(long)]
+    pub sign_method: Option<String>,
+    /// GPG key ID or fingerprint to sign with
+    #[arg(long)]
+    pub gpg_key_id: Option<String>,
+    /// cosign key file or key reference (env://, awskms://, ...); keyless when unset
+    #[arg(long)]
+    pub cosign_key: Option<String>,
+    /// Upload cosign signatures to the Rekor transparency log (true/false)
+    #[arg(long)]
+    pub cosign_tlog_upload: Option<bool>,
+}
+
+// Private keys and passphrases are only read from the environment so that
+// they never show up in process listings.
+impl ToSettings for SignerArgs {
+    fn push_settings(&self, settings: &mut Settings) {
+        push(settings, "SIGN_METHOD", &self.sign_method);
+        push(settings, "GPG_KEY_ID", &self.gpg_key_id);
+        push(settings, "COSIGN_KEY", &self.cosign_key);
+        push_bool(settings, "COSIGN_TLOG_UPLOAD", self.cosign_tlog_upload);
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct SignArgs {
+    #[command(flatten)]
+    pub signer: SignerArgs,
+    /// Path to the artifact to sign
+    #[arg(long)]
+    pub artifact: Option<String>,
+}
+
+impl ToSettings for SignArgs {
+    fn push_settings(&self, settings: &mut Settings) {
+        self.signer.push_settings(settings);
+        push(settings, "ARTIFACT_PATH", &self.artifact);
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct SignArtifactsArgs {
+    #[command(flatten)]
+    pub signer: SignerArgs,
+    /// Directory containing release artifacts
+    #[arg(long)]
+    pub artifacts_dir: Option<String>,
+    /// Also sign SHA256SUMS-style checksum manifests
+    #[arg(long)]
+    pub sign_manifests: bool,
+}
+
+impl ToSettings for SignArtifactsArgs {
+    fn push_settings(&self, settings: &mut Settings) {
+        self.signer.push_settings(settings);
+        push(settings, "ARTIFACTS_DIR", &self.artifacts_dir);
+        push_flag(settings, "SIGN_MANIFESTS", self.sign_manifests);
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct VerifySignatureArgs {
+    /// Path to the signed artifact
+    #[arg(long)]
+    pub artifact: Option<String>,
+    /// Signing backend: cosign, gpg or minisign
+    #[arg(long)]
+    pub sign_method: Option<String>,
+    /// Public key (cosign key file, armored GPG key or minisign key)
+    #[arg(long)]
+    pub public_key: Option<String>,
+    /// Expected certificate identity of a keyless cosign signature
+    #[arg(long)]
+    pub certificate_identity: Option<String>,
+    /// Regular expression the certificate identity must match
+    #[arg(long)]
+    pub certificate_identity_regexp: Option<String>,
+    /// Expected OIDC issuer of a keyless cosign signature
+    #[arg(long)]
+    pub certificate_oidc_issuer: Option<String>,
+    /// Regular expression the OIDC issuer must match
+    #[arg(long)]
+    pub certificate_oidc_issuer_regexp: Option<String>,
+    /// Whether cosign signatures were uploaded to Rekor (false skips the tlog check)
+    #[arg(long)]
+    pub cosign_tlog_upload: Option<bool>,
+    /// Accept GPG signatures from any key in the default keyring when no public key is set
+    #[arg(long)]
+    pub gpg_use_keyring: bool,
+}
+
+impl ToSettings for VerifySignatureArgs {
+    fn push_settings(&self, settings: &mut Settings) {
+        push(settings, "ARTIFACT_PATH", &self.artifact);
+        push(settings, "SIGN_METHOD", &self.sign_method);
+        push(settings, "PUBLIC_KEY", &self.public_key);
+        push(settings, "CERTIFICATE_IDENTITY", &self.certificate_identity);
+        push(
+            settings,
+            "CERTIFICATE_IDENTITY_REGEXP",
+            &self.certificate_identity_regexp,
+        );
+        push(
+            settings,
+            "CERTIFICATE_OIDC_ISSUER",
+            &self.certificate_oidc_issuer,
+        );
+        push(
+            settings,
+            "CERTIFICATE_OIDC_ISSUER_REGEXP",
+            &self.certificate_oidc_issuer_regexp,
+        );
+        push_bool(settings, "COSIGN_TLOG_UPLOAD", self.cosign_tlog_upload);
+        push_flag(settings, "GPG_USE_KEYRING", self.gpg_use_keyring);
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct FormatReleaseArgs {
+    #[command(flatten)]
+    pub version: VersionArgs,
+    /// Directory containing release artifacts
+    #[arg(long)]
+    pub artifacts_dir: Option<String>,
+    /// Release notes file to include in the body
+    #[arg(long)]
+    pub notes_file: Option<String>,
+    /// Include the checksums section (true/false)
+    #[arg(long)]
+    pub include_checksums: Option<bool>,
+    /// Include the signatures section (true/false)
+    #[arg(long)]
+    pub include_signatures: Option<bool>,
+    /// Homebrew tap for installation instructions
+    #[arg(long)]
+    pub homebrew_tap: Option<String>,
+    /// AUR package name for installation instructions
+    #[arg(long)]
+    pub aur_package: Option<String>,
+    /// Winget package ID for installation instructions
+    #[arg(long)]
+    pub winget_id: Option<String>,
+}
+
+impl ToSettings for FormatReleaseArgs {
+    fn push_settings(&self, settings: &mut Settings) {
+        self.version.push_settings(settings);
+        push(settings, "ARTIFACTS_DIR", &self.artifacts_dir);
+        push(settings, "RELEASE_NOTES_FILE", &self.notes_file);
+        push_bool(settings, "INCLUDE_CHECKSUMS", self.include_checksums);
+        push_bool(settings, "INCLUDE_SIGNATURES", self.include_signatures);
+        push(settings, "HOMEBREW_TAP", &self.homebrew_tap);
+        push(settings, "AUR_PACKAGE", &self.aur_package);
+        push(settings, "WINGET_ID", &self.winget_id);
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct ProvenanceArgs {
+    #[command(flatten)]
+    pub build: BuildArgs,
+    /// Directory containing release artifacts
+    #[arg(long)]
+    pub artifacts_dir: Option<String>,
+    /// `collection` JSON from collect-artifacts (hashes artifacts-dir when unset)
+    #[arg(long)]
+    pub collection: Option<String>,
+    /// Additional digest algorithms when hashing (comma-separated: sha512, b2, sha3-256, b3)
+    #[arg(long)]
+    pub checksum: Option<String>,
+    /// Path of the provenance statement to write
+    #[arg(long)]
+    pub output: Option<String>,
+}
+
+impl ToSettings for ProvenanceArgs {
+    fn push_settings(&self, settings: &mut Settings) {
+        self.build.push_settings(settings);
+        push(settings, "ARTIFACTS_DIR", &self.artifacts_dir);
+        push(settings, "COLLECTION", &self.collection);
+        push(settings, "CHECKSUM", &self.checksum);
+        push(settings, "PROVENANCE_OUTPUT", &self.output);
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct CollectArgs {
+    /// Directory containing release artifacts
+    #[arg(long)]
+    pub artifacts_dir: Option<String>,
+    /// Additional manifest algorithms (comma-separated: sha512, b2, sha3-256, b3)
+    #[arg(long)]
+    pub checksum: Option<String>,
+    /// Base URL for artifact downloads
+    #[arg(long)]
+    pub base_url: Option<String>,
+}
+
+impl ToSettings for CollectArgs {
+    fn push_settings(&self, settings: &mut Settings) {
+        push(settings, "ARTIFACTS_DIR", &self.artifacts_dir);
+        push(settings, "CHECKSUM", &self.checksum);
+        push(settings, "BASE_URL", &self.base_url);
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct VerifyChecksumsArgs {
+    /// Directory containing the files to verify
+    #[arg(long)]
+    pub artifacts_dir: Option<String>,
+    /// Checksum manifests to verify against (comma-separated, defaults to *SUMS in the directory)
+    #[arg(long)]
+    pub checksum_file: Option<String>,
+}
+
+impl ToSettings for VerifyChecksumsArgs {
+    fn push_settings(&self, settings: &mut Settings) {
+        push(settings, "ARTIFACTS_DIR", &self.artifacts_dir);
+        push(settings, "CHECKSUM_FILE", &self.checksum_file);
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct PlanArgs {
+    #[command(flatten)]
+    pub project: ProjectArgs,
+    /// Target triples to build (comma-separated)
+    #[arg(long)]
+    pub targets: Option<String>,
+    /// Formats to produce (comma-separated: binary, archive, deb, rpm, apk, dmg, msi)
+    #[arg(long)]
+    pub formats: Option<String>,
+    /// Build Linux targets with cargo-zigbuild
+    #[arg(long)]
+    pub use_zigbuild: bool,
+    /// Build tool for Linux targets: cargo, zigbuild or cross
+    #[arg(long)]
+    pub build_tool: Option<String>,
+}
+
+impl ToSettings for PlanArgs {
+    fn push_settings(&self, settings: &mut Settings) {
+        self.project.push_settings(settings);
+        push(settings, "PLAN_TARGETS", &self.targets);
+        push(settings, "PLAN_FORMATS", &self.formats);
+        push_flag(settings, "USE_ZIGBUILD", self.use_zigbuild);
+        push(settings, "BUILD_TOOL", &self.build_tool);
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct PublishArgs {
+    #[command(flatten)]
+    pub project: ProjectArgs,
+    /// Cargo features to enable (comma-separated)
+    #[arg(long)]
+    pub features: Option<String>,
+    /// Publish with --no-default-features
+    #[arg(long)]
+    pub no_default_features: bool,
+    /// Publish with --locked
+    #[arg(long)]
+    pub locked: bool,
+    /// Git tag to validate (defaults to GITHUB_REF_NAME)
+    #[arg(long)]
+    pub tag: Option<String>,
+    /// Run cargo publish --dry-run only
+    #[arg(long)]
+    pub publish_dry_run: bool,
+}
+
+impl ToSettings for PublishArgs {
+    fn push_settings(&self, settings: &mut Settings) {
+        self.project.push_settings(settings);
+        push(settings, "FEATURES", &self.features);
+        push_flag(settings, "NO_DEFAULT_FEATURES", self.no_default_features);
+        push_flag(settings, "LOCKED", self.locked);
+        push(settings, "TAG", &self.tag);
+        push_flag(settings, "PUBLISH_DRY_RUN", self.publish_dry_run);
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct TestPackageArgs {
+    /// Binary name installed by the package
+    #[arg(long)]
+    pub binary_name: Option<String>,
+    #[command(flatten)]
+    pub version: VersionArgs,
+    /// Path to the package to test
+    #[arg(long)]
+    pub artifact: Option<String>,
+    /// Checksum file to verify the package against
+    #[arg(long)]
+    pub checksum_file: Option<String>,
+    /// Download the package from the GitHub release
+    #[arg(long)]
+    pub download_from_release: bool,
+    /// Architecture to download
+    #[arg(long)]
+    pub arch: Option<String>,
+}
+
+impl ToSettings for TestPackageArgs {
+    fn push_settings(&self, settings: &mut Settings) {
+        push(settings, "BINARY_NAME", &self.binary_name);
+        self.version.push_settings(settings);
+        push(settings, "ARTIFACT_PATH", &self.artifact);
+        push(settings, "CHECKSUM_FILE", &self.checksum_file);
+        push_flag(
+            settings,
+            "DOWNLOAD_FROM_RELEASE",
+            self.download_from_release,
+        );
+        push(settings, "ARCH", &self.arch);
+    }
+}
+
+#[derive(Args, Debug, Default)]
+pub struct TestWindowsArgs {
+    /// Binary name to test
+    #[arg(long)]
+    pub binary_name: Option<String>,
+    #[command(flatten)]
+    pub version: VersionArgs,
+    /// Path to the Windows binary
+    #[arg(long)]
+    pub binary_path: Option<String>,
+    /// Checksum file for the binary
+    #[arg(long)]
+    pub checksum_file: Option<String>,
+    /// Path to the MSI installer
+    #[arg(long)]
+    pub msi_path: Option<String>,
+    /// Checksum file for the MSI installer
+    #[arg(long)]
+    pub msi_checksum_file: Option<String>,
+    /// Download the artifacts from the GitHub release
+    #[arg(long)]
+    pub download_from_release: bool,
+}
+
+impl ToSettings for TestWindowsArgs {
+    fn push_settings(&self, settings: &mut Settings) {
+        push(settings, "BINARY_NAME", &self.binary_name);
+        self.version.push_settings(settings);
+        push(settings, "BINARY_PATH", &self.binary_path);
+        push(settings, "CHECKSUM_FILE", &self.checksum_file);
+        push(settings, "MSI_PATH", &self.msi_path);
+        push(settings, "MSI_CHECKSUM_FILE", &self.msi_checksum_file);
+        push_flag(
+            settings,
+            "DOWNLOAD_FROM_RELEASE",
+            self.download_from_release,
+        );
+    }
+}
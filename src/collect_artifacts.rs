@@ -1,5 +1,8 @@
-use crate::checksum::sha256_file;
-use crate::env_or;
+use crate::DEFAULT_ARTIFACTS_DIR;
+use crate::checksum::{
+    ChecksumAlgorithm, Checksums, hash_file, parse_checksum_algorithms, parse_checksum_file,
+};
+use crate::config::ReleaseConfig;
 use crate::error::{Error, Result};
 use crate::output::{output, output_multiline};
 use crate::platform::detect_platform_short;
@@ -10,29 +13,20 @@ use std::io::Write;
 use std::path::Path;
 
 #[derive(Debug, Serialize)]
-struct ArtifactEntry {
-    artifact: String,
-    path: String,
-    sha256: String,
-    platform: String,
-    url: String,
+pub struct ArtifactEntry {
+    pub artifact: String,
+    pub path: String,
+    #[serde(flatten)]
+    pub checksums: Checksums,
+    pub platform: String,
+    pub url: String,
 }
 
-pub fn run_collect_artifacts() -> Result<()> {
-    let artifacts_dir = env_or("ARTIFACTS_DIR", "artifacts");
-    let base_url = env_or("BASE_URL", "");
-    let artifacts_path = Path::new(&artifacts_dir);
-    if !artifacts_path.exists() {
-        return Err(Error::User(format!(
-            "artifacts directory not found: {artifacts_dir}"
-        )));
-    }
-
-    println!("\x1b[32mCollecting artifacts from:\x1b[0m {artifacts_dir}");
-
-    let archive_re = Regex::new(r"\.(tar\.gz|zip|dmg|msi|deb|rpm|apk)$").unwrap();
+/// Lists release artifacts (archives and packages) in `dir`, sorted by name.
+pub fn find_artifacts(dir: &Path) -> Vec<String> {
+    let archive_re = Regex::new(r"\.(tar\.gz|tar\.xz|tar\.zst|zip|dmg|msi|deb|rpm|apk)$").unwrap();
     let mut artifact_names: Vec<String> = Vec::new();
-    if let Ok(entries) = fs::read_dir(artifacts_path) {
+    if let Ok(entries) = fs::read_dir(dir) {
         for entry in entries.flatten() {
             let path = entry.path();
             if !path.is_file() {
@@ -48,20 +42,58 @@ pub fn run_collect_artifacts() -> Result<()> {
             }
         }
     }
+    artifact_names.sort();
+    artifact_names
+}
 
-    if artifact_names.is_empty() {
-        return Err(Error::User(format!(
-            "no artifacts found in {artifacts_dir}"
-        )));
+/// Returns SHA-256 plus the algorithms requested via CHECKSUM and those of any
+/// `<artifact>.<algorithm>` sidecar found next to the artifacts.
+pub fn detect_algorithms(
+    dir: &Path,
+    artifacts: &[String],
+    requested: &str,
+) -> Result<Vec<ChecksumAlgorithm>> {
+    let mut selected = parse_checksum_algorithms(requested)?;
+    for artifact in artifacts {
+        for algorithm in ChecksumAlgorithm::ALL {
+            if dir
+                .join(format!("{artifact}.{}", algorithm.name()))
+                .is_file()
+            {
+                selected.push(algorithm);
+            }
+        }
     }
+    Ok(ChecksumAlgorithm::ALL
+        .into_iter()
+        .filter(|a| *a == ChecksumAlgorithm::Sha256 || selected.contains(a))
+        .collect())
+}
 
-    artifact_names.sort();
-
-    let mut collection: Vec<ArtifactEntry> = Vec::new();
-    for artifact in &artifact_names {
-        let artifact_path = format!("{artifacts_dir}/{artifact}");
-        let sha256 = sha256_file(Path::new(&artifact_path))?;
-        let platform = detect_platform_short(artifact).to_string();
+/// Hashes every artifact once with all `algorithms`, checking existing sidecars.
+pub fn collect_artifacts(
+    dir: &Path,
+    artifacts: &[String],
+    algorithms: &[ChecksumAlgorithm],
+    base_url: &str,
+) -> Result<Vec<ArtifactEntry>> {
+    let mut collection = Vec::new();
+    for artifact in artifacts {
+        let artifact_path = dir.join(artifact);
+        let mut checksums = Checksums::default();
+        for (algorithm, hash) in hash_file(&artifact_path, algorithms)? {
+            let sidecar = dir.join(format!("{artifact}.{}", algorithm.name()));
+            if sidecar.is_file() {
+                let expected = parse_checksum_file(&sidecar)?;
+                if !expected.eq_ignore_ascii_case(&hash) {
+                    return Err(Error::User(format!(
+                        "checksum mismatch for {artifact}: {} expected {expected}, got {hash}",
+                        sidecar.display()
+                    )));
+                }
+            }
+            checksums.set(algorithm, hash);
+        }
         let url = if !base_url.is_empty() {
             format!("{base_url}/{artifact}")
         } else {
@@ -70,12 +102,61 @@ pub fn run_collect_artifacts() -> Result<()> {
 
         collection.push(ArtifactEntry {
             artifact: artifact.clone(),
-            path: artifact_path,
-            sha256,
-            platform,
+            path: format!("{}/{artifact}", dir.display()),
+            checksums,
+            platform: detect_platform_short(artifact).to_string(),
             url,
         });
     }
+    Ok(collection)
+}
+
+/// Writes a `<ALGORITHM>SUMS` manifest per algorithm and returns their paths.
+pub fn write_manifests(
+    dir: &Path,
+    collection: &[ArtifactEntry],
+    algorithms: &[ChecksumAlgorithm],
+) -> Result<Vec<String>> {
+    let mut paths = Vec::new();
+    for algorithm in algorithms {
+        let content: String = collection
+            .iter()
+            .map(|a| format!("{}  {}", a.checksums.get(*algorithm), a.artifact))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let path = format!("{}/{}", dir.display(), algorithm.manifest_name());
+        let mut f = fs::File::create(&path)?;
+        writeln!(f, "{content}")?;
+        paths.push(path);
+    }
+    Ok(paths)
+}
+
+pub fn run_collect_artifacts(config: &ReleaseConfig) -> Result<()> {
+    let artifacts_dir = config.get("ARTIFACTS_DIR", DEFAULT_ARTIFACTS_DIR);
+    let base_url = config.get("BASE_URL", "");
+    let artifacts_path = Path::new(&artifacts_dir);
+    if !artifacts_path.exists() {
+        return Err(Error::User(format!(
+            "artifacts directory not found: {artifacts_dir}"
+        )));
+    }
+
+    println!("\x1b[32mCollecting artifacts from:\x1b[0m {artifacts_dir}");
+
+    let artifact_names = find_artifacts(artifacts_path);
+    if artifact_names.is_empty() {
+        return Err(Error::User(format!(
+            "no artifacts found in {artifacts_dir}"
+        )));
+    }
+
+    let algorithms = detect_algorithms(
+        artifacts_path,
+        &artifact_names,
+        &config.get("CHECKSUM", "sha256"),
+    )?;
+    let collection = collect_artifacts(artifacts_path, &artifact_names, &algorithms, &base_url)?;
 
     println!("\x1b[32mFound:\x1b[0m {} artifacts", collection.len());
     for a in &collection {
@@ -86,13 +167,19 @@ pub fn run_collect_artifacts() -> Result<()> {
     for (platform, prefix) in &[
         ("macos-arm64", "macos_arm64"),
         ("macos-x64", "macos_x64"),
+        ("macos-universal", "macos_universal"),
         ("linux-arm64", "linux_arm64"),
         ("linux-x64", "linux_x64"),
         ("windows-x64", "windows_x64"),
         ("windows-arm64", "windows_arm64"),
     ] {
         if let Some(a) = collection.iter().find(|a| a.platform == *platform) {
-            output(&format!("{prefix}_sha256"), &a.sha256);
+            for algorithm in &algorithms {
+                output(
+                    &format!("{prefix}_{}", algorithm.output_key()),
+                    a.checksums.get(*algorithm),
+                );
+            }
             output(&format!("{prefix}_url"), &a.url);
             output(&format!("{prefix}_artifact"), &a.artifact);
         }
@@ -102,17 +189,12 @@ pub fn run_collect_artifacts() -> Result<()> {
     let json = serde_json::to_string_pretty(&collection)?;
     output_multiline("collection", &json);
 
-    // Generate consolidated checksums file
-    let checksums_content: String = collection
-        .iter()
-        .map(|a| format!("{}  {}", a.sha256, a.artifact))
-        .collect::<Vec<_>>()
-        .join("\n");
-
-    let checksums_path = format!("{artifacts_dir}/SHA256SUMS");
-    let mut f = fs::File::create(&checksums_path)?;
-    writeln!(f, "{checksums_content}")?;
-    println!("\x1b[32mCreated:\x1b[0m {checksums_path}");
-    output("checksums_file", &checksums_path);
+    // Generate consolidated checksums files
+    let manifests = write_manifests(artifacts_path, &collection, &algorithms)?;
+    for path in &manifests {
+        println!("\x1b[32mCreated:\x1b[0m {path}");
+    }
+    output("checksums_file", &manifests[0]);
+    output("checksums_files", &manifests.join(","));
     Ok(())
 }
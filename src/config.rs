@@ -0,0 +1,395 @@
+use crate::archive::ArchiveFormat;
+use crate::build::{BinaryPackaging, BuildTool};
+use crate::check_sbom::parse_license_expression;
+use crate::checksum::ChecksumAlgorithm;
+use crate::error::{Error, Result};
+use crate::plan::PlanFormat;
+use crate::sign::SignMethod;
+use crate::split_src_dst;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::{env, fs};
+
+/// Default name of the release config file, looked up next to the manifest.
+pub const CONFIG_FILE_NAME: &str = "release.toml";
+
+const SBOM_FORMATS: &[&str] = &["spdx", "cyclonedx", "cyclonedx-1.5", "cyclonedx-1.6"];
+
+/// Declarative release settings, read from `release.toml` or
+/// `[package.metadata.release]` in Cargo.toml.
+///
+/// Every field is optional. Settings given as action inputs or command-line
+/// flags, then environment variables, take precedence over values from the
+/// file; see [`ReleaseConfig::get`].
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ReleaseConfig {
+    pub build: BuildConfig,
+    pub archive: ArchiveConfig,
+    pub checksum: Option<Vec<String>>,
+    pub changelog: ChangelogConfig,
+    pub pkg: PkgConfig,
+    pub sbom: SbomConfig,
+    pub sign: SignConfig,
+    pub homebrew: HomebrewConfig,
+    pub aur: AurConfig,
+    pub winget: WingetConfig,
+    pub plan: PlanConfig,
+    /// Settings applied with [`ReleaseConfig::set`], keyed like environment variables.
+    #[serde(skip)]
+    overrides: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct BuildConfig {
+    pub target: Option<String>,
+    pub package: Option<String>,
+    pub binary_name: Option<String>,
+    pub binaries: Option<Vec<String>>,
+    pub packages: Option<Vec<String>>,
+    pub binary_packaging: Option<String>,
+    pub features: Option<Vec<String>>,
+    pub no_default_features: Option<bool>,
+    pub profile: Option<String>,
+    pub locked: Option<bool>,
+    pub rustflags: Option<String>,
+    pub use_zigbuild: Option<bool>,
+    pub tool: Option<String>,
+    pub pre_build: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ArchiveConfig {
+    pub enabled: Option<bool>,
+    pub format: Option<String>,
+    pub top_level_dir: Option<bool>,
+    pub include: Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct ChangelogConfig {
+    pub path: Option<String>,
+    pub notes_output: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct PkgConfig {
+    pub description: Option<String>,
+    pub maintainer: Option<String>,
+    pub homepage: Option<String>,
+    pub license: Option<String>,
+    pub vendor: Option<String>,
+    pub depends: Option<Vec<String>>,
+    pub recommends: Option<Vec<String>>,
+    pub suggests: Option<Vec<String>>,
+    pub conflicts: Option<Vec<String>>,
+    pub replaces: Option<Vec<String>>,
+    pub provides: Option<Vec<String>>,
+    pub contents: Option<Vec<String>>,
+    pub section: Option<String>,
+    pub priority: Option<String>,
+    pub group: Option<String>,
+    pub release: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct SbomConfig {
+    pub format: Option<Vec<String>>,
+    pub dir: Option<String>,
+    pub per_artifact: Option<bool>,
+    pub license_allow: Option<Vec<String>>,
+    pub license_deny: Option<Vec<String>>,
+}
+
+/// Signing settings. Keys and passphrases are deliberately not accepted here:
+/// they must come from secrets.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct SignConfig {
+    pub method: Option<String>,
+    pub gpg_key_id: Option<String>,
+    pub cosign_tlog_upload: Option<bool>,
+    pub manifests: Option<bool>,
+    pub public_key: Option<String>,
+    pub certificate_identity: Option<String>,
+    pub certificate_oidc_issuer: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct HomebrewConfig {
+    pub class: Option<String>,
+    pub copyright: Option<String>,
+    pub dir: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct AurConfig {
+    pub name: Option<String>,
+    pub maintainer: Option<String>,
+    pub makedepends: Option<Vec<String>>,
+    pub optdepends: Option<Vec<String>>,
+    pub dir: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct WingetConfig {
+    pub publisher: Option<String>,
+    pub publisher_id: Option<String>,
+    pub package_id: Option<String>,
+    pub license_url: Option<String>,
+    pub copyright: Option<String>,
+    pub tags: Option<Vec<String>>,
+    pub dir: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct PlanConfig {
+    pub targets: Option<Vec<String>>,
+    pub formats: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+struct ManifestWithMetadata {
+    package: Option<ManifestPackage>,
+}
+
+#[derive(Deserialize)]
+struct ManifestPackage {
+    metadata: Option<ManifestMetadata>,
+}
+
+#[derive(Deserialize)]
+struct ManifestMetadata {
+    release: Option<toml::Value>,
+}
+
+fn join(list: &Option<Vec<String>>) -> Option<String> {
+    list.as_ref().map(|items| items.join(","))
+}
+
+fn flag(value: Option<bool>) -> Option<String> {
+    value.map(|b| b.to_string())
+}
+
+impl ReleaseConfig {
+    /// Parses a `release.toml` document.
+    pub fn from_toml_str(content: &str) -> Result<Self> {
+        Ok(toml::from_str(content)?)
+    }
+
+    /// Extracts `[package.metadata.release]` from a Cargo.toml document.
+    /// Returns `None` when the table is absent.
+    pub fn from_cargo_manifest_str(content: &str) -> Result<Option<Self>> {
+        let manifest: ManifestWithMetadata = toml::from_str(content)?;
+        let table = manifest
+            .package
+            .and_then(|p| p.metadata)
+            .and_then(|m| m.release);
+        match table {
+            Some(value) => Ok(Some(value.try_into()?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Loads the config for a manifest: an explicit `config_path` wins,
+    /// then `release.toml` next to the manifest, then `[package.metadata.release]`.
+    pub fn load(manifest_path: &str, config_path: &str) -> Result<Option<Self>> {
+        if !config_path.is_empty() {
+            let content = fs::read_to_string(config_path)
+                .map_err(|e| Error::User(format!("could not read {config_path}: {e}")))?;
+            return Self::from_toml_str(&content).map(Some);
+        }
+
+        let manifest_dir = Path::new(manifest_path)
+            .parent()
+            .unwrap_or_else(|| Path::new(""));
+        let release_toml = manifest_dir.join(CONFIG_FILE_NAME);
+        if release_toml.is_file() {
+            let content = fs::read_to_string(&release_toml)?;
+            return Self::from_toml_str(&content).map(Some);
+        }
+
+        match fs::read_to_string(manifest_path) {
+            Ok(content) => Self::from_cargo_manifest_str(&content),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Loads and validates the release config for the manifest named by
+    /// `MANIFEST_PATH` (or `RELEASE_CONFIG`, when set). A missing config file
+    /// yields an empty config.
+    pub fn from_env() -> Result<Self> {
+        Self::from_settings(&[])
+    }
+
+    /// Like [`ReleaseConfig::from_env`], with `settings` applied on top. They
+    /// also locate the config file, and later settings win over earlier ones.
+    pub fn from_settings(settings: &[(&str, String)]) -> Result<Self> {
+        let mut overrides = ReleaseConfig::default();
+        for (key, value) in settings {
+            overrides.set(key, value);
+        }
+        let manifest = overrides.get("MANIFEST_PATH", "Cargo.toml");
+        let config_path = overrides.get("RELEASE_CONFIG", "");
+        let mut config = Self::load(&manifest, &config_path)?.unwrap_or_default();
+        config.validate()?;
+        config.overrides = overrides.overrides;
+        Ok(config)
+    }
+
+    /// Sets `key` for this run, taking precedence over the environment and
+    /// the config file.
+    pub fn set(&mut self, key: &str, value: &str) {
+        self.overrides.insert(key.to_string(), value.to_string());
+    }
+
+    /// Validates values that cannot be checked by deserialization alone.
+    pub fn validate(&self) -> Result<()> {
+        for algorithm in self.checksum.iter().flatten() {
+            algorithm.parse::<ChecksumAlgorithm>()?;
+        }
+        for format in self.sbom.format.iter().flatten() {
+            if !SBOM_FORMATS.contains(&format.as_str()) {
+                return Err(Error::User(format!(
+                    "unsupported SBOM format in release config: {format} (expected one of: {})",
+                    SBOM_FORMATS.join(", ")
+                )));
+            }
+        }
+        for license in self
+            .sbom
+            .license_allow
+            .iter()
+            .chain(self.sbom.license_deny.iter())
+            .flatten()
+        {
+            parse_license_expression(license)?;
+        }
+        if let Some(format) = &self.archive.format {
+            format.parse::<ArchiveFormat>()?;
+        }
+        if let Some(method) = &self.sign.method {
+            method.parse::<SignMethod>()?;
+        }
+        for format in self.plan.formats.iter().flatten() {
+            format.parse::<PlanFormat>()?;
+        }
+        if let Some(tool) = &self.build.tool {
+            tool.parse::<BuildTool>()?;
+        }
+        if let Some(packaging) = &self.build.binary_packaging {
+            packaging.parse::<BinaryPackaging>()?;
+        }
+        if let Some(profile) = &self.build.profile {
+            if profile.trim().is_empty() {
+                return Err(Error::User(
+                    "build.profile in release config must not be empty".into(),
+                ));
+            }
+        }
+        for entry in self.pkg.contents.iter().flatten() {
+            if !matches!(split_src_dst(entry), (src, Some(dst)) if !src.is_empty() && !dst.is_empty())
+            {
+                return Err(Error::User(format!(
+                    "invalid pkg.contents entry in release config: '{entry}' (expected src:dst)"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the value for a setting, keyed by the environment variable
+    /// name the command handlers read. Lists are joined with commas.
+    pub fn lookup(&self, key: &str) -> Option<String> {
+        match key {
+            "TARGET" => self.build.target.clone(),
+            "PACKAGE" => self.build.package.clone(),
+            "BINARY_NAME" => self.build.binary_name.clone(),
+            "BINARIES" => join(&self.build.binaries),
+            "PACKAGES" => join(&self.build.packages),
+            "BINARY_PACKAGING" => self.build.binary_packaging.clone(),
+            "FEATURES" => join(&self.build.features),
+            "NO_DEFAULT_FEATURES" => flag(self.build.no_default_features),
+            "PROFILE" => self.build.profile.clone(),
+            "LOCKED" => flag(self.build.locked),
+            "TARGET_RUSTFLAGS" => self.build.rustflags.clone(),
+            "USE_ZIGBUILD" => flag(self.build.use_zigbuild),
+            "BUILD_TOOL" => self.build.tool.clone(),
+            "PRE_BUILD" => self.build.pre_build.clone(),
+            "ARCHIVE" => flag(self.archive.enabled),
+            "ARCHIVE_FORMAT" => self.archive.format.clone(),
+            "ARCHIVE_TOP_LEVEL_DIR" => flag(self.archive.top_level_dir),
+            "ARCHIVE_INCLUDE" => join(&self.archive.include),
+            "CHECKSUM" => join(&self.checksum),
+            "CHANGELOG_PATH" => self.changelog.path.clone(),
+            "OUTPUT_PATH" => self.changelog.notes_output.clone(),
+            "PKG_DESCRIPTION" => self.pkg.description.clone(),
+            "PKG_MAINTAINER" => self.pkg.maintainer.clone(),
+            "PKG_HOMEPAGE" => self.pkg.homepage.clone(),
+            "PKG_LICENSE" => self.pkg.license.clone(),
+            "PKG_VENDOR" => self.pkg.vendor.clone(),
+            "PKG_DEPENDS" => join(&self.pkg.depends),
+            "PKG_RECOMMENDS" => join(&self.pkg.recommends),
+            "PKG_SUGGESTS" => join(&self.pkg.suggests),
+            "PKG_CONFLICTS" => join(&self.pkg.conflicts),
+            "PKG_REPLACES" => join(&self.pkg.replaces),
+            "PKG_PROVIDES" => join(&self.pkg.provides),
+            "PKG_CONTENTS" => join(&self.pkg.contents),
+            "PKG_SECTION" => self.pkg.section.clone(),
+            "PKG_PRIORITY" => self.pkg.priority.clone(),
+            "PKG_GROUP" => self.pkg.group.clone(),
+            "PKG_RELEASE" => self.pkg.release.clone(),
+            "SBOM_FORMAT" => join(&self.sbom.format),
+            "SBOM_OUTPUT_DIR" => self.sbom.dir.clone(),
+            "SBOM_PER_ARTIFACT" => flag(self.sbom.per_artifact),
+            "SBOM_LICENSE_ALLOW" => join(&self.sbom.license_allow),
+            "SBOM_LICENSE_DENY" => join(&self.sbom.license_deny),
+            "SIGN_METHOD" => self.sign.method.clone(),
+            "GPG_KEY_ID" => self.sign.gpg_key_id.clone(),
+            "COSIGN_TLOG_UPLOAD" => flag(self.sign.cosign_tlog_upload),
+            "SIGN_MANIFESTS" => flag(self.sign.manifests),
+            "PUBLIC_KEY" => self.sign.public_key.clone(),
+            "CERTIFICATE_IDENTITY" => self.sign.certificate_identity.clone(),
+            "CERTIFICATE_OIDC_ISSUER" => self.sign.certificate_oidc_issuer.clone(),
+            "HOMEBREW_FORMULA_CLASS" => self.homebrew.class.clone(),
+            "HOMEBREW_COPYRIGHT" => self.homebrew.copyright.clone(),
+            "HOMEBREW_OUTPUT_DIR" => self.homebrew.dir.clone(),
+            "AUR_PACKAGE_NAME" => self.aur.name.clone(),
+            "AUR_MAINTAINER" => self.aur.maintainer.clone(),
+            "AUR_MAKEDEPENDS" => join(&self.aur.makedepends),
+            "AUR_OPTDEPENDS" => join(&self.aur.optdepends),
+            "AUR_OUTPUT_DIR" => self.aur.dir.clone(),
+            "WINGET_PUBLISHER" => self.winget.publisher.clone(),
+            "WINGET_PUBLISHER_ID" => self.winget.publisher_id.clone(),
+            "WINGET_PACKAGE_ID" => self.winget.package_id.clone(),
+            "WINGET_LICENSE_URL" => self.winget.license_url.clone(),
+            "WINGET_COPYRIGHT" => self.winget.copyright.clone(),
+            "WINGET_TAGS" => join(&self.winget.tags),
+            "WINGET_OUTPUT_DIR" => self.winget.dir.clone(),
+            "PLAN_TARGETS" => join(&self.plan.targets),
+            "PLAN_FORMATS" => join(&self.plan.formats),
+            _ => None,
+        }
+    }
+
+    /// Reads a setting: a value applied with [`ReleaseConfig::set`] wins,
+    /// then the environment variable `key`, then this config, then `default`.
+    pub fn get(&self, key: &str, default: &str) -> String {
+        self.overrides
+            .get(key)
+            .cloned()
+            .or_else(|| env::var(key).ok())
+            .or_else(|| self.lookup(key))
+            .unwrap_or_else(|| default.to_string())
+    }
+}
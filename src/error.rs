@@ -14,6 +14,12 @@ pub enum Error {
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
 
+    #[error("zip error: {0}")]
+    Zip(#[from] zip::result::ZipError),
+
+    #[error("minisign error: {0}")]
+    Minisign(#[from] minisign::PError),
+
     #[error("command failed: {command}\n{stderr}")]
     Command { command: String, stderr: String },
 }
@@ -1,4 +1,5 @@
-use crate::env_or;
+use crate::DEFAULT_ARTIFACTS_DIR;
+use crate::config::ReleaseConfig;
 use crate::error::{Error, Result};
 use crate::output::{output, output_multiline, print_hr};
 use crate::platform::detect_platform_display;
@@ -25,12 +26,15 @@ struct ArtifactInfo {
     platform: &'static str,
 }
 
-/// Lists release artifacts (excludes checksums, signatures, SBOM, and metadata).
-fn list_release_artifacts(dir: &Path) -> Vec<ArtifactInfo> {
-    let exclude =
-        Regex::new(r"\.(sha256|sha512|b2|sig|pem|sigstore\.json|spdx\.json|cdx\.json)$").unwrap();
+/// Matches files written next to artifacts: checksums, signatures and SBOMs.
+pub const SIDECAR_PATTERN: &str = r"\.(sha256|sha512|b2|sha3-256|b3|sig|asc|minisig|pem|sigstore\.json|spdx\.json|cdx\.json|intoto\.json)$";
+
+/// Lists the names of release artifacts in `dir`, sorted (excludes checksums,
+/// signatures, SBOM, and metadata).
+pub fn release_artifact_names(dir: &Path) -> Vec<String> {
+    let exclude = Regex::new(SIDECAR_PATTERN).unwrap();
 
-    let mut artifacts = Vec::new();
+    let mut names = Vec::new();
     if let Ok(entries) = fs::read_dir(dir) {
         for entry in entries.flatten() {
             let path = entry.path();
@@ -42,19 +46,28 @@ fn list_release_artifacts(dir: &Path) -> Vec<ArtifactInfo> {
                 .unwrap_or_default()
                 .to_string_lossy()
                 .to_string();
-            if exclude.is_match(&name) {
-                continue;
+            if !exclude.is_match(&name) {
+                names.push(name);
             }
-            let size = path.metadata().map(|m| m.len()).unwrap_or(0);
-            artifacts.push(ArtifactInfo {
-                name: name.clone(),
-                size: format_size(size),
-                platform: detect_platform_display(&name),
-            });
         }
     }
-    artifacts.sort_by(|a, b| a.name.cmp(&b.name));
-    artifacts
+    names.sort();
+    names
+}
+
+/// Lists release artifacts with their sizes and platforms.
+fn list_release_artifacts(dir: &Path) -> Vec<ArtifactInfo> {
+    release_artifact_names(dir)
+        .into_iter()
+        .map(|name| {
+            let size = dir.join(&name).metadata().map(|m| m.len()).unwrap_or(0);
+            ArtifactInfo {
+                size: format_size(size),
+                platform: detect_platform_display(&name),
+                name,
+            }
+        })
+        .collect()
 }
 
 /// Formats artifacts as a Markdown table.
@@ -126,9 +139,50 @@ fn format_sbom_section(dir: &Path) -> String {
     section
 }
 
+/// Formats verification instructions for every kind of signature present.
+pub fn format_signatures_section(dir: &Path) -> String {
+    let names: Vec<String> = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|e| e.file_name().to_string_lossy().to_string())
+        .collect();
+    let has = |re: &str| {
+        let re = Regex::new(re).unwrap();
+        names.iter().any(|n| re.is_match(n))
+    };
+
+    let mut section =
+        "\n## Signatures\n\nAll release artifacts are signed. Verify with:\n\n".to_string();
+    let mut has_any = false;
+
+    if has(r"\.(sig|pem|sigstore\.json)$") {
+        has_any = true;
+        section.push_str("**[Sigstore](https://www.sigstore.dev/):**\n```bash\n");
+        section.push_str("cosign verify-blob --bundle <artifact>.sigstore.json <artifact>\n");
+        section.push_str("```\n\n");
+    }
+
+    if has(r"\.asc$") {
+        has_any = true;
+        section.push_str("**GPG:**\n```bash\n");
+        section.push_str("gpg --verify <artifact>.asc <artifact>\n");
+        section.push_str("```\n\n");
+    }
+
+    if has(r"\.minisig$") {
+        has_any = true;
+        section.push_str("**[minisign](https://jedisct1.github.io/minisign/):**\n```bash\n");
+        section.push_str("minisign -Vm <artifact> -P <public key>\n");
+        section.push_str("```\n\n");
+    }
+
+    if has_any { section } else { String::new() }
+}
+
 /// Collects all checksum file contents.
 fn collect_checksums(dir: &Path) -> String {
-    let checksum_re = Regex::new(r"\.(sha256|sha512|b2)$").unwrap();
+    let checksum_re = Regex::new(r"\.(sha256|sha512|b2|sha3-256|b3)$").unwrap();
     let mut files = Vec::new();
     if let Ok(entries) = fs::read_dir(dir) {
         for entry in entries.flatten() {
@@ -157,20 +211,20 @@ fn collect_checksums(dir: &Path) -> String {
     checksums
 }
 
-pub fn run_format_release() -> Result<()> {
-    let version = env_or("VERSION", "");
+pub fn run_format_release(config: &ReleaseConfig) -> Result<()> {
+    let version = config.get("VERSION", "");
     if version.is_empty() {
         return Err(Error::User("VERSION is required".into()));
     }
 
-    let artifacts_dir = env_or("ARTIFACTS_DIR", "release");
-    let release_notes_file = env_or("RELEASE_NOTES_FILE", "release_notes.md");
-    let include_checksums = env_or("INCLUDE_CHECKSUMS", "true") == "true";
-    let include_signatures = env_or("INCLUDE_SIGNATURES", "true") == "true";
+    let artifacts_dir = config.get("ARTIFACTS_DIR", DEFAULT_ARTIFACTS_DIR);
+    let release_notes_file = config.get("RELEASE_NOTES_FILE", "release_notes.md");
+    let include_checksums = config.get("INCLUDE_CHECKSUMS", "true") == "true";
+    let include_signatures = config.get("INCLUDE_SIGNATURES", "true") == "true";
 
-    let homebrew_tap = env_or("HOMEBREW_TAP", "");
-    let aur_package = env_or("AUR_PACKAGE", "");
-    let winget_id = env_or("WINGET_ID", "");
+    let homebrew_tap = config.get("HOMEBREW_TAP", "");
+    let aur_package = config.get("AUR_PACKAGE", "");
+    let winget_id = config.get("WINGET_ID", "");
 
     println!("\x1b[32mFormatting release:\x1b[0m v{version}");
 
@@ -220,22 +274,7 @@ pub fn run_format_release() -> Result<()> {
 
         // 6. Signatures section
         if include_signatures {
-            let sig_re = Regex::new(r"\.(sig|pem|sigstore\.json)$").unwrap();
-            let has_sigs = fs::read_dir(artifacts_path)
-                .into_iter()
-                .flatten()
-                .flatten()
-                .any(|e| sig_re.is_match(&e.file_name().to_string_lossy()));
-            if has_sigs {
-                body.push_str("\n## Signatures\n\n");
-                body.push_str(
-                    "All release artifacts are signed with [Sigstore](https://www.sigstore.dev/). ",
-                );
-                body.push_str("Verify with:\n\n");
-                body.push_str("```bash\n");
-                body.push_str("cosign verify-blob --bundle <artifact>.sigstore.json <artifact>\n");
-                body.push_str("```\n");
-            }
+            body.push_str(&format_signatures_section(artifacts_path));
         }
     }
 
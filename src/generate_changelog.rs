@@ -0,0 +1,329 @@
+use crate::changelog::{ChangeKind, Changelog, ChangelogStyle, release_tag};
+use crate::config::ReleaseConfig;
+use crate::env_or;
+use crate::error::{Error, Result};
+use crate::output::{output, output_multiline, print_hr};
+use crate::timestamp::today;
+use crate::tools::run_command;
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::LazyLock;
+
+/// Commit types left out of generated changelogs: they do not affect users.
+pub const HIDDEN_COMMIT_TYPES: &[&str] = &["chore", "ci", "build", "test", "tests", "style"];
+
+static CONVENTIONAL_SUBJECT: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?P<kind>[A-Za-z]+)(?:\((?P<scope>[^()]*)\))?(?P<bang>!)?:\s+(?P<desc>.+)$")
+        .unwrap()
+});
+static BREAKING_FOOTER: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(?m)^BREAKING[ -]CHANGE:\s").unwrap());
+static PULL_REQUEST_REF: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"(^|[\s(\[])#(\d+)\b").unwrap());
+static LINK_DEFINITION: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^[ \t]{0,3}\[([^\]]+)\]:\s*\S").unwrap());
+
+/// A commit read from `git log`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Commit {
+    pub hash: String,
+    pub subject: String,
+    pub body: String,
+}
+
+/// A commit message in the `type(scope)!: description` format.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConventionalCommit {
+    pub kind: String,
+    pub scope: Option<String>,
+    pub breaking: bool,
+    pub description: String,
+}
+
+impl ConventionalCommit {
+    /// Parses the subject line. A `BREAKING CHANGE:` (or `BREAKING-CHANGE:`)
+    /// footer in the body also marks the commit as breaking.
+    pub fn parse(subject: &str, body: &str) -> Option<Self> {
+        let caps = CONVENTIONAL_SUBJECT.captures(subject.trim())?;
+        Some(ConventionalCommit {
+            kind: caps["kind"].to_lowercase(),
+            scope: caps
+                .name("scope")
+                .map(|s| s.as_str().trim().to_string())
+                .filter(|s| !s.is_empty()),
+            breaking: caps.name("bang").is_some() || BREAKING_FOOTER.is_match(body),
+            description: caps["desc"].trim().to_string(),
+        })
+    }
+}
+
+/// A changelog entry derived from a commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangeEntry {
+    pub scope: Option<String>,
+    pub description: String,
+    pub hash: String,
+}
+
+/// Groups commits by change kind. Breaking changes are listed under
+/// [`ChangeKind::Breaking`] regardless of their type, commits that do not
+/// follow the conventional format under [`ChangeKind::Other`], and
+/// [`HIDDEN_COMMIT_TYPES`] are skipped.
+pub fn group_commits(commits: &[Commit]) -> BTreeMap<ChangeKind, Vec<ChangeEntry>> {
+    let mut groups: BTreeMap<ChangeKind, Vec<ChangeEntry>> = BTreeMap::new();
+    for commit in commits {
+        let (kind, entry) = match ConventionalCommit::parse(&commit.subject, &commit.body) {
+            Some(cc) => {
+                if !cc.breaking && HIDDEN_COMMIT_TYPES.contains(&cc.kind.as_str()) {
+                    continue;
+                }
+                let kind = if cc.breaking {
+                    ChangeKind::Breaking
+                } else {
+                    ChangeKind::from_commit_type(&cc.kind)
+                };
+                (
+                    kind,
+                    ChangeEntry {
+                        scope: cc.scope,
+                        description: cc.description,
+                        hash: commit.hash.clone(),
+                    },
+                )
+            }
+            None => (
+                ChangeKind::Other,
+                ChangeEntry {
+                    scope: None,
+                    description: commit.subject.trim().to_string(),
+                    hash: commit.hash.clone(),
+                },
+            ),
+        };
+        groups.entry(kind).or_default().push(entry);
+    }
+    groups
+}
+
+/// Turns `#123` references into links to `<repo_url>/pull/123`.
+pub fn link_pull_requests(text: &str, repo_url: &str) -> String {
+    if repo_url.is_empty() {
+        return text.to_string();
+    }
+    PULL_REQUEST_REF
+        .replace_all(text, |caps: &regex::Captures| {
+            format!("{}[#{}]({repo_url}/pull/{})", &caps[1], &caps[2], &caps[2])
+        })
+        .to_string()
+}
+
+/// Renders a changelog section for `version` from grouped commits.
+pub fn render_section(
+    version: &str,
+    date: &str,
+    groups: &BTreeMap<ChangeKind, Vec<ChangeEntry>>,
+    style: &ChangelogStyle,
+    repo_url: &str,
+) -> String {
+    let mut lines = vec![style.heading(version, date)];
+    if groups.is_empty() {
+        lines.push(String::new());
+        lines.push(format!("{}No notable changes.", style.bullet));
+    }
+    for (kind, entries) in groups {
+        lines.push(String::new());
+        lines.push(format!("### {}", kind.heading()));
+        lines.push(String::new());
+        for entry in entries {
+            let text = match &entry.scope {
+                Some(scope) => format!("**{scope}:** {}", entry.description),
+                None => entry.description.clone(),
+            };
+            lines.push(format!(
+                "{}{}",
+                style.bullet,
+                link_pull_requests(&text, repo_url)
+            ));
+        }
+    }
+    lines.join("\n")
+}
+
+/// Inserts `section` above the newest release, below an `Unreleased` section
+/// if there is one. With `link` (a `[version]: url` definition), the link is
+/// added above the existing version links.
+pub fn insert_section(
+    content: &str,
+    version: &str,
+    section: &str,
+    link: Option<&str>,
+) -> Result<String> {
+    let changelog = Changelog::parse(content);
+    if changelog.find(version).is_some() {
+        return Err(Error::User(format!(
+            "changelog already has a section for version {version}"
+        )));
+    }
+
+    let mut lines: Vec<String> = content.lines().map(String::from).collect();
+    let insert_at = match changelog.sections.iter().find(|s| !s.is_unreleased()) {
+        Some(release) => release.line - 1,
+        None => match changelog.sections.last() {
+            Some(last) => last.body.last().map_or(last.line, |(n, _)| *n),
+            None => lines
+                .iter()
+                .position(|l| l.starts_with("# "))
+                .map_or(0, |title| title + 1),
+        },
+    };
+
+    if let Some(link) = link {
+        let position = lines.iter().position(|l| {
+            LINK_DEFINITION
+                .captures(l)
+                .is_some_and(|caps| !caps[1].eq_ignore_ascii_case("unreleased"))
+        });
+        if let Some(position) = position {
+            lines.insert(position, link.to_string());
+        }
+    }
+
+    let mut block: Vec<String> = section.lines().map(String::from).collect();
+    if insert_at < lines.len() {
+        block.push(String::new());
+    }
+    if insert_at > 0
+        && lines
+            .get(insert_at - 1)
+            .is_some_and(|l| !l.trim().is_empty())
+    {
+        block.insert(0, String::new());
+    }
+    let tail = lines.split_off(insert_at.min(lines.len()));
+    lines.extend(block);
+    lines.extend(tail);
+
+    let mut updated = lines.join("\n");
+    updated.push('\n');
+    Ok(updated)
+}
+
+/// The most recent tag reachable from the parent of `to`, i.e. the previous
+/// release when `to` is itself tagged.
+pub fn previous_tag(to: &str) -> Option<String> {
+    let output = run_command(
+        "git",
+        &["describe", "--tags", "--abbrev=0", &format!("{to}^")],
+    )
+    .ok()?;
+    let tag = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    (!tag.is_empty()).then_some(tag)
+}
+
+/// Reads non-merge commits in `from..to` (the whole history of `to` without `from`).
+pub fn read_commits(from: Option<&str>, to: &str) -> Result<Vec<Commit>> {
+    let range = match from {
+        Some(from) => format!("{from}..{to}"),
+        None => to.to_string(),
+    };
+    let output = run_command(
+        "git",
+        &["log", "--no-merges", "--format=%H%x1f%s%x1f%b%x1e", &range],
+    )?;
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .split('\x1e')
+        .filter_map(|record| {
+            let mut fields = record.trim_start_matches('\n').splitn(3, '\x1f');
+            let hash = fields.next()?.trim();
+            if hash.is_empty() {
+                return None;
+            }
+            Some(Commit {
+                hash: hash.to_string(),
+                subject: fields.next().unwrap_or_default().to_string(),
+                body: fields.next().unwrap_or_default().trim().to_string(),
+            })
+        })
+        .collect())
+}
+
+/// `https://github.com/<owner>/<repo>`, from GITHUB_SERVER_URL and GITHUB_REPOSITORY.
+fn repository_url() -> String {
+    let repository = env_or("GITHUB_REPOSITORY", "");
+    if repository.is_empty() {
+        return String::new();
+    }
+    format!(
+        "{}/{repository}",
+        env_or("GITHUB_SERVER_URL", "https://github.com").trim_end_matches('/')
+    )
+}
+
+pub fn run_generate_changelog(config: &ReleaseConfig) -> Result<()> {
+    let version = config.get("VERSION", "");
+    if version.is_empty() {
+        return Err(Error::User(
+            "VERSION environment variable is required".to_string(),
+        ));
+    }
+
+    let to = config.get("CHANGELOG_TO_REF", "HEAD");
+    let from = match config.get("CHANGELOG_FROM_REF", "") {
+        from if from.is_empty() => previous_tag(&to),
+        from => Some(from),
+    };
+    let commits = read_commits(from.as_deref(), &to)?;
+    let groups = group_commits(&commits);
+
+    let changelog_path = config.get("CHANGELOG_PATH", "CHANGELOG.md");
+    let existing = if Path::new(&changelog_path).exists() {
+        fs::read_to_string(&changelog_path)?
+    } else {
+        String::new()
+    };
+    let style = ChangelogStyle::detect(&existing);
+    let repo_url = repository_url();
+    let date = today();
+    let section = render_section(&version, &date, &groups, &style, &repo_url);
+
+    println!(
+        "\x1b[32mChanges since {}:\x1b[0m {} commits",
+        from.as_deref().unwrap_or("the first commit"),
+        commits.len()
+    );
+    print_hr();
+    println!("{section}");
+    print_hr();
+
+    if config.get("CHANGELOG_WRITE", "false") == "true" {
+        let link = match (&from, style.keep_a_changelog && !repo_url.is_empty()) {
+            (Some(from), true) => Some(format!(
+                "[{version}]: {repo_url}/compare/{from}...{}",
+                release_tag(from, &version)
+            )),
+            _ => None,
+        };
+        let existing = if existing.is_empty() {
+            "# Changelog\n".to_string()
+        } else {
+            existing
+        };
+        let updated = insert_section(&existing, &version, &section, link.as_deref())?;
+        fs::write(&changelog_path, updated)?;
+        println!("\x1b[32mUpdated\x1b[0m {changelog_path} with v{version}");
+        output("changelog_file", &changelog_path);
+    } else {
+        let output_path = config.get("OUTPUT_PATH", "release_notes.md");
+        fs::write(&output_path, &section)?;
+        println!("\x1b[32mWrote\x1b[0m release notes for v{version} to {output_path}");
+        output("release_notes_file", &output_path);
+    }
+
+    output("version", &version);
+    output("previous_tag", from.as_deref().unwrap_or_default());
+    output("commit_count", &commits.len().to_string());
+    output_multiline("release_notes", &section);
+    Ok(())
+}
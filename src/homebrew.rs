@@ -1,7 +1,8 @@
 use crate::cargo_info::get_cargo_info;
-use crate::env_or;
+use crate::config::ReleaseConfig;
 use crate::error::{Error, Result};
 use crate::output::{output, output_multiline, print_hr};
+use crate::single_line;
 use std::fs;
 
 /// Converts a binary name to a Ruby class name (e.g., my-tool -> MyTool).
@@ -34,21 +35,34 @@ pub struct FormulaConfig {
     pub macos_arm64_sha256: String,
     pub macos_x64_url: String,
     pub macos_x64_sha256: String,
+    /// A universal macOS binary; replaces the per-architecture macOS URLs.
+    pub macos_universal_url: String,
+    pub macos_universal_sha256: String,
     pub linux_arm64_url: String,
     pub linux_arm64_sha256: String,
     pub linux_x64_url: String,
     pub linux_x64_sha256: String,
 }
 
+/// Quotes `value` as a single-line Ruby double-quoted string, escaping
+/// `#` so that `#{...}` is not interpolated.
+fn ruby_string(value: &str) -> String {
+    let escaped = single_line(value)
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('#', "\\#");
+    format!("\"{escaped}\"")
+}
+
 fn format_license(license: &str) -> String {
     if license.contains(" OR ") {
         let quoted: Vec<String> = license
             .split(" OR ")
-            .map(|p| format!("\"{}\"", p.trim()))
+            .map(|p| ruby_string(p.trim()))
             .collect();
         format!("any_of: [{}]", quoted.join(", "))
     } else {
-        format!("\"{license}\"")
+        ruby_string(license)
     }
 }
 
@@ -80,6 +94,8 @@ pub fn generate_formula(config: &FormulaConfig) -> String {
     let has_macos_arm64 =
         !config.macos_arm64_url.is_empty() && !config.macos_arm64_sha256.is_empty();
     let has_macos_x64 = !config.macos_x64_url.is_empty() && !config.macos_x64_sha256.is_empty();
+    let has_macos_universal =
+        !config.macos_universal_url.is_empty() && !config.macos_universal_sha256.is_empty();
     let has_linux_arm64 =
         !config.linux_arm64_url.is_empty() && !config.linux_arm64_sha256.is_empty();
     let has_linux_x64 = !config.linux_x64_url.is_empty() && !config.linux_x64_sha256.is_empty();
@@ -87,15 +103,16 @@ pub fn generate_formula(config: &FormulaConfig) -> String {
     let mut formula = String::new();
 
     if !config.copyright.is_empty() {
-        formula.push_str(&MIT_LICENSE_HEADER.replace("{COPYRIGHT}", &config.copyright));
+        formula
+            .push_str(&MIT_LICENSE_HEADER.replace("{COPYRIGHT}", &single_line(&config.copyright)));
         formula.push('\n');
     }
 
     formula.push_str(&format!("class {} < Formula\n", config.class));
-    formula.push_str(&format!("  desc \"{}\"\n", config.description));
+    formula.push_str(&format!("  desc {}\n", ruby_string(&config.description)));
 
     if !config.homepage.is_empty() {
-        formula.push_str(&format!("  homepage \"{}\"\n", config.homepage));
+        formula.push_str(&format!("  homepage {}\n", ruby_string(&config.homepage)));
     }
 
     formula.push_str(&format!("  version \"{}\"\n", config.version));
@@ -107,7 +124,15 @@ pub fn generate_formula(config: &FormulaConfig) -> String {
     formula.push('\n');
 
     // macOS section
-    if has_macos_arm64 || has_macos_x64 {
+    if has_macos_universal {
+        formula.push_str("  on_macos do\n");
+        formula.push_str(&format!("    url \"{}\"\n", config.macos_universal_url));
+        formula.push_str(&format!(
+            "    sha256 \"{}\"\n",
+            config.macos_universal_sha256
+        ));
+        formula.push_str("  end\n\n");
+    } else if has_macos_arm64 || has_macos_x64 {
         formula.push_str("  on_macos do\n");
         if has_macos_arm64 {
             formula.push_str("    on_arm do\n");
@@ -157,10 +182,10 @@ pub fn generate_formula(config: &FormulaConfig) -> String {
     formula
 }
 
-pub fn run_generate_homebrew() -> Result<()> {
-    let info = get_cargo_info()?;
-    let binary_name = env_or("BINARY_NAME", &info.name);
-    let version = env_or("VERSION", &info.version);
+pub fn run_generate_homebrew(config: &ReleaseConfig) -> Result<()> {
+    let info = get_cargo_info(config)?;
+    let binary_name = config.get("BINARY_NAME", &info.name);
+    let version = config.get("VERSION", &info.version);
 
     if binary_name.is_empty() {
         return Err(Error::User("could not determine binary name".into()));
@@ -169,35 +194,37 @@ pub fn run_generate_homebrew() -> Result<()> {
         return Err(Error::User("could not determine version".into()));
     }
 
-    let formula_class = env_or("HOMEBREW_FORMULA_CLASS", &to_class_name(&binary_name));
-    let description = env_or(
+    let formula_class = config.get("HOMEBREW_FORMULA_CLASS", &to_class_name(&binary_name));
+    let description = config.get(
         "PKG_DESCRIPTION",
-        &format!("{binary_name} - built with rust-build-package-release-action"),
+        &info.description_or_default(&binary_name),
     );
 
-    let config = FormulaConfig {
+    let formula_config = FormulaConfig {
         class: formula_class.clone(),
         binary_name: binary_name.clone(),
         version,
         description,
-        homepage: env_or("PKG_HOMEPAGE", ""),
-        license: env_or("PKG_LICENSE", ""),
-        copyright: env_or("HOMEBREW_COPYRIGHT", ""),
-        macos_arm64_url: env_or("HOMEBREW_MACOS_ARM64_URL", ""),
-        macos_arm64_sha256: env_or("HOMEBREW_MACOS_ARM64_SHA256", ""),
-        macos_x64_url: env_or("HOMEBREW_MACOS_X64_URL", ""),
-        macos_x64_sha256: env_or("HOMEBREW_MACOS_X64_SHA256", ""),
-        linux_arm64_url: env_or("HOMEBREW_LINUX_ARM64_URL", ""),
-        linux_arm64_sha256: env_or("HOMEBREW_LINUX_ARM64_SHA256", ""),
-        linux_x64_url: env_or("HOMEBREW_LINUX_X64_URL", ""),
-        linux_x64_sha256: env_or("HOMEBREW_LINUX_X64_SHA256", ""),
+        homepage: config.get("PKG_HOMEPAGE", info.homepage_or_repository()),
+        license: config.get("PKG_LICENSE", &info.license),
+        copyright: config.get("HOMEBREW_COPYRIGHT", ""),
+        macos_arm64_url: config.get("HOMEBREW_MACOS_ARM64_URL", ""),
+        macos_arm64_sha256: config.get("HOMEBREW_MACOS_ARM64_SHA256", ""),
+        macos_x64_url: config.get("HOMEBREW_MACOS_X64_URL", ""),
+        macos_x64_sha256: config.get("HOMEBREW_MACOS_X64_SHA256", ""),
+        macos_universal_url: config.get("HOMEBREW_MACOS_UNIVERSAL_URL", ""),
+        macos_universal_sha256: config.get("HOMEBREW_MACOS_UNIVERSAL_SHA256", ""),
+        linux_arm64_url: config.get("HOMEBREW_LINUX_ARM64_URL", ""),
+        linux_arm64_sha256: config.get("HOMEBREW_LINUX_ARM64_SHA256", ""),
+        linux_x64_url: config.get("HOMEBREW_LINUX_X64_URL", ""),
+        linux_x64_sha256: config.get("HOMEBREW_LINUX_X64_SHA256", ""),
     };
 
     println!("\x1b[32mGenerating Homebrew formula:\x1b[0m {formula_class}");
 
-    let formula = generate_formula(&config);
+    let formula = generate_formula(&formula_config);
 
-    let output_dir = env_or("HOMEBREW_OUTPUT_DIR", "target/homebrew");
+    let output_dir = config.get("HOMEBREW_OUTPUT_DIR", "target/homebrew");
     fs::create_dir_all(&output_dir)?;
 
     let formula_file = format!("{output_dir}/{binary_name}.rb");
@@ -3,26 +3,40 @@ use std::env;
 pub mod archive;
 pub mod aur;
 pub mod build;
+pub mod bump_version;
 pub mod cargo_info;
 pub mod changelog;
+pub mod check_sbom;
 pub mod checksum;
+pub mod cli;
 pub mod collect_artifacts;
+pub mod config;
 pub mod download;
 pub mod error;
 pub mod format_release;
+pub mod generate_changelog;
 pub mod homebrew;
+pub mod macho;
 pub mod nfpm;
 pub mod output;
+pub mod plan;
 pub mod platform;
+pub mod provenance;
 pub mod publish;
 pub mod release;
 pub mod sbom;
 pub mod sign;
 pub mod testing;
+pub mod timestamp;
 pub mod tools;
+pub mod verify_checksums;
+pub mod verify_signature;
 pub mod version;
 pub mod winget;
 
+/// Directory commands read release artifacts from when `ARTIFACTS_DIR` is unset.
+pub const DEFAULT_ARTIFACTS_DIR: &str = "release";
+
 /// Read an environment variable with a fallback default.
 pub fn env_or(key: &str, default: &str) -> String {
     env::var(key).unwrap_or_else(|_| default.to_string())
@@ -38,3 +52,24 @@ pub fn parse_comma_list(raw: &str) -> Vec<String> {
         .filter(|s| !s.is_empty())
         .collect()
 }
+
+/// Collapse runs of whitespace, including newlines, into single spaces so a
+/// value fits on one line of a generated file.
+pub fn single_line(value: &str) -> String {
+    value.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Split a `src:dst` mapping at its last `:`, returning `dst` if present.
+/// The colon of a Windows drive letter (`C:\dist\tool`) belongs to the path.
+pub fn split_src_dst(spec: &str) -> (&str, Option<&str>) {
+    match spec.rsplit_once(':') {
+        Some((src, dst)) if !is_drive_letter(src, dst) => (src, Some(dst)),
+        _ => (spec, None),
+    }
+}
+
+fn is_drive_letter(src: &str, rest: &str) -> bool {
+    src.len() == 1
+        && src.chars().all(|c| c.is_ascii_alphabetic())
+        && (rest.starts_with('\\') || rest.starts_with('/'))
+}
@@ -0,0 +1,148 @@
+//! Mach-O universal ("fat") binaries, as produced by `lipo -create`.
+
+use crate::error::{Error, Result};
+
+pub const FAT_MAGIC: u32 = 0xcafe_babe;
+const MH_MAGIC: u32 = 0xfeed_face;
+const MH_MAGIC_64: u32 = 0xfeed_facf;
+
+pub const CPU_TYPE_X86_64: u32 = 0x0100_0007;
+pub const CPU_TYPE_ARM64: u32 = 0x0100_000c;
+
+const FAT_HEADER_SIZE: usize = 8;
+const FAT_ARCH_SIZE: usize = 20;
+
+/// A slice entry of a universal binary's header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FatArch {
+    pub cputype: u32,
+    pub cpusubtype: u32,
+    pub offset: u32,
+    pub size: u32,
+    /// Alignment of the slice as a power of two.
+    pub align: u32,
+}
+
+/// Architecture name of a CPU type, as printed by `lipo -info`.
+pub fn cpu_type_name(cputype: u32) -> &'static str {
+    match cputype {
+        CPU_TYPE_ARM64 => "arm64",
+        CPU_TYPE_X86_64 => "x86_64",
+        0x0000_0007 => "i386",
+        0x0000_000c => "arm",
+        _ => "unknown",
+    }
+}
+
+fn read_u32(data: &[u8], offset: usize, big_endian: bool) -> Option<u32> {
+    let bytes: [u8; 4] = data.get(offset..offset + 4)?.try_into().ok()?;
+    Some(if big_endian {
+        u32::from_be_bytes(bytes)
+    } else {
+        u32::from_le_bytes(bytes)
+    })
+}
+
+/// Returns the CPU type and subtype of a thin (single-architecture) Mach-O binary.
+pub fn thin_arch(data: &[u8]) -> Result<(u32, u32)> {
+    let not_macho = || Error::User("not a Mach-O binary".into());
+    if read_u32(data, 0, true) == Some(FAT_MAGIC) {
+        return Err(Error::User("binary is already universal".into()));
+    }
+    let big_endian = match read_u32(data, 0, false).ok_or_else(not_macho)? {
+        MH_MAGIC | MH_MAGIC_64 => false,
+        _ if matches!(read_u32(data, 0, true), Some(MH_MAGIC | MH_MAGIC_64)) => true,
+        _ => return Err(not_macho()),
+    };
+    let cputype = read_u32(data, 4, big_endian).ok_or_else(not_macho)?;
+    let cpusubtype = read_u32(data, 8, big_endian).ok_or_else(not_macho)?;
+    Ok((cputype, cpusubtype))
+}
+
+/// The slice alignment `lipo` uses: 16 KiB pages on arm64, 4 KiB elsewhere.
+fn slice_align(cputype: u32) -> u32 {
+    if cputype == CPU_TYPE_ARM64 { 14 } else { 12 }
+}
+
+/// Merges thin Mach-O binaries into a universal binary. Slices are ordered
+/// by alignment, like `lipo` does, and each architecture may appear once.
+pub fn create_universal(slices: &[&[u8]]) -> Result<Vec<u8>> {
+    if slices.is_empty() {
+        return Err(Error::User("no binaries to merge".into()));
+    }
+    let mut thin: Vec<(u32, u32, &[u8])> = Vec::new();
+    for data in slices {
+        let (cputype, cpusubtype) = thin_arch(data)?;
+        if thin.iter().any(|(t, _, _)| *t == cputype) {
+            return Err(Error::User(format!(
+                "more than one {} binary",
+                cpu_type_name(cputype)
+            )));
+        }
+        thin.push((cputype, cpusubtype, data));
+    }
+    thin.sort_by_key(|(cputype, _, _)| (slice_align(*cputype), *cputype));
+
+    let too_large = || Error::User("universal binary exceeds 4 GiB".into());
+    let mut archs = Vec::new();
+    let mut end = FAT_HEADER_SIZE + FAT_ARCH_SIZE * thin.len();
+    for (cputype, cpusubtype, data) in &thin {
+        let align = slice_align(*cputype);
+        let offset = end.next_multiple_of(1 << align);
+        end = offset + data.len();
+        archs.push(FatArch {
+            cputype: *cputype,
+            cpusubtype: *cpusubtype,
+            offset: u32::try_from(offset).map_err(|_| too_large())?,
+            size: u32::try_from(data.len()).map_err(|_| too_large())?,
+            align,
+        });
+    }
+    u32::try_from(end).map_err(|_| too_large())?;
+
+    let mut out = Vec::with_capacity(end);
+    out.extend_from_slice(&FAT_MAGIC.to_be_bytes());
+    out.extend_from_slice(&(archs.len() as u32).to_be_bytes());
+    for arch in &archs {
+        for field in [
+            arch.cputype,
+            arch.cpusubtype,
+            arch.offset,
+            arch.size,
+            arch.align,
+        ] {
+            out.extend_from_slice(&field.to_be_bytes());
+        }
+    }
+    for (arch, (_, _, data)) in archs.iter().zip(&thin) {
+        out.resize(arch.offset as usize, 0);
+        out.extend_from_slice(data);
+    }
+    Ok(out)
+}
+
+/// Parses the header of a universal binary and checks that every slice is in bounds.
+pub fn parse_universal(data: &[u8]) -> Result<Vec<FatArch>> {
+    let invalid = || Error::User("invalid universal binary header".into());
+    if read_u32(data, 0, true) != Some(FAT_MAGIC) {
+        return Err(Error::User("not a universal binary".into()));
+    }
+    let count = read_u32(data, 4, true).ok_or_else(invalid)? as usize;
+    (0..count)
+        .map(|i| {
+            let base = FAT_HEADER_SIZE + i * FAT_ARCH_SIZE;
+            let field = |n: usize| read_u32(data, base + n * 4, true).ok_or_else(invalid);
+            let arch = FatArch {
+                cputype: field(0)?,
+                cpusubtype: field(1)?,
+                offset: field(2)?,
+                size: field(3)?,
+                align: field(4)?,
+            };
+            if arch.offset as usize + arch.size as usize > data.len() {
+                return Err(invalid());
+            }
+            Ok(arch)
+        })
+        .collect()
+}
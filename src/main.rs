@@ -1,80 +1,59 @@
-use clap::{Parser, Subcommand};
+use clap::Parser;
+use rust_release_action::cli::{Cli, Command, Settings, ToSettings};
+use rust_release_action::config::ReleaseConfig;
 use rust_release_action::{
-    aur, changelog, collect_artifacts, format_release, homebrew, publish, release, sbom, sign,
-    testing, version, winget,
+    aur, bump_version, changelog, check_sbom, collect_artifacts, format_release,
+    generate_changelog, homebrew, plan, provenance, publish, release, sbom, sign, testing,
+    verify_checksums, verify_signature, version, winget,
 };
 use std::{env, process};
 
-#[derive(Parser)]
-#[command(name = "rust-release-action")]
-struct Cli {
-    #[command(subcommand)]
-    command: Command,
-}
-
-#[derive(Subcommand)]
-enum Command {
-    ExtractChangelog,
-    ValidateChangelog,
-    ValidateVersion,
-    GetVersion,
-    GetReleaseVersion,
-    GenerateSbom,
-    GenerateHomebrew,
-    GenerateAur,
-    GenerateWinget,
-    SignArtifact,
-    FormatRelease,
-    CollectArtifacts,
-    Release,
-    ReleaseLinux,
-    ReleaseLinuxDeb,
-    ReleaseLinuxRpm,
-    ReleaseLinuxApk,
-    ReleaseMacos,
-    ReleaseMacosDmg,
-    ReleaseWindows,
-    ReleaseWindowsMsi,
-    PublishCrate,
-    TestDeb,
-    TestRpm,
-    TestWindows,
-}
-
 fn main() {
-    // Map INPUT_* env vars to the variables expected by command handlers,
-    // matching the dispatch.nu logic.
-    map_input_env_vars();
-
     let cli = Cli::parse();
 
-    let result = match cli.command {
-        Command::ExtractChangelog => changelog::run_extract_changelog(),
-        Command::ValidateChangelog => changelog::run_validate_changelog(),
-        Command::ValidateVersion => version::run_validate_version(),
-        Command::GetVersion => version::run_get_version(),
-        Command::GetReleaseVersion => version::run_get_release_version(),
-        Command::GenerateSbom => sbom::run_generate_sbom(),
-        Command::GenerateHomebrew => homebrew::run_generate_homebrew(),
-        Command::GenerateAur => aur::run_generate_aur(),
-        Command::GenerateWinget => winget::run_generate_winget(),
-        Command::SignArtifact => sign::run_sign_artifact(),
-        Command::FormatRelease => format_release::run_format_release(),
-        Command::CollectArtifacts => collect_artifacts::run_collect_artifacts(),
-        Command::Release => release::run_release(),
-        Command::ReleaseLinux => release::run_release_linux(),
-        Command::ReleaseLinuxDeb => release::run_release_linux_deb(),
-        Command::ReleaseLinuxRpm => release::run_release_linux_rpm(),
-        Command::ReleaseLinuxApk => release::run_release_linux_apk(),
-        Command::ReleaseMacos => release::run_release_macos(),
-        Command::ReleaseMacosDmg => release::run_release_macos_dmg(),
-        Command::ReleaseWindows => release::run_release_windows(),
-        Command::ReleaseWindowsMsi => release::run_release_windows_msi(),
-        Command::PublishCrate => publish::run_publish_crate(),
-        Command::TestDeb => testing::run_test_deb(),
-        Command::TestRpm => testing::run_test_rpm(),
-        Command::TestWindows => testing::run_test_windows(),
-    };
+    // Command-line flags take precedence over action inputs, which take
+    // precedence over environment variables and the release config
+    let mut settings = input_settings();
+    settings.extend(cli.command.settings());
+
+    let result = ReleaseConfig::from_settings(&settings).and_then(|config| match cli.command {
+        Command::ExtractChangelog(_) => changelog::run_extract_changelog(&config),
+        Command::ValidateChangelog(_) => changelog::run_validate_changelog(&config),
+        Command::LintChangelog(_) => changelog::run_lint_changelog(&config),
+        Command::PrepareChangelog(_) => changelog::run_prepare_changelog(&config),
+        Command::GenerateChangelog(_) => generate_changelog::run_generate_changelog(&config),
+        Command::ValidateVersion(_) => version::run_validate_version(&config),
+        Command::GetVersion(_) => version::run_get_version(&config),
+        Command::BumpVersion(_) => bump_version::run_bump_version(&config),
+        Command::GetReleaseVersion(_) => version::run_get_release_version(&config),
+        Command::GenerateSbom(_) => sbom::run_generate_sbom(&config),
+        Command::CheckSbom(_) => check_sbom::run_check_sbom(&config),
+        Command::GenerateProvenance(_) => provenance::run_generate_provenance(&config),
+        Command::GenerateHomebrew(_) => homebrew::run_generate_homebrew(&config),
+        Command::GenerateAur(_) => aur::run_generate_aur(&config),
+        Command::GenerateWinget(_) => winget::run_generate_winget(&config),
+        Command::SignArtifact(_) => sign::run_sign_artifact(&config),
+        Command::SignArtifacts(_) => sign::run_sign_artifacts(&config),
+        Command::VerifySignature(_) => verify_signature::run_verify_signature(&config),
+        Command::FormatRelease(_) => format_release::run_format_release(&config),
+        Command::CollectArtifacts(_) => collect_artifacts::run_collect_artifacts(&config),
+        Command::VerifyChecksums(_) => verify_checksums::run_verify_checksums(&config),
+        Command::Plan(_) => plan::run_plan(&config),
+        Command::Release(_) => release::run_release(&config),
+        Command::ReleaseLinux(_) => release::run_release_linux(&config),
+        Command::ReleaseLinuxDeb(_) => release::run_release_linux_deb(&config),
+        Command::ReleaseLinuxRpm(_) => release::run_release_linux_rpm(&config),
+        Command::ReleaseLinuxApk(_) => release::run_release_linux_apk(&config),
+        Command::ReleaseMacos(_) => release::run_release_macos(&config),
+        Command::ReleaseMacosUniversal(_) => release::run_release_macos_universal(&config),
+        Command::ReleaseMacosDmg(_) => release::run_release_macos_dmg(&config),
+        Command::ReleaseWindows(_) => release::run_release_windows(&config),
+        Command::ReleaseWindowsMsi(_) => release::run_release_windows_msi(&config),
+        Command::PublishCrate(_) => publish::run_publish_crate(&config),
+        Command::TestDeb(_) => testing::run_test_deb(&config),
+        Command::TestRpm(_) => testing::run_test_rpm(&config),
+        Command::TestWindows(_) => testing::run_test_windows(&config),
+    });
 
     if let Err(e) = result {
         eprintln!("\x1b[31mERROR:\x1b[0m {e}");
@@ -82,14 +61,11 @@ fn main() {
     }
 }
 
-/// Maps INPUT_* environment variables to the internal env vars expected by command handlers.
+/// Maps INPUT_* environment variables to the settings expected by command handlers.
 /// This mirrors the dispatch.nu logic where action.yml inputs are propagated.
-fn map_input_env_vars() {
-    // Safety: running single-threaded at this point during build setup
-    unsafe { map_input_env_vars_inner() }
-}
+fn input_settings() -> Settings {
+    let mut settings = Settings::new();
 
-unsafe fn map_input_env_vars_inner() {
     // Version - auto-detect from tag if not provided
     let version_input = env::var("INPUT_VERSION").unwrap_or_default();
     let version = if !version_input.is_empty() {
@@ -103,26 +79,38 @@ unsafe fn map_input_env_vars_inner() {
         }
     };
     if !version.is_empty() {
-        unsafe { env::set_var("VERSION", &version) };
+        settings.push(("VERSION", version));
     }
 
     // Simple 1:1 mappings (INPUT_X -> Y)
-    let mappings: &[(&str, &str)] = &[
+    let mappings: &[(&str, &'static str)] = &[
         ("INPUT_TARGET", "TARGET"),
         ("INPUT_BINARY_NAME", "BINARY_NAME"),
         ("INPUT_PACKAGE", "PACKAGE"),
+        ("INPUT_BINARIES", "BINARIES"),
+        ("INPUT_PACKAGES", "PACKAGES"),
+        ("INPUT_BINARY_PACKAGING", "BINARY_PACKAGING"),
         ("INPUT_MANIFEST", "MANIFEST_PATH"),
+        ("INPUT_CONFIG", "RELEASE_CONFIG"),
         ("INPUT_PRE_BUILD", "PRE_BUILD"),
         ("INPUT_BINARY_PATH", "BINARY_PATH"),
+        ("INPUT_BINARY_PATH_ARM64", "BINARY_PATH_ARM64"),
+        ("INPUT_BINARY_PATH_X64", "BINARY_PATH_X64"),
         ("INPUT_FEATURES", "FEATURES"),
         ("INPUT_PROFILE", "PROFILE"),
         ("INPUT_RUSTFLAGS", "TARGET_RUSTFLAGS"),
+        ("INPUT_BUILD_TOOL", "BUILD_TOOL"),
+        ("INPUT_ARCHIVE_FORMAT", "ARCHIVE_FORMAT"),
         ("INPUT_CHECKSUM", "CHECKSUM"),
         ("INPUT_INCLUDE", "ARCHIVE_INCLUDE"),
         ("INPUT_CHANGELOG", "CHANGELOG_PATH"),
         ("INPUT_NOTES_OUTPUT", "OUTPUT_PATH"),
+        ("INPUT_CHANGELOG_FROM", "CHANGELOG_FROM_REF"),
+        ("INPUT_CHANGELOG_TO", "CHANGELOG_TO_REF"),
         ("INPUT_TAG", "TAG"),
         ("INPUT_EXPECTED_VERSION", "EXPECTED_VERSION"),
+        ("INPUT_BUMP_LEVEL", "BUMP_LEVEL"),
+        ("INPUT_BUMP_PRE_ID", "BUMP_PRE_ID"),
         ("INPUT_PKG_DESCRIPTION", "PKG_DESCRIPTION"),
         ("INPUT_PKG_MAINTAINER", "PKG_MAINTAINER"),
         ("INPUT_PKG_HOMEPAGE", "PKG_HOMEPAGE"),
@@ -141,6 +129,10 @@ unsafe fn map_input_env_vars_inner() {
         ("INPUT_PKG_RELEASE", "PKG_RELEASE"),
         ("INPUT_SBOM_FORMAT", "SBOM_FORMAT"),
         ("INPUT_SBOM_DIR", "SBOM_OUTPUT_DIR"),
+        ("INPUT_SBOM_PER_ARTIFACT", "SBOM_PER_ARTIFACT"),
+        ("INPUT_SBOM_FILES", "SBOM_FILES"),
+        ("INPUT_SBOM_LICENSE_ALLOW", "SBOM_LICENSE_ALLOW"),
+        ("INPUT_SBOM_LICENSE_DENY", "SBOM_LICENSE_DENY"),
         ("INPUT_BREW_CLASS", "HOMEBREW_FORMULA_CLASS"),
         ("INPUT_BREW_COPYRIGHT", "HOMEBREW_COPYRIGHT"),
         ("INPUT_BREW_MACOS_ARM64_URL", "HOMEBREW_MACOS_ARM64_URL"),
@@ -150,6 +142,14 @@ unsafe fn map_input_env_vars_inner() {
         ),
         ("INPUT_BREW_MACOS_X64_URL", "HOMEBREW_MACOS_X64_URL"),
         ("INPUT_BREW_MACOS_X64_SHA256", "HOMEBREW_MACOS_X64_SHA256"),
+        (
+            "INPUT_BREW_MACOS_UNIVERSAL_URL",
+            "HOMEBREW_MACOS_UNIVERSAL_URL",
+        ),
+        (
+            "INPUT_BREW_MACOS_UNIVERSAL_SHA256",
+            "HOMEBREW_MACOS_UNIVERSAL_SHA256",
+        ),
         ("INPUT_BREW_LINUX_ARM64_URL", "HOMEBREW_LINUX_ARM64_URL"),
         (
             "INPUT_BREW_LINUX_ARM64_SHA256",
@@ -159,8 +159,30 @@ unsafe fn map_input_env_vars_inner() {
         ("INPUT_BREW_LINUX_X64_SHA256", "HOMEBREW_LINUX_X64_SHA256"),
         ("INPUT_BREW_DIR", "HOMEBREW_OUTPUT_DIR"),
         ("INPUT_ARTIFACT", "ARTIFACT_PATH"),
+        ("INPUT_SIGN_METHOD", "SIGN_METHOD"),
+        ("INPUT_GPG_PRIVATE_KEY", "GPG_PRIVATE_KEY"),
+        ("INPUT_GPG_PASSPHRASE", "GPG_PASSPHRASE"),
+        ("INPUT_GPG_KEY_ID", "GPG_KEY_ID"),
+        ("INPUT_MINISIGN_SECRET_KEY", "MINISIGN_SECRET_KEY"),
+        ("INPUT_MINISIGN_PASSWORD", "MINISIGN_PASSWORD"),
+        ("INPUT_COSIGN_KEY", "COSIGN_KEY"),
+        ("INPUT_COSIGN_PASSWORD", "COSIGN_PASSWORD"),
+        ("INPUT_COSIGN_TLOG_UPLOAD", "COSIGN_TLOG_UPLOAD"),
+        ("INPUT_PUBLIC_KEY", "PUBLIC_KEY"),
+        ("INPUT_CERTIFICATE_IDENTITY", "CERTIFICATE_IDENTITY"),
+        (
+            "INPUT_CERTIFICATE_IDENTITY_REGEXP",
+            "CERTIFICATE_IDENTITY_REGEXP",
+        ),
+        ("INPUT_CERTIFICATE_OIDC_ISSUER", "CERTIFICATE_OIDC_ISSUER"),
+        (
+            "INPUT_CERTIFICATE_OIDC_ISSUER_REGEXP",
+            "CERTIFICATE_OIDC_ISSUER_REGEXP",
+        ),
         ("INPUT_ARTIFACTS_DIR", "ARTIFACTS_DIR"),
         ("INPUT_BASE_URL", "BASE_URL"),
+        ("INPUT_COLLECTION", "COLLECTION"),
+        ("INPUT_PROVENANCE_OUTPUT", "PROVENANCE_OUTPUT"),
         ("INPUT_NOTES_FILE", "RELEASE_NOTES_FILE"),
         ("INPUT_INCLUDE_CHECKSUMS", "INCLUDE_CHECKSUMS"),
         ("INPUT_INCLUDE_SIGNATURES", "INCLUDE_SIGNATURES"),
@@ -189,33 +211,41 @@ unsafe fn map_input_env_vars_inner() {
         ("INPUT_MSI_PATH", "MSI_PATH"),
         ("INPUT_MSI_CHECKSUM_FILE", "MSI_CHECKSUM_FILE"),
         ("INPUT_ARCH", "ARCH"),
+        ("INPUT_PLAN_TARGETS", "PLAN_TARGETS"),
+        ("INPUT_PLAN_FORMATS", "PLAN_FORMATS"),
     ];
 
     for (input_key, target_key) in mappings {
         if let Ok(val) = env::var(input_key) {
             if !val.is_empty() {
-                unsafe { env::set_var(target_key, &val) };
+                settings.push((*target_key, val));
             }
         }
     }
 
     // Boolean mappings (INPUT_X == "true" -> Y = "true")
-    let bool_mappings: &[(&str, &str)] = &[
+    let bool_mappings: &[(&str, &'static str)] = &[
         ("INPUT_SKIP_BUILD", "SKIP_BUILD"),
         ("INPUT_LOCKED", "LOCKED"),
         ("INPUT_NO_DEFAULT_FEATURES", "NO_DEFAULT_FEATURES"),
         ("INPUT_USE_ZIGBUILD", "USE_ZIGBUILD"),
         ("INPUT_ARCHIVE", "ARCHIVE"),
+        ("INPUT_ARCHIVE_TOP_LEVEL_DIR", "ARCHIVE_TOP_LEVEL_DIR"),
         ("INPUT_VALIDATE_CARGO_TOML", "VALIDATE_CARGO_TOML"),
         ("INPUT_DOWNLOAD_FROM_RELEASE", "DOWNLOAD_FROM_RELEASE"),
         ("INPUT_PUBLISH_DRY_RUN", "PUBLISH_DRY_RUN"),
+        ("INPUT_SIGN_MANIFESTS", "SIGN_MANIFESTS"),
+        ("INPUT_GPG_USE_KEYRING", "GPG_USE_KEYRING"),
+        ("INPUT_CHANGELOG_WRITE", "CHANGELOG_WRITE"),
     ];
 
     for (input_key, target_key) in bool_mappings {
         if let Ok(val) = env::var(input_key) {
             if val == "true" {
-                unsafe { env::set_var(target_key, "true") };
+                settings.push((*target_key, val));
             }
         }
     }
+
+    settings
 }
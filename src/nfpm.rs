@@ -1,7 +1,16 @@
-use crate::env_or;
-use crate::parse_comma_list;
+use crate::cargo_info::CargoInfo;
+use crate::config::ReleaseConfig;
+use crate::{parse_comma_list, single_line, split_src_dst};
 use std::fs;
-use std::path::Path;
+use std::path::PathBuf;
+
+/// Quotes `value` as a single-line YAML double-quoted scalar.
+pub fn yaml_string(value: &str) -> String {
+    let escaped = single_line(value)
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"");
+    format!("\"{escaped}\"")
+}
 
 /// Formats a list of dependencies for nfpm YAML.
 pub fn format_dependency_list(key: &str, raw: &str) -> String {
@@ -11,114 +20,145 @@ pub fn format_dependency_list(key: &str, raw: &str) -> String {
     }
     let mut result = format!("{key}:\n");
     for item in &items {
-        result.push_str(&format!("  - \"{item}\"\n"));
+        result.push_str(&format!("  - {}\n", yaml_string(item)));
     }
     result
 }
 
-/// Generates base nfpm config header.
-pub fn nfpm_base_config(binary_name: &str, version: &str, arch: &str) -> String {
-    let description = env_or(
-        "PKG_DESCRIPTION",
-        &format!("{binary_name} - built with rust-build-package-release-action"),
+/// Generates base nfpm config header. Unset `PKG_*` metadata defaults to the
+/// values in Cargo.toml.
+pub fn nfpm_base_config(
+    config: &ReleaseConfig,
+    info: &CargoInfo,
+    binary_name: &str,
+    version: &str,
+    arch: &str,
+) -> String {
+    let description = config.get("PKG_DESCRIPTION", &info.description_or_default(binary_name));
+    let maintainer = config.get(
+        "PKG_MAINTAINER",
+        info.maintainer_or("Unknown <unknown@example.com>"),
     );
-    let maintainer = env_or("PKG_MAINTAINER", "Unknown <unknown@example.com>");
-    let homepage = env_or("PKG_HOMEPAGE", "");
-    let license = env_or("PKG_LICENSE", "");
-    let vendor = env_or("PKG_VENDOR", "");
+    let homepage = config.get("PKG_HOMEPAGE", info.homepage_or_repository());
+    let license = config.get("PKG_LICENSE", &info.license);
+    let vendor = config.get("PKG_VENDOR", "");
 
-    let mut config = format!(
+    let mut yaml = format!(
         "name: \"{binary_name}\"\n\
          arch: \"{arch}\"\n\
          platform: linux\n\
          version: \"{version}\"\n\
-         maintainer: \"{maintainer}\"\n\
-         description: \"{description}\"\n"
+         maintainer: {}\n\
+         description: {}\n",
+        yaml_string(&maintainer),
+        yaml_string(&description)
     );
     if !homepage.is_empty() {
-        config.push_str(&format!("homepage: \"{homepage}\"\n"));
+        yaml.push_str(&format!("homepage: {}\n", yaml_string(&homepage)));
     }
     if !license.is_empty() {
-        config.push_str(&format!("license: \"{license}\"\n"));
+        yaml.push_str(&format!("license: {}\n", yaml_string(&license)));
     }
     if !vendor.is_empty() {
-        config.push_str(&format!("vendor: \"{vendor}\"\n"));
+        yaml.push_str(&format!("vendor: {}\n", yaml_string(&vendor)));
     }
-    config
+    yaml
 }
 
 /// Generates nfpm contents section for binary and docs.
-pub fn nfpm_contents_section(binary_name: &str, binary_path: &str) -> String {
-    let mut config = format!(
-        "\ncontents:\n  - src: \"{binary_path}\"\n    dst: \"/usr/bin/{binary_name}\"\n    file_info:\n      mode: 0755\n"
-    );
+pub fn nfpm_contents_section(
+    config: &ReleaseConfig,
+    info: &CargoInfo,
+    binary_name: &str,
+    binary_path: &str,
+) -> String {
+    nfpm_binaries_contents_section(config, info, binary_name, &[(binary_name, binary_path)])
+}
 
-    // Add LICENSE files
-    let licenses: Vec<_> = glob::glob("LICENSE*")
-        .unwrap()
-        .filter_map(|e| e.ok())
-        .collect();
-    for lic in &licenses {
-        let abs = fs::canonicalize(lic).unwrap_or_else(|_| lic.clone());
-        let basename = lic.file_name().unwrap_or_default().to_string_lossy();
-        config.push_str(&format!(
-            "  - src: \"{}\"\n    dst: \"/usr/share/doc/{binary_name}/{basename}\"\n    file_info:\n      mode: 0644\n",
-            abs.display()
+/// Generates nfpm contents section for a package that installs several
+/// `(name, path)` binaries. Docs go under `/usr/share/doc/<package_name>`,
+/// including the `readme` and `license-file` from Cargo.toml.
+pub fn nfpm_binaries_contents_section(
+    config: &ReleaseConfig,
+    info: &CargoInfo,
+    package_name: &str,
+    binaries: &[(&str, &str)],
+) -> String {
+    let mut yaml = String::from("\ncontents:\n");
+    for (name, path) in binaries {
+        yaml.push_str(&format!(
+            "  - src: {}\n    dst: {}\n    file_info:\n      mode: 0755\n",
+            yaml_string(path),
+            yaml_string(&format!("/usr/bin/{name}"))
         ));
     }
 
-    if Path::new("README.md").exists() {
-        let readme = fs::canonicalize("README.md").unwrap_or_else(|_| "README.md".into());
-        config.push_str(&format!(
-            "  - src: \"{}\"\n    dst: \"/usr/share/doc/{binary_name}/README.md\"\n    file_info:\n      mode: 0644\n",
-            readme.display()
+    // LICENSE files, README.md and the manifest's readme and license-file
+    let mut docs: Vec<PathBuf> = glob::glob("LICENSE*")
+        .unwrap()
+        .filter_map(|e| e.ok())
+        .collect();
+    docs.push("README.md".into());
+    docs.push(PathBuf::from(&info.readme));
+    docs.push(PathBuf::from(&info.license_file));
+    let mut installed: Vec<String> = Vec::new();
+    for doc in docs.iter().filter(|d| d.is_file()) {
+        let basename = doc.file_name().unwrap_or_default().to_string_lossy();
+        if installed.iter().any(|name| *name == basename) {
+            continue;
+        }
+        let abs = fs::canonicalize(doc).unwrap_or_else(|_| doc.clone());
+        yaml.push_str(&format!(
+            "  - src: {}\n    dst: {}\n    file_info:\n      mode: 0644\n",
+            yaml_string(&abs.to_string_lossy()),
+            yaml_string(&format!("/usr/share/doc/{package_name}/{basename}"))
         ));
+        installed.push(basename.to_string());
     }
 
-    let includes = parse_comma_list(&env_or("PKG_CONTENTS", ""));
+    let includes = parse_comma_list(&config.get("PKG_CONTENTS", ""));
     if !includes.is_empty() {
         for inc in &includes {
-            let parts: Vec<&str> = inc.split(':').collect();
-            if parts.len() == 2 {
-                let src = fs::canonicalize(parts[0]).unwrap_or_else(|_| parts[0].into());
-                let dst = parts[1];
-                config.push_str(&format!(
-                    "  - src: \"{}\"\n    dst: \"{dst}\"\n",
-                    src.display()
+            if let (src, Some(dst)) = split_src_dst(inc) {
+                let src = fs::canonicalize(src).unwrap_or_else(|_| src.into());
+                yaml.push_str(&format!(
+                    "  - src: {}\n    dst: {}\n",
+                    yaml_string(&src.to_string_lossy()),
+                    yaml_string(dst)
                 ));
             }
         }
     }
 
-    config
+    yaml
 }
 
 /// Generates nfpm dependency sections.
-pub fn nfpm_dependencies_section() -> String {
-    let mut config = String::new();
-    config.push_str(&format_dependency_list(
+pub fn nfpm_dependencies_section(config: &ReleaseConfig) -> String {
+    let mut yaml = String::new();
+    yaml.push_str(&format_dependency_list(
         "depends",
-        &env_or("PKG_DEPENDS", ""),
+        &config.get("PKG_DEPENDS", ""),
     ));
-    config.push_str(&format_dependency_list(
+    yaml.push_str(&format_dependency_list(
         "recommends",
-        &env_or("PKG_RECOMMENDS", ""),
+        &config.get("PKG_RECOMMENDS", ""),
     ));
-    config.push_str(&format_dependency_list(
+    yaml.push_str(&format_dependency_list(
         "suggests",
-        &env_or("PKG_SUGGESTS", ""),
+        &config.get("PKG_SUGGESTS", ""),
     ));
-    config.push_str(&format_dependency_list(
+    yaml.push_str(&format_dependency_list(
         "conflicts",
-        &env_or("PKG_CONFLICTS", ""),
+        &config.get("PKG_CONFLICTS", ""),
     ));
-    config.push_str(&format_dependency_list(
+    yaml.push_str(&format_dependency_list(
         "replaces",
-        &env_or("PKG_REPLACES", ""),
+        &config.get("PKG_REPLACES", ""),
     ));
-    config.push_str(&format_dependency_list(
+    yaml.push_str(&format_dependency_list(
         "provides",
-        &env_or("PKG_PROVIDES", ""),
+        &config.get("PKG_PROVIDES", ""),
     ));
-    config
+    yaml
 }
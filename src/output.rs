@@ -23,6 +23,33 @@ pub fn output_multiline(key: &str, value: &str) {
     }
 }
 
+/// Appends Markdown to the job's step summary.
+pub fn step_summary(markdown: &str) {
+    if let Ok(path) = env::var("GITHUB_STEP_SUMMARY") {
+        if !path.is_empty() {
+            if let Ok(mut f) = OpenOptions::new().append(true).create(true).open(&path) {
+                let _ = writeln!(f, "{markdown}");
+            }
+        }
+    }
+}
+
+/// Formats a workflow command that GitHub shows as an annotation on `file` at `line`.
+/// `level` is `error`, `warning` or `notice`.
+pub fn annotation(level: &str, file: &str, line: usize, message: &str) -> String {
+    let escape_data = |s: &str| {
+        s.replace('%', "%25")
+            .replace('\r', "%0D")
+            .replace('\n', "%0A")
+    };
+    let escape_property = |s: &str| escape_data(s).replace(':', "%3A").replace(',', "%2C");
+    format!(
+        "::{level} file={},line={line}::{}",
+        escape_property(file),
+        escape_data(message)
+    )
+}
+
 pub fn print_hr() {
     println!(
         "\x1b[32m---------------------------------------------------------------------------->\x1b[0m"
@@ -0,0 +1,214 @@
+use crate::build::{BuildTool, build_tool_for, configured_build_tool};
+use crate::config::ReleaseConfig;
+use crate::error::{Error, Result};
+use crate::output::{output, print_hr};
+use crate::parse_comma_list;
+use crate::platform::{target_to_apk_arch, target_to_deb_arch, target_to_rpm_arch};
+use crate::release::UNIVERSAL_MACOS_TARGET;
+use crate::tools::linux_cross_packages;
+use serde::Serialize;
+use std::str::FromStr;
+
+/// Runner used for Linux targets. Non-native targets are cross-compiled on it.
+pub const LINUX_RUNNER: &str = "ubuntu-22.04";
+const LINUX_RUNNER_ARCH: &str = "x86_64";
+
+/// What a matrix entry produces for its target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanFormat {
+    /// The bare binary
+    Binary,
+    /// The bare binary and an archive
+    Archive,
+    Deb,
+    Rpm,
+    Apk,
+    Dmg,
+    Msi,
+}
+
+impl PlanFormat {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PlanFormat::Binary => "binary",
+            PlanFormat::Archive => "archive",
+            PlanFormat::Deb => "deb",
+            PlanFormat::Rpm => "rpm",
+            PlanFormat::Apk => "apk",
+            PlanFormat::Dmg => "dmg",
+            PlanFormat::Msi => "msi",
+        }
+    }
+}
+
+impl FromStr for PlanFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "binary" => Ok(PlanFormat::Binary),
+            "archive" => Ok(PlanFormat::Archive),
+            "deb" => Ok(PlanFormat::Deb),
+            "rpm" => Ok(PlanFormat::Rpm),
+            "apk" => Ok(PlanFormat::Apk),
+            "dmg" => Ok(PlanFormat::Dmg),
+            "msi" => Ok(PlanFormat::Msi),
+            other => Err(Error::User(format!(
+                "unsupported plan format: {other} (expected one of: binary, archive, deb, rpm, apk, dmg, msi)"
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum TargetPlatform {
+    Linux,
+    Macos,
+    MacosUniversal,
+    Windows,
+}
+
+fn target_platform(target: &str) -> Result<TargetPlatform> {
+    if target.contains("linux") {
+        Ok(TargetPlatform::Linux)
+    } else if target == UNIVERSAL_MACOS_TARGET {
+        Ok(TargetPlatform::MacosUniversal)
+    } else if target.contains("darwin") || target.contains("apple") {
+        Ok(TargetPlatform::Macos)
+    } else if target.contains("windows") {
+        Ok(TargetPlatform::Windows)
+    } else {
+        Err(Error::User(format!(
+            "cannot determine platform from target: {target}"
+        )))
+    }
+}
+
+/// The GitHub Actions runner that builds `target`: Intel Macs for
+/// `x86_64-apple-darwin`, Apple Silicon for the other macOS targets.
+pub fn runner_for_target(target: &str) -> Result<&'static str> {
+    Ok(match target_platform(target)? {
+        TargetPlatform::Linux => LINUX_RUNNER,
+        TargetPlatform::Macos if target.starts_with("x86_64") => "macos-15-intel",
+        TargetPlatform::Macos | TargetPlatform::MacosUniversal => "macos-14",
+        TargetPlatform::Windows => "windows-2022",
+    })
+}
+
+/// The `release-*` command producing `format` for `target`, or `None` when
+/// the format does not apply to the target.
+pub fn release_command(target: &str, format: PlanFormat) -> Result<Option<&'static str>> {
+    let platform = target_platform(target)?;
+    let linux = platform == TargetPlatform::Linux;
+    Ok(match format {
+        PlanFormat::Binary | PlanFormat::Archive => Some(match platform {
+            TargetPlatform::Linux => "release-linux",
+            TargetPlatform::Macos => "release-macos",
+            TargetPlatform::MacosUniversal => "release-macos-universal",
+            TargetPlatform::Windows => "release-windows",
+        }),
+        PlanFormat::Deb if linux && target_to_deb_arch(target).is_ok() => Some("release-linux-deb"),
+        PlanFormat::Rpm if linux && target_to_rpm_arch(target).is_ok() => Some("release-linux-rpm"),
+        PlanFormat::Apk if linux && target_to_apk_arch(target).is_ok() => Some("release-linux-apk"),
+        PlanFormat::Dmg if platform == TargetPlatform::Macos => Some("release-macos-dmg"),
+        PlanFormat::Msi if platform == TargetPlatform::Windows => Some("release-windows-msi"),
+        _ => None,
+    })
+}
+
+/// One job of the build matrix.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MatrixEntry {
+    pub target: String,
+    pub os: String,
+    pub command: String,
+    pub format: String,
+    pub archive: bool,
+    /// `cargo`, `zigbuild` or `cross`
+    pub build_tool: String,
+    /// System packages the command installs before building
+    pub cross_deps: Vec<String>,
+}
+
+/// Plans one matrix entry per applicable target and format combination,
+/// ordered by target. Every format must apply to at least one target.
+pub fn plan_matrix(
+    targets: &[String],
+    formats: &[PlanFormat],
+    tool: Option<BuildTool>,
+) -> Result<Vec<MatrixEntry>> {
+    if targets.is_empty() {
+        return Err(Error::User("no targets to plan".into()));
+    }
+    let mut entries = Vec::new();
+    let mut seen = Vec::new();
+    for target in targets {
+        if seen.contains(&target) {
+            continue;
+        }
+        seen.push(target);
+        let linux = target_platform(target)? == TargetPlatform::Linux;
+        let os = runner_for_target(target)?;
+        let build_tool = build_tool_for(target, tool);
+        for format in formats {
+            let Some(command) = release_command(target, *format)? else {
+                continue;
+            };
+            // cross brings its own toolchain
+            let cross_deps = if linux && build_tool != BuildTool::Cross {
+                linux_cross_packages(target, LINUX_RUNNER_ARCH)
+            } else {
+                &[]
+            };
+            entries.push(MatrixEntry {
+                target: target.clone(),
+                os: os.to_string(),
+                command: command.to_string(),
+                format: format.as_str().to_string(),
+                archive: *format == PlanFormat::Archive,
+                build_tool: build_tool.as_str().to_string(),
+                cross_deps: cross_deps.iter().map(|p| p.to_string()).collect(),
+            });
+        }
+    }
+    for format in formats {
+        if !entries.iter().any(|e| e.format == format.as_str()) {
+            return Err(Error::User(format!(
+                "format {} does not apply to any of the targets",
+                format.as_str()
+            )));
+        }
+    }
+    Ok(entries)
+}
+
+pub fn run_plan(config: &ReleaseConfig) -> Result<()> {
+    let targets = parse_comma_list(&config.get("PLAN_TARGETS", ""));
+    if targets.is_empty() {
+        return Err(Error::User(
+            "plan-targets is required (comma-separated target triples)".into(),
+        ));
+    }
+    let mut formats = Vec::new();
+    for format in parse_comma_list(&config.get("PLAN_FORMATS", "archive")) {
+        let format: PlanFormat = format.parse()?;
+        if !formats.contains(&format) {
+            formats.push(format);
+        }
+    }
+    let entries = plan_matrix(&targets, &formats, configured_build_tool(config)?)?;
+
+    println!("\x1b[32mPlanned:\x1b[0m {} jobs", entries.len());
+    print_hr();
+    for e in &entries {
+        println!(
+            "  {} on {}: {} ({}, {})",
+            e.target, e.os, e.command, e.format, e.build_tool
+        );
+    }
+    print_hr();
+
+    let matrix = serde_json::json!({ "include": entries });
+    output("matrix", &serde_json::to_string(&matrix)?);
+    Ok(())
+}
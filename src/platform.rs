@@ -5,6 +5,11 @@ use crate::error::{Error, Result};
 pub fn detect_platform_short(filename: &str) -> &'static str {
     let f = filename.to_lowercase();
     if regex_matches(
+        &f,
+        r"universal.*(apple|darwin|macos)|(darwin|macos).*universal",
+    ) {
+        "macos-universal"
+    } else if regex_matches(
         &f,
         r"darwin.*arm64|aarch64.*apple|apple.*aarch64|macos.*arm64",
     ) {
@@ -45,7 +50,9 @@ pub fn detect_platform_short(filename: &str) -> &'static str {
 pub fn detect_platform_display(name: &str) -> &'static str {
     let n = name.to_lowercase();
     if regex_matches(&n, r"darwin|macos|osx") {
-        if regex_matches(&n, r"arm64|aarch64") {
+        if n.contains("universal") {
+            "macOS (Universal)"
+        } else if regex_matches(&n, r"arm64|aarch64") {
             "macOS (Apple Silicon)"
         } else {
             "macOS (Intel)"
@@ -100,6 +107,11 @@ pub fn target_to_deb_arch(target: &str) -> Result<&'static str> {
         "aarch64-unknown-linux-gnu" | "aarch64-unknown-linux-musl" => Ok("arm64"),
         "armv7-unknown-linux-gnueabihf" => Ok("armhf"),
         "i686-unknown-linux-gnu" | "i686-unknown-linux-musl" => Ok("i386"),
+        "powerpc64le-unknown-linux-gnu" | "powerpc64le-unknown-linux-musl" => Ok("ppc64el"),
+        "powerpc64-unknown-linux-gnu" => Ok("ppc64"),
+        "s390x-unknown-linux-gnu" => Ok("s390x"),
+        "riscv64gc-unknown-linux-gnu" | "riscv64gc-unknown-linux-musl" => Ok("riscv64"),
+        "loongarch64-unknown-linux-gnu" => Ok("loong64"),
         _ => {
             if target.contains("x86_64") {
                 Ok("amd64")
@@ -109,6 +121,12 @@ pub fn target_to_deb_arch(target: &str) -> Result<&'static str> {
                 Ok("armhf")
             } else if target.contains("i686") {
                 Ok("i386")
+            } else if target.contains("powerpc64le") {
+                Ok("ppc64el")
+            } else if target.contains("s390x") {
+                Ok("s390x")
+            } else if target.contains("riscv64") {
+                Ok("riscv64")
             } else {
                 Err(Error::User(format!(
                     "unsupported target for .deb: {target}"
@@ -125,6 +143,11 @@ pub fn target_to_rpm_arch(target: &str) -> Result<&'static str> {
         "aarch64-unknown-linux-gnu" | "aarch64-unknown-linux-musl" => Ok("aarch64"),
         "armv7-unknown-linux-gnueabihf" => Ok("armv7hl"),
         "i686-unknown-linux-gnu" | "i686-unknown-linux-musl" => Ok("i686"),
+        "powerpc64le-unknown-linux-gnu" | "powerpc64le-unknown-linux-musl" => Ok("ppc64le"),
+        "powerpc64-unknown-linux-gnu" => Ok("ppc64"),
+        "s390x-unknown-linux-gnu" => Ok("s390x"),
+        "riscv64gc-unknown-linux-gnu" | "riscv64gc-unknown-linux-musl" => Ok("riscv64"),
+        "loongarch64-unknown-linux-gnu" => Ok("loongarch64"),
         _ => {
             if target.contains("x86_64") {
                 Ok("x86_64")
@@ -134,6 +157,12 @@ pub fn target_to_rpm_arch(target: &str) -> Result<&'static str> {
                 Ok("armv7hl")
             } else if target.contains("i686") {
                 Ok("i686")
+            } else if target.contains("powerpc64le") {
+                Ok("ppc64le")
+            } else if target.contains("s390x") {
+                Ok("s390x")
+            } else if target.contains("riscv64") {
+                Ok("riscv64")
             } else {
                 Err(Error::User(format!(
                     "unsupported target for .rpm: {target}"
@@ -150,6 +179,10 @@ pub fn target_to_apk_arch(target: &str) -> Result<&'static str> {
         "aarch64-unknown-linux-gnu" | "aarch64-unknown-linux-musl" => Ok("aarch64"),
         "armv7-unknown-linux-gnueabihf" | "armv7-unknown-linux-musleabihf" => Ok("armv7"),
         "i686-unknown-linux-gnu" | "i686-unknown-linux-musl" => Ok("x86"),
+        "powerpc64le-unknown-linux-gnu" | "powerpc64le-unknown-linux-musl" => Ok("ppc64le"),
+        "s390x-unknown-linux-gnu" | "s390x-unknown-linux-musl" => Ok("s390x"),
+        "riscv64gc-unknown-linux-gnu" | "riscv64gc-unknown-linux-musl" => Ok("riscv64"),
+        "loongarch64-unknown-linux-gnu" | "loongarch64-unknown-linux-musl" => Ok("loongarch64"),
         _ => {
             if target.contains("x86_64") {
                 Ok("x86_64")
@@ -159,6 +192,12 @@ pub fn target_to_apk_arch(target: &str) -> Result<&'static str> {
                 Ok("armv7")
             } else if target.contains("i686") {
                 Ok("x86")
+            } else if target.contains("powerpc64le") {
+                Ok("ppc64le")
+            } else if target.contains("s390x") {
+                Ok("s390x")
+            } else if target.contains("riscv64") {
+                Ok("riscv64")
             } else {
                 Err(Error::User(format!(
                     "unsupported target for .apk: {target}"
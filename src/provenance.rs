@@ -0,0 +1,343 @@
+use crate::build::build_tool;
+use crate::cargo_info::get_cargo_info;
+use crate::checksum::ChecksumAlgorithm;
+use crate::collect_artifacts::{collect_artifacts, detect_algorithms, find_artifacts};
+use crate::config::ReleaseConfig;
+use crate::error::{Error, Result};
+use crate::output::{output, print_hr};
+use crate::sbom::artifact_target;
+use crate::{DEFAULT_ARTIFACTS_DIR, env_or, parse_comma_list};
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+pub const STATEMENT_TYPE: &str = "https://in-toto.io/Statement/v1";
+pub const PREDICATE_TYPE: &str = "https://slsa.dev/provenance/v1";
+pub const BUILD_TYPE: &str =
+    "https://github.com/michaelklishin/rust-build-package-release-action/buildtypes/v1";
+
+/// An artifact covered by the provenance, with digests keyed by in-toto
+/// algorithm name.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Subject {
+    pub name: String,
+    pub digest: BTreeMap<String, String>,
+}
+
+/// Name of `algorithm` in an in-toto digest set.
+pub fn digest_name(algorithm: ChecksumAlgorithm) -> &'static str {
+    match algorithm {
+        ChecksumAlgorithm::Sha256 => "sha256",
+        ChecksumAlgorithm::Sha512 => "sha512",
+        ChecksumAlgorithm::B2 => "blake2b",
+        ChecksumAlgorithm::Sha3_256 => "sha3_256",
+        ChecksumAlgorithm::B3 => "blake3",
+    }
+}
+
+/// Reads subjects from the `collection` JSON written by `collect-artifacts`.
+pub fn subjects_from_collection(json: &str) -> Result<Vec<Subject>> {
+    let entries: Vec<Map<String, Value>> = serde_json::from_str(json)?;
+    let mut subjects = Vec::new();
+    for entry in &entries {
+        let name = entry
+            .get("artifact")
+            .and_then(Value::as_str)
+            .unwrap_or_default();
+        let mut digest = BTreeMap::new();
+        for algorithm in ChecksumAlgorithm::ALL {
+            if let Some(hash) = entry.get(algorithm.output_key()).and_then(Value::as_str) {
+                if !hash.is_empty() {
+                    digest.insert(digest_name(algorithm).to_string(), hash.to_string());
+                }
+            }
+        }
+        if name.is_empty() || !digest.contains_key("sha256") {
+            return Err(Error::User(format!(
+                "collection entry without artifact name or sha256: {}",
+                Value::Object(entry.clone())
+            )));
+        }
+        subjects.push(Subject {
+            name: name.to_string(),
+            digest,
+        });
+    }
+    Ok(subjects)
+}
+
+/// Hashes the release artifacts in `dir` the same way `collect-artifacts` does.
+pub fn subjects_from_dir(dir: &Path, requested: &str) -> Result<Vec<Subject>> {
+    let artifacts = find_artifacts(dir);
+    let algorithms = detect_algorithms(dir, &artifacts, requested)?;
+    let collection = collect_artifacts(dir, &artifacts, &algorithms, "")?;
+    Ok(collection
+        .iter()
+        .map(|entry| Subject {
+            name: entry.artifact.clone(),
+            digest: algorithms
+                .iter()
+                .map(|a| {
+                    (
+                        digest_name(*a).to_string(),
+                        entry.checksums.get(*a).to_string(),
+                    )
+                })
+                .collect(),
+        })
+        .collect())
+}
+
+/// Build settings recorded as external parameters.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BuildParameters {
+    pub features: Vec<String>,
+    pub no_default_features: bool,
+    pub profile: String,
+    pub rustflags: String,
+    pub locked: bool,
+    pub use_zigbuild: bool,
+    /// The target and build tool of each subject, keyed by subject name.
+    pub artifacts: BTreeMap<String, ArtifactBuild>,
+}
+
+/// The target triple an artifact was built for and the tool that built it.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ArtifactBuild {
+    pub target: String,
+    pub build_tool: String,
+}
+
+impl BuildParameters {
+    /// Resolves the parameters from the same settings `cargo_build` reads.
+    /// Each subject's target comes from its name, as in
+    /// `<binary>-<version>-<target>.tar.gz`, falling back to TARGET.
+    /// Subjects without a known target get no target or build tool.
+    pub fn from_env(
+        config: &ReleaseConfig,
+        subjects: &[Subject],
+        binary_name: &str,
+        version: &str,
+    ) -> Self {
+        let default_target = config.get("TARGET", "");
+        let artifacts = subjects
+            .iter()
+            .filter_map(|subject| {
+                let target = artifact_target(&subject.name, binary_name, version)
+                    .unwrap_or_else(|| default_target.clone());
+                if target.is_empty() {
+                    return None;
+                }
+                let build_tool = build_tool(config, &target).map_or_else(
+                    |_| config.get("BUILD_TOOL", ""),
+                    |tool| tool.as_str().to_string(),
+                );
+                Some((subject.name.clone(), ArtifactBuild { target, build_tool }))
+            })
+            .collect();
+        BuildParameters {
+            features: parse_comma_list(&config.get("FEATURES", "")),
+            no_default_features: config.get("NO_DEFAULT_FEATURES", "false") == "true",
+            profile: config.get("PROFILE", "release"),
+            rustflags: config.get("TARGET_RUSTFLAGS", ""),
+            locked: config.get("LOCKED", "false") == "true",
+            use_zigbuild: config.get("USE_ZIGBUILD", "false") == "true",
+            artifacts,
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        let artifacts: Map<String, Value> = self
+            .artifacts
+            .iter()
+            .map(|(name, build)| {
+                (
+                    name.clone(),
+                    json!({ "target": build.target, "buildTool": build.build_tool }),
+                )
+            })
+            .collect();
+        json!({
+            "features": self.features,
+            "noDefaultFeatures": self.no_default_features,
+            "profile": self.profile,
+            "rustflags": self.rustflags,
+            "locked": self.locked,
+            "useZigbuild": self.use_zigbuild,
+            "artifacts": artifacts,
+        })
+    }
+}
+
+/// The GitHub Actions run that produced the artifacts.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GitHubContext {
+    pub server_url: String,
+    pub repository: String,
+    pub repository_id: String,
+    pub repository_owner_id: String,
+    pub sha: String,
+    pub git_ref: String,
+    pub workflow_ref: String,
+    pub event_name: String,
+    pub run_id: String,
+    pub run_attempt: String,
+    pub runner_environment: String,
+}
+
+impl GitHubContext {
+    /// Reads the default `GITHUB_*` and `RUNNER_*` variables.
+    pub fn from_env() -> Self {
+        GitHubContext {
+            server_url: env_or("GITHUB_SERVER_URL", "https://github.com"),
+            repository: env_or("GITHUB_REPOSITORY", ""),
+            repository_id: env_or("GITHUB_REPOSITORY_ID", ""),
+            repository_owner_id: env_or("GITHUB_REPOSITORY_OWNER_ID", ""),
+            sha: env_or("GITHUB_SHA", ""),
+            git_ref: env_or("GITHUB_REF", ""),
+            workflow_ref: env_or("GITHUB_WORKFLOW_REF", ""),
+            event_name: env_or("GITHUB_EVENT_NAME", ""),
+            run_id: env_or("GITHUB_RUN_ID", ""),
+            run_attempt: env_or("GITHUB_RUN_ATTEMPT", "1"),
+            runner_environment: env_or("RUNNER_ENVIRONMENT", ""),
+        }
+    }
+
+    /// The workflow file, e.g. `.github/workflows/release.yml`.
+    pub fn workflow_path(&self) -> String {
+        let without_ref = self.workflow_ref.split('@').next().unwrap_or_default();
+        without_ref
+            .strip_prefix(&format!("{}/", self.repository))
+            .unwrap_or(without_ref)
+            .to_string()
+    }
+
+    /// The workflow that ran the build, e.g.
+    /// `https://github.com/org/repo/.github/workflows/release.yml@refs/tags/v1.0.0`.
+    pub fn builder_id(&self) -> String {
+        format!("{}/{}", self.server_url, self.workflow_ref)
+    }
+
+    pub fn invocation_id(&self) -> String {
+        format!(
+            "{}/{}/actions/runs/{}/attempts/{}",
+            self.server_url, self.repository, self.run_id, self.run_attempt
+        )
+    }
+
+    pub fn source_uri(&self) -> String {
+        let server = self
+            .server_url
+            .trim_start_matches("https://")
+            .trim_start_matches("http://");
+        format!("git+https://{server}/{}@{}", self.repository, self.git_ref)
+    }
+}
+
+/// Builds an in-toto v1 statement with a SLSA v1 provenance predicate.
+pub fn build_statement(
+    subjects: &[Subject],
+    params: &BuildParameters,
+    github: &GitHubContext,
+) -> Result<Value> {
+    if subjects.is_empty() {
+        return Err(Error::User("no artifacts to describe in provenance".into()));
+    }
+    for (name, value) in [
+        ("GITHUB_REPOSITORY", &github.repository),
+        ("GITHUB_SHA", &github.sha),
+        ("GITHUB_WORKFLOW_REF", &github.workflow_ref),
+        ("GITHUB_RUN_ID", &github.run_id),
+    ] {
+        if value.is_empty() {
+            return Err(Error::User(format!(
+                "{name} is not set: provenance can only be generated in GitHub Actions"
+            )));
+        }
+    }
+
+    Ok(json!({
+        "_type": STATEMENT_TYPE,
+        "subject": subjects,
+        "predicateType": PREDICATE_TYPE,
+        "predicate": {
+            "buildDefinition": {
+                "buildType": BUILD_TYPE,
+                "externalParameters": {
+                    "workflow": {
+                        "ref": github.git_ref,
+                        "repository": format!("{}/{}", github.server_url, github.repository),
+                        "path": github.workflow_path(),
+                    },
+                    "build": params.to_json(),
+                },
+                "internalParameters": {
+                    "github": {
+                        "event_name": github.event_name,
+                        "repository_id": github.repository_id,
+                        "repository_owner_id": github.repository_owner_id,
+                        "runner_environment": github.runner_environment,
+                    },
+                },
+                "resolvedDependencies": [{
+                    "uri": github.source_uri(),
+                    "digest": { "gitCommit": github.sha },
+                }],
+            },
+            "runDetails": {
+                "builder": { "id": github.builder_id() },
+                "metadata": { "invocationId": github.invocation_id() },
+            },
+        },
+    }))
+}
+
+pub fn run_generate_provenance(config: &ReleaseConfig) -> Result<()> {
+    let artifacts_dir = config.get("ARTIFACTS_DIR", DEFAULT_ARTIFACTS_DIR);
+    let collection = config.get("COLLECTION", "");
+
+    let subjects = if !collection.trim().is_empty() {
+        println!("\x1b[32mReading subjects from collection\x1b[0m");
+        subjects_from_collection(&collection)?
+    } else {
+        let dir = Path::new(&artifacts_dir);
+        if !dir.is_dir() {
+            return Err(Error::User(format!(
+                "artifacts directory not found: {artifacts_dir}"
+            )));
+        }
+        println!("\x1b[32mHashing artifacts in:\x1b[0m {artifacts_dir}");
+        subjects_from_dir(dir, &config.get("CHECKSUM", "sha256"))?
+    };
+
+    // Without a readable manifest, only TARGET can be recorded
+    let info = get_cargo_info(config).unwrap_or_default();
+    let binary_name = config.get("BINARY_NAME", &info.name);
+    let params = BuildParameters::from_env(config, &subjects, &binary_name, &info.version);
+    let statement = build_statement(&subjects, &params, &GitHubContext::from_env())?;
+
+    let output_path = config.get(
+        "PROVENANCE_OUTPUT",
+        &format!("{artifacts_dir}/provenance.intoto.json"),
+    );
+    if let Some(parent) = Path::new(&output_path).parent() {
+        if !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent)?;
+        }
+    }
+    fs::write(&output_path, serde_json::to_string_pretty(&statement)?)?;
+
+    println!();
+    println!("\x1b[32mProvenance subjects:\x1b[0m");
+    print_hr();
+    for subject in &subjects {
+        println!("  {} sha256:{}", subject.name, subject.digest["sha256"]);
+    }
+    println!("\x1b[32mCreated:\x1b[0m {output_path}");
+
+    output("provenance_file", &output_path);
+    output("subject_count", &subjects.len().to_string());
+    Ok(())
+}
@@ -1,18 +1,19 @@
 use crate::cargo_info::get_cargo_info;
+use crate::config::ReleaseConfig;
 use crate::env_or;
 use crate::error::{Error, Result};
 use crate::output::{output, print_hr};
 use crate::tools;
-use crate::version::{is_valid_semver, version_from_tag};
+use crate::version::{Version, is_valid_semver, version_from_tag};
 use std::env;
 
 /// Build the args for `cargo publish`.
-pub fn build_publish_args(dry_run: bool) -> Vec<String> {
-    let package = env::var("PACKAGE").unwrap_or_default();
-    let features = env::var("FEATURES").unwrap_or_default();
-    let no_default_features = env::var("NO_DEFAULT_FEATURES").unwrap_or_default() == "true";
-    let locked = env::var("LOCKED").unwrap_or_default() == "true";
-    let manifest_path = env_or("MANIFEST_PATH", "Cargo.toml");
+pub fn build_publish_args(config: &ReleaseConfig, dry_run: bool) -> Vec<String> {
+    let package = config.get("PACKAGE", "");
+    let features = config.get("FEATURES", "");
+    let no_default_features = config.get("NO_DEFAULT_FEATURES", "") == "true";
+    let locked = config.get("LOCKED", "") == "true";
+    let manifest_path = config.get("MANIFEST_PATH", "Cargo.toml");
 
     let mut args = vec!["publish".to_string()];
 
@@ -52,15 +53,12 @@ pub fn build_publish_args(dry_run: bool) -> Vec<String> {
 /// so that `publish-crate --publish-dry-run` can run as a pure packaging check
 /// on PRs where no version tag exists.
 pub fn is_version_tag(reference: &str) -> bool {
-    match version_from_tag(reference) {
-        Some(version) => is_valid_semver(version),
-        None => false,
-    }
+    version_from_tag(reference).is_some_and(is_valid_semver)
 }
 
 /// Validate that the tag version matches Cargo.toml before publishing.
-fn validate_version_for_publish() -> Result<String> {
-    let tag = env_or("TAG", &env_or("GITHUB_REF_NAME", ""));
+fn validate_version_for_publish(config: &ReleaseConfig) -> Result<String> {
+    let tag = config.get("TAG", &env_or("GITHUB_REF_NAME", ""));
 
     if tag.is_empty() {
         return Err(Error::User(
@@ -74,18 +72,17 @@ fn validate_version_for_publish() -> Result<String> {
         ))
     })?;
 
-    if !is_valid_semver(tag_version) {
-        return Err(Error::User(format!("invalid semver in tag: {tag_version}")));
-    }
+    let parsed = Version::parse(tag_version)
+        .map_err(|e| Error::User(format!("invalid semver in tag {tag}: {e}")))?;
 
-    let cargo_info = get_cargo_info()?;
+    let cargo_info = get_cargo_info(config)?;
     if cargo_info.version.is_empty() {
         return Err(Error::User(
             "could not read version from Cargo.toml".to_string(),
         ));
     }
 
-    if cargo_info.version != tag_version {
+    if Version::parse(&cargo_info.version)? != parsed {
         return Err(Error::User(format!(
             "Cargo.toml version ({}) does not match tag ({tag_version})\n\n\
              Update Cargo.toml version to '{tag_version}' before publishing",
@@ -138,8 +135,8 @@ fn run_cargo_publish(args: &[String]) -> Result<bool> {
     }
 }
 
-pub fn run_publish_crate() -> Result<()> {
-    let dry_run = env_or("PUBLISH_DRY_RUN", "false") == "true";
+pub fn run_publish_crate(config: &ReleaseConfig) -> Result<()> {
+    let dry_run = config.get("PUBLISH_DRY_RUN", "false") == "true";
 
     print_hr();
     if dry_run {
@@ -151,12 +148,12 @@ pub fn run_publish_crate() -> Result<()> {
 
     tools::check_rust_toolchain()?;
 
-    let reference = env_or("TAG", &env_or("GITHUB_REF_NAME", ""));
+    let reference = config.get("TAG", &env_or("GITHUB_REF_NAME", ""));
     let version = if dry_run && !is_version_tag(&reference) {
         println!("\x1b[32mDry run without a version tag — skipping version validation\x1b[0m");
         String::new()
     } else {
-        validate_version_for_publish()?
+        validate_version_for_publish(config)?
     };
 
     if !dry_run {
@@ -164,11 +161,11 @@ pub fn run_publish_crate() -> Result<()> {
 
         // Dry run first to catch packaging errors before consuming a version on crates.io
         println!("\x1b[32mRunning dry-run validation...\x1b[0m");
-        run_cargo_publish(&build_publish_args(true))?;
+        run_cargo_publish(&build_publish_args(config, true))?;
         println!("\x1b[32mDry run passed\x1b[0m");
     }
 
-    let published = run_cargo_publish(&build_publish_args(dry_run))?;
+    let published = run_cargo_publish(&build_publish_args(config, dry_run))?;
 
     if dry_run {
         println!("\x1b[32mDry run completed successfully\x1b[0m");
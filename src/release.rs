@@ -1,17 +1,24 @@
-use crate::archive::{copy_docs, copy_includes, list_archivable_files};
-use crate::build::{cargo_build, output_build_results};
-use crate::cargo_info::get_cargo_info;
-use crate::checksum::generate_checksums;
-use crate::env_or;
+use crate::archive::{
+    ArchiveFormat, copy_docs, copy_includes, create_archive, release_entries_for,
+};
+use crate::build::{
+    BinaryPackaging, BinaryTarget, cargo_build, cargo_build_binaries, discover_binaries,
+    output_build_results,
+};
+use crate::cargo_info::{CargoInfo, get_cargo_info};
+use crate::checksum::{Checksums, generate_checksums};
+use crate::config::ReleaseConfig;
 use crate::error::{Error, Result};
-use crate::nfpm::{nfpm_base_config, nfpm_contents_section, nfpm_dependencies_section};
-use crate::output::{output, print_hr};
+use crate::macho::{cpu_type_name, create_universal, parse_universal};
+use crate::nfpm::{
+    nfpm_base_config, nfpm_binaries_contents_section, nfpm_dependencies_section, yaml_string,
+};
+use crate::output::{output, output_multiline, print_hr};
 use crate::platform::{target_to_apk_arch, target_to_deb_arch, target_to_rpm_arch};
 use crate::tools::{
     check_nfpm, check_rust_toolchain, command_exists, ensure_lockfile, install_linux_cross_deps,
     run_command, run_command_inherit, run_pre_build_hook,
 };
-use std::env;
 use std::fs;
 use std::path::Path;
 use std::process::Command;
@@ -46,141 +53,262 @@ fn copy_with_retry(from: &str, to: &str) -> Result<()> {
 }
 
 /// Common build logic for release commands that produce a binary.
-struct BuildContext {
+struct BuildContext<'a> {
+    config: &'a ReleaseConfig,
     binary_name: String,
     version: String,
     target: String,
     release_dir: String,
     skip_build: bool,
     create_archive: bool,
+    archive_format: ArchiveFormat,
+    archive_top_level_dir: bool,
+    /// Binaries selected by BINARIES; empty for single-binary releases.
+    binaries: Vec<BinaryTarget>,
+    packaging: BinaryPackaging,
 }
 
-fn setup_build_context(default_target: &str) -> Result<BuildContext> {
-    let skip_build = env_or("SKIP_BUILD", "") == "true";
+fn setup_build_context<'a>(
+    config: &'a ReleaseConfig,
+    default_target: &str,
+) -> Result<BuildContext<'a>> {
+    let skip_build = config.get("SKIP_BUILD", "") == "true";
 
     if !skip_build {
         check_rust_toolchain()?;
     }
 
-    let target = env_or("TARGET", default_target);
-    let info = get_cargo_info()?;
-    let binary_name = env_or("BINARY_NAME", &info.name);
-    let version = info.version;
-    let create_archive = env_or("ARCHIVE", "") == "true";
-
-    if binary_name.is_empty() {
-        return Err(Error::User("could not determine binary name".into()));
-    }
-    if version.is_empty() {
-        return Err(Error::User("could not determine version".into()));
-    }
+    let target = config.get("TARGET", default_target);
+    let info = get_cargo_info(config)?;
+    let binaries = discover_binaries(config)?;
+    let packaging: BinaryPackaging = config.get("BINARY_PACKAGING", "").parse()?;
+    let (binary_name, version) = package_name_and_version(config, &info, &binaries)?;
+    let create_archive = config.get("ARCHIVE", "") == "true";
+    let archive_format = ArchiveFormat::from_env(config, &target)?;
+    let archive_top_level_dir = config.get("ARCHIVE_TOP_LEVEL_DIR", "") == "true";
 
     let release_dir = format!("target/{target}/release");
 
     Ok(BuildContext {
+        config,
         binary_name,
         version,
         target,
         release_dir,
         skip_build,
         create_archive,
+        archive_format,
+        archive_top_level_dir,
+        binaries,
+        packaging,
     })
 }
 
-pub fn run_release() -> Result<()> {
-    let target = env_or("TARGET", "");
-    if target.is_empty() {
-        return Err(Error::User(
-            "TARGET is required for the unified release command".into(),
-        ));
-    }
-
-    println!("\x1b[32mAuto-selected:\x1b[0m platform for target {target}");
-
-    if target.contains("linux") {
-        run_release_linux()
-    } else if target.contains("darwin") || target.contains("apple") {
-        run_release_macos()
-    } else if target.contains("windows") {
-        run_release_windows()
-    } else {
-        Err(Error::User(format!(
-            "Cannot determine platform from target: {target}. Use release-linux, release-macos, or release-windows directly."
-        )))
-    }
+/// Creates `<artifact_base>.<ext>` in the release directory and returns its name and path.
+fn create_release_archive(
+    ctx: &BuildContext,
+    binary_paths: &[&str],
+    artifact_base: &str,
+) -> Result<(String, String)> {
+    let artifact = format!("{artifact_base}.{}", ctx.archive_format.extension());
+    let artifact_path = format!("{}/{artifact}", ctx.release_dir);
+    println!("\x1b[32mCreating archive:\x1b[0m {artifact}");
+    let top_level_dir = ctx.archive_top_level_dir.then_some(artifact_base);
+    let binary_paths: Vec<&Path> = binary_paths.iter().map(Path::new).collect();
+    let entries = release_entries_for(ctx.config, &binary_paths, top_level_dir)?;
+    create_archive(Path::new(&artifact_path), ctx.archive_format, &entries)?;
+    Ok((artifact, artifact_path))
 }
 
-pub fn run_release_linux() -> Result<()> {
-    let ctx = setup_build_context("x86_64-unknown-linux-gnu")?;
-
+/// Releases every binary in `ctx.binaries`: one cargo build, a bare artifact per
+/// binary, and with ARCHIVE one archive holding all of them (or one archive per
+/// binary with separate packaging). `add_target` prepares the toolchain.
+/// The `artifact` and checksum outputs describe the combined archive, or else
+/// the first binary's artifact.
+fn release_binaries(
+    ctx: &BuildContext,
+    exe_suffix: &str,
+    add_target: &dyn Fn(&str) -> Result<()>,
+) -> Result<()> {
+    let names: Vec<&str> = ctx.binaries.iter().map(|b| b.name.as_str()).collect();
     if ctx.skip_build {
-        let custom = env_or("BINARY_PATH", "");
-        if custom.is_empty() {
-            return Err(Error::User(
-                "binary-path is required when skip-build is true".into(),
-            ));
-        }
-        if !Path::new(&custom).exists() {
-            return Err(Error::User(format!("binary not found: {custom}")));
-        }
         println!(
             "\x1b[32mPackaging\x1b[0m {} v{} for {} (skip-build)",
-            ctx.binary_name, ctx.version, ctx.target
+            names.join(", "),
+            ctx.version,
+            ctx.target
         );
-        fs::create_dir_all(&ctx.release_dir)?;
-        fs::copy(&custom, format!("{}/{}", ctx.release_dir, ctx.binary_name))?;
-        run_command_inherit(
-            "chmod",
-            &["+x", &format!("{}/{}", ctx.release_dir, ctx.binary_name)],
-        )?;
     } else {
         println!(
             "\x1b[32mBuilding\x1b[0m {} v{} for {}",
-            ctx.binary_name, ctx.version, ctx.target
+            names.join(", "),
+            ctx.version,
+            ctx.target
         );
         let _ = fs::remove_dir_all(&ctx.release_dir);
         fs::create_dir_all(&ctx.release_dir)?;
         ensure_lockfile()?;
-        run_pre_build_hook()?;
-        install_linux_cross_deps(&ctx.target)?;
-        cargo_build(&ctx.target, &ctx.binary_name)?;
+        run_pre_build_hook(ctx.config)?;
+        add_target(&ctx.target)?;
+        cargo_build_binaries(ctx.config, &ctx.target, &ctx.binaries)?;
     }
 
-    let binary_path = format!("{}/{}", ctx.release_dir, ctx.binary_name);
-    if !Path::new(&binary_path).exists() {
-        return Err(Error::User(format!("binary not found: {binary_path}")));
+    let binary_paths: Vec<String> = names
+        .iter()
+        .map(|name| format!("{}/{name}{exe_suffix}", ctx.release_dir))
+        .collect();
+    if let Some(missing) = binary_paths.iter().find(|p| !Path::new(p).exists()) {
+        return Err(Error::User(format!("binary not found: {missing}")));
+    }
+
+    output("version", &ctx.version);
+    output("binary_name", &names.join(","));
+    output("target", &ctx.target);
+
+    let combined = ctx.create_archive && ctx.packaging == BinaryPackaging::Combined;
+    let mut created = Vec::new();
+    let mut results = Vec::new();
+    let mut first = None;
+    for (binary, binary_path) in ctx.binaries.iter().zip(&binary_paths) {
+        let artifact_base = format!("{}-{}-{}", binary.name, binary.version, ctx.target);
+        let bare_artifact = format!("{artifact_base}{exe_suffix}");
+        let bare_artifact_path = format!("{}/{bare_artifact}", ctx.release_dir);
+        fs::copy(binary_path, &bare_artifact_path)?;
+        if exe_suffix.is_empty() {
+            run_command_inherit("chmod", &["+x", &bare_artifact_path])?;
+        }
+        let mut checksums = generate_checksums(ctx.config, Path::new(&bare_artifact_path))?;
+        created.push(bare_artifact.clone());
+
+        let (artifact, artifact_path) = if ctx.create_archive && !combined {
+            let (artifact, artifact_path) =
+                create_release_archive(ctx, &[binary_path], &artifact_base)?;
+            checksums = generate_checksums(ctx.config, Path::new(&artifact_path))?;
+            created.push(artifact.clone());
+            (artifact, artifact_path)
+        } else {
+            (bare_artifact.clone(), bare_artifact_path.clone())
+        };
+
+        results.push(serde_json::json!({
+            "name": binary.name,
+            "package": binary.package,
+            "version": binary.version,
+            "binary_path": binary_path.replace('\\', "/"),
+            "bare_artifact": bare_artifact,
+            "bare_artifact_path": bare_artifact_path.replace('\\', "/"),
+            "artifact": artifact,
+            "artifact_path": artifact_path.replace('\\', "/"),
+            "sha256": checksums.sha256,
+        }));
+        if first.is_none() {
+            first = Some((
+                binary.name.clone(),
+                binary.version.clone(),
+                artifact,
+                artifact_path,
+                checksums,
+            ));
+        }
     }
 
-    copy_docs(Path::new(&ctx.release_dir))?;
-    copy_includes(Path::new(&ctx.release_dir))?;
+    let primary = if combined {
+        let artifact_base = format!("{}-{}-{}", ctx.binary_name, ctx.version, ctx.target);
+        let paths: Vec<&str> = binary_paths.iter().map(String::as_str).collect();
+        let (artifact, artifact_path) = create_release_archive(ctx, &paths, &artifact_base)?;
+        let checksums = generate_checksums(ctx.config, Path::new(&artifact_path))?;
+        for result in &mut results {
+            result["artifact"] = artifact.clone().into();
+            result["artifact_path"] = artifact_path.replace('\\', "/").into();
+            result["sha256"] = checksums.sha256.clone().into();
+        }
+        created.push(artifact.clone());
+        Some((
+            ctx.binary_name.clone(),
+            ctx.version.clone(),
+            artifact,
+            artifact_path,
+            checksums,
+        ))
+    } else {
+        first
+    };
 
+    println!();
+    println!("\x1b[32mBuild artifacts:\x1b[0m");
+    print_hr();
+    println!("\x1b[32mCreated:\x1b[0m {}", created.join(", "));
+
+    output_multiline(
+        "binaries",
+        &serde_json::to_string_pretty(&results).unwrap_or_default(),
+    );
+    if let Some((binary_name, version, artifact, artifact_path, checksums)) = primary {
+        output_build_results(
+            &binary_name,
+            &version,
+            &ctx.target,
+            &artifact,
+            &artifact_path.replace('\\', "/"),
+            &checksums,
+        );
+    }
+    Ok(())
+}
+
+fn rustup_target_add(target: &str) -> Result<()> {
+    run_command_inherit("rustup", &["target", "add", target])
+}
+
+pub fn run_release(config: &ReleaseConfig) -> Result<()> {
+    let target = config.get("TARGET", "");
+    if target.is_empty() {
+        return Err(Error::User(
+            "TARGET is required for the unified release command".into(),
+        ));
+    }
+
+    println!("\x1b[32mAuto-selected:\x1b[0m platform for target {target}");
+
+    if target.contains("linux") {
+        run_release_linux(config)
+    } else if target == UNIVERSAL_MACOS_TARGET {
+        run_release_macos_universal(config)
+    } else if target.contains("darwin") || target.contains("apple") {
+        run_release_macos(config)
+    } else if target.contains("windows") {
+        run_release_windows(config)
+    } else {
+        Err(Error::User(format!(
+            "Cannot determine platform from target: {target}. Use release-linux, release-macos, or release-windows directly."
+        )))
+    }
+}
+
+/// Packages a built unix binary: the bare `<bin>-<version>-<target>` artifact,
+/// an optional archive, checksums and outputs.
+fn package_release_binary(ctx: &BuildContext, binary_path: &str) -> Result<()> {
     let artifact_base = format!("{}-{}-{}", ctx.binary_name, ctx.version, ctx.target);
 
     output("version", &ctx.version);
     output("binary_name", &ctx.binary_name);
     output("target", &ctx.target);
-    output("binary_path", &binary_path);
+    output("binary_path", binary_path);
 
     // Always create bare binary artifact
     let bare_artifact = &artifact_base;
     let bare_artifact_path = format!("{}/{bare_artifact}", ctx.release_dir);
-    fs::copy(&binary_path, &bare_artifact_path)?;
+    fs::copy(binary_path, &bare_artifact_path)?;
     run_command_inherit("chmod", &["+x", &bare_artifact_path])?;
     output("bare_artifact", bare_artifact);
     output("bare_artifact_path", &bare_artifact_path);
 
     if ctx.create_archive {
-        let artifact = format!("{artifact_base}.tar.gz");
-        let artifact_path = format!("{}/{artifact}", ctx.release_dir);
-        println!("\x1b[32mCreating archive:\x1b[0m {artifact}");
-        let files = list_archivable_files(Path::new(&ctx.release_dir));
-        let file_refs: Vec<&str> = files.iter().map(|s| s.as_str()).collect();
-        let mut tar_args = vec!["-C", &ctx.release_dir, "-czf", &artifact_path];
-        tar_args.extend_from_slice(&file_refs);
-        run_command_inherit("tar", &tar_args)?;
-
-        generate_checksums(Path::new(&bare_artifact_path))?;
-        let checksums = generate_checksums(Path::new(&artifact_path))?;
+        let (artifact, artifact_path) =
+            create_release_archive(ctx, &[binary_path], &artifact_base)?;
+
+        generate_checksums(ctx.config, Path::new(&bare_artifact_path))?;
+        let checksums = generate_checksums(ctx.config, Path::new(&artifact_path))?;
         println!();
         println!("\x1b[32mBuild artifacts:\x1b[0m");
         print_hr();
@@ -194,7 +322,7 @@ pub fn run_release_linux() -> Result<()> {
             &checksums,
         );
     } else {
-        let checksums = generate_checksums(Path::new(&bare_artifact_path))?;
+        let checksums = generate_checksums(ctx.config, Path::new(&bare_artifact_path))?;
         println!();
         println!("\x1b[32mBuild artifacts:\x1b[0m");
         print_hr();
@@ -212,11 +340,61 @@ pub fn run_release_linux() -> Result<()> {
     Ok(())
 }
 
-pub fn run_release_macos() -> Result<()> {
-    let ctx = setup_build_context("aarch64-apple-darwin")?;
+pub fn run_release_linux(config: &ReleaseConfig) -> Result<()> {
+    let ctx = setup_build_context(config, "x86_64-unknown-linux-gnu")?;
+    if !ctx.binaries.is_empty() {
+        return release_binaries(&ctx, "", &|target| install_linux_cross_deps(config, target));
+    }
+
+    if ctx.skip_build {
+        let custom = config.get("BINARY_PATH", "");
+        if custom.is_empty() {
+            return Err(Error::User(
+                "binary-path is required when skip-build is true".into(),
+            ));
+        }
+        if !Path::new(&custom).exists() {
+            return Err(Error::User(format!("binary not found: {custom}")));
+        }
+        println!(
+            "\x1b[32mPackaging\x1b[0m {} v{} for {} (skip-build)",
+            ctx.binary_name, ctx.version, ctx.target
+        );
+        fs::create_dir_all(&ctx.release_dir)?;
+        fs::copy(&custom, format!("{}/{}", ctx.release_dir, ctx.binary_name))?;
+        run_command_inherit(
+            "chmod",
+            &["+x", &format!("{}/{}", ctx.release_dir, ctx.binary_name)],
+        )?;
+    } else {
+        println!(
+            "\x1b[32mBuilding\x1b[0m {} v{} for {}",
+            ctx.binary_name, ctx.version, ctx.target
+        );
+        let _ = fs::remove_dir_all(&ctx.release_dir);
+        fs::create_dir_all(&ctx.release_dir)?;
+        ensure_lockfile()?;
+        run_pre_build_hook(config)?;
+        install_linux_cross_deps(config, &ctx.target)?;
+        cargo_build(config, &ctx.target, &ctx.binary_name)?;
+    }
+
+    let binary_path = format!("{}/{}", ctx.release_dir, ctx.binary_name);
+    if !Path::new(&binary_path).exists() {
+        return Err(Error::User(format!("binary not found: {binary_path}")));
+    }
+
+    package_release_binary(&ctx, &binary_path)
+}
+
+pub fn run_release_macos(config: &ReleaseConfig) -> Result<()> {
+    let ctx = setup_build_context(config, "aarch64-apple-darwin")?;
+    if !ctx.binaries.is_empty() {
+        return release_binaries(&ctx, "", &rustup_target_add);
+    }
 
     if ctx.skip_build {
-        let custom = env_or("BINARY_PATH", "");
+        let custom = config.get("BINARY_PATH", "");
         if custom.is_empty() {
             return Err(Error::User(
                 "binary-path is required when skip-build is true".into(),
@@ -243,9 +421,9 @@ pub fn run_release_macos() -> Result<()> {
         let _ = fs::remove_dir_all(&ctx.release_dir);
         fs::create_dir_all(&ctx.release_dir)?;
         ensure_lockfile()?;
-        run_pre_build_hook()?;
+        run_pre_build_hook(config)?;
         run_command_inherit("rustup", &["target", "add", &ctx.target])?;
-        cargo_build(&ctx.target, &ctx.binary_name)?;
+        cargo_build(config, &ctx.target, &ctx.binary_name)?;
     }
 
     let binary_path = format!("{}/{}", ctx.release_dir, ctx.binary_name);
@@ -253,71 +431,102 @@ pub fn run_release_macos() -> Result<()> {
         return Err(Error::User(format!("binary not found: {binary_path}")));
     }
 
-    copy_docs(Path::new(&ctx.release_dir))?;
-    copy_includes(Path::new(&ctx.release_dir))?;
+    package_release_binary(&ctx, &binary_path)
+}
 
-    let artifact_base = format!("{}-{}-{}", ctx.binary_name, ctx.version, ctx.target);
+/// Target name of universal macOS releases, after the convention of other Rust release tools.
+pub const UNIVERSAL_MACOS_TARGET: &str = "universal-apple-darwin";
 
-    output("version", &ctx.version);
-    output("binary_name", &ctx.binary_name);
-    output("target", &ctx.target);
-    output("binary_path", &binary_path);
+/// The thin targets merged into a universal binary, with the settings that
+/// point at pre-built binaries for skip-build.
+const UNIVERSAL_MACOS_SLICES: [(&str, &str); 2] = [
+    ("aarch64-apple-darwin", "BINARY_PATH_ARM64"),
+    ("x86_64-apple-darwin", "BINARY_PATH_X64"),
+];
 
-    let bare_artifact = &artifact_base;
-    let bare_artifact_path = format!("{}/{bare_artifact}", ctx.release_dir);
-    fs::copy(&binary_path, &bare_artifact_path)?;
-    run_command_inherit("chmod", &["+x", &bare_artifact_path])?;
-    output("bare_artifact", bare_artifact);
-    output("bare_artifact_path", &bare_artifact_path);
+pub fn run_release_macos_universal(config: &ReleaseConfig) -> Result<()> {
+    if !config.get("BINARIES", "").is_empty() {
+        return Err(Error::User(
+            "release-macos-universal packages a single binary; unset binaries or use binary-name"
+                .into(),
+        ));
+    }
+    let target = config.get("TARGET", "");
+    if !target.is_empty() && target != UNIVERSAL_MACOS_TARGET {
+        return Err(Error::User(format!(
+            "release-macos-universal builds {UNIVERSAL_MACOS_TARGET}; unset target (got {target})"
+        )));
+    }
+    let ctx = setup_build_context(config, UNIVERSAL_MACOS_TARGET)?;
 
-    if ctx.create_archive {
-        let artifact = format!("{artifact_base}.tar.gz");
-        let artifact_path = format!("{}/{artifact}", ctx.release_dir);
-        println!("\x1b[32mCreating archive:\x1b[0m {artifact}");
-        let files = list_archivable_files(Path::new(&ctx.release_dir));
-        let file_refs: Vec<&str> = files.iter().map(|s| s.as_str()).collect();
-        let mut tar_args = vec!["-C", &ctx.release_dir, "-czf", &artifact_path];
-        tar_args.extend_from_slice(&file_refs);
-        run_command_inherit("tar", &tar_args)?;
-
-        generate_checksums(Path::new(&bare_artifact_path))?;
-        let checksums = generate_checksums(Path::new(&artifact_path))?;
-        println!();
-        println!("\x1b[32mBuild artifacts:\x1b[0m");
-        print_hr();
-        println!("\x1b[32mCreated:\x1b[0m {bare_artifact}, {artifact}");
-        output_build_results(
-            &ctx.binary_name,
-            &ctx.version,
-            &ctx.target,
-            &artifact,
-            &artifact_path,
-            &checksums,
+    let mut thin_paths = Vec::new();
+    if ctx.skip_build {
+        println!(
+            "\x1b[32mPackaging\x1b[0m {} v{} for {} (skip-build)",
+            ctx.binary_name, ctx.version, ctx.target
         );
+        for (target, key) in UNIVERSAL_MACOS_SLICES {
+            let path = ctx
+                .config
+                .get(key, &format!("target/{target}/release/{}", ctx.binary_name));
+            if !Path::new(&path).exists() {
+                return Err(Error::User(format!("binary not found: {path}")));
+            }
+            thin_paths.push(path);
+        }
     } else {
-        let checksums = generate_checksums(Path::new(&bare_artifact_path))?;
-        println!();
-        println!("\x1b[32mBuild artifacts:\x1b[0m");
-        print_hr();
-        println!("\x1b[32mCreated:\x1b[0m {bare_artifact}");
-        output_build_results(
-            &ctx.binary_name,
-            &ctx.version,
-            &ctx.target,
-            bare_artifact,
-            &bare_artifact_path,
-            &checksums,
+        println!(
+            "\x1b[32mBuilding\x1b[0m {} v{} for {}",
+            ctx.binary_name, ctx.version, ctx.target
         );
+        ensure_lockfile()?;
+        run_pre_build_hook(config)?;
+        for (target, _) in UNIVERSAL_MACOS_SLICES {
+            let release_dir = format!("target/{target}/release");
+            let _ = fs::remove_dir_all(&release_dir);
+            fs::create_dir_all(&release_dir)?;
+            run_command_inherit("rustup", &["target", "add", target])?;
+            cargo_build(config, target, &ctx.binary_name)?;
+            let path = format!("{release_dir}/{}", ctx.binary_name);
+            if !Path::new(&path).exists() {
+                return Err(Error::User(format!("binary not found: {path}")));
+            }
+            thin_paths.push(path);
+        }
     }
 
-    Ok(())
+    let slices = thin_paths
+        .iter()
+        .map(fs::read)
+        .collect::<std::io::Result<Vec<_>>>()?;
+    let slice_refs: Vec<&[u8]> = slices.iter().map(Vec::as_slice).collect();
+    let universal = create_universal(&slice_refs)?;
+    let archs: Vec<&str> = parse_universal(&universal)?
+        .iter()
+        .map(|arch| cpu_type_name(arch.cputype))
+        .collect();
+
+    let _ = fs::remove_dir_all(&ctx.release_dir);
+    fs::create_dir_all(&ctx.release_dir)?;
+    let binary_path = format!("{}/{}", ctx.release_dir, ctx.binary_name);
+    fs::write(&binary_path, universal)?;
+    run_command_inherit("chmod", &["+x", &binary_path])?;
+    println!(
+        "\x1b[32mCreated universal binary:\x1b[0m {binary_path} ({})",
+        archs.join(", ")
+    );
+
+    package_release_binary(&ctx, &binary_path)
 }
 
-pub fn run_release_windows() -> Result<()> {
-    let ctx = setup_build_context("x86_64-pc-windows-msvc")?;
+pub fn run_release_windows(config: &ReleaseConfig) -> Result<()> {
+    let ctx = setup_build_context(config, "x86_64-pc-windows-msvc")?;
+    if !ctx.binaries.is_empty() {
+        return release_binaries(&ctx, ".exe", &rustup_target_add);
+    }
 
     if ctx.skip_build {
-        let custom = env_or("BINARY_PATH", "");
+        let custom = config.get("BINARY_PATH", "");
         if custom.is_empty() {
             return Err(Error::User(
                 "binary-path is required when skip-build is true".into(),
@@ -343,9 +552,9 @@ pub fn run_release_windows() -> Result<()> {
         let _ = fs::remove_dir_all(&ctx.release_dir);
         fs::create_dir_all(&ctx.release_dir)?;
         ensure_lockfile()?;
-        run_pre_build_hook()?;
+        run_pre_build_hook(config)?;
         run_command_inherit("rustup", &["target", "add", &ctx.target])?;
-        cargo_build(&ctx.target, &ctx.binary_name)?;
+        cargo_build(config, &ctx.target, &ctx.binary_name)?;
     }
 
     let binary_path = format!("{}/{}.exe", ctx.release_dir, ctx.binary_name);
@@ -353,9 +562,6 @@ pub fn run_release_windows() -> Result<()> {
         return Err(Error::User(format!("binary not found: {binary_path}")));
     }
 
-    copy_docs(Path::new(&ctx.release_dir))?;
-    copy_includes(Path::new(&ctx.release_dir))?;
-
     let artifact_base = format!("{}-{}-{}", ctx.binary_name, ctx.version, ctx.target);
 
     output("version", &ctx.version);
@@ -370,22 +576,11 @@ pub fn run_release_windows() -> Result<()> {
     output("bare_artifact_path", &bare_artifact_path.replace('\\', "/"));
 
     if ctx.create_archive {
-        let artifact = format!("{artifact_base}.zip");
-        let artifact_path = format!("{}/{artifact}", ctx.release_dir);
-        println!("\x1b[32mCreating archive:\x1b[0m {artifact}");
-        let files = list_archivable_files(Path::new(&ctx.release_dir));
-        let file_refs: Vec<&str> = files.iter().map(|s| s.as_str()).collect();
-        // Use 7z on Windows
-        let original_dir = env::current_dir()?;
-        env::set_current_dir(&ctx.release_dir)?;
-        let mut zip_args = vec!["a", &artifact];
-        zip_args.extend_from_slice(&file_refs);
-        let zip_result = run_command_inherit("7z", &zip_args);
-        env::set_current_dir(original_dir)?;
-        zip_result?;
-
-        generate_checksums(Path::new(&bare_artifact_path))?;
-        let checksums = generate_checksums(Path::new(&artifact_path))?;
+        let (artifact, artifact_path) =
+            create_release_archive(&ctx, &[&binary_path], &artifact_base)?;
+
+        generate_checksums(config, Path::new(&bare_artifact_path))?;
+        let checksums = generate_checksums(config, Path::new(&artifact_path))?;
         let normalised_path = artifact_path.replace('\\', "/");
         println!();
         println!("\x1b[32mBuild artifacts:\x1b[0m");
@@ -400,7 +595,7 @@ pub fn run_release_windows() -> Result<()> {
             &checksums,
         );
     } else {
-        let checksums = generate_checksums(Path::new(&bare_artifact_path))?;
+        let checksums = generate_checksums(config, Path::new(&bare_artifact_path))?;
         let normalised_path = bare_artifact_path.replace('\\', "/");
         println!();
         println!("\x1b[32mBuild artifacts:\x1b[0m");
@@ -419,77 +614,141 @@ pub fn run_release_windows() -> Result<()> {
     Ok(())
 }
 
-pub fn run_release_linux_deb() -> Result<()> {
-    let skip_build = env_or("SKIP_BUILD", "") == "true";
-    let custom_binary_path = env_or("BINARY_PATH", "");
-
-    if !skip_build {
-        check_rust_toolchain()?;
-    }
-    check_nfpm()?;
-
-    let target = env_or("TARGET", "x86_64-unknown-linux-gnu");
-    let info = get_cargo_info()?;
-    let binary_name = env_or("BINARY_NAME", &info.name);
-    let version = info.version;
-
-    if binary_name.is_empty() {
-        return Err(Error::User("could not determine binary name".into()));
-    }
-    if version.is_empty() {
-        return Err(Error::User("could not determine version".into()));
-    }
+/// A deb/rpm/apk package to build: its name, version and `(name, path)` binaries.
+struct PackageUnit {
+    name: String,
+    version: String,
+    binaries: Vec<(String, String)>,
+}
 
-    let arch = target_to_deb_arch(&target)?;
-    println!("\x1b[32mBuilding .deb package:\x1b[0m {binary_name} v{version} for {arch}");
+/// A package built by nfpm.
+struct PackagedUnit {
+    unit: PackageUnit,
+    artifact: String,
+    artifact_path: String,
+    checksums: Checksums,
+}
 
-    let release_dir = format!("target/{target}/release");
-    let binary_path = if skip_build && !custom_binary_path.is_empty() {
-        custom_binary_path
-    } else {
-        format!("{release_dir}/{binary_name}")
+/// Resolves the packages of a deb/rpm/apk release, building the binaries if
+/// they are missing. Without BINARIES this is the single BINARY_NAME binary;
+/// otherwise one package with every binary, or one per binary.
+fn package_units(
+    config: &ReleaseConfig,
+    target: &str,
+    release_dir: &str,
+    skip_build: bool,
+    binary_name: &str,
+    version: &str,
+    binaries: &[BinaryTarget],
+) -> Result<Vec<PackageUnit>> {
+    let build = |build_binaries: &dyn Fn() -> Result<()>| -> Result<()> {
+        println!("\x1b[33mBinary not found, building...\x1b[0m");
+        let _ = fs::remove_dir_all(release_dir);
+        fs::create_dir_all(release_dir)?;
+        ensure_lockfile()?;
+        run_pre_build_hook(config)?;
+        install_linux_cross_deps(config, target)?;
+        build_binaries()
     };
 
-    if !Path::new(&binary_path).exists() {
-        if skip_build {
+    if binaries.is_empty() {
+        let custom_binary_path = config.get("BINARY_PATH", "");
+        let binary_path = if skip_build && !custom_binary_path.is_empty() {
+            custom_binary_path
+        } else {
+            format!("{release_dir}/{binary_name}")
+        };
+        if !Path::new(&binary_path).exists() {
+            if skip_build {
+                return Err(Error::User(format!("binary not found: {binary_path}")));
+            }
+            build(&|| cargo_build(config, target, binary_name))?;
+        }
+        if !Path::new(&binary_path).exists() {
             return Err(Error::User(format!("binary not found: {binary_path}")));
         }
-        println!("\x1b[33mBinary not found, building...\x1b[0m");
-        let _ = fs::remove_dir_all(&release_dir);
-        fs::create_dir_all(&release_dir)?;
-        ensure_lockfile()?;
-        run_pre_build_hook()?;
-        install_linux_cross_deps(&target)?;
-        cargo_build(&target, &binary_name)?;
+        return Ok(vec![PackageUnit {
+            name: binary_name.to_string(),
+            version: version.to_string(),
+            binaries: vec![(binary_name.to_string(), binary_path)],
+        }]);
     }
 
-    if !Path::new(&binary_path).exists() {
-        return Err(Error::User(format!("binary not found: {binary_path}")));
+    let paths: Vec<String> = binaries
+        .iter()
+        .map(|b| format!("{release_dir}/{}", b.name))
+        .collect();
+    if let Some(missing) = paths.iter().find(|p| !Path::new(p).exists()) {
+        if skip_build {
+            return Err(Error::User(format!("binary not found: {missing}")));
+        }
+        build(&|| cargo_build_binaries(config, target, binaries))?;
+    }
+    if let Some(missing) = paths.iter().find(|p| !Path::new(p).exists()) {
+        return Err(Error::User(format!("binary not found: {missing}")));
     }
 
-    let pkg_dir = "target/pkg-deb";
-    let _ = fs::remove_dir_all(pkg_dir);
-    fs::create_dir_all(pkg_dir)?;
-
-    let abs_binary_path = fs::canonicalize(&binary_path)?;
-    let abs_str = abs_binary_path.to_string_lossy().to_string();
+    let packaging: BinaryPackaging = config.get("BINARY_PACKAGING", "").parse()?;
+    Ok(match packaging {
+        BinaryPackaging::Combined => vec![PackageUnit {
+            name: binary_name.to_string(),
+            version: version.to_string(),
+            binaries: binaries
+                .iter()
+                .zip(paths)
+                .map(|(b, path)| (b.name.clone(), path))
+                .collect(),
+        }],
+        BinaryPackaging::Separate => binaries
+            .iter()
+            .zip(paths)
+            .map(|(b, path)| PackageUnit {
+                name: b.name.clone(),
+                version: b.version.clone(),
+                binaries: vec![(b.name.clone(), path)],
+            })
+            .collect(),
+    })
+}
 
-    let section = env_or("PKG_SECTION", "utils");
-    let priority = env_or("PKG_PRIORITY", "optional");
+/// Resolves the package name and version of a deb/rpm/apk release.
+fn package_name_and_version(
+    config: &ReleaseConfig,
+    info: &CargoInfo,
+    binaries: &[BinaryTarget],
+) -> Result<(String, String)> {
+    let first = binaries.first();
+    let mut binary_name = config.get("BINARY_NAME", &info.name);
+    if binary_name.is_empty() {
+        binary_name = first.map(|b| b.package.clone()).unwrap_or_default();
+    }
+    let mut version = info.version.clone();
+    if version.is_empty() {
+        version = first.map(|b| b.version.clone()).unwrap_or_default();
+    }
 
-    let mut nfpm_config = nfpm_base_config(&binary_name, &version, arch);
-    nfpm_config.push_str(&format!("section: \"{section}\"\n"));
-    nfpm_config.push_str(&format!("priority: \"{priority}\"\n"));
-    nfpm_config.push_str(&nfpm_contents_section(&binary_name, &abs_str));
-    nfpm_config.push_str(&nfpm_dependencies_section());
+    if binary_name.is_empty() {
+        return Err(Error::User("could not determine binary name".into()));
+    }
+    if version.is_empty() {
+        return Err(Error::User("could not determine version".into()));
+    }
+    Ok((binary_name, version))
+}
 
+/// Runs nfpm with `nfpm_config` and checks that `artifact_path` was created.
+fn run_nfpm(
+    config: &ReleaseConfig,
+    pkg_dir: &str,
+    nfpm_config: &str,
+    packager: &str,
+    artifact: &str,
+    artifact_path: &str,
+) -> Result<Checksums> {
     let config_path = format!("{pkg_dir}/nfpm.yaml");
-    fs::write(&config_path, &nfpm_config)?;
-
-    let artifact = format!("{binary_name}_{version}_{arch}.deb");
-    let artifact_path = format!("{release_dir}/{artifact}");
+    fs::write(&config_path, nfpm_config)?;
 
-    println!("\x1b[32mRunning nfpm...\x1b[0m");
+    println!("\x1b[32mRunning nfpm...\x1b[0m \x1b[2m{artifact}\x1b[0m");
     run_command_inherit(
         "nfpm",
         &[
@@ -497,275 +756,324 @@ pub fn run_release_linux_deb() -> Result<()> {
             "--config",
             &config_path,
             "--packager",
-            "deb",
+            packager,
             "--target",
-            &artifact_path,
+            artifact_path,
         ],
     )?;
 
-    if !Path::new(&artifact_path).exists() {
+    if !Path::new(artifact_path).exists() {
         return Err(Error::User(format!(
             "failed to create package: {artifact_path}"
         )));
     }
+    generate_checksums(config, Path::new(artifact_path))
+}
+
+/// Absolute `(name, path)` pairs for an nfpm contents section.
+fn absolute_binaries(unit: &PackageUnit) -> Result<Vec<(String, String)>> {
+    unit.binaries
+        .iter()
+        .map(|(name, path)| {
+            let abs = fs::canonicalize(path)?;
+            Ok((name.clone(), abs.to_string_lossy().to_string()))
+        })
+        .collect()
+}
 
-    let checksums = generate_checksums(Path::new(&artifact_path))?;
+fn contents_section(
+    config: &ReleaseConfig,
+    info: &CargoInfo,
+    unit: &PackageUnit,
+) -> Result<String> {
+    let binaries = absolute_binaries(unit)?;
+    let binaries: Vec<(&str, &str)> = binaries
+        .iter()
+        .map(|(name, path)| (name.as_str(), path.as_str()))
+        .collect();
+    Ok(nfpm_binaries_contents_section(
+        config, info, &unit.name, &binaries,
+    ))
+}
+
+/// Prints and outputs the results of a deb/rpm/apk release. The single-artifact
+/// outputs describe the first package; several binaries are also listed in the
+/// `binaries` JSON output.
+fn output_package_results(
+    target: &str,
+    binary_name: &str,
+    version: &str,
+    binaries: &[BinaryTarget],
+    packaged: &[PackagedUnit],
+) {
+    let artifacts: Vec<&str> = packaged.iter().map(|p| p.artifact.as_str()).collect();
     println!();
     println!("\x1b[32mBuild artifacts:\x1b[0m");
     print_hr();
-    println!("\x1b[32mCreated:\x1b[0m {artifact}");
+    println!("\x1b[32mCreated:\x1b[0m {}", artifacts.join(", "));
+
+    output("version", version);
+    output("target", target);
+    if binaries.is_empty() {
+        output("binary_name", binary_name);
+        if let Some((_, path)) = packaged[0].unit.binaries.first() {
+            output("binary_path", path);
+        }
+    } else {
+        let names: Vec<&str> = binaries.iter().map(|b| b.name.as_str()).collect();
+        output("binary_name", &names.join(","));
+        let results: Vec<serde_json::Value> = packaged
+            .iter()
+            .flat_map(|p| {
+                p.unit.binaries.iter().map(move |(name, path)| {
+                    let binary = binaries.iter().find(|b| &b.name == name);
+                    serde_json::json!({
+                        "name": name,
+                        "package": binary.map(|b| b.package.as_str()).unwrap_or_default(),
+                        "version": binary.map(|b| b.version.as_str()).unwrap_or_default(),
+                        "binary_path": path,
+                        "artifact": p.artifact,
+                        "artifact_path": p.artifact_path,
+                        "sha256": p.checksums.sha256,
+                    })
+                })
+            })
+            .collect();
+        output_multiline(
+            "binaries",
+            &serde_json::to_string_pretty(&results).unwrap_or_default(),
+        );
+    }
 
-    output("version", &version);
-    output("binary_name", &binary_name);
-    output("target", &target);
-    output("binary_path", &binary_path);
-    output_build_results(
-        &binary_name,
-        &version,
-        &target,
-        &artifact,
-        &artifact_path,
-        &checksums,
-    );
-    Ok(())
+    if let Some(first) = packaged.first() {
+        let (name, version) = if packaged.len() == 1 {
+            (binary_name, version)
+        } else {
+            (first.unit.name.as_str(), first.unit.version.as_str())
+        };
+        output_build_results(
+            name,
+            version,
+            target,
+            &first.artifact,
+            &first.artifact_path,
+            &first.checksums,
+        );
+    }
 }
 
-pub fn run_release_linux_rpm() -> Result<()> {
-    let skip_build = env_or("SKIP_BUILD", "") == "true";
-    let custom_binary_path = env_or("BINARY_PATH", "");
+pub fn run_release_linux_deb(config: &ReleaseConfig) -> Result<()> {
+    let skip_build = config.get("SKIP_BUILD", "") == "true";
 
     if !skip_build {
         check_rust_toolchain()?;
     }
     check_nfpm()?;
 
-    let target = env_or("TARGET", "x86_64-unknown-linux-gnu");
-    let info = get_cargo_info()?;
-    let binary_name = env_or("BINARY_NAME", &info.name);
-    let version = info.version;
+    let target = config.get("TARGET", "x86_64-unknown-linux-gnu");
+    let info = get_cargo_info(config)?;
+    let binaries = discover_binaries(config)?;
+    let (binary_name, version) = package_name_and_version(config, &info, &binaries)?;
 
-    if binary_name.is_empty() {
-        return Err(Error::User("could not determine binary name".into()));
-    }
-    if version.is_empty() {
-        return Err(Error::User("could not determine version".into()));
-    }
-
-    let arch = target_to_rpm_arch(&target)?;
-    println!("\x1b[32mBuilding .rpm package:\x1b[0m {binary_name} v{version} for {arch}");
+    let arch = target_to_deb_arch(&target)?;
+    println!("\x1b[32mBuilding .deb package:\x1b[0m {binary_name} v{version} for {arch}");
 
     let release_dir = format!("target/{target}/release");
-    let binary_path = if skip_build && !custom_binary_path.is_empty() {
-        custom_binary_path
-    } else {
-        format!("{release_dir}/{binary_name}")
-    };
+    let units = package_units(
+        config,
+        &target,
+        &release_dir,
+        skip_build,
+        &binary_name,
+        &version,
+        &binaries,
+    )?;
 
-    if !Path::new(&binary_path).exists() {
-        if skip_build {
-            return Err(Error::User(format!("binary not found: {binary_path}")));
-        }
-        println!("\x1b[33mBinary not found, building...\x1b[0m");
-        let _ = fs::remove_dir_all(&release_dir);
-        fs::create_dir_all(&release_dir)?;
-        ensure_lockfile()?;
-        run_pre_build_hook()?;
-        install_linux_cross_deps(&target)?;
-        cargo_build(&target, &binary_name)?;
-    }
+    let pkg_dir = "target/pkg-deb";
+    let _ = fs::remove_dir_all(pkg_dir);
+    fs::create_dir_all(pkg_dir)?;
 
-    if !Path::new(&binary_path).exists() {
-        return Err(Error::User(format!("binary not found: {binary_path}")));
+    let section = config.get("PKG_SECTION", "utils");
+    let priority = config.get("PKG_PRIORITY", "optional");
+
+    let mut packaged = Vec::new();
+    for unit in units {
+        let mut nfpm_config = nfpm_base_config(config, &info, &unit.name, &unit.version, arch);
+        nfpm_config.push_str(&format!("section: {}\n", yaml_string(&section)));
+        nfpm_config.push_str(&format!("priority: {}\n", yaml_string(&priority)));
+        nfpm_config.push_str(&contents_section(config, &info, &unit)?);
+        nfpm_config.push_str(&nfpm_dependencies_section(config));
+
+        let artifact = format!("{}_{}_{arch}.deb", unit.name, unit.version);
+        let artifact_path = format!("{release_dir}/{artifact}");
+        let checksums = run_nfpm(
+            config,
+            pkg_dir,
+            &nfpm_config,
+            "deb",
+            &artifact,
+            &artifact_path,
+        )?;
+        packaged.push(PackagedUnit {
+            unit,
+            artifact,
+            artifact_path,
+            checksums,
+        });
     }
 
-    let pkg_dir = "target/pkg-rpm";
-    let _ = fs::remove_dir_all(pkg_dir);
-    fs::create_dir_all(pkg_dir)?;
+    output_package_results(&target, &binary_name, &version, &binaries, &packaged);
+    Ok(())
+}
 
-    let abs_binary_path = fs::canonicalize(&binary_path)?;
-    let abs_str = abs_binary_path.to_string_lossy().to_string();
+pub fn run_release_linux_rpm(config: &ReleaseConfig) -> Result<()> {
+    let skip_build = config.get("SKIP_BUILD", "") == "true";
 
-    let release_num = env_or("PKG_RELEASE", "1");
-    let group = env_or("PKG_GROUP", "Applications/System");
-    let description = env_or(
-        "PKG_DESCRIPTION",
-        &format!("{binary_name} - built with rust-build-package-release-action"),
-    );
-    let summary = env_or("PKG_SUMMARY", &description);
+    if !skip_build {
+        check_rust_toolchain()?;
+    }
+    check_nfpm()?;
 
-    let mut nfpm_config = nfpm_base_config(&binary_name, &version, arch);
-    nfpm_config.push_str(&format!("release: \"{release_num}\"\n"));
-    nfpm_config.push_str(&nfpm_contents_section(&binary_name, &abs_str));
-    nfpm_config.push_str(&format!(
-        "\nrpm:\n  group: \"{group}\"\n  summary: \"{summary}\"\n  compression: gzip\n"
-    ));
-    nfpm_config.push_str(&nfpm_dependencies_section());
+    let target = config.get("TARGET", "x86_64-unknown-linux-gnu");
+    let info = get_cargo_info(config)?;
+    let binaries = discover_binaries(config)?;
+    let (binary_name, version) = package_name_and_version(config, &info, &binaries)?;
 
-    let config_path = format!("{pkg_dir}/nfpm.yaml");
-    fs::write(&config_path, &nfpm_config)?;
+    let arch = target_to_rpm_arch(&target)?;
+    println!("\x1b[32mBuilding .rpm package:\x1b[0m {binary_name} v{version} for {arch}");
 
-    let artifact = format!("{binary_name}-{version}-{release_num}.{arch}.rpm");
-    let artifact_path = format!("{release_dir}/{artifact}");
+    let release_dir = format!("target/{target}/release");
+    let units = package_units(
+        config,
+        &target,
+        &release_dir,
+        skip_build,
+        &binary_name,
+        &version,
+        &binaries,
+    )?;
 
-    println!("\x1b[32mRunning nfpm...\x1b[0m");
-    run_command_inherit(
-        "nfpm",
-        &[
-            "package",
-            "--config",
-            &config_path,
-            "--packager",
+    let pkg_dir = "target/pkg-rpm";
+    let _ = fs::remove_dir_all(pkg_dir);
+    fs::create_dir_all(pkg_dir)?;
+
+    let release_num = config.get("PKG_RELEASE", "1");
+    let group = config.get("PKG_GROUP", "Applications/System");
+
+    let mut packaged = Vec::new();
+    for unit in units {
+        let description = config.get("PKG_DESCRIPTION", &info.description_or_default(&unit.name));
+        let summary = config.get("PKG_SUMMARY", &description);
+
+        let mut nfpm_config = nfpm_base_config(config, &info, &unit.name, &unit.version, arch);
+        nfpm_config.push_str(&format!("release: \"{release_num}\"\n"));
+        nfpm_config.push_str(&contents_section(config, &info, &unit)?);
+        nfpm_config.push_str(&format!(
+            "\nrpm:\n  group: {}\n  summary: {}\n  compression: gzip\n",
+            yaml_string(&group),
+            yaml_string(&summary)
+        ));
+        nfpm_config.push_str(&nfpm_dependencies_section(config));
+
+        let artifact = format!("{}-{}-{release_num}.{arch}.rpm", unit.name, unit.version);
+        let artifact_path = format!("{release_dir}/{artifact}");
+        let checksums = run_nfpm(
+            config,
+            pkg_dir,
+            &nfpm_config,
             "rpm",
-            "--target",
+            &artifact,
             &artifact_path,
-        ],
-    )?;
-
-    if !Path::new(&artifact_path).exists() {
-        return Err(Error::User(format!(
-            "failed to create package: {artifact_path}"
-        )));
+        )?;
+        packaged.push(PackagedUnit {
+            unit,
+            artifact,
+            artifact_path,
+            checksums,
+        });
     }
 
-    let checksums = generate_checksums(Path::new(&artifact_path))?;
-    println!();
-    println!("\x1b[32mBuild artifacts:\x1b[0m");
-    print_hr();
-    println!("\x1b[32mCreated:\x1b[0m {artifact}");
-
-    output("version", &version);
-    output("binary_name", &binary_name);
-    output("target", &target);
-    output("binary_path", &binary_path);
-    output_build_results(
-        &binary_name,
-        &version,
-        &target,
-        &artifact,
-        &artifact_path,
-        &checksums,
-    );
+    output_package_results(&target, &binary_name, &version, &binaries, &packaged);
     Ok(())
 }
 
-pub fn run_release_linux_apk() -> Result<()> {
-    let skip_build = env_or("SKIP_BUILD", "") == "true";
-    let custom_binary_path = env_or("BINARY_PATH", "");
+pub fn run_release_linux_apk(config: &ReleaseConfig) -> Result<()> {
+    let skip_build = config.get("SKIP_BUILD", "") == "true";
 
     if !skip_build {
         check_rust_toolchain()?;
     }
     check_nfpm()?;
 
-    let target = env_or("TARGET", "x86_64-unknown-linux-musl");
-    let info = get_cargo_info()?;
-    let binary_name = env_or("BINARY_NAME", &info.name);
-    let version = info.version;
-
-    if binary_name.is_empty() {
-        return Err(Error::User("could not determine binary name".into()));
-    }
-    if version.is_empty() {
-        return Err(Error::User("could not determine version".into()));
-    }
+    let target = config.get("TARGET", "x86_64-unknown-linux-musl");
+    let info = get_cargo_info(config)?;
+    let binaries = discover_binaries(config)?;
+    let (binary_name, version) = package_name_and_version(config, &info, &binaries)?;
 
     let arch = target_to_apk_arch(&target)?;
     println!("\x1b[32mBuilding .apk package:\x1b[0m {binary_name} v{version} for {arch}");
 
     let release_dir = format!("target/{target}/release");
-    let binary_path = if skip_build && !custom_binary_path.is_empty() {
-        custom_binary_path
-    } else {
-        format!("{release_dir}/{binary_name}")
-    };
-
-    if !Path::new(&binary_path).exists() {
-        if skip_build {
-            return Err(Error::User(format!("binary not found: {binary_path}")));
-        }
-        println!("\x1b[33mBinary not found, building...\x1b[0m");
-        let _ = fs::remove_dir_all(&release_dir);
-        fs::create_dir_all(&release_dir)?;
-        ensure_lockfile()?;
-        run_pre_build_hook()?;
-        install_linux_cross_deps(&target)?;
-        cargo_build(&target, &binary_name)?;
-    }
-
-    if !Path::new(&binary_path).exists() {
-        return Err(Error::User(format!("binary not found: {binary_path}")));
-    }
+    let units = package_units(
+        config,
+        &target,
+        &release_dir,
+        skip_build,
+        &binary_name,
+        &version,
+        &binaries,
+    )?;
 
     let pkg_dir = "target/pkg-apk";
     let _ = fs::remove_dir_all(pkg_dir);
     fs::create_dir_all(pkg_dir)?;
 
-    let abs_binary_path = fs::canonicalize(&binary_path)?;
-    let abs_str = abs_binary_path.to_string_lossy().to_string();
+    let release_num = config.get("PKG_RELEASE", "0");
 
-    let mut nfpm_config = nfpm_base_config(&binary_name, &version, arch);
-    nfpm_config.push_str(&nfpm_contents_section(&binary_name, &abs_str));
-    nfpm_config.push_str(&nfpm_dependencies_section());
+    let mut packaged = Vec::new();
+    for unit in units {
+        let mut nfpm_config = nfpm_base_config(config, &info, &unit.name, &unit.version, arch);
+        nfpm_config.push_str(&contents_section(config, &info, &unit)?);
+        nfpm_config.push_str(&nfpm_dependencies_section(config));
 
-    let config_path = format!("{pkg_dir}/nfpm.yaml");
-    fs::write(&config_path, &nfpm_config)?;
-
-    let release_num = env_or("PKG_RELEASE", "0");
-    let artifact = format!("{binary_name}-{version}-r{release_num}.apk");
-    let artifact_path = format!("{release_dir}/{artifact}");
-
-    println!("\x1b[32mRunning nfpm...\x1b[0m");
-    run_command_inherit(
-        "nfpm",
-        &[
-            "package",
-            "--config",
-            &config_path,
-            "--packager",
+        let artifact = format!("{}-{}-r{release_num}.apk", unit.name, unit.version);
+        let artifact_path = format!("{release_dir}/{artifact}");
+        let checksums = run_nfpm(
+            config,
+            pkg_dir,
+            &nfpm_config,
             "apk",
-            "--target",
+            &artifact,
             &artifact_path,
-        ],
-    )?;
-
-    if !Path::new(&artifact_path).exists() {
-        return Err(Error::User(format!(
-            "failed to create package: {artifact_path}"
-        )));
+        )?;
+        packaged.push(PackagedUnit {
+            unit,
+            artifact,
+            artifact_path,
+            checksums,
+        });
     }
 
-    let checksums = generate_checksums(Path::new(&artifact_path))?;
-    println!();
-    println!("\x1b[32mBuild artifacts:\x1b[0m");
-    print_hr();
-    println!("\x1b[32mCreated:\x1b[0m {artifact}");
-
-    output("version", &version);
-    output("binary_name", &binary_name);
-    output("target", &target);
-    output("binary_path", &binary_path);
-    output_build_results(
-        &binary_name,
-        &version,
-        &target,
-        &artifact,
-        &artifact_path,
-        &checksums,
-    );
+    output_package_results(&target, &binary_name, &version, &binaries, &packaged);
     Ok(())
 }
 
-pub fn run_release_macos_dmg() -> Result<()> {
-    let skip_build = env_or("SKIP_BUILD", "") == "true";
-    let custom_binary_path = env_or("BINARY_PATH", "");
+pub fn run_release_macos_dmg(config: &ReleaseConfig) -> Result<()> {
+    if !config.get("BINARIES", "").is_empty() {
+        return Err(Error::User(
+            "release-macos-dmg packages a single binary; unset binaries or use binary-name".into(),
+        ));
+    }
+    let skip_build = config.get("SKIP_BUILD", "") == "true";
+    let custom_binary_path = config.get("BINARY_PATH", "");
 
     if !skip_build {
         check_rust_toolchain()?;
     }
 
-    let target = env_or("TARGET", "aarch64-apple-darwin");
-    let info = get_cargo_info()?;
-    let binary_name = env_or("BINARY_NAME", &info.name);
+    let target = config.get("TARGET", "aarch64-apple-darwin");
+    let info = get_cargo_info(config)?;
+    let binary_name = config.get("BINARY_NAME", &info.name);
     let version = info.version;
 
     if binary_name.is_empty() {
@@ -792,9 +1100,9 @@ pub fn run_release_macos_dmg() -> Result<()> {
         let _ = fs::remove_dir_all(&release_dir);
         fs::create_dir_all(&release_dir)?;
         ensure_lockfile()?;
-        run_pre_build_hook()?;
+        run_pre_build_hook(config)?;
         run_command_inherit("rustup", &["target", "add", &target])?;
-        cargo_build(&target, &binary_name)?;
+        cargo_build(config, &target, &binary_name)?;
     }
 
     if !Path::new(&binary_path).exists() {
@@ -808,7 +1116,7 @@ pub fn run_release_macos_dmg() -> Result<()> {
     fs::copy(&binary_path, format!("{dmg_dir}/{binary_name}"))?;
     run_command_inherit("chmod", &["+x", &format!("{dmg_dir}/{binary_name}")])?;
     copy_docs(Path::new(dmg_dir))?;
-    copy_includes(Path::new(dmg_dir))?;
+    copy_includes(config, Path::new(dmg_dir))?;
 
     // Create install/uninstall scripts
     create_install_script(dmg_dir, &binary_name)?;
@@ -827,7 +1135,7 @@ pub fn run_release_macos_dmg() -> Result<()> {
         )));
     }
 
-    let checksums = generate_checksums(Path::new(&artifact_path))?;
+    let checksums = generate_checksums(config, Path::new(&artifact_path))?;
     println!();
     println!("\x1b[32mBuild artifacts:\x1b[0m");
     print_hr();
@@ -962,18 +1270,24 @@ fi"#
     Ok(())
 }
 
-pub fn run_release_windows_msi() -> Result<()> {
-    let skip_build = env_or("SKIP_BUILD", "") == "true";
-    let custom_binary_path = env_or("BINARY_PATH", "");
+pub fn run_release_windows_msi(config: &ReleaseConfig) -> Result<()> {
+    if !config.get("BINARIES", "").is_empty() {
+        return Err(Error::User(
+            "release-windows-msi packages a single binary; unset binaries or use binary-name"
+                .into(),
+        ));
+    }
+    let skip_build = config.get("SKIP_BUILD", "") == "true";
+    let custom_binary_path = config.get("BINARY_PATH", "");
 
     if !skip_build {
         check_rust_toolchain()?;
     }
 
-    let target = env_or("TARGET", "x86_64-pc-windows-msvc");
-    let info = get_cargo_info()?;
-    let package_name = env_or("PACKAGE", &info.name);
-    let binary_name = env_or("BINARY_NAME", &info.name);
+    let target = config.get("TARGET", "x86_64-pc-windows-msvc");
+    let info = get_cargo_info(config)?;
+    let package_name = config.get("PACKAGE", &info.name);
+    let binary_name = config.get("BINARY_NAME", &info.name);
     let version = info.version;
 
     if binary_name.is_empty() {
@@ -1007,9 +1321,9 @@ pub fn run_release_windows_msi() -> Result<()> {
         let _ = fs::remove_dir_all(&release_dir);
         fs::create_dir_all(&release_dir)?;
         ensure_lockfile()?;
-        run_pre_build_hook()?;
+        run_pre_build_hook(config)?;
         run_command_inherit("rustup", &["target", "add", &target])?;
-        cargo_build(&target, &binary_name)?;
+        cargo_build(config, &target, &binary_name)?;
     }
 
     let binary_path = format!("{release_dir}/{binary_name}.exe");
@@ -1018,7 +1332,7 @@ pub fn run_release_windows_msi() -> Result<()> {
     }
 
     copy_docs(Path::new(&release_dir))?;
-    copy_includes(Path::new(&release_dir))?;
+    copy_includes(config, Path::new(&release_dir))?;
 
     // Copy to target/release for cargo-wix
     fs::create_dir_all("target/release")?;
@@ -1075,7 +1389,7 @@ pub fn run_release_windows_msi() -> Result<()> {
         return Err(Error::User(format!("MSI not created: {msi_path}")));
     }
 
-    let checksums = generate_checksums(Path::new(&msi_path))?;
+    let checksums = generate_checksums(config, Path::new(&msi_path))?;
     let artifact_path = msi_path.replace('\\', "/");
     let artifact = Path::new(&artifact_path)
         .file_name()
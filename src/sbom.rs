@@ -1,67 +1,829 @@
 use crate::cargo_info::get_cargo_info;
-use crate::env_or;
+use crate::checksum::{ChecksumAlgorithm, hash_file, sha256_bytes};
+use crate::collect_artifacts::find_artifacts;
+use crate::config::ReleaseConfig;
 use crate::error::{Error, Result};
-use crate::output::{output, print_hr};
-use crate::tools::{check_cargo_sbom, check_rust_toolchain};
+use crate::output::{output, output_multiline, print_hr};
+use crate::timestamp::{format_timestamp, now_secs};
+use crate::tools::check_rust_toolchain;
+use crate::{DEFAULT_ARTIFACTS_DIR, env_or, parse_comma_list};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::str::FromStr;
 
-/// Generate SPDX and CycloneDX SBOMs.
-fn generate_sbom_files(
-    output_dir: &str,
-    binary_name: &str,
-    version: &str,
-) -> Result<(String, String)> {
-    let spdx_path = format!("{output_dir}/{binary_name}-{version}.spdx.json");
-    let cyclonedx_path = format!("{output_dir}/{binary_name}-{version}.cdx.json");
+const TOOL_NAME: &str = "rust-release-action";
+const TOOL_VERSION: &str = env!("CARGO_PKG_VERSION");
 
-    println!("\x1b[32mGenerating SPDX SBOM...\x1b[0m");
-    let result = Command::new("cargo")
-        .args(["sbom", "--output-format", "spdx_json_2_3"])
-        .output()
-        .map_err(|e| Error::User(format!("cargo-sbom failed: {e}")))?;
-    if !result.status.success() {
-        return Err(Error::User(format!(
-            "cargo-sbom SPDX generation failed: {}",
-            String::from_utf8_lossy(&result.stderr)
-        )));
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SbomFormat {
+    Spdx23,
+    CycloneDx15,
+    CycloneDx16,
+}
+
+impl SbomFormat {
+    /// File name suffix, e.g. `spdx.json`.
+    pub fn extension(self) -> &'static str {
+        match self {
+            SbomFormat::Spdx23 => "spdx.json",
+            SbomFormat::CycloneDx15 | SbomFormat::CycloneDx16 => "cdx.json",
+        }
+    }
+}
+
+impl FromStr for SbomFormat {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "spdx" | "spdx-2.3" => Ok(SbomFormat::Spdx23),
+            "cyclonedx-1.5" => Ok(SbomFormat::CycloneDx15),
+            "cyclonedx" | "cyclonedx-1.6" => Ok(SbomFormat::CycloneDx16),
+            other => Err(Error::User(format!(
+                "unsupported SBOM format: {other} (expected spdx, cyclonedx, cyclonedx-1.5 or cyclonedx-1.6)"
+            ))),
+        }
+    }
+}
+
+/// Parses a comma-separated list of SBOM formats. Both CycloneDX versions
+/// write to `.cdx.json`, so only one of them may be requested.
+pub fn parse_sbom_formats(raw: &str) -> Result<Vec<SbomFormat>> {
+    let mut formats = Vec::new();
+    for value in parse_comma_list(raw) {
+        let format: SbomFormat = value.parse()?;
+        if !formats.contains(&format) {
+            formats.push(format);
+        }
+    }
+    if formats.contains(&SbomFormat::CycloneDx15) && formats.contains(&SbomFormat::CycloneDx16) {
+        return Err(Error::User(
+            "sbom-format can include only one of cyclonedx-1.5 and cyclonedx-1.6".into(),
+        ));
+    }
+    Ok(formats)
+}
+
+/// The subset of `cargo metadata` output used for SBOMs.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Metadata {
+    pub packages: Vec<MetadataPackage>,
+    pub workspace_members: Vec<String>,
+    pub resolve: Option<Resolve>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct MetadataPackage {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub license: Option<String>,
+    pub source: Option<String>,
+    pub description: Option<String>,
+    pub repository: Option<String>,
+    pub homepage: Option<String>,
+    #[serde(default)]
+    pub authors: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Resolve {
+    pub nodes: Vec<ResolveNode>,
+    pub root: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ResolveNode {
+    pub id: String,
+    #[serde(default)]
+    pub deps: Vec<NodeDep>,
+    #[serde(default)]
+    pub features: Vec<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct NodeDep {
+    pub pkg: String,
+    #[serde(default)]
+    pub dep_kinds: Vec<DepKind>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct DepKind {
+    pub kind: Option<String>,
+}
+
+/// A package in the SBOM with its enabled features.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SbomPackage {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    pub license: Option<String>,
+    pub source: Option<String>,
+    pub description: Option<String>,
+    pub repository: Option<String>,
+    pub homepage: Option<String>,
+    pub authors: Vec<String>,
+    pub features: Vec<String>,
+}
+
+impl SbomPackage {
+    pub fn purl(&self) -> String {
+        format!("pkg:cargo/{}@{}", self.name, self.version)
+    }
+
+    pub fn is_crates_io(&self) -> bool {
+        self.source
+            .as_deref()
+            .is_some_and(|s| s.contains("crates.io-index"))
+    }
+
+    /// The license as an SPDX expression: Cargo's legacy `MIT/Apache-2.0`
+    /// becomes `MIT OR Apache-2.0`.
+    pub fn license_expression(&self) -> Option<String> {
+        self.license
+            .as_ref()
+            .map(|l| l.split('/').map(str::trim).collect::<Vec<_>>().join(" OR "))
+    }
+
+    fn download_location(&self) -> String {
+        if self.is_crates_io() {
+            format!(
+                "https://crates.io/api/v1/crates/{}/{}/download",
+                self.name, self.version
+            )
+        } else if let Some(git) = self.source.as_deref().and_then(|s| s.strip_prefix("git+")) {
+            format!("git+{git}")
+        } else {
+            "NOASSERTION".to_string()
+        }
+    }
+}
+
+/// The root package and its runtime dependency graph.
+#[derive(Debug, Clone)]
+pub struct DependencyGraph {
+    pub root: SbomPackage,
+    /// Every dependency reachable from the root, sorted by name and version.
+    pub packages: Vec<SbomPackage>,
+    /// Direct dependencies of each package, by package id.
+    pub edges: BTreeMap<String, Vec<String>>,
+}
+
+/// Walks the resolved graph from the root package, following normal
+/// dependencies only: dev-dependencies and build-dependencies do not end up
+/// in the released binary. The metadata must come from `cargo metadata`
+/// invoked with the build's `--features` and `--filter-platform`, so that
+/// optional and platform-specific dependencies match the actual build.
+pub fn resolve_dependencies(metadata: &Metadata, package: &str) -> Result<DependencyGraph> {
+    let resolve = metadata
+        .resolve
+        .as_ref()
+        .ok_or_else(|| Error::User("cargo metadata returned no dependency resolution".into()))?;
+    let by_id: BTreeMap<&str, &MetadataPackage> = metadata
+        .packages
+        .iter()
+        .map(|p| (p.id.as_str(), p))
+        .collect();
+    let nodes: BTreeMap<&str, &ResolveNode> =
+        resolve.nodes.iter().map(|n| (n.id.as_str(), n)).collect();
+
+    let root_id = if !package.is_empty() {
+        metadata
+            .workspace_members
+            .iter()
+            .find(|id| by_id.get(id.as_str()).is_some_and(|p| p.name == package))
+            .cloned()
+            .ok_or_else(|| Error::User(format!("package not found in workspace: {package}")))?
+    } else if let Some(root) = &resolve.root {
+        root.clone()
+    } else {
+        metadata
+            .workspace_members
+            .first()
+            .cloned()
+            .ok_or_else(|| Error::User("cargo metadata returned no workspace members".into()))?
+    };
+
+    let to_package = |id: &str| -> Result<SbomPackage> {
+        let p = by_id
+            .get(id)
+            .ok_or_else(|| Error::User(format!("package missing from cargo metadata: {id}")))?;
+        Ok(SbomPackage {
+            id: p.id.clone(),
+            name: p.name.clone(),
+            version: p.version.clone(),
+            license: p.license.clone(),
+            source: p.source.clone(),
+            description: p.description.clone(),
+            repository: p.repository.clone(),
+            homepage: p.homepage.clone(),
+            authors: p.authors.clone(),
+            features: nodes
+                .get(id)
+                .map(|n| n.features.clone())
+                .unwrap_or_default(),
+        })
+    };
+
+    let mut edges: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut seen: BTreeSet<String> = BTreeSet::new();
+    let mut queue = vec![root_id.clone()];
+    while let Some(id) = queue.pop() {
+        if !seen.insert(id.clone()) {
+            continue;
+        }
+        let mut direct: Vec<String> = nodes
+            .get(id.as_str())
+            .map(|n| {
+                n.deps
+                    .iter()
+                    .filter(|d| d.dep_kinds.iter().any(|k| k.kind.is_none()))
+                    .map(|d| d.pkg.clone())
+                    .collect()
+            })
+            .unwrap_or_default();
+        direct.sort();
+        direct.dedup();
+        queue.extend(direct.iter().cloned());
+        edges.insert(id, direct);
+    }
+
+    let mut packages = seen
+        .iter()
+        .filter(|id| **id != root_id)
+        .map(|id| to_package(id))
+        .collect::<Result<Vec<_>>>()?;
+    packages.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+
+    Ok(DependencyGraph {
+        root: to_package(&root_id)?,
+        packages,
+        edges,
+    })
+}
+
+/// Runs `cargo metadata` resolved for the given target and feature selection.
+pub fn cargo_metadata(
+    manifest_path: &str,
+    target: &str,
+    features: &[String],
+    no_default_features: bool,
+) -> Result<Metadata> {
+    let mut args = vec![
+        "metadata".to_string(),
+        "--format-version".to_string(),
+        "1".to_string(),
+        "--manifest-path".to_string(),
+        manifest_path.to_string(),
+    ];
+    if !target.is_empty() {
+        args.extend(["--filter-platform".to_string(), target.to_string()]);
+    }
+    if !features.is_empty() {
+        args.extend(["--features".to_string(), features.join(",")]);
+    }
+    if no_default_features {
+        args.push("--no-default-features".to_string());
     }
-    fs::write(&spdx_path, &result.stdout)?;
 
-    println!("\x1b[32mGenerating CycloneDX SBOM...\x1b[0m");
     let result = Command::new("cargo")
-        .args(["sbom", "--output-format", "cyclone_dx_json_1_4"])
+        .args(&args)
         .output()
-        .map_err(|e| Error::User(format!("cargo-sbom failed: {e}")))?;
+        .map_err(|e| Error::User(format!("cargo metadata failed: {e}")))?;
     if !result.status.success() {
-        return Err(Error::User(format!(
-            "cargo-sbom CycloneDX generation failed: {}",
-            String::from_utf8_lossy(&result.stderr)
-        )));
+        return Err(Error::Command {
+            command: format!("cargo {}", args.join(" ")),
+            stderr: String::from_utf8_lossy(&result.stderr).to_string(),
+        });
+    }
+    Ok(serde_json::from_slice(&result.stdout)?)
+}
+
+/// The document creation time: SOURCE_DATE_EPOCH when set, for reproducible
+/// SBOMs, otherwise the current time.
+pub fn creation_timestamp() -> String {
+    let secs = env_or("SOURCE_DATE_EPOCH", "")
+        .parse::<u64>()
+        .ok()
+        .unwrap_or_else(now_secs);
+    format_timestamp(secs)
+}
+
+/// The release file an SBOM describes, for SBOMs generated per artifact.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SbomArtifact {
+    pub name: String,
+    pub sha256: String,
+    pub sha512: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub target: String,
+}
+
+impl SbomArtifact {
+    /// Hashes `path` and records `target` as the triple it was built for.
+    pub fn from_file(path: &Path, target: &str) -> Result<Self> {
+        let hashes = hash_file(
+            path,
+            &[ChecksumAlgorithm::Sha256, ChecksumAlgorithm::Sha512],
+        )?;
+        let hash = |algorithm| {
+            hashes
+                .iter()
+                .find(|(a, _)| *a == algorithm)
+                .map(|(_, h)| h.clone())
+                .unwrap_or_default()
+        };
+        Ok(SbomArtifact {
+            name: path
+                .file_name()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .to_string(),
+            sha256: hash(ChecksumAlgorithm::Sha256),
+            sha512: hash(ChecksumAlgorithm::Sha512),
+            target: target.to_string(),
+        })
     }
-    fs::write(&cyclonedx_path, &result.stdout)?;
 
-    if !Path::new(&spdx_path).exists() {
+    /// SPDX 2.3 primary package purpose for the file type.
+    fn spdx_purpose(&self) -> &'static str {
+        let name = self.name.to_lowercase();
+        if [".deb", ".rpm", ".apk", ".msi", ".dmg"]
+            .iter()
+            .any(|ext| name.ends_with(ext))
+        {
+            "INSTALL"
+        } else if [".tar.gz", ".tar.xz", ".tar.zst", ".zip"]
+            .iter()
+            .any(|ext| name.ends_with(ext))
+        {
+            "ARCHIVE"
+        } else {
+            "APPLICATION"
+        }
+    }
+}
+
+/// Extracts the target triple from an artifact named
+/// `<binary>-<version>-<target>[.<ext>]`, as produced by the release commands.
+pub fn artifact_target(artifact: &str, binary_name: &str, version: &str) -> Option<String> {
+    let rest = artifact.strip_prefix(&format!("{binary_name}-{version}-"))?;
+    let target = [
+        ".tar.gz", ".tar.xz", ".tar.zst", ".zip", ".dmg", ".msi", ".exe",
+    ]
+    .iter()
+    .find_map(|ext| rest.strip_suffix(ext))
+    .unwrap_or(rest);
+    (target.split('-').count() >= 2 && !target.contains('.')).then(|| target.to_string())
+}
+
+/// A stable fingerprint of the graph and the described artifact, used for
+/// document identifiers so that the same build produces the same SBOM.
+fn document_digest(graph: &DependencyGraph, artifact: Option<&SbomArtifact>) -> String {
+    let mut ids = vec![graph.root.id.clone()];
+    ids.extend(graph.packages.iter().map(|p| p.id.clone()));
+    ids.extend(graph.root.features.iter().cloned());
+    if let Some(artifact) = artifact {
+        ids.extend([artifact.name.clone(), artifact.sha256.clone()]);
+    }
+    sha256_bytes(ids.join("\n").as_bytes())
+}
+
+/// Formats the first 128 bits of `digest` as an RFC 4122 version 4 UUID.
+fn uuid_from_digest(digest: &str) -> String {
+    let hex: Vec<char> = digest.chars().take(32).collect();
+    let mut hex: String = hex.into_iter().collect();
+    hex.replace_range(12..13, "4");
+    let variant = u8::from_str_radix(&hex[16..17], 16).unwrap_or(0) & 0x3 | 0x8;
+    hex.replace_range(16..17, &format!("{variant:x}"));
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+fn spdx_id(package: &SbomPackage) -> String {
+    let sanitized: String = format!("{}-{}", package.name, package.version)
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("SPDXRef-Package-{sanitized}")
+}
+
+fn spdx_package(package: &SbomPackage) -> Value {
+    let mut value = json!({
+        "SPDXID": spdx_id(package),
+        "name": package.name,
+        "versionInfo": package.version,
+        "downloadLocation": package.download_location(),
+        "filesAnalyzed": false,
+        "licenseConcluded": "NOASSERTION",
+        "licenseDeclared": package.license_expression().unwrap_or_else(|| "NOASSERTION".into()),
+        "copyrightText": "NOASSERTION",
+        "externalRefs": [{
+            "referenceCategory": "PACKAGE-MANAGER",
+            "referenceType": "purl",
+            "referenceLocator": package.purl(),
+        }],
+    });
+    if let Some(description) = &package.description {
+        value["description"] = json!(description.trim());
+    }
+    if let Some(homepage) = package.homepage.as_ref().or(package.repository.as_ref()) {
+        value["homepage"] = json!(homepage);
+    }
+    if !package.features.is_empty() {
+        value["comment"] = json!(format!("Enabled features: {}", package.features.join(", ")));
+    }
+    value
+}
+
+fn spdx_artifact_id(artifact: &SbomArtifact) -> String {
+    let sanitized: String = artifact
+        .name
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    format!("SPDXRef-Artifact-{sanitized}")
+}
+
+fn spdx_artifact_package(artifact: &SbomArtifact, root: &SbomPackage) -> Value {
+    let mut value = json!({
+        "SPDXID": spdx_artifact_id(artifact),
+        "name": artifact.name,
+        "packageFileName": artifact.name,
+        "versionInfo": root.version,
+        "downloadLocation": "NOASSERTION",
+        "filesAnalyzed": false,
+        "primaryPackagePurpose": artifact.spdx_purpose(),
+        "checksums": [
+            { "algorithm": "SHA256", "checksumValue": artifact.sha256 },
+            { "algorithm": "SHA512", "checksumValue": artifact.sha512 },
+        ],
+        "licenseConcluded": "NOASSERTION",
+        "licenseDeclared": root.license_expression().unwrap_or_else(|| "NOASSERTION".into()),
+        "copyrightText": "NOASSERTION",
+    });
+    if !artifact.target.is_empty() {
+        value["comment"] = json!(format!("Target: {}", artifact.target));
+    }
+    value
+}
+
+/// Builds an SPDX 2.3 JSON document. With `artifact`, the document describes
+/// the release file (with its checksums), which contains the root package.
+pub fn spdx_document(
+    graph: &DependencyGraph,
+    artifact: Option<&SbomArtifact>,
+    created: &str,
+) -> Value {
+    let root = &graph.root;
+    let by_id: BTreeMap<&str, &SbomPackage> = graph
+        .packages
+        .iter()
+        .chain(std::iter::once(root))
+        .map(|p| (p.id.as_str(), p))
+        .collect();
+
+    let mut packages = Vec::new();
+    let mut relationships = Vec::new();
+    if let Some(artifact) = artifact {
+        packages.push(spdx_artifact_package(artifact, root));
+        relationships.push(json!({
+            "spdxElementId": "SPDXRef-DOCUMENT",
+            "relationshipType": "DESCRIBES",
+            "relatedSpdxElement": spdx_artifact_id(artifact),
+        }));
+        relationships.push(json!({
+            "spdxElementId": spdx_artifact_id(artifact),
+            "relationshipType": "CONTAINS",
+            "relatedSpdxElement": spdx_id(root),
+        }));
+    } else {
+        relationships.push(json!({
+            "spdxElementId": "SPDXRef-DOCUMENT",
+            "relationshipType": "DESCRIBES",
+            "relatedSpdxElement": spdx_id(root),
+        }));
+    }
+    packages.push(spdx_package(root));
+    packages.extend(graph.packages.iter().map(spdx_package));
+
+    for (from, deps) in &graph.edges {
+        for to in deps {
+            if let (Some(from), Some(to)) = (by_id.get(from.as_str()), by_id.get(to.as_str())) {
+                relationships.push(json!({
+                    "spdxElementId": spdx_id(from),
+                    "relationshipType": "DEPENDS_ON",
+                    "relatedSpdxElement": spdx_id(to),
+                }));
+            }
+        }
+    }
+
+    let name = artifact
+        .map(|a| a.name.clone())
+        .unwrap_or_else(|| format!("{}-{}", root.name, root.version));
+    json!({
+        "spdxVersion": "SPDX-2.3",
+        "dataLicense": "CC0-1.0",
+        "SPDXID": "SPDXRef-DOCUMENT",
+        "name": name,
+        "documentNamespace": format!(
+            "https://spdx.org/spdxdocs/{}-{}-{}",
+            root.name,
+            root.version,
+            uuid_from_digest(&document_digest(graph, artifact))
+        ),
+        "creationInfo": {
+            "created": created,
+            "creators": [format!("Tool: {TOOL_NAME}-{TOOL_VERSION}")],
+        },
+        "packages": packages,
+        "relationships": relationships,
+    })
+}
+
+fn cyclonedx_component(package: &SbomPackage, kind: &str) -> Value {
+    let mut value = json!({
+        "type": kind,
+        "bom-ref": package.purl(),
+        "name": package.name,
+        "version": package.version,
+        "purl": package.purl(),
+    });
+    if let Some(expression) = package.license_expression() {
+        value["licenses"] = json!([{ "expression": expression }]);
+    }
+    if let Some(description) = &package.description {
+        value["description"] = json!(description.trim());
+    }
+    if !package.authors.is_empty() {
+        value["author"] = json!(package.authors.join(", "));
+    }
+    let mut references = Vec::new();
+    if let Some(repository) = &package.repository {
+        references.push(json!({ "type": "vcs", "url": repository }));
+    }
+    if let Some(homepage) = &package.homepage {
+        references.push(json!({ "type": "website", "url": homepage }));
+    }
+    if !references.is_empty() {
+        value["externalReferences"] = json!(references);
+    }
+    if !package.features.is_empty() {
+        value["properties"] = json!([{
+            "name": "cdx:cargo:features",
+            "value": package.features.join(","),
+        }]);
+    }
+    value
+}
+
+fn cyclonedx_artifact_component(artifact: &SbomArtifact, root: &SbomPackage) -> Value {
+    let mut value = json!({
+        "type": "file",
+        "bom-ref": artifact.name,
+        "name": artifact.name,
+        "version": root.version,
+        "hashes": [
+            { "alg": "SHA-256", "content": artifact.sha256 },
+            { "alg": "SHA-512", "content": artifact.sha512 },
+        ],
+    });
+    if !artifact.target.is_empty() {
+        value["properties"] = json!([{
+            "name": "cdx:rustc:sbom:target:triple",
+            "value": artifact.target,
+        }]);
+    }
+    value
+}
+
+/// Builds a CycloneDX JSON document with the given spec version (`1.5` or `1.6`).
+/// With `artifact`, the release file is the subject of the BOM and the root
+/// package becomes one of its components.
+pub fn cyclonedx_document(
+    graph: &DependencyGraph,
+    artifact: Option<&SbomArtifact>,
+    spec_version: &str,
+    created: &str,
+) -> Value {
+    let by_id: BTreeMap<&str, &SbomPackage> = graph
+        .packages
+        .iter()
+        .chain(std::iter::once(&graph.root))
+        .map(|p| (p.id.as_str(), p))
+        .collect();
+
+    let mut dependencies: Vec<Value> = graph
+        .edges
+        .iter()
+        .filter_map(|(from, deps)| {
+            let from = by_id.get(from.as_str())?;
+            let depends_on: Vec<String> = deps
+                .iter()
+                .filter_map(|d| by_id.get(d.as_str()).map(|p| p.purl()))
+                .collect();
+            Some(json!({ "ref": from.purl(), "dependsOn": depends_on }))
+        })
+        .collect();
+
+    let mut components: Vec<Value> = graph
+        .packages
+        .iter()
+        .map(|p| cyclonedx_component(p, "library"))
+        .collect();
+    let subject = match artifact {
+        Some(artifact) => {
+            components.insert(0, cyclonedx_component(&graph.root, "application"));
+            dependencies.insert(
+                0,
+                json!({ "ref": artifact.name, "dependsOn": [graph.root.purl()] }),
+            );
+            cyclonedx_artifact_component(artifact, &graph.root)
+        }
+        None => cyclonedx_component(&graph.root, "application"),
+    };
+
+    json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": spec_version,
+        "serialNumber": format!("urn:uuid:{}", uuid_from_digest(&document_digest(graph, artifact))),
+        "version": 1,
+        "metadata": {
+            "timestamp": created,
+            "tools": {
+                "components": [{
+                    "type": "application",
+                    "name": TOOL_NAME,
+                    "version": TOOL_VERSION,
+                }],
+            },
+            "component": subject,
+        },
+        "components": components,
+        "dependencies": dependencies,
+    })
+}
+
+/// Renders `graph` in `format`, optionally describing a release `artifact`.
+pub fn sbom_document(
+    graph: &DependencyGraph,
+    artifact: Option<&SbomArtifact>,
+    format: SbomFormat,
+    created: &str,
+) -> Value {
+    match format {
+        SbomFormat::Spdx23 => spdx_document(graph, artifact, created),
+        SbomFormat::CycloneDx15 => cyclonedx_document(graph, artifact, "1.5", created),
+        SbomFormat::CycloneDx16 => cyclonedx_document(graph, artifact, "1.6", created),
+    }
+}
+
+/// SBOMs written for one release artifact.
+#[derive(Debug, Clone, Serialize)]
+pub struct ArtifactSbom {
+    #[serde(flatten)]
+    pub artifact: SbomArtifact,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spdx: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cyclonedx: Option<String>,
+}
+
+struct SbomSettings {
+    manifest: String,
+    package: String,
+    features: Vec<String>,
+    no_default_features: bool,
+    formats: Vec<SbomFormat>,
+    output_dir: String,
+    created: String,
+}
+
+impl SbomSettings {
+    fn resolve(&self, target: &str) -> Result<DependencyGraph> {
+        let metadata = cargo_metadata(
+            &self.manifest,
+            target,
+            &self.features,
+            self.no_default_features,
+        )?;
+        let graph = resolve_dependencies(&metadata, &self.package)?;
+        println!(
+            "\x1b[32mResolved:\x1b[0m {} dependencies{}",
+            graph.packages.len(),
+            if target.is_empty() {
+                String::new()
+            } else {
+                format!(" for {target}")
+            }
+        );
+        Ok(graph)
+    }
+
+    /// Writes one SBOM per format as `<output_dir>/<base>.<ext>`.
+    fn write(
+        &self,
+        graph: &DependencyGraph,
+        artifact: Option<&SbomArtifact>,
+        base: &str,
+    ) -> Result<Vec<(SbomFormat, String)>> {
+        let mut written = Vec::new();
+        for format in &self.formats {
+            let path = format!("{}/{base}.{}", self.output_dir, format.extension());
+            let document = sbom_document(graph, artifact, *format, &self.created);
+            fs::write(&path, serde_json::to_string_pretty(&document)?)?;
+            if !Path::new(&path).exists() {
+                return Err(Error::User(format!("SBOM was not created: {path}")));
+            }
+            println!("  {path}");
+            written.push((*format, path));
+        }
+        Ok(written)
+    }
+}
+
+/// Generates SBOMs for every release artifact in ARTIFACTS_DIR, resolving
+/// dependencies for the target triple in each artifact's name.
+fn generate_artifact_sboms(
+    config: &ReleaseConfig,
+    settings: &SbomSettings,
+    binary_name: &str,
+    version: &str,
+) -> Result<Vec<ArtifactSbom>> {
+    let artifacts_dir = config.get("ARTIFACTS_DIR", DEFAULT_ARTIFACTS_DIR);
+    let dir = Path::new(&artifacts_dir);
+    if !dir.is_dir() {
         return Err(Error::User(format!(
-            "SPDX SBOM was not created: {spdx_path}"
+            "artifacts directory not found: {artifacts_dir}"
         )));
     }
-    if !Path::new(&cyclonedx_path).exists() {
+    let files: Vec<PathBuf> = find_artifacts(dir)
+        .into_iter()
+        .map(|name| dir.join(name))
+        .collect();
+    if files.is_empty() {
         return Err(Error::User(format!(
-            "CycloneDX SBOM was not created: {cyclonedx_path}"
+            "no release artifacts found in {artifacts_dir}"
         )));
     }
 
-    Ok((spdx_path, cyclonedx_path))
+    let default_target = config.get("TARGET", "");
+    let mut graphs: BTreeMap<String, DependencyGraph> = BTreeMap::new();
+    let mut sboms = Vec::new();
+    for file in &files {
+        let name = file.file_name().unwrap_or_default().to_string_lossy();
+        let target =
+            artifact_target(&name, binary_name, version).unwrap_or_else(|| default_target.clone());
+        if !graphs.contains_key(&target) {
+            graphs.insert(target.clone(), settings.resolve(&target)?);
+        }
+        let artifact = SbomArtifact::from_file(file, &target)?;
+        let mut sbom = ArtifactSbom {
+            artifact,
+            spdx: None,
+            cyclonedx: None,
+        };
+        for (format, path) in settings.write(&graphs[&target], Some(&sbom.artifact), &name)? {
+            match format {
+                SbomFormat::Spdx23 => sbom.spdx = Some(path),
+                SbomFormat::CycloneDx15 | SbomFormat::CycloneDx16 => sbom.cyclonedx = Some(path),
+            }
+        }
+        sboms.push(sbom);
+    }
+    Ok(sboms)
 }
 
-pub fn run_generate_sbom() -> Result<()> {
+pub fn run_generate_sbom(config: &ReleaseConfig) -> Result<()> {
     check_rust_toolchain()?;
-    check_cargo_sbom()?;
 
-    let info = get_cargo_info()?;
-    let binary_name = env_or("BINARY_NAME", &info.name);
+    let info = get_cargo_info(config)?;
+    let binary_name = config.get("BINARY_NAME", &info.name);
     let version = &info.version;
 
     if binary_name.is_empty() {
@@ -71,20 +833,47 @@ pub fn run_generate_sbom() -> Result<()> {
         return Err(Error::User("could not determine version".into()));
     }
 
-    println!("\x1b[32mGenerating SBOM:\x1b[0m {binary_name} v{version}");
+    let formats = parse_sbom_formats(&config.get("SBOM_FORMAT", "spdx,cyclonedx"))?;
+    if formats.is_empty() {
+        return Err(Error::User("no SBOM format selected".into()));
+    }
 
-    let output_dir = env_or("SBOM_OUTPUT_DIR", "target/sbom");
-    fs::create_dir_all(&output_dir)?;
+    println!("\x1b[32mGenerating SBOM:\x1b[0m {binary_name} v{version}");
 
-    let (spdx, cyclonedx) = generate_sbom_files(&output_dir, &binary_name, version)?;
+    let settings = SbomSettings {
+        manifest: config.get("MANIFEST_PATH", "Cargo.toml"),
+        package: config.get("PACKAGE", ""),
+        features: parse_comma_list(&config.get("FEATURES", "")),
+        no_default_features: config.get("NO_DEFAULT_FEATURES", "false") == "true",
+        formats,
+        output_dir: config.get("SBOM_OUTPUT_DIR", "target/sbom"),
+        created: creation_timestamp(),
+    };
+    fs::create_dir_all(&settings.output_dir)?;
 
-    println!();
-    println!("\x1b[32mSBOM files:\x1b[0m");
-    print_hr();
+    if config.get("SBOM_PER_ARTIFACT", "false") == "true" {
+        println!();
+        println!("\x1b[32mSBOM files:\x1b[0m");
+        print_hr();
+        let sboms = generate_artifact_sboms(config, &settings, &binary_name, version)?;
+        output_multiline("sbom_files", &serde_json::to_string_pretty(&sboms)?);
+        output("sbom_count", &sboms.len().to_string());
+    } else {
+        let graph = settings.resolve(&config.get("TARGET", ""))?;
+        println!();
+        println!("\x1b[32mSBOM files:\x1b[0m");
+        print_hr();
+        for (format, path) in settings.write(&graph, None, &format!("{binary_name}-{version}"))? {
+            match format {
+                SbomFormat::Spdx23 => output("sbom_spdx", &path),
+                SbomFormat::CycloneDx15 | SbomFormat::CycloneDx16 => {
+                    output("sbom_cyclonedx", &path)
+                }
+            }
+        }
+    }
 
     output("version", version);
     output("binary_name", &binary_name);
-    output("sbom_spdx", &spdx);
-    output("sbom_cyclonedx", &cyclonedx);
     Ok(())
 }
@@ -1,14 +1,107 @@
+use crate::DEFAULT_ARTIFACTS_DIR;
+use crate::config::ReleaseConfig;
 use crate::error::{Error, Result};
-use crate::output::{output, print_hr};
+use crate::format_release::release_artifact_names;
+use crate::output::{output, output_multiline, print_hr};
+use crate::timestamp::now_secs;
 use crate::tools::{command_exists, run_command};
-use std::env;
-#[cfg(target_os = "windows")]
+use crate::verify_checksums::find_manifests;
+use minisign::{SecretKey, SecretKeyBox};
+use serde::Serialize;
 use std::fs;
-use std::path::Path;
-use std::process::Command;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::str::FromStr;
+use tempfile::TempDir;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SignMethod {
+    Cosign,
+    Gpg,
+    Minisign,
+}
+
+impl SignMethod {
+    pub fn name(self) -> &'static str {
+        match self {
+            SignMethod::Cosign => "cosign",
+            SignMethod::Gpg => "gpg",
+            SignMethod::Minisign => "minisign",
+        }
+    }
+
+    /// Reads SIGN_METHOD, defaulting to cosign.
+    pub fn from_env(config: &ReleaseConfig) -> Result<Self> {
+        config.get("SIGN_METHOD", "cosign").parse()
+    }
+
+    /// Builds the signer for this method, reading keys from the environment.
+    pub fn signer(self, config: &ReleaseConfig) -> Result<Box<dyn Signer>> {
+        match self {
+            SignMethod::Cosign => Ok(Box::new(CosignSigner::from_env(config)?)),
+            SignMethod::Gpg => Ok(Box::new(GpgSigner::from_env(config)?)),
+            SignMethod::Minisign => Ok(Box::new(MinisignSigner::from_env(config)?)),
+        }
+    }
+}
+
+impl FromStr for SignMethod {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "" | "cosign" | "sigstore" => Ok(SignMethod::Cosign),
+            "gpg" | "pgp" => Ok(SignMethod::Gpg),
+            "minisign" => Ok(SignMethod::Minisign),
+            other => Err(Error::User(format!(
+                "unsupported sign method: {other} (expected cosign, gpg or minisign)"
+            ))),
+        }
+    }
+}
+
+/// Files written next to a signed artifact.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct SignatureFiles {
+    pub signature: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub certificate: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bundle: Option<String>,
+}
+
+/// An artifact and the signature files produced for it.
+#[derive(Debug, Clone, Serialize)]
+pub struct SignedArtifact {
+    pub artifact: String,
+    #[serde(flatten)]
+    pub files: SignatureFiles,
+}
+
+/// A signing backend that produces detached signature sidecars.
+pub trait Signer {
+    fn method(&self) -> SignMethod;
+
+    fn sign(&self, artifact: &Path) -> Result<SignatureFiles>;
+}
+
+/// Returns `<artifact>.<extension>`.
+pub fn sidecar(artifact: &Path, extension: &str) -> String {
+    format!("{}.{extension}", artifact.display())
+}
+
+/// Returns the path of the detached signature `method` writes for `artifact`.
+pub fn signature_path(artifact: &Path, method: SignMethod) -> String {
+    match method {
+        SignMethod::Cosign => sidecar(artifact, "sig"),
+        SignMethod::Gpg => sidecar(artifact, "asc"),
+        SignMethod::Minisign => sidecar(artifact, "minisig"),
+    }
+}
 
 /// Get cosign path, installing if missing.
-fn get_cosign_path() -> Result<String> {
+pub fn get_cosign_path() -> Result<String> {
     if command_exists("cosign") {
         return Ok("cosign".to_string());
     }
@@ -67,64 +160,418 @@ fn get_cosign_path() -> Result<String> {
     }
 }
 
-pub fn run_sign_artifact() -> Result<()> {
-    let cosign_path = get_cosign_path()?;
+/// Environment variable used to hand PEM key contents to cosign via `env://`.
+const COSIGN_KEY_ENV: &str = "COSIGN_PRIVATE_KEY";
 
-    let artifact_path = env::var("ARTIFACT_PATH").unwrap_or_default();
-    if artifact_path.is_empty() {
-        return Err(Error::User("ARTIFACT_PATH is required".into()));
+/// Sigstore signing with `cosign sign-blob`.
+///
+/// Keyless (OIDC + Fulcio) unless `key` is set. `key` can be a key file path,
+/// a cosign key reference such as `env://VAR` or `awskms://...`, or PEM contents.
+/// The key password is handed to cosign in COSIGN_PASSWORD.
+pub struct CosignSigner {
+    pub cosign_path: String,
+    pub key: String,
+    pub password: String,
+    pub tlog_upload: bool,
+}
+
+impl CosignSigner {
+    /// Reads COSIGN_KEY, COSIGN_PASSWORD and COSIGN_TLOG_UPLOAD, installing
+    /// cosign if missing.
+    pub fn from_env(config: &ReleaseConfig) -> Result<Self> {
+        Ok(CosignSigner {
+            cosign_path: get_cosign_path()?,
+            key: config.get("COSIGN_KEY", ""),
+            password: config.get("COSIGN_PASSWORD", ""),
+            tlog_upload: config.get("COSIGN_TLOG_UPLOAD", "true") != "false",
+        })
     }
 
-    if !Path::new(&artifact_path).exists() {
-        return Err(Error::User(format!("artifact not found: {artifact_path}")));
+    pub fn is_keyless(&self) -> bool {
+        self.key.trim().is_empty()
     }
 
-    println!("\x1b[32mSigning artifact:\x1b[0m {artifact_path}");
+    fn key_is_pem(&self) -> bool {
+        self.key.trim_start().starts_with("-----BEGIN")
+    }
 
-    let sig_path = format!("{artifact_path}.sig");
-    let cert_path = format!("{artifact_path}.pem");
-    let bundle_path = format!("{artifact_path}.sigstore.json");
+    /// The value passed to `--key`.
+    pub fn key_ref(&self) -> String {
+        if self.key_is_pem() {
+            format!("env://{COSIGN_KEY_ENV}")
+        } else {
+            self.key.trim().to_string()
+        }
+    }
+
+    /// Arguments for `cosign` that sign `artifact` and write its sidecars.
+    pub fn sign_args(&self, artifact: &Path) -> Vec<String> {
+        let mut args = vec!["sign-blob".to_string(), "--yes".to_string()];
+        if !self.is_keyless() {
+            args.extend(["--key".to_string(), self.key_ref()]);
+        }
+        if !self.tlog_upload {
+            // cosign 3 rejects --tlog-upload=false while a signing config is in use
+            args.push("--use-signing-config=false".to_string());
+            args.push("--tlog-upload=false".to_string());
+        }
+        args.extend([
+            "--output-signature".to_string(),
+            signature_path(artifact, SignMethod::Cosign),
+        ]);
+        if self.is_keyless() {
+            args.extend(["--output-certificate".to_string(), sidecar(artifact, "pem")]);
+        }
+        args.extend([
+            "--bundle".to_string(),
+            sidecar(artifact, "sigstore.json"),
+            artifact.display().to_string(),
+        ]);
+        args
+    }
+}
+
+impl Signer for CosignSigner {
+    fn method(&self) -> SignMethod {
+        SignMethod::Cosign
+    }
+
+    fn sign(&self, artifact: &Path) -> Result<SignatureFiles> {
+        let sig_path = signature_path(artifact, SignMethod::Cosign);
+        let cert_path = sidecar(artifact, "pem");
+        let bundle_path = sidecar(artifact, "sigstore.json");
+
+        let mut command = Command::new(&self.cosign_path);
+        command.args(self.sign_args(artifact));
+        if self.key_is_pem() {
+            command.env(COSIGN_KEY_ENV, &self.key);
+        }
+        if !self.password.is_empty() {
+            command.env("COSIGN_PASSWORD", &self.password);
+        }
+        let result = command
+            .output()
+            .map_err(|e| Error::User(format!("cosign failed: {e}")))?;
 
-    let result = Command::new(&cosign_path)
-        .args([
-            "sign-blob",
-            "--yes",
-            "--output-signature",
-            &sig_path,
-            "--output-certificate",
-            &cert_path,
-            "--bundle",
-            &bundle_path,
-            &artifact_path,
-        ])
-        .output()
-        .map_err(|e| Error::User(format!("cosign failed: {e}")))?;
+        if !result.status.success() {
+            eprintln!("\x1b[31mcosign output:\x1b[0m");
+            eprintln!("{}", String::from_utf8_lossy(&result.stderr));
+            return Err(Error::User("cosign signing failed".into()));
+        }
+
+        Ok(SignatureFiles {
+            signature: sig_path,
+            certificate: Some(cert_path).filter(|p| Path::new(p).exists()),
+            bundle: Some(bundle_path).filter(|p| Path::new(p).exists()),
+        })
+    }
+}
+
+/// ASCII-armored detached signatures (`.asc`) made with `gpg`.
+///
+/// When a private key is provided it is imported once into a throwaway
+/// keyring that every artifact is signed with, otherwise the default keyring
+/// of the current user is used.
+pub struct GpgSigner {
+    pub home: Option<TempGnupgHome>,
+    pub passphrase: String,
+    pub key_id: String,
+}
+
+impl GpgSigner {
+    /// Reads GPG_PRIVATE_KEY, GPG_PASSPHRASE and GPG_KEY_ID.
+    pub fn from_env(config: &ReleaseConfig) -> Result<Self> {
+        if !command_exists("gpg") {
+            return Err(Error::User(
+                "gpg not found: install GnuPG to use sign-method: gpg".into(),
+            ));
+        }
+        let private_key = config.get("GPG_PRIVATE_KEY", "");
+        let home = if private_key.is_empty() {
+            None
+        } else {
+            Some(TempGnupgHome::with_key(&private_key)?)
+        };
+        Ok(GpgSigner {
+            home,
+            passphrase: config.get("GPG_PASSPHRASE", ""),
+            key_id: config.get("GPG_KEY_ID", ""),
+        })
+    }
+
+    /// Arguments for `gpg` that write a detached signature of `artifact` to `output`.
+    pub fn sign_args(&self, artifact: &str, output: &str) -> Vec<String> {
+        let mut args: Vec<String> = ["--batch", "--yes", "--armor", "--detach-sign"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        if !self.key_id.is_empty() {
+            args.extend(["--local-user".to_string(), self.key_id.clone()]);
+        }
+        if !self.passphrase.is_empty() {
+            args.extend(
+                ["--pinentry-mode", "loopback", "--passphrase-fd", "0"]
+                    .iter()
+                    .map(|s| s.to_string()),
+            );
+        }
+        args.extend(["--output".to_string(), output.to_string()]);
+        args.push(artifact.to_string());
+        args
+    }
+}
 
+/// Runs gpg with `stdin` piped in, optionally against a separate GNUPGHOME.
+pub fn run_gpg(args: &[String], stdin: &str, home: Option<&Path>) -> Result<()> {
+    let mut command = Command::new("gpg");
+    command
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(home) = home {
+        command.env("GNUPGHOME", home);
+    }
+    let describe = || format!("gpg {}", args.join(" "));
+    let mut child = command.spawn().map_err(|e| Error::Command {
+        command: describe(),
+        stderr: e.to_string(),
+    })?;
+    if let Some(mut input) = child.stdin.take() {
+        input.write_all(stdin.as_bytes())?;
+    }
+    let result = child.wait_with_output()?;
     if !result.status.success() {
-        eprintln!("\x1b[31mcosign output:\x1b[0m");
-        eprintln!("{}", String::from_utf8_lossy(&result.stderr));
-        return Err(Error::User("cosign signing failed".into()));
+        return Err(Error::Command {
+            command: describe(),
+            stderr: String::from_utf8_lossy(&result.stderr).to_string(),
+        });
+    }
+    Ok(())
+}
+
+/// A keyring directory with an unpredictable name, only accessible to the
+/// current user and removed on drop.
+pub struct TempGnupgHome(TempDir);
+
+impl TempGnupgHome {
+    pub fn create() -> Result<Self> {
+        let dir = tempfile::Builder::new()
+            .prefix("rust-release-action-gnupg-")
+            .tempdir()?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(dir.path(), fs::Permissions::from_mode(0o700))?;
+        }
+        Ok(TempGnupgHome(dir))
+    }
+
+    /// Creates a keyring and imports `key` into it.
+    pub fn with_key(key: &str) -> Result<Self> {
+        let home = Self::create()?;
+        run_gpg(
+            &["--batch".to_string(), "--import".to_string()],
+            key,
+            Some(home.path()),
+        )?;
+        Ok(home)
+    }
+
+    pub fn path(&self) -> &Path {
+        self.0.path()
+    }
+}
+
+impl Signer for GpgSigner {
+    fn method(&self) -> SignMethod {
+        SignMethod::Gpg
+    }
+
+    fn sign(&self, artifact: &Path) -> Result<SignatureFiles> {
+        let sig_path = signature_path(artifact, SignMethod::Gpg);
+        let args = self.sign_args(&artifact.display().to_string(), &sig_path);
+
+        run_gpg(
+            &args,
+            &self.passphrase,
+            self.home.as_ref().map(TempGnupgHome::path),
+        )?;
+
+        Ok(SignatureFiles {
+            signature: sig_path,
+            ..Default::default()
+        })
+    }
+}
+
+/// Minisign signatures (`.minisig`), computed in-process.
+pub struct MinisignSigner {
+    secret_key: SecretKey,
+}
+
+impl MinisignSigner {
+    /// Decodes a secret key in the `minisign -G` file format. An empty
+    /// password is used for keys generated with `minisign -G -W`.
+    pub fn from_key_str(key: &str, password: &str) -> Result<Self> {
+        let key_box = SecretKeyBox::from_string(key.trim())?;
+        let secret_key = if password.is_empty() {
+            key_box.into_unencrypted_secret_key()?
+        } else {
+            key_box.into_secret_key(Some(password.to_string()))?
+        };
+        Ok(MinisignSigner { secret_key })
+    }
+
+    /// Reads MINISIGN_SECRET_KEY and MINISIGN_PASSWORD.
+    pub fn from_env(config: &ReleaseConfig) -> Result<Self> {
+        let key = config.get("MINISIGN_SECRET_KEY", "");
+        if key.is_empty() {
+            return Err(Error::User(
+                "MINISIGN_SECRET_KEY is required for sign-method: minisign".into(),
+            ));
+        }
+        Self::from_key_str(&key, &config.get("MINISIGN_PASSWORD", ""))
+    }
+}
+
+impl Signer for MinisignSigner {
+    fn method(&self) -> SignMethod {
+        SignMethod::Minisign
+    }
+
+    fn sign(&self, artifact: &Path) -> Result<SignatureFiles> {
+        let file_name = artifact
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        let timestamp = now_secs();
+        // Same trusted comment as the minisign CLI
+        let trusted_comment = format!("timestamp:{timestamp}\tfile:{file_name}\thashed");
+        let signature = minisign::sign(
+            None,
+            &self.secret_key,
+            fs::File::open(artifact)?,
+            Some(&trusted_comment),
+            None,
+        )?;
+
+        let sig_path = signature_path(artifact, SignMethod::Minisign);
+        fs::write(&sig_path, signature.to_string())?;
+        Ok(SignatureFiles {
+            signature: sig_path,
+            ..Default::default()
+        })
     }
+}
+
+pub fn run_sign_artifact(config: &ReleaseConfig) -> Result<()> {
+    let method = SignMethod::from_env(config)?;
+
+    let artifact_path = config.get("ARTIFACT_PATH", "");
+    if artifact_path.is_empty() {
+        return Err(Error::User("ARTIFACT_PATH is required".into()));
+    }
+
+    if !Path::new(&artifact_path).exists() {
+        return Err(Error::User(format!("artifact not found: {artifact_path}")));
+    }
+
+    let signer = method.signer(config)?;
+    println!(
+        "\x1b[32mSigning artifact with {}:\x1b[0m {artifact_path}",
+        method.name()
+    );
+    let files = signer.sign(Path::new(&artifact_path))?;
 
     println!();
     println!("\x1b[32mSignature files:\x1b[0m");
     print_hr();
 
-    if Path::new(&sig_path).exists() {
-        println!("\x1b[32mSignature:\x1b[0m {sig_path}");
-        output("signature_path", &sig_path);
-    }
+    println!("\x1b[32mSignature:\x1b[0m {}", files.signature);
+    output("signature_path", &files.signature);
 
-    if Path::new(&cert_path).exists() {
+    if let Some(cert_path) = &files.certificate {
         println!("\x1b[32mCertificate:\x1b[0m {cert_path}");
-        output("certificate_path", &cert_path);
+        output("certificate_path", cert_path);
     }
 
-    if Path::new(&bundle_path).exists() {
+    if let Some(bundle_path) = &files.bundle {
         println!("\x1b[32mBundle:\x1b[0m {bundle_path}");
-        output("bundle_path", &bundle_path);
+        output("bundle_path", bundle_path);
     }
 
+    output("sign_method", method.name());
     output("artifact_path", &artifact_path);
     Ok(())
 }
+
+/// Lists the files `sign-artifacts` signs in `dir`: release artifacts, plus
+/// checksum manifests such as `SHA256SUMS` when `include_manifests` is set.
+pub fn files_to_sign(dir: &Path, include_manifests: bool) -> Vec<PathBuf> {
+    let manifests: Vec<String> = find_manifests(dir)
+        .iter()
+        .filter_map(|m| m.file_name())
+        .map(|n| n.to_string_lossy().to_string())
+        .collect();
+    release_artifact_names(dir)
+        .into_iter()
+        .filter(|name| include_manifests || !manifests.contains(name))
+        .map(|name| dir.join(name))
+        .collect()
+}
+
+/// Signs every file in `files` with `signer`.
+pub fn sign_all(signer: &dyn Signer, files: &[PathBuf]) -> Result<Vec<SignedArtifact>> {
+    let mut signed = Vec::new();
+    for file in files {
+        println!("\x1b[32mSigning:\x1b[0m {}", file.display());
+        signed.push(SignedArtifact {
+            artifact: file.display().to_string(),
+            files: signer.sign(file)?,
+        });
+    }
+    Ok(signed)
+}
+
+pub fn run_sign_artifacts(config: &ReleaseConfig) -> Result<()> {
+    let method = SignMethod::from_env(config)?;
+    let artifacts_dir = config.get("ARTIFACTS_DIR", DEFAULT_ARTIFACTS_DIR);
+    let dir = Path::new(&artifacts_dir);
+    if !dir.is_dir() {
+        return Err(Error::User(format!(
+            "artifacts directory not found: {artifacts_dir}"
+        )));
+    }
+
+    let include_manifests = config.get("SIGN_MANIFESTS", "false") == "true";
+    let files = files_to_sign(dir, include_manifests);
+    if files.is_empty() {
+        return Err(Error::User(format!(
+            "no artifacts to sign in {artifacts_dir}"
+        )));
+    }
+
+    let signer = method.signer(config)?;
+    println!(
+        "\x1b[32mSigning {} files in {artifacts_dir} with {}\x1b[0m",
+        files.len(),
+        method.name()
+    );
+    print_hr();
+    let signed = sign_all(signer.as_ref(), &files)?;
+
+    println!();
+    println!("\x1b[32mSignature files:\x1b[0m");
+    print_hr();
+    for s in &signed {
+        println!("  {}", s.files.signature);
+    }
+
+    let json = serde_json::to_string_pretty(&signed)?;
+    output_multiline("signatures", &json);
+    output("signed_count", &signed.len().to_string());
+    output("sign_method", method.name());
+    Ok(())
+}
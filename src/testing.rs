@@ -1,4 +1,5 @@
 use crate::checksum::verify_checksum;
+use crate::config::ReleaseConfig;
 use crate::download;
 use crate::env_or;
 use crate::error::{Error, Result};
@@ -78,10 +79,10 @@ fn ensure_sudo_rpm() {
         .status();
 }
 
-pub fn run_test_deb() -> Result<()> {
+pub fn run_test_deb(config: &ReleaseConfig) -> Result<()> {
     ensure_sudo_deb();
-    let binary_name = env_or("BINARY_NAME", "");
-    let version = env_or("VERSION", "");
+    let binary_name = config.get("BINARY_NAME", "");
+    let version = config.get("VERSION", "");
 
     if binary_name.is_empty() {
         return Err(Error::User("binary-name is required".into()));
@@ -90,8 +91,8 @@ pub fn run_test_deb() -> Result<()> {
         return Err(Error::User("version is required".into()));
     }
 
-    let artifact_path = if env_or("DOWNLOAD_FROM_RELEASE", "false") == "true" {
-        let arch = env_or("ARCH", "");
+    let artifact_path = if config.get("DOWNLOAD_FROM_RELEASE", "false") == "true" {
+        let arch = config.get("ARCH", "");
         if arch.is_empty() {
             return Err(Error::User(
                 "arch is required when download-from-release is true".into(),
@@ -106,7 +107,7 @@ pub fn run_test_deb() -> Result<()> {
         }
         download::download_artifact(&binary_name, &version, &arch, "deb")?
     } else {
-        let path = env_or("ARTIFACT_PATH", "");
+        let path = config.get("ARTIFACT_PATH", "");
         if path.is_empty() {
             return Err(Error::User(
                 "artifact is required when download-from-release is false".into(),
@@ -115,7 +116,7 @@ pub fn run_test_deb() -> Result<()> {
         if !Path::new(&path).exists() {
             return Err(Error::User(format!("artifact not found: {path}")));
         }
-        let checksum_file = env_or("CHECKSUM_FILE", "");
+        let checksum_file = config.get("CHECKSUM_FILE", "");
         if !checksum_file.is_empty() {
             verify_checksum(Path::new(&path), Path::new(&checksum_file))?;
         }
@@ -143,10 +144,10 @@ pub fn run_test_deb() -> Result<()> {
     Ok(())
 }
 
-pub fn run_test_rpm() -> Result<()> {
+pub fn run_test_rpm(config: &ReleaseConfig) -> Result<()> {
     ensure_sudo_rpm();
-    let binary_name = env_or("BINARY_NAME", "");
-    let version = env_or("VERSION", "");
+    let binary_name = config.get("BINARY_NAME", "");
+    let version = config.get("VERSION", "");
 
     if binary_name.is_empty() {
         return Err(Error::User("binary-name is required".into()));
@@ -155,8 +156,8 @@ pub fn run_test_rpm() -> Result<()> {
         return Err(Error::User("version is required".into()));
     }
 
-    let artifact_path = if env_or("DOWNLOAD_FROM_RELEASE", "false") == "true" {
-        let arch = env_or("ARCH", "");
+    let artifact_path = if config.get("DOWNLOAD_FROM_RELEASE", "false") == "true" {
+        let arch = config.get("ARCH", "");
         if arch.is_empty() {
             return Err(Error::User(
                 "arch is required when download-from-release is true".into(),
@@ -171,7 +172,7 @@ pub fn run_test_rpm() -> Result<()> {
         }
         download::download_artifact(&binary_name, &version, &arch, "rpm")?
     } else {
-        let path = env_or("ARTIFACT_PATH", "");
+        let path = config.get("ARTIFACT_PATH", "");
         if path.is_empty() {
             return Err(Error::User(
                 "artifact is required when download-from-release is false".into(),
@@ -180,7 +181,7 @@ pub fn run_test_rpm() -> Result<()> {
         if !Path::new(&path).exists() {
             return Err(Error::User(format!("artifact not found: {path}")));
         }
-        let checksum_file = env_or("CHECKSUM_FILE", "");
+        let checksum_file = config.get("CHECKSUM_FILE", "");
         if !checksum_file.is_empty() {
             verify_checksum(Path::new(&path), Path::new(&checksum_file))?;
         }
@@ -208,9 +209,9 @@ pub fn run_test_rpm() -> Result<()> {
     Ok(())
 }
 
-pub fn run_test_windows() -> Result<()> {
-    let binary_name = env_or("BINARY_NAME", "");
-    let version = env_or("VERSION", "");
+pub fn run_test_windows(config: &ReleaseConfig) -> Result<()> {
+    let binary_name = config.get("BINARY_NAME", "");
+    let version = config.get("VERSION", "");
 
     if binary_name.is_empty() {
         return Err(Error::User("binary-name is required".into()));
@@ -219,14 +220,14 @@ pub fn run_test_windows() -> Result<()> {
         return Err(Error::User("version is required".into()));
     }
 
-    let (binary_path, msi_path) = if env_or("DOWNLOAD_FROM_RELEASE", "false") == "true" {
+    let (binary_path, msi_path) = if config.get("DOWNLOAD_FROM_RELEASE", "false") == "true" {
         let downloaded = download::download_windows_artifacts(&binary_name, &version)?;
         (downloaded.binary, downloaded.msi)
     } else {
-        let binary_path = env_or("BINARY_PATH", "");
-        let msi_path = env_or("MSI_PATH", "");
-        let checksum_file = env_or("CHECKSUM_FILE", "");
-        let msi_checksum_file = env_or("MSI_CHECKSUM_FILE", "");
+        let binary_path = config.get("BINARY_PATH", "");
+        let msi_path = config.get("MSI_PATH", "");
+        let checksum_file = config.get("CHECKSUM_FILE", "");
+        let msi_checksum_file = config.get("MSI_CHECKSUM_FILE", "");
 
         if binary_path.is_empty() && msi_path.is_empty() {
             return Err(Error::User(
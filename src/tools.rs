@@ -1,6 +1,8 @@
+use crate::build::{BuildTool, build_tool};
+use crate::config::ReleaseConfig;
 use crate::error::{Error, Result};
 use std::process::Command;
-use std::{env, path::Path, process};
+use std::{path::Path, process};
 
 /// Check that a command exists in PATH.
 pub fn command_exists(name: &str) -> bool {
@@ -48,16 +50,6 @@ pub fn check_nfpm() -> Result<()> {
     Ok(())
 }
 
-/// Check that cargo-sbom is available, install if missing.
-pub fn check_cargo_sbom() -> Result<()> {
-    if command_exists("cargo-sbom") {
-        return Ok(());
-    }
-    println!("\x1b[33mcargo-sbom not found, installing...\x1b[0m");
-    run_command("cargo", &["install", "cargo-sbom"])?;
-    Ok(())
-}
-
 /// Check that cargo-zigbuild is available, install if missing.
 pub fn check_zigbuild() -> Result<()> {
     if command_exists("cargo-zigbuild") {
@@ -74,6 +66,21 @@ pub fn check_zigbuild() -> Result<()> {
     Ok(())
 }
 
+/// Check that cross and a container engine are available, install cross if missing.
+pub fn check_cross() -> Result<()> {
+    if !command_exists("docker") && !command_exists("podman") {
+        return Err(Error::User(
+            "cross requires Docker or Podman, neither was found".into(),
+        ));
+    }
+    if command_exists("cross") {
+        return Ok(());
+    }
+    println!("\x1b[33mcross not found, installing...\x1b[0m");
+    run_command("cargo", &["install", "cross", "--locked"])?;
+    Ok(())
+}
+
 /// Ensure Cargo.lock exists.
 pub fn ensure_lockfile() -> Result<()> {
     if !Path::new("Cargo.lock").exists() {
@@ -84,8 +91,8 @@ pub fn ensure_lockfile() -> Result<()> {
 }
 
 /// Run pre-build hook command if PRE_BUILD is set.
-pub fn run_pre_build_hook() -> Result<()> {
-    let pre_build = env::var("PRE_BUILD").unwrap_or_default();
+pub fn run_pre_build_hook(config: &ReleaseConfig) -> Result<()> {
+    let pre_build = config.get("PRE_BUILD", "");
     if pre_build.is_empty() {
         return Ok(());
     }
@@ -106,68 +113,57 @@ pub fn run_pre_build_hook() -> Result<()> {
     Ok(())
 }
 
-/// Install cross-compilation dependencies for Linux targets.
-pub fn install_linux_cross_deps(target: &str) -> Result<()> {
-    let is_ubuntu = command_exists("apt-get");
-    let is_fedora = command_exists("dnf");
-
+/// System packages needed to build for a Linux `target` on a `host_arch` machine.
+/// The names are the same on Ubuntu and Fedora.
+pub fn linux_cross_packages(target: &str, host_arch: &str) -> &'static [&'static str] {
     if target.contains("musl") {
-        if is_ubuntu {
-            run_command("sudo", &["apt-get", "update", "-qq"])?;
-            run_command("sudo", &["apt-get", "install", "-y", "-qq", "musl-tools"])?;
-        }
-    } else if target == "aarch64-unknown-linux-gnu" {
-        let arch = get_uname_arch();
-        if arch != "aarch64" {
-            if is_ubuntu {
-                run_command("sudo", &["apt-get", "update", "-qq"])?;
-                run_command(
-                    "sudo",
-                    &["apt-get", "install", "-y", "-qq", "gcc-aarch64-linux-gnu"],
-                )?;
-            } else if is_fedora {
-                run_command("sudo", &["dnf", "install", "-y", "gcc-aarch64-linux-gnu"])?;
-            }
-            // Safety: running single-threaded at this point during build setup
-            unsafe {
-                env::set_var(
-                    "CARGO_TARGET_AARCH64_UNKNOWN_LINUX_GNU_LINKER",
-                    "aarch64-linux-gnu-gcc",
-                );
-            }
-        }
+        &["musl-tools"]
+    } else if target == "aarch64-unknown-linux-gnu" && host_arch != "aarch64" {
+        &["gcc-aarch64-linux-gnu"]
+    } else if target == "armv7-unknown-linux-gnueabihf" {
+        &["pkg-config", "gcc-arm-linux-gnueabihf"]
+    } else {
+        &[]
+    }
+}
+
+/// The Cargo linker variable and cross linker for a Linux `target` on a `host_arch` machine.
+pub fn linux_cross_linker(target: &str, host_arch: &str) -> Option<(&'static str, &'static str)> {
+    if target == "aarch64-unknown-linux-gnu" && host_arch != "aarch64" {
+        Some((
+            "CARGO_TARGET_AARCH64_UNKNOWN_LINUX_GNU_LINKER",
+            "aarch64-linux-gnu-gcc",
+        ))
     } else if target == "armv7-unknown-linux-gnueabihf" {
-        if is_ubuntu {
+        Some((
+            "CARGO_TARGET_ARMV7_UNKNOWN_LINUX_GNUEABIHF_LINKER",
+            "arm-linux-gnueabihf-gcc",
+        ))
+    } else {
+        None
+    }
+}
+
+/// Install cross-compilation dependencies for Linux targets.
+pub fn install_linux_cross_deps(config: &ReleaseConfig, target: &str) -> Result<()> {
+    // cross builds in a container that provides the toolchain and adds the target
+    if build_tool(config, target)? == BuildTool::Cross {
+        return Ok(());
+    }
+    let host_arch = get_uname_arch();
+    let packages = linux_cross_packages(target, &host_arch);
+
+    if !packages.is_empty() {
+        if command_exists("apt-get") {
             run_command("sudo", &["apt-get", "update", "-qq"])?;
-            run_command(
-                "sudo",
-                &[
-                    "apt-get",
-                    "install",
-                    "-y",
-                    "-qq",
-                    "pkg-config",
-                    "gcc-arm-linux-gnueabihf",
-                ],
-            )?;
-        } else if is_fedora {
-            run_command(
-                "sudo",
-                &[
-                    "dnf",
-                    "install",
-                    "-y",
-                    "pkg-config",
-                    "gcc-arm-linux-gnueabihf",
-                ],
-            )?;
-        }
-        // Safety: running single-threaded at this point during build setup
-        unsafe {
-            env::set_var(
-                "CARGO_TARGET_ARMV7_UNKNOWN_LINUX_GNUEABIHF_LINKER",
-                "arm-linux-gnueabihf-gcc",
-            );
+            let mut args = vec!["apt-get", "install", "-y", "-qq"];
+            args.extend_from_slice(packages);
+            run_command("sudo", &args)?;
+        } else if command_exists("dnf") && !target.contains("musl") {
+            // Fedora does not package musl-tools
+            let mut args = vec!["dnf", "install", "-y"];
+            args.extend_from_slice(packages);
+            run_command("sudo", &args)?;
         }
     }
 
@@ -195,8 +191,14 @@ pub fn run_command(program: &str, args: &[&str]) -> Result<process::Output> {
 
 /// Run a command, inheriting stdio (for commands that need to show output).
 pub fn run_command_inherit(program: &str, args: &[&str]) -> Result<()> {
+    run_command_inherit_env(program, args, &[])
+}
+
+/// Like [`run_command_inherit`], with extra environment variables for the command.
+pub fn run_command_inherit_env(program: &str, args: &[&str], envs: &[(&str, &str)]) -> Result<()> {
     let status = Command::new(program)
         .args(args)
+        .envs(envs.iter().copied())
         .status()
         .map_err(|e| Error::Command {
             command: format!("{program} {}", args.join(" ")),
@@ -211,6 +213,11 @@ pub fn run_command_inherit(program: &str, args: &[&str]) -> Result<()> {
     Ok(())
 }
 
+/// The linker variable and linker for cross-compiling `target` on this host, if any.
+pub fn host_cross_linker(target: &str) -> Option<(&'static str, &'static str)> {
+    linux_cross_linker(target, &get_uname_arch())
+}
+
 fn get_uname_arch() -> String {
     Command::new("uname")
         .arg("-m")
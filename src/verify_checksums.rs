@@ -0,0 +1,236 @@
+use crate::checksum::{
+    ChecksumAlgorithm, ManifestEntry, algorithm_for_manifest, hash_file, parse_checksum_manifest,
+};
+use crate::config::ReleaseConfig;
+use crate::error::{Error, Result};
+use crate::format_release::SIDECAR_PATTERN;
+use crate::output::{output, print_hr};
+use crate::{DEFAULT_ARTIFACTS_DIR, parse_comma_list};
+use regex::Regex;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A file whose digest does not match its manifest entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub file: String,
+    pub algorithm: ChecksumAlgorithm,
+    pub expected: String,
+    pub actual: String,
+}
+
+/// Outcome of verifying a directory against one or more checksum manifests.
+#[derive(Debug, Default)]
+pub struct VerificationReport {
+    pub verified: Vec<String>,
+    pub mismatched: Vec<Mismatch>,
+    pub missing: Vec<String>,
+    pub extra: Vec<String>,
+}
+
+impl VerificationReport {
+    /// Extra files are reported but do not fail verification.
+    pub fn is_ok(&self) -> bool {
+        self.mismatched.is_empty() && self.missing.is_empty()
+    }
+}
+
+/// Finds the consolidated manifests (`SHA256SUMS`, `SHA512SUMS`, ...) in `dir`.
+pub fn find_manifests(dir: &Path) -> Vec<PathBuf> {
+    let mut manifests: Vec<PathBuf> = ChecksumAlgorithm::ALL
+        .iter()
+        .map(|a| dir.join(a.manifest_name()))
+        .filter(|p| p.is_file())
+        .collect();
+    manifests.sort();
+    manifests
+}
+
+/// Reads and parses the given manifests.
+pub fn read_manifests(manifests: &[PathBuf]) -> Result<Vec<ManifestEntry>> {
+    let mut entries = Vec::new();
+    for manifest in manifests {
+        let content = fs::read_to_string(manifest).map_err(|e| {
+            Error::User(format!(
+                "cannot read checksum manifest {}: {e}",
+                manifest.display()
+            ))
+        })?;
+        let name = manifest
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        entries.extend(parse_checksum_manifest(
+            &content,
+            algorithm_for_manifest(&name),
+        )?);
+    }
+    Ok(entries)
+}
+
+/// Verifies every file listed in `entries` against the files in `dir`.
+///
+/// Entries must name files directly inside `dir`: paths with separators,
+/// `..` or a drive letter are rejected so a manifest cannot reach outside it.
+///
+/// Each file is read once for all algorithms it is listed with. Files in `dir`
+/// that no manifest mentions are reported as extra, ignoring the manifests
+/// themselves, checksum sidecars, signatures and SBOMs.
+pub fn verify_entries(
+    entries: &[ManifestEntry],
+    dir: &Path,
+    manifests: &[PathBuf],
+) -> Result<VerificationReport> {
+    let mut by_file: BTreeMap<String, Vec<&ManifestEntry>> = BTreeMap::new();
+    for entry in entries {
+        let file = entry.file.trim_start_matches("./").to_string();
+        if file.is_empty() || file == ".." || file.contains(['/', '\\']) || is_drive_path(&file) {
+            return Err(Error::User(format!(
+                "checksum manifest entry must be a file name in the artifacts directory: {}",
+                entry.file
+            )));
+        }
+        by_file.entry(file).or_default().push(entry);
+    }
+
+    let mut report = VerificationReport::default();
+    for (file, listed) in &by_file {
+        let path = dir.join(file);
+        if !path.is_file() {
+            report.missing.push(file.clone());
+            continue;
+        }
+        let mut algorithms: Vec<ChecksumAlgorithm> = Vec::new();
+        for entry in listed {
+            if !algorithms.contains(&entry.algorithm) {
+                algorithms.push(entry.algorithm);
+            }
+        }
+        let actual = hash_file(&path, &algorithms)?;
+        let mut ok = true;
+        for entry in listed {
+            let computed = actual
+                .iter()
+                .find(|(a, _)| *a == entry.algorithm)
+                .map(|(_, h)| h.as_str())
+                .unwrap_or_default();
+            if computed != entry.hash {
+                ok = false;
+                report.mismatched.push(Mismatch {
+                    file: file.clone(),
+                    algorithm: entry.algorithm,
+                    expected: entry.hash.clone(),
+                    actual: computed.to_string(),
+                });
+            }
+        }
+        if ok {
+            report.verified.push(file.clone());
+        }
+    }
+
+    let ignored = Regex::new(SIDECAR_PATTERN).unwrap();
+    let manifest_names: Vec<String> = manifests
+        .iter()
+        .filter_map(|m| m.file_name())
+        .map(|n| n.to_string_lossy().to_string())
+        .collect();
+    if let Ok(dir_entries) = fs::read_dir(dir) {
+        for entry in dir_entries.flatten() {
+            if !entry.path().is_file() {
+                continue;
+            }
+            let name = entry.file_name().to_string_lossy().to_string();
+            if by_file.contains_key(&name)
+                || manifest_names.contains(&name)
+                || algorithm_for_manifest(&name).is_some_and(|_| name.ends_with("SUMS"))
+                || ignored.is_match(&name)
+            {
+                continue;
+            }
+            report.extra.push(name);
+        }
+    }
+    report.extra.sort();
+
+    Ok(report)
+}
+
+pub fn run_verify_checksums(config: &ReleaseConfig) -> Result<()> {
+    let artifacts_dir = config.get("ARTIFACTS_DIR", DEFAULT_ARTIFACTS_DIR);
+    let dir = Path::new(&artifacts_dir);
+    if !dir.is_dir() {
+        return Err(Error::User(format!(
+            "artifacts directory not found: {artifacts_dir}"
+        )));
+    }
+
+    let configured = parse_comma_list(&config.get("CHECKSUM_FILE", ""));
+    let manifests: Vec<PathBuf> = if configured.is_empty() {
+        find_manifests(dir)
+    } else {
+        configured.iter().map(PathBuf::from).collect()
+    };
+    if manifests.is_empty() {
+        return Err(Error::User(format!(
+            "no checksum manifests found in {artifacts_dir}; set checksum-file"
+        )));
+    }
+
+    let entries = read_manifests(&manifests)?;
+    if entries.is_empty() {
+        return Err(Error::User("checksum manifests list no files".into()));
+    }
+
+    println!("\x1b[32mVerifying checksums in:\x1b[0m {artifacts_dir}");
+    for manifest in &manifests {
+        println!("  {}", manifest.display());
+    }
+    print_hr();
+
+    let report = verify_entries(&entries, dir, &manifests)?;
+
+    for file in &report.verified {
+        println!("  {file}: OK");
+    }
+    for m in &report.mismatched {
+        println!(
+            "  \x1b[31m{}: FAILED\x1b[0m ({} expected {}, got {})",
+            m.file,
+            m.algorithm.label(),
+            m.expected,
+            m.actual
+        );
+    }
+    for file in &report.missing {
+        println!("  \x1b[31m{file}: MISSING\x1b[0m");
+    }
+    for file in &report.extra {
+        println!("  \x1b[33m{file}: not listed in any manifest\x1b[0m");
+    }
+
+    let mut mismatched: Vec<&str> = report.mismatched.iter().map(|m| m.file.as_str()).collect();
+    mismatched.dedup();
+    output("verified_count", &report.verified.len().to_string());
+    output("mismatched_files", &mismatched.join(","));
+    output("missing_files", &report.missing.join(","));
+    output("extra_files", &report.extra.join(","));
+
+    if !report.is_ok() {
+        return Err(Error::User(format!(
+            "checksum verification failed: {} mismatched, {} missing",
+            mismatched.len(),
+            report.missing.len()
+        )));
+    }
+
+    println!("\x1b[32mVerified:\x1b[0m {} files", report.verified.len());
+    Ok(())
+}
+
+fn is_drive_path(file: &str) -> bool {
+    let bytes = file.as_bytes();
+    bytes.len() >= 2 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':'
+}
@@ -0,0 +1,220 @@
+use crate::config::ReleaseConfig;
+use crate::error::{Error, Result};
+use crate::output::{output, print_hr};
+use crate::sign::{SignMethod, TempGnupgHome, get_cosign_path, run_gpg, sidecar, signature_path};
+use minisign::{PublicKey, PublicKeyBox, SignatureBox};
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+/// What a cosign signature must match: a public key for key-based
+/// signatures, or a certificate identity and OIDC issuer for keyless ones.
+#[derive(Debug, Clone, Default)]
+pub struct CosignVerification {
+    pub public_key: String,
+    pub certificate_identity: String,
+    pub certificate_identity_regexp: String,
+    pub certificate_oidc_issuer: String,
+    pub certificate_oidc_issuer_regexp: String,
+    pub ignore_tlog: bool,
+}
+
+impl CosignVerification {
+    /// Reads PUBLIC_KEY, CERTIFICATE_IDENTITY(_REGEXP), CERTIFICATE_OIDC_ISSUER(_REGEXP)
+    /// and COSIGN_TLOG_UPLOAD.
+    pub fn from_env(config: &ReleaseConfig) -> Self {
+        CosignVerification {
+            public_key: config.get("PUBLIC_KEY", ""),
+            certificate_identity: config.get("CERTIFICATE_IDENTITY", ""),
+            certificate_identity_regexp: config.get("CERTIFICATE_IDENTITY_REGEXP", ""),
+            certificate_oidc_issuer: config.get("CERTIFICATE_OIDC_ISSUER", ""),
+            certificate_oidc_issuer_regexp: config.get("CERTIFICATE_OIDC_ISSUER_REGEXP", ""),
+            ignore_tlog: config.get("COSIGN_TLOG_UPLOAD", "true") == "false",
+        }
+    }
+
+    /// Arguments for `cosign verify-blob`. Prefers the `.sigstore.json` bundle,
+    /// which carries the transparency log proof and allows offline verification,
+    /// and falls back to the `.sig` and `.pem` sidecars.
+    pub fn args(&self, artifact: &Path) -> Result<Vec<String>> {
+        let mut args = vec!["verify-blob".to_string()];
+
+        let bundle = sidecar(artifact, "sigstore.json");
+        let sig = signature_path(artifact, SignMethod::Cosign);
+        let cert = sidecar(artifact, "pem");
+        if Path::new(&bundle).is_file() {
+            args.extend(["--bundle".to_string(), bundle, "--offline=true".to_string()]);
+        } else if Path::new(&sig).is_file() {
+            args.extend(["--signature".to_string(), sig]);
+            if Path::new(&cert).is_file() {
+                args.extend(["--certificate".to_string(), cert]);
+            }
+        } else {
+            return Err(Error::User(format!(
+                "no cosign signature found for {} (expected {bundle} or {sig})",
+                artifact.display()
+            )));
+        }
+
+        if !self.public_key.is_empty() {
+            args.extend(["--key".to_string(), self.public_key.clone()]);
+        } else {
+            let identity = match (
+                self.certificate_identity.as_str(),
+                self.certificate_identity_regexp.as_str(),
+            ) {
+                ("", "") => None,
+                (exact, "") => Some(("--certificate-identity", exact)),
+                (_, regexp) => Some(("--certificate-identity-regexp", regexp)),
+            };
+            let issuer = match (
+                self.certificate_oidc_issuer.as_str(),
+                self.certificate_oidc_issuer_regexp.as_str(),
+            ) {
+                ("", "") => None,
+                (exact, "") => Some(("--certificate-oidc-issuer", exact)),
+                (_, regexp) => Some(("--certificate-oidc-issuer-regexp", regexp)),
+            };
+            let (Some(identity), Some(issuer)) = (identity, issuer) else {
+                return Err(Error::User(
+                    "keyless signatures require certificate-identity and certificate-oidc-issuer (or public-key for key-based signatures)".into(),
+                ));
+            };
+            for (flag, value) in [identity, issuer] {
+                args.extend([flag.to_string(), value.to_string()]);
+            }
+        }
+
+        if self.ignore_tlog {
+            args.push("--insecure-ignore-tlog=true".to_string());
+        }
+        args.push(artifact.display().to_string());
+        Ok(args)
+    }
+}
+
+/// Decodes a minisign public key given as a `.pub` file path, its contents,
+/// or the bare base64 key accepted by `minisign -P`.
+pub fn parse_minisign_public_key(value: &str) -> Result<PublicKey> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(Error::User(
+            "public-key is required to verify minisign signatures".into(),
+        ));
+    }
+    let content = if Path::new(value).is_file() {
+        fs::read_to_string(value)?
+    } else {
+        value.to_string()
+    };
+    let content = content.trim();
+    if content.lines().count() > 1 {
+        Ok(PublicKeyBox::from_string(content)?.into_public_key()?)
+    } else {
+        Ok(PublicKey::from_base64(content)?)
+    }
+}
+
+/// Checks a `.minisig` signature of `artifact` against `public_key`.
+pub fn verify_minisign(artifact: &Path, public_key: &PublicKey) -> Result<()> {
+    let sig_path = signature_path(artifact, SignMethod::Minisign);
+    if !Path::new(&sig_path).is_file() {
+        return Err(Error::User(format!("signature not found: {sig_path}")));
+    }
+    let signature = SignatureBox::from_file(&sig_path)?;
+    minisign::verify(
+        public_key,
+        &signature,
+        fs::File::open(artifact)?,
+        true,
+        false,
+        false,
+    )?;
+    Ok(())
+}
+
+fn verify_cosign(config: &ReleaseConfig, artifact: &Path) -> Result<()> {
+    let args = CosignVerification::from_env(config).args(artifact)?;
+    let cosign_path = get_cosign_path()?;
+    let result = Command::new(&cosign_path)
+        .args(&args)
+        .output()
+        .map_err(|e| Error::User(format!("cosign failed: {e}")))?;
+    if !result.status.success() {
+        eprintln!("\x1b[31mcosign output:\x1b[0m");
+        eprintln!("{}", String::from_utf8_lossy(&result.stderr));
+        return Err(Error::User(format!(
+            "signature verification failed for {}",
+            artifact.display()
+        )));
+    }
+    Ok(())
+}
+
+/// Verifies a `.asc` signature against PUBLIC_KEY (a file or armored key)
+/// imported into a temporary keyring. Any key in the default keyring is only
+/// accepted when GPG_USE_KEYRING opts in.
+fn verify_gpg(config: &ReleaseConfig, artifact: &Path) -> Result<()> {
+    let sig_path = signature_path(artifact, SignMethod::Gpg);
+    if !Path::new(&sig_path).is_file() {
+        return Err(Error::User(format!("signature not found: {sig_path}")));
+    }
+    let public_key = config.get("PUBLIC_KEY", "");
+    let home = if public_key.is_empty() {
+        if config.get("GPG_USE_KEYRING", "false") != "true" {
+            return Err(Error::User(
+                "PUBLIC_KEY is required to verify GPG signatures (or set GPG_USE_KEYRING)".into(),
+            ));
+        }
+        None
+    } else {
+        let key = if Path::new(&public_key).is_file() {
+            fs::read_to_string(&public_key)?
+        } else {
+            public_key
+        };
+        Some(TempGnupgHome::with_key(&key)?)
+    };
+    let args = [
+        "--batch".to_string(),
+        "--verify".to_string(),
+        sig_path,
+        artifact.display().to_string(),
+    ];
+    run_gpg(&args, "", home.as_ref().map(TempGnupgHome::path))
+        .map_err(|e| Error::User(format!("signature verification failed: {e}")))
+}
+
+pub fn run_verify_signature(config: &ReleaseConfig) -> Result<()> {
+    let method = SignMethod::from_env(config)?;
+
+    let artifact_path = config.get("ARTIFACT_PATH", "");
+    if artifact_path.is_empty() {
+        return Err(Error::User("ARTIFACT_PATH is required".into()));
+    }
+    let artifact = Path::new(&artifact_path);
+    if !artifact.exists() {
+        return Err(Error::User(format!("artifact not found: {artifact_path}")));
+    }
+
+    println!(
+        "\x1b[32mVerifying {} signature of:\x1b[0m {artifact_path}",
+        method.name()
+    );
+    print_hr();
+
+    match method {
+        SignMethod::Cosign => verify_cosign(config, artifact)?,
+        SignMethod::Gpg => verify_gpg(config, artifact)?,
+        SignMethod::Minisign => {
+            let public_key = parse_minisign_public_key(&config.get("PUBLIC_KEY", ""))?;
+            verify_minisign(artifact, &public_key)?
+        }
+    }
+
+    println!("\x1b[32mVerified:\x1b[0m {artifact_path}");
+    output("verified", "true");
+    output("sign_method", method.name());
+    output("artifact_path", &artifact_path);
+    Ok(())
+}
@@ -1,23 +1,316 @@
 use crate::cargo_info::get_cargo_info;
+use crate::config::ReleaseConfig;
 use crate::env_or;
 use crate::error::{Error, Result};
 use crate::output::output;
-use regex::Regex;
 use serde_json::Value;
+use std::cmp::Ordering;
+use std::fmt;
 use std::path::Path;
 use std::process::{self, Command};
-
-/// Semver pattern: MAJOR.MINOR.PATCH[-PRERELEASE][+BUILD]
-pub fn semver_pattern() -> Regex {
-    Regex::new(
-        r"^\d+\.\d+\.\d+(-[a-zA-Z0-9]+(\.[a-zA-Z0-9]+)*)?(\+[a-zA-Z0-9]+(\.[a-zA-Z0-9]+)*)?$",
-    )
-    .unwrap()
-}
+use std::str::FromStr;
 
 /// Validates that a semver string is well-formed.
 pub fn is_valid_semver(version: &str) -> bool {
-    semver_pattern().is_match(version)
+    Version::parse(version).is_ok()
+}
+
+/// A pre-release identifier. Numeric identifiers sort numerically and
+/// before alphanumeric ones, which sort in ASCII order.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Identifier {
+    Numeric(u64),
+    AlphaNumeric(String),
+}
+
+impl fmt::Display for Identifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Identifier::Numeric(n) => write!(f, "{n}"),
+            Identifier::AlphaNumeric(s) => f.write_str(s),
+        }
+    }
+}
+
+/// A Semantic Versioning 2.0.0 version.
+///
+/// Ordering follows semver precedence, with build metadata compared last
+/// only to keep `Ord` consistent with `Eq`; use [`Version::precedence`]
+/// to ignore it.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+    pub pre: Vec<Identifier>,
+    pub build: Vec<String>,
+}
+
+impl Version {
+    pub fn new(major: u64, minor: u64, patch: u64) -> Self {
+        Version {
+            major,
+            minor,
+            patch,
+            pre: Vec::new(),
+            build: Vec::new(),
+        }
+    }
+
+    pub fn parse(version: &str) -> Result<Self> {
+        let invalid = |reason: &str| Error::User(format!("invalid version '{version}': {reason}"));
+
+        let (rest, build) = match version.split_once('+') {
+            Some((rest, build)) => (rest, Some(build)),
+            None => (version, None),
+        };
+        let (core, pre) = match rest.split_once('-') {
+            Some((core, pre)) => (core, Some(pre)),
+            None => (rest, None),
+        };
+
+        let numbers: Vec<&str> = core.split('.').collect();
+        if numbers.len() != 3 {
+            return Err(invalid("expected MAJOR.MINOR.PATCH"));
+        }
+        let mut parsed = [0u64; 3];
+        for (slot, number) in parsed.iter_mut().zip(&numbers) {
+            *slot = parse_numeric(number).ok_or_else(|| {
+                invalid(&format!("'{number}' is not a number without leading zeros"))
+            })?;
+        }
+
+        let pre = match pre {
+            Some(pre) => pre
+                .split('.')
+                .map(|id| {
+                    if !is_identifier(id) {
+                        Err(invalid(&format!("invalid pre-release identifier '{id}'")))
+                    } else if id.bytes().all(|b| b.is_ascii_digit()) {
+                        parse_numeric(id).map(Identifier::Numeric).ok_or_else(|| {
+                            invalid(&format!(
+                                "numeric pre-release identifier '{id}' has leading zeros"
+                            ))
+                        })
+                    } else {
+                        Ok(Identifier::AlphaNumeric(id.to_string()))
+                    }
+                })
+                .collect::<Result<Vec<_>>>()?,
+            None => Vec::new(),
+        };
+
+        let build = match build {
+            Some(build) => build
+                .split('.')
+                .map(|id| {
+                    if is_identifier(id) {
+                        Ok(id.to_string())
+                    } else {
+                        Err(invalid(&format!(
+                            "invalid build metadata identifier '{id}'"
+                        )))
+                    }
+                })
+                .collect::<Result<Vec<_>>>()?,
+            None => Vec::new(),
+        };
+
+        let [major, minor, patch] = parsed;
+        Ok(Version {
+            major,
+            minor,
+            patch,
+            pre,
+            build,
+        })
+    }
+
+    pub fn is_prerelease(&self) -> bool {
+        !self.pre.is_empty()
+    }
+
+    /// The pre-release part without the leading `-`, e.g. `rc.1`.
+    pub fn pre_release(&self) -> Option<String> {
+        (!self.pre.is_empty()).then(|| join_identifiers(&self.pre))
+    }
+
+    /// The build metadata without the leading `+`.
+    pub fn build_metadata(&self) -> Option<String> {
+        (!self.build.is_empty()).then(|| self.build.join("."))
+    }
+
+    /// Compares by semver precedence, ignoring build metadata: a pre-release
+    /// sorts before its release, and `1.0.0+a` has the same precedence as `1.0.0`.
+    pub fn precedence(&self, other: &Version) -> Ordering {
+        (self.major, self.minor, self.patch)
+            .cmp(&(other.major, other.minor, other.patch))
+            .then_with(|| match (self.pre.is_empty(), other.pre.is_empty()) {
+                (true, true) => Ordering::Equal,
+                (true, false) => Ordering::Greater,
+                (false, true) => Ordering::Less,
+                (false, false) => self.pre.cmp(&other.pre),
+            })
+    }
+}
+
+/// How `bump-version` derives the next version from the current one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BumpLevel {
+    Major,
+    Minor,
+    Patch,
+    Prerelease,
+    /// An explicit version.
+    Exact(Version),
+}
+
+impl FromStr for BumpLevel {
+    type Err = Error;
+
+    /// Parses `major`, `minor`, `patch`, `prerelease` or an explicit version
+    /// (with or without a `v` prefix).
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_lowercase().as_str() {
+            "major" => Ok(BumpLevel::Major),
+            "minor" => Ok(BumpLevel::Minor),
+            "patch" => Ok(BumpLevel::Patch),
+            "prerelease" | "pre" => Ok(BumpLevel::Prerelease),
+            _ => {
+                let version = s.trim();
+                Version::parse(version.strip_prefix('v').unwrap_or(version))
+                    .map(BumpLevel::Exact)
+                    .map_err(|_| {
+                        Error::User(format!(
+                            "invalid bump level '{s}': expected major, minor, patch, prerelease or a version"
+                        ))
+                    })
+            }
+        }
+    }
+}
+
+impl Version {
+    /// The next version at `level`. Build metadata is dropped.
+    ///
+    /// A pre-release is released by the level it was heading for:
+    /// `2.0.0-rc.1` bumps to `2.0.0` for `major`, while `2.1.0-rc.1` bumps to `3.0.0`.
+    /// `prerelease` increments the last numeric identifier (`1.0.0-rc.1` → `1.0.0-rc.2`),
+    /// starts a new `pre_id` series (`1.0.0-alpha.3` → `1.0.0-beta.1`), or begins
+    /// one after a release (`1.2.3` → `1.2.4-rc.1`).
+    pub fn bump(&self, level: &BumpLevel, pre_id: Option<&str>) -> Result<Version> {
+        let pre = self.is_prerelease();
+        let mut next = Version::new(self.major, self.minor, self.patch);
+        match level {
+            BumpLevel::Major => {
+                if !(pre && self.minor == 0 && self.patch == 0) {
+                    next = Version::new(self.major + 1, 0, 0);
+                }
+            }
+            BumpLevel::Minor => {
+                if !(pre && self.patch == 0) {
+                    next = Version::new(self.major, self.minor + 1, 0);
+                }
+            }
+            BumpLevel::Patch => {
+                if !pre {
+                    next.patch += 1;
+                }
+            }
+            BumpLevel::Prerelease => {
+                let new_series = |id: &str| -> Result<Vec<Identifier>> {
+                    if !is_identifier(id) || id.bytes().all(|b| b.is_ascii_digit()) {
+                        return Err(Error::User(format!(
+                            "invalid pre-release identifier '{id}'"
+                        )));
+                    }
+                    Ok(vec![
+                        Identifier::AlphaNumeric(id.to_string()),
+                        Identifier::Numeric(1),
+                    ])
+                };
+                let same_series = match (pre_id, self.pre.first()) {
+                    (Some(id), Some(Identifier::AlphaNumeric(current))) => id == current,
+                    (Some(_), _) => false,
+                    (None, _) => true,
+                };
+                next.pre = if !pre {
+                    next.patch += 1;
+                    new_series(pre_id.unwrap_or("rc"))?
+                } else if !same_series {
+                    new_series(pre_id.unwrap_or_default())?
+                } else {
+                    let mut ids = self.pre.clone();
+                    match ids.last_mut() {
+                        Some(Identifier::Numeric(n)) => *n += 1,
+                        _ => ids.push(Identifier::Numeric(1)),
+                    }
+                    ids
+                };
+            }
+            BumpLevel::Exact(version) => {
+                if version.precedence(self) != Ordering::Greater {
+                    return Err(Error::User(format!(
+                        "new version {version} must be greater than the current version {self}"
+                    )));
+                }
+                next = version.clone();
+            }
+        }
+        Ok(next)
+    }
+}
+
+fn parse_numeric(number: &str) -> Option<u64> {
+    let valid = !number.is_empty()
+        && number.bytes().all(|b| b.is_ascii_digit())
+        && (number == "0" || !number.starts_with('0'));
+    if valid { number.parse().ok() } else { None }
+}
+
+fn is_identifier(id: &str) -> bool {
+    !id.is_empty() && id.bytes().all(|b| b.is_ascii_alphanumeric() || b == b'-')
+}
+
+fn join_identifiers(ids: &[Identifier]) -> String {
+    ids.iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+impl FromStr for Version {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Version::parse(s)
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)?;
+        if let Some(pre) = self.pre_release() {
+            write!(f, "-{pre}")?;
+        }
+        if let Some(build) = self.build_metadata() {
+            write!(f, "+{build}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.precedence(other)
+            .then_with(|| self.build.cmp(&other.build))
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
 }
 
 /// Extracts version from a tag string (strips leading 'v').
@@ -25,10 +318,10 @@ pub fn version_from_tag(tag: &str) -> Option<&str> {
     tag.strip_prefix('v')
 }
 
-pub fn run_validate_version() -> Result<()> {
-    let tag = env_or("TAG", &env_or("GITHUB_REF_NAME", ""));
-    let expected = env_or("EXPECTED_VERSION", &env_or("NEXT_RELEASE_VERSION", ""));
-    let validate_cargo = env_or("VALIDATE_CARGO_TOML", "") == "true";
+pub fn run_validate_version(config: &ReleaseConfig) -> Result<()> {
+    let tag = config.get("TAG", &env_or("GITHUB_REF_NAME", ""));
+    let expected = config.get("EXPECTED_VERSION", &env_or("NEXT_RELEASE_VERSION", ""));
+    let validate_cargo = config.get("VALIDATE_CARGO_TOML", "") == "true";
 
     if tag.is_empty() {
         eprintln!("\x1b[31mERROR:\x1b[0m GITHUB_REF_NAME is not available");
@@ -46,16 +339,19 @@ pub fn run_validate_version() -> Result<()> {
 
     let tag_version = &tag[1..];
 
-    if !is_valid_semver(tag_version) {
-        eprintln!("\x1b[31mERROR:\x1b[0m Invalid version format: {tag_version}");
-        eprintln!();
-        eprintln!("Expected semantic versioning: MAJOR.MINOR.PATCH[-PRERELEASE][+BUILD]");
-        eprintln!("Examples: 1.2.3, 1.0.0-alpha.1, 2.0.0-rc.1+build.123");
-        process::exit(1);
-    }
+    let parsed = match Version::parse(tag_version) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("\x1b[31mERROR:\x1b[0m {e}");
+            eprintln!();
+            eprintln!("Expected semantic versioning: MAJOR.MINOR.PATCH[-PRERELEASE][+BUILD]");
+            eprintln!("Examples: 1.2.3, 1.0.0-alpha.1, 2.0.0-rc.1+build.123");
+            process::exit(1);
+        }
+    };
 
     if !expected.is_empty() {
-        if expected == tag_version {
+        if Version::parse(&expected).is_ok_and(|e| e == parsed) {
             println!("\x1b[32mVersion validated:\x1b[0m {expected} matches tag {tag}");
         } else {
             eprintln!(
@@ -74,16 +370,17 @@ pub fn run_validate_version() -> Result<()> {
     }
 
     output("version", tag_version);
+    output("prerelease", &parsed.is_prerelease().to_string());
 
     if validate_cargo {
-        let cargo_info = get_cargo_info()?;
+        let cargo_info = get_cargo_info(config)?;
         let cargo_version = &cargo_info.version;
         if cargo_version.is_empty() {
             return Err(Error::User(
                 "Could not read version from Cargo.toml".to_string(),
             ));
         }
-        if cargo_version != tag_version {
+        if Version::parse(cargo_version)? != parsed {
             eprintln!(
                 "\x1b[31mERROR:\x1b[0m Cargo.toml version ({cargo_version}) does not match tag ({tag_version})"
             );
@@ -97,13 +394,13 @@ pub fn run_validate_version() -> Result<()> {
     Ok(())
 }
 
-pub fn run_get_version() -> Result<()> {
-    let manifest_path = env_or("MANIFEST_PATH", "Cargo.toml");
+pub fn run_get_version(config: &ReleaseConfig) -> Result<()> {
+    let manifest_path = config.get("MANIFEST_PATH", "Cargo.toml");
     if !Path::new(&manifest_path).exists() {
         return Err(Error::User(format!("manifest not found: {manifest_path}")));
     }
 
-    let info = get_cargo_info()?;
+    let info = get_cargo_info(config)?;
     let version = &info.version;
 
     if version.is_empty() {
@@ -113,18 +410,15 @@ pub fn run_get_version() -> Result<()> {
         process::exit(1);
     }
 
-    let version_start = Regex::new(r"^\d+\.\d+\.\d+").unwrap();
-    if !version_start.is_match(version) {
-        return Err(Error::User(format!("invalid version format: {version}")));
-    }
+    Version::parse(version)?;
 
     println!("{version}");
     output("version", version);
     Ok(())
 }
 
-pub fn run_get_release_version() -> Result<()> {
-    let version_override = env_or("VERSION", "");
+pub fn run_get_release_version(config: &ReleaseConfig) -> Result<()> {
+    let version_override = config.get("VERSION", "");
 
     let version = if !version_override.is_empty() {
         version_override
@@ -173,12 +467,10 @@ pub fn run_get_release_version() -> Result<()> {
         tag.strip_prefix('v').unwrap_or(&tag).to_string()
     };
 
-    let first_char = version.chars().next().unwrap_or(' ');
-    if !first_char.is_ascii_digit() {
-        return Err(Error::User(format!("invalid version format: {version}")));
-    }
+    let parsed = Version::parse(&version)?;
 
     println!("\x1b[32mVersion:\x1b[0m {version}");
     output("version", &version);
+    output("prerelease", &parsed.is_prerelease().to_string());
     Ok(())
 }
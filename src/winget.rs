@@ -1,5 +1,5 @@
 use crate::cargo_info::get_cargo_info;
-use crate::env_or;
+use crate::config::ReleaseConfig;
 use crate::error::{Error, Result};
 use crate::output::{output, print_hr};
 use crate::parse_comma_list;
@@ -119,10 +119,10 @@ pub fn generate_installer_manifest(config: &InstallerConfig) -> String {
     lines.join("\n")
 }
 
-pub fn run_generate_winget() -> Result<()> {
-    let info = get_cargo_info()?;
-    let binary_name = env_or("BINARY_NAME", &info.name);
-    let version = env_or("VERSION", &info.version);
+pub fn run_generate_winget(config: &ReleaseConfig) -> Result<()> {
+    let info = get_cargo_info(config)?;
+    let binary_name = config.get("BINARY_NAME", &info.name);
+    let version = config.get("VERSION", &info.version);
 
     if binary_name.is_empty() {
         return Err(Error::User("could not determine binary name".into()));
@@ -131,18 +131,18 @@ pub fn run_generate_winget() -> Result<()> {
         return Err(Error::User("could not determine version".into()));
     }
 
-    let publisher = env_or("WINGET_PUBLISHER", "");
+    let publisher = config.get("WINGET_PUBLISHER", "");
     if publisher.is_empty() {
         return Err(Error::User("WINGET_PUBLISHER is required".into()));
     }
 
-    let publisher_id = env_or("WINGET_PUBLISHER_ID", &publisher.replace(' ', ""));
-    let package_id = env_or("WINGET_PACKAGE_ID", &binary_name);
+    let publisher_id = config.get("WINGET_PUBLISHER_ID", &publisher.replace(' ', ""));
+    let package_id = config.get("WINGET_PACKAGE_ID", &binary_name);
     let manifest_id = format!("{publisher_id}.{package_id}");
 
     println!("\x1b[32mGenerating Winget manifest:\x1b[0m {manifest_id} v{version}");
 
-    let output_dir = env_or("WINGET_OUTPUT_DIR", "target/winget");
+    let output_dir = config.get("WINGET_OUTPUT_DIR", "target/winget");
     let first_char = publisher_id
         .chars()
         .next()
@@ -162,15 +162,15 @@ pub fn run_generate_winget() -> Result<()> {
         version: version.clone(),
         publisher: publisher.clone(),
         name: binary_name.clone(),
-        description: env_or(
+        description: config.get(
             "PKG_DESCRIPTION",
-            &format!("{binary_name} - built with rust-build-package-release-action"),
+            &info.description_or_default(&binary_name),
         ),
-        homepage: env_or("PKG_HOMEPAGE", ""),
-        license: env_or("PKG_LICENSE", "MIT"),
-        license_url: env_or("WINGET_LICENSE_URL", ""),
-        copyright: env_or("WINGET_COPYRIGHT", ""),
-        tags: env_or("WINGET_TAGS", ""),
+        homepage: config.get("PKG_HOMEPAGE", info.homepage_or_repository()),
+        license: config.get("PKG_LICENSE", info.license_or("MIT")),
+        license_url: config.get("WINGET_LICENSE_URL", ""),
+        copyright: config.get("WINGET_COPYRIGHT", ""),
+        tags: config.get("WINGET_TAGS", &info.keywords.join(",")),
     };
     let locale_manifest = generate_locale_manifest(&locale_config);
     let locale_path = format!("{manifest_dir}/{manifest_id}.locale.en-US.yaml");
@@ -179,10 +179,10 @@ pub fn run_generate_winget() -> Result<()> {
     let installer_config = InstallerConfig {
         id: manifest_id.clone(),
         version,
-        x64_url: env_or("WINGET_X64_URL", ""),
-        x64_sha256: env_or("WINGET_X64_SHA256", ""),
-        arm64_url: env_or("WINGET_ARM64_URL", ""),
-        arm64_sha256: env_or("WINGET_ARM64_SHA256", ""),
+        x64_url: config.get("WINGET_X64_URL", ""),
+        x64_sha256: config.get("WINGET_X64_SHA256", ""),
+        arm64_url: config.get("WINGET_ARM64_URL", ""),
+        arm64_sha256: config.get("WINGET_ARM64_SHA256", ""),
     };
     let installer_manifest = generate_installer_manifest(&installer_config);
     let installer_path = format!("{manifest_dir}/{manifest_id}.installer.yaml");
@@ -60,6 +60,20 @@ fn deb_arch_fallback_x86_64() {
     );
 }
 
+#[test]
+fn deb_arch_cross_targets() {
+    let cases = [
+        ("powerpc64le-unknown-linux-gnu", "ppc64el"),
+        ("powerpc64-unknown-linux-gnu", "ppc64"),
+        ("s390x-unknown-linux-gnu", "s390x"),
+        ("riscv64gc-unknown-linux-gnu", "riscv64"),
+        ("loongarch64-unknown-linux-gnu", "loong64"),
+    ];
+    for (target, arch) in cases {
+        assert_eq!(target_to_deb_arch(target).unwrap(), arch, "{target}");
+    }
+}
+
 #[test]
 fn deb_arch_unsupported() {
     assert!(target_to_deb_arch("mips-unknown-linux-gnu").is_err());
@@ -97,6 +111,20 @@ fn rpm_arch_i686() {
     );
 }
 
+#[test]
+fn rpm_arch_cross_targets() {
+    let cases = [
+        ("powerpc64le-unknown-linux-gnu", "ppc64le"),
+        ("powerpc64-unknown-linux-gnu", "ppc64"),
+        ("s390x-unknown-linux-gnu", "s390x"),
+        ("riscv64gc-unknown-linux-gnu", "riscv64"),
+        ("loongarch64-unknown-linux-gnu", "loongarch64"),
+    ];
+    for (target, arch) in cases {
+        assert_eq!(target_to_rpm_arch(target).unwrap(), arch, "{target}");
+    }
+}
+
 #[test]
 fn rpm_arch_unsupported() {
     assert!(target_to_rpm_arch("mips-unknown-linux-gnu").is_err());
@@ -139,6 +167,20 @@ fn apk_arch_i686() {
     );
 }
 
+#[test]
+fn apk_arch_cross_targets() {
+    let cases = [
+        ("powerpc64le-unknown-linux-musl", "ppc64le"),
+        ("s390x-unknown-linux-musl", "s390x"),
+        ("riscv64gc-unknown-linux-musl", "riscv64"),
+        ("loongarch64-unknown-linux-musl", "loongarch64"),
+    ];
+    for (target, arch) in cases {
+        assert_eq!(target_to_apk_arch(target).unwrap(), arch, "{target}");
+    }
+    assert!(target_to_apk_arch("powerpc64-unknown-linux-gnu").is_err());
+}
+
 #[test]
 fn apk_arch_unsupported() {
     assert!(target_to_apk_arch("mips-unknown-linux-gnu").is_err());
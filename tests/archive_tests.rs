@@ -1,24 +1,24 @@
 mod test_helpers;
 
-use rust_release_action::archive::{copy_docs, copy_includes, list_archivable_files};
-use std::fs;
+use test_helpers::dir_with_files;
+
+use rust_release_action::archive::{
+    ArchiveEntry, ArchiveFormat, NORMALIZED_MTIME, copy_docs, copy_includes, create_archive,
+    include_entries, list_archivable_files, release_entries_for,
+};
+use rust_release_action::config::ReleaseConfig;
+use std::fs::{self, File};
+use std::io::Read;
+use std::path::Path;
 use std::sync::{LazyLock, Mutex};
 use tempfile::TempDir;
 
 /// Serializes tests that change the process-wide CWD.
 static CWD_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
 
-fn create_test_dir_with_files(files: &[&str]) -> TempDir {
-    let dir = TempDir::new().unwrap();
-    for name in files {
-        fs::write(dir.path().join(name), "content").unwrap();
-    }
-    dir
-}
-
 #[test]
 fn archivable_includes_binary_and_docs() {
-    let dir = create_test_dir_with_files(&["myapp", "README.md", "LICENSE"]);
+    let dir = dir_with_files(&["myapp", "README.md", "LICENSE"]);
     let files = list_archivable_files(dir.path());
     assert!(files.contains(&"myapp".to_string()));
     assert!(files.contains(&"README.md".to_string()));
@@ -27,7 +27,7 @@ fn archivable_includes_binary_and_docs() {
 
 #[test]
 fn archivable_excludes_archives() {
-    let dir = create_test_dir_with_files(&["myapp", "myapp.tar.gz", "myapp.zip"]);
+    let dir = dir_with_files(&["myapp", "myapp.tar.gz", "myapp.zip"]);
     let files = list_archivable_files(dir.path());
     assert!(files.contains(&"myapp".to_string()));
     assert!(!files.contains(&"myapp.tar.gz".to_string()));
@@ -36,7 +36,7 @@ fn archivable_excludes_archives() {
 
 #[test]
 fn archivable_excludes_checksums() {
-    let dir = create_test_dir_with_files(&["myapp", "myapp.sha256", "myapp.sha512", "myapp.b2"]);
+    let dir = dir_with_files(&["myapp", "myapp.sha256", "myapp.sha512", "myapp.b2"]);
     let files = list_archivable_files(dir.path());
     assert!(files.contains(&"myapp".to_string()));
     assert!(!files.contains(&"myapp.sha256".to_string()));
@@ -46,8 +46,7 @@ fn archivable_excludes_checksums() {
 
 #[test]
 fn archivable_excludes_signatures() {
-    let dir =
-        create_test_dir_with_files(&["myapp", "myapp.sig", "myapp.pem", "myapp.sigstore.json"]);
+    let dir = dir_with_files(&["myapp", "myapp.sig", "myapp.pem", "myapp.sigstore.json"]);
     let files = list_archivable_files(dir.path());
     assert!(files.contains(&"myapp".to_string()));
     assert!(!files.contains(&"myapp.sig".to_string()));
@@ -55,9 +54,23 @@ fn archivable_excludes_signatures() {
     assert!(!files.contains(&"myapp.sigstore.json".to_string()));
 }
 
+#[test]
+fn archivable_excludes_every_sidecar() {
+    let dir = dir_with_files(&[
+        "myapp",
+        "myapp.asc",
+        "myapp.minisig",
+        "myapp.intoto.json",
+        "myapp.sha3-256",
+        "myapp.b3",
+    ]);
+    let files = list_archivable_files(dir.path());
+    assert_eq!(files, vec!["myapp".to_string()]);
+}
+
 #[test]
 fn archivable_excludes_sbom_files() {
-    let dir = create_test_dir_with_files(&["myapp", "myapp.spdx.json", "myapp.cdx.json"]);
+    let dir = dir_with_files(&["myapp", "myapp.spdx.json", "myapp.cdx.json"]);
     let files = list_archivable_files(dir.path());
     assert!(files.contains(&"myapp".to_string()));
     assert!(!files.contains(&"myapp.spdx.json".to_string()));
@@ -66,7 +79,7 @@ fn archivable_excludes_sbom_files() {
 
 #[test]
 fn archivable_returns_sorted() {
-    let dir = create_test_dir_with_files(&["zzz", "aaa", "mmm"]);
+    let dir = dir_with_files(&["zzz", "aaa", "mmm"]);
     let files = list_archivable_files(dir.path());
     assert_eq!(files, vec!["aaa", "mmm", "zzz"]);
 }
@@ -132,7 +145,7 @@ fn copy_includes_copies_matching_files() {
     unsafe {
         std::env::set_var("ARCHIVE_INCLUDE", "*.toml, *.json");
     }
-    copy_includes(dest.path()).unwrap();
+    copy_includes(&ReleaseConfig::default(), dest.path()).unwrap();
     unsafe {
         std::env::remove_var("ARCHIVE_INCLUDE");
     }
@@ -151,7 +164,456 @@ fn copy_includes_empty_is_noop() {
     unsafe {
         std::env::remove_var("ARCHIVE_INCLUDE");
     }
-    copy_includes(dest.path()).unwrap();
+    copy_includes(&ReleaseConfig::default(), dest.path()).unwrap();
 
     assert_eq!(fs::read_dir(dest.path()).unwrap().count(), 0);
 }
+
+fn sample_entries(dir: &Path) -> Vec<ArchiveEntry> {
+    fs::write(dir.join("myapp"), "binary").unwrap();
+    fs::write(dir.join("README.md"), "readme").unwrap();
+    fs::write(dir.join("LICENSE"), "license").unwrap();
+    vec![
+        ArchiveEntry::file(dir.join("README.md"), "README.md"),
+        ArchiveEntry::executable(dir.join("myapp"), "myapp"),
+        ArchiveEntry::file(dir.join("LICENSE"), "docs/LICENSE"),
+    ]
+}
+
+fn read_tar<R: Read>(reader: R) -> Vec<(String, u32, u64, u64, String)> {
+    let mut archive = tar::Archive::new(reader);
+    archive
+        .entries()
+        .unwrap()
+        .map(|e| {
+            let mut e = e.unwrap();
+            let header = e.header().clone();
+            let mut content = String::new();
+            e.read_to_string(&mut content).unwrap();
+            (
+                e.path().unwrap().to_string_lossy().to_string(),
+                header.mode().unwrap(),
+                header.mtime().unwrap(),
+                header.uid().unwrap(),
+                content,
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn archive_format_parses_known_formats() {
+    assert_eq!(
+        "tar.gz".parse::<ArchiveFormat>().unwrap(),
+        ArchiveFormat::TarGz
+    );
+    assert_eq!(
+        "tgz".parse::<ArchiveFormat>().unwrap(),
+        ArchiveFormat::TarGz
+    );
+    assert_eq!(
+        ".tar.xz".parse::<ArchiveFormat>().unwrap(),
+        ArchiveFormat::TarXz
+    );
+    assert_eq!(
+        "tar.zst".parse::<ArchiveFormat>().unwrap(),
+        ArchiveFormat::TarZst
+    );
+    assert_eq!("zip".parse::<ArchiveFormat>().unwrap(), ArchiveFormat::Zip);
+    assert!("rar".parse::<ArchiveFormat>().is_err());
+}
+
+#[test]
+fn archive_format_defaults_by_target() {
+    assert_eq!(
+        ArchiveFormat::default_for_target("x86_64-pc-windows-msvc"),
+        ArchiveFormat::Zip
+    );
+    assert_eq!(
+        ArchiveFormat::default_for_target("aarch64-apple-darwin"),
+        ArchiveFormat::TarGz
+    );
+    assert_eq!(ArchiveFormat::TarZst.extension(), "tar.zst");
+}
+
+#[test]
+fn tar_gz_entries_are_sorted_and_normalized() {
+    let dir = TempDir::new().unwrap();
+    let entries = sample_entries(dir.path());
+    let dest = dir.path().join("out.tar.gz");
+    create_archive(&dest, ArchiveFormat::TarGz, &entries).unwrap();
+
+    let contents = read_tar(flate2::read::GzDecoder::new(File::open(&dest).unwrap()));
+    let names: Vec<&str> = contents.iter().map(|c| c.0.as_str()).collect();
+    assert_eq!(names, vec!["README.md", "docs/", "docs/LICENSE", "myapp"]);
+    for (name, mode, mtime, uid, _) in &contents {
+        assert_eq!(*mtime, NORMALIZED_MTIME, "{name}");
+        assert_eq!(*uid, 0, "{name}");
+        let expected = if name == "myapp" || name == "docs/" {
+            0o755
+        } else {
+            0o644
+        };
+        assert_eq!(*mode, expected, "{name}");
+    }
+    assert_eq!(contents[3].4, "binary");
+}
+
+#[test]
+fn tar_xz_round_trips() {
+    let dir = TempDir::new().unwrap();
+    let entries = sample_entries(dir.path());
+    let dest = dir.path().join("out.tar.xz");
+    create_archive(&dest, ArchiveFormat::TarXz, &entries).unwrap();
+
+    let reader = lzma_rust2::XzReader::new(File::open(&dest).unwrap(), false);
+    let contents = read_tar(reader);
+    assert_eq!(contents.len(), 4);
+    assert_eq!(contents[0].4, "readme");
+}
+
+#[test]
+fn tar_zst_round_trips() {
+    let dir = TempDir::new().unwrap();
+    let entries = sample_entries(dir.path());
+    let dest = dir.path().join("out.tar.zst");
+    create_archive(&dest, ArchiveFormat::TarZst, &entries).unwrap();
+
+    let reader = ruzstd::decoding::StreamingDecoder::new(File::open(&dest).unwrap()).unwrap();
+    let contents = read_tar(reader);
+    assert_eq!(contents.len(), 4);
+    assert_eq!(contents[3].0, "myapp");
+}
+
+#[test]
+fn tar_zst_streams_large_files() {
+    let dir = TempDir::new().unwrap();
+    let content: String = (0..200_000).map(|i| format!("{i:x}")).collect();
+    fs::write(dir.path().join("large"), &content).unwrap();
+    let entries = vec![ArchiveEntry::file(dir.path().join("large"), "large")];
+    let dest = dir.path().join("out.tar.zst");
+    create_archive(&dest, ArchiveFormat::TarZst, &entries).unwrap();
+
+    let reader = ruzstd::decoding::StreamingDecoder::new(File::open(&dest).unwrap()).unwrap();
+    let contents = read_tar(reader);
+    assert_eq!(contents.len(), 1);
+    assert_eq!(contents[0].4, content);
+}
+
+#[test]
+fn tar_zst_reports_unreadable_entries() {
+    let dir = TempDir::new().unwrap();
+    let entries = vec![ArchiveEntry::file(dir.path().join("missing"), "missing")];
+    let dest = dir.path().join("out.tar.zst");
+    assert!(create_archive(&dest, ArchiveFormat::TarZst, &entries).is_err());
+}
+
+#[cfg(target_os = "linux")]
+#[test]
+fn tar_zst_reports_write_errors() {
+    let dir = TempDir::new().unwrap();
+    let content: String = (0..200_000).map(|i| format!("{i:x}")).collect();
+    fs::write(dir.path().join("large"), &content).unwrap();
+    let entries = vec![ArchiveEntry::file(dir.path().join("large"), "large")];
+    assert!(create_archive(Path::new("/dev/full"), ArchiveFormat::TarZst, &entries).is_err());
+}
+
+#[test]
+fn zip_round_trips_with_modes() {
+    let dir = TempDir::new().unwrap();
+    let entries = sample_entries(dir.path());
+    let dest = dir.path().join("out.zip");
+    create_archive(&dest, ArchiveFormat::Zip, &entries).unwrap();
+
+    let mut zip = zip::ZipArchive::new(File::open(&dest).unwrap()).unwrap();
+    let names: Vec<String> = zip.file_names().map(String::from).collect();
+    assert_eq!(names.len(), 4);
+    let mut binary = zip.by_name("myapp").unwrap();
+    assert_eq!(binary.unix_mode().unwrap() & 0o777, 0o755);
+    let mut content = String::new();
+    binary.read_to_string(&mut content).unwrap();
+    assert_eq!(content, "binary");
+    drop(binary);
+    let readme = zip.by_name("README.md").unwrap();
+    assert_eq!(readme.unix_mode().unwrap() & 0o777, 0o644);
+}
+
+#[test]
+fn archives_are_reproducible() {
+    for format in [
+        ArchiveFormat::TarGz,
+        ArchiveFormat::TarXz,
+        ArchiveFormat::TarZst,
+        ArchiveFormat::Zip,
+    ] {
+        let dir = TempDir::new().unwrap();
+        let mut entries = sample_entries(dir.path());
+        let first = dir.path().join("first");
+        create_archive(&first, format, &entries).unwrap();
+
+        // Input order and source timestamps must not matter
+        entries.reverse();
+        fs::write(dir.path().join("myapp"), "binary").unwrap();
+        let second = dir.path().join("second");
+        create_archive(&second, format, &entries).unwrap();
+
+        assert_eq!(
+            fs::read(&first).unwrap(),
+            fs::read(&second).unwrap(),
+            "{format:?}"
+        );
+    }
+}
+
+#[test]
+fn archive_rejects_unsafe_names() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("f"), "x").unwrap();
+    for name in ["../f", "/etc/f", "a//f", ""] {
+        let entries = vec![ArchiveEntry::file(dir.path().join("f"), name)];
+        let result = create_archive(&dir.path().join("out.zip"), ArchiveFormat::Zip, &entries);
+        assert!(result.is_err(), "{name}");
+    }
+}
+
+#[test]
+fn archive_rejects_duplicate_names() {
+    let dir = TempDir::new().unwrap();
+    fs::write(dir.path().join("f"), "x").unwrap();
+    let entries = vec![
+        ArchiveEntry::file(dir.path().join("f"), "f"),
+        ArchiveEntry::executable(dir.path().join("f"), "f"),
+    ];
+    let result = create_archive(
+        &dir.path().join("out.tar.gz"),
+        ArchiveFormat::TarGz,
+        &entries,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn archivable_excludes_other_archive_formats() {
+    let dir = dir_with_files(&["myapp", "myapp.tar.xz", "myapp.tar.zst"]);
+    let files = list_archivable_files(dir.path());
+    assert_eq!(files, vec!["myapp".to_string()]);
+}
+
+/// Runs `f` with `dir` as the working directory and ARCHIVE_INCLUDE set to `include`.
+fn in_dir_with_includes<T>(dir: &Path, include: &str, f: impl FnOnce() -> T) -> T {
+    let original = std::env::current_dir().unwrap();
+    std::env::set_current_dir(dir).unwrap();
+    unsafe { std::env::set_var("ARCHIVE_INCLUDE", include) };
+    let result = f();
+    unsafe { std::env::remove_var("ARCHIVE_INCLUDE") };
+    std::env::set_current_dir(original).unwrap();
+    result
+}
+
+fn create_project_layout() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    fs::create_dir_all(dir.path().join("completions")).unwrap();
+    fs::create_dir_all(dir.path().join("man/extra")).unwrap();
+    fs::write(dir.path().join("completions/tool.bash"), "bash").unwrap();
+    fs::write(dir.path().join("completions/_tool"), "zsh").unwrap();
+    fs::write(dir.path().join("man/tool.1"), "man").unwrap();
+    fs::write(dir.path().join("man/extra/tool-config.5"), "man5").unwrap();
+    fs::write(dir.path().join("LICENSE"), "MIT").unwrap();
+    dir
+}
+
+#[test]
+fn include_glob_preserves_relative_paths() {
+    let _lock = CWD_LOCK.lock().unwrap();
+    let dir = create_project_layout();
+    let entries =
+        in_dir_with_includes(dir.path(), "", || include_entries("completions/*").unwrap());
+    assert_eq!(
+        entries.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(),
+        vec!["completions/_tool", "completions/tool.bash"]
+    );
+}
+
+#[test]
+fn include_directory_is_recursive() {
+    let _lock = CWD_LOCK.lock().unwrap();
+    let dir = create_project_layout();
+    let entries = in_dir_with_includes(dir.path(), "", || include_entries("man").unwrap());
+    assert_eq!(
+        entries.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(),
+        vec!["man/extra/tool-config.5", "man/tool.1"]
+    );
+}
+
+#[test]
+fn include_src_dst_remaps_single_file() {
+    let _lock = CWD_LOCK.lock().unwrap();
+    let dir = create_project_layout();
+    let entries = in_dir_with_includes(dir.path(), "", || {
+        include_entries("man/tool.1:share/man/man1/tool.1").unwrap()
+    });
+    assert_eq!(
+        entries.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(),
+        vec!["share/man/man1/tool.1"]
+    );
+}
+
+#[test]
+fn include_src_dst_with_trailing_slash_is_a_directory() {
+    let _lock = CWD_LOCK.lock().unwrap();
+    let dir = create_project_layout();
+    let entries = in_dir_with_includes(dir.path(), "", || {
+        include_entries("man/tool.1:share/man/man1/").unwrap()
+    });
+    assert_eq!(
+        entries.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(),
+        vec!["share/man/man1/tool.1"]
+    );
+
+    let entries = in_dir_with_includes(dir.path(), "", || {
+        include_entries("completions/*:share/completions").unwrap()
+    });
+    assert_eq!(
+        entries.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(),
+        vec!["share/completions/_tool", "share/completions/tool.bash"]
+    );
+}
+
+#[test]
+fn include_rejects_paths_outside_project() {
+    let _lock = CWD_LOCK.lock().unwrap();
+    let dir = create_project_layout();
+    let nested = dir.path().join("completions");
+    let result = in_dir_with_includes(&nested, "", || include_entries("../man/tool.1"));
+    assert!(result.is_err());
+
+    let entries = in_dir_with_includes(&nested, "", || {
+        include_entries("../man/tool.1:man/tool.1").unwrap()
+    });
+    assert_eq!(
+        entries.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(),
+        vec!["man/tool.1"]
+    );
+}
+
+#[test]
+fn include_rejects_malformed_spec() {
+    assert!(include_entries("a:b:c").is_err());
+}
+
+#[test]
+fn include_without_matches_is_an_error() {
+    let _lock = CWD_LOCK.lock().unwrap();
+    let dir = TempDir::new().unwrap();
+    let result = in_dir_with_includes(dir.path(), "", || include_entries("completions/*"));
+    let message = result.unwrap_err().to_string();
+    assert!(message.contains("matched no files"), "{message}");
+
+    // A drive letter is part of the source path, not a src:dst separator
+    let message = include_entries("C:\\missing\\*").unwrap_err().to_string();
+    assert!(message.contains("matched no files"), "{message}");
+}
+
+#[test]
+fn release_entries_include_binary_docs_and_includes() {
+    let _lock = CWD_LOCK.lock().unwrap();
+    let dir = create_project_layout();
+    fs::write(dir.path().join("tool"), "bin").unwrap();
+    let binary = dir.path().join("tool");
+    let entries = in_dir_with_includes(
+        dir.path(),
+        "completions/*,man/tool.1:man/man1/tool.1",
+        || release_entries_for(&ReleaseConfig::default(), &[&binary], None).unwrap(),
+    );
+    assert_eq!(
+        entries.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(),
+        vec![
+            "tool",
+            "LICENSE",
+            "completions/_tool",
+            "completions/tool.bash",
+            "man/man1/tool.1",
+        ]
+    );
+    let bin = entries.iter().find(|e| e.name == "tool").unwrap();
+    assert_eq!(bin.mode, 0o755);
+}
+
+#[test]
+fn release_entries_nest_under_top_level_dir() {
+    let _lock = CWD_LOCK.lock().unwrap();
+    let dir = create_project_layout();
+    fs::write(dir.path().join("tool"), "bin").unwrap();
+    let binary = dir.path().join("tool");
+    let entries = in_dir_with_includes(dir.path(), "man/tool.1", || {
+        release_entries_for(
+            &ReleaseConfig::default(),
+            &[&binary],
+            Some("tool-1.0.0-x86_64-unknown-linux-gnu"),
+        )
+        .unwrap()
+    });
+    assert_eq!(
+        entries.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(),
+        vec![
+            "tool-1.0.0-x86_64-unknown-linux-gnu/tool",
+            "tool-1.0.0-x86_64-unknown-linux-gnu/LICENSE",
+            "tool-1.0.0-x86_64-unknown-linux-gnu/man/tool.1",
+        ]
+    );
+}
+
+#[test]
+fn release_entries_prefer_included_docs() {
+    let _lock = CWD_LOCK.lock().unwrap();
+    let dir = create_project_layout();
+    fs::write(dir.path().join("tool"), "bin").unwrap();
+    let binary = dir.path().join("tool");
+    let entries = in_dir_with_includes(dir.path(), "LICENSE", || {
+        release_entries_for(&ReleaseConfig::default(), &[&binary], None).unwrap()
+    });
+    assert_eq!(
+        entries.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(),
+        vec!["tool", "LICENSE"]
+    );
+}
+
+#[test]
+fn release_entries_for_several_binaries() {
+    let _lock = CWD_LOCK.lock().unwrap();
+    let dir = create_project_layout();
+    fs::write(dir.path().join("tool"), "bin").unwrap();
+    fs::write(dir.path().join("toold"), "bin").unwrap();
+    let tool = dir.path().join("tool");
+    let daemon = dir.path().join("toold");
+    let entries = in_dir_with_includes(dir.path(), "", || {
+        release_entries_for(
+            &ReleaseConfig::default(),
+            &[tool.as_path(), daemon.as_path()],
+            Some("tool-1.0.0"),
+        )
+        .unwrap()
+    });
+    assert_eq!(
+        entries.iter().map(|e| e.name.as_str()).collect::<Vec<_>>(),
+        vec!["tool-1.0.0/tool", "tool-1.0.0/toold", "tool-1.0.0/LICENSE"]
+    );
+    assert!(
+        entries
+            .iter()
+            .filter(|e| e.name.ends_with("toold") || e.name.ends_with("/tool"))
+            .all(|e| e.mode == 0o755)
+    );
+}
+
+#[test]
+fn copy_includes_preserves_directories() {
+    let _lock = CWD_LOCK.lock().unwrap();
+    let dir = create_project_layout();
+    let dest = TempDir::new().unwrap();
+    in_dir_with_includes(dir.path(), "completions/*", || {
+        copy_includes(&ReleaseConfig::default(), dest.path()).unwrap()
+    });
+    assert!(dest.path().join("completions/tool.bash").exists());
+    assert!(dest.path().join("completions/_tool").exists());
+}
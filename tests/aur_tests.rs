@@ -206,3 +206,28 @@ fn srcinfo_with_dependencies() {
     assert!(srcinfo.contains("\tconflicts = tool-git\n"));
     assert!(!srcinfo.contains("\turl ="));
 }
+
+#[test]
+fn pkgbuild_escapes_shell_strings() {
+    let config = PkgbuildConfig {
+        pkgname: "mytool".into(),
+        pkgver: "1.0.0".into(),
+        pkgdesc: "Prints \"$HOME\" via `echo`\nfast".into(),
+        url: "https://example.com".into(),
+        license: "Bob's License".into(),
+        maintainer: "Test\n<test@example.com>".into(),
+        source_url: "https://example.com/src.tar.gz".into(),
+        source_sha256: "abc123".into(),
+        depends: String::new(),
+        makedepends: String::new(),
+        optdepends: String::new(),
+        provides: String::new(),
+        conflicts: String::new(),
+        binary_name: "mytool".into(),
+    };
+
+    let pkgbuild = generate_pkgbuild(&config);
+    assert!(pkgbuild.contains("pkgdesc=\"Prints \\\"\\$HOME\\\" via \\`echo\\` fast\"\n"));
+    assert!(pkgbuild.contains("license=('Bob'\\''s License')\n"));
+    assert!(pkgbuild.contains("# Maintainer: Test <test@example.com>\n"));
+}
@@ -1,4 +1,7 @@
-use rust_release_action::build::build_summary;
+use rust_release_action::build::{
+    BinaryPackaging, BinaryTarget, BuildTool, build_summary, build_tool_for, requires_cross,
+    workspace_binaries,
+};
 use rust_release_action::checksum::Checksums;
 
 #[test]
@@ -7,6 +10,8 @@ fn build_summary_contains_all_fields() {
         sha256: "abc123".into(),
         sha512: "def456".into(),
         b2: "ghi789".into(),
+        sha3_256: "jkl012".into(),
+        b3: "mno345".into(),
     };
     let result = build_summary(
         "myapp",
@@ -26,6 +31,8 @@ fn build_summary_contains_all_fields() {
     assert_eq!(parsed["sha256"], "abc123");
     assert_eq!(parsed["sha512"], "def456");
     assert_eq!(parsed["b2"], "ghi789");
+    assert_eq!(parsed["sha3_256"], "jkl012");
+    assert_eq!(parsed["b3"], "mno345");
 }
 
 #[test]
@@ -45,3 +52,147 @@ fn build_summary_with_empty_checksums() {
     assert_eq!(parsed["sha512"], "");
     assert_eq!(parsed["b2"], "");
 }
+
+fn workspace_metadata() -> serde_json::Value {
+    serde_json::json!({
+        "workspace_members": ["path+file:///ws/cli#tools@1.2.0", "path+file:///ws/daemon#toold@1.1.0"],
+        "packages": [
+            {
+                "id": "path+file:///ws/cli#tools@1.2.0",
+                "name": "tools",
+                "version": "1.2.0",
+                "targets": [
+                    {"name": "tools", "kind": ["lib"]},
+                    {"name": "tool", "kind": ["bin"]},
+                    {"name": "tool-admin", "kind": ["bin"]},
+                    {"name": "bench", "kind": ["bench"]}
+                ]
+            },
+            {
+                "id": "path+file:///ws/daemon#toold@1.1.0",
+                "name": "toold",
+                "version": "1.1.0",
+                "targets": [{"name": "toold", "kind": ["bin"]}]
+            },
+            {
+                "id": "registry+https://github.com/rust-lang/crates.io-index#dep@1.0.0",
+                "name": "dep",
+                "version": "1.0.0",
+                "targets": [{"name": "dep-cli", "kind": ["bin"]}]
+            }
+        ]
+    })
+}
+
+#[test]
+fn workspace_binaries_finds_all_member_binaries() {
+    let binaries = workspace_binaries(&workspace_metadata(), &[], &["all".into()]).unwrap();
+    assert_eq!(
+        binaries.iter().map(|b| b.name.as_str()).collect::<Vec<_>>(),
+        vec!["tool", "tool-admin", "toold"]
+    );
+    assert_eq!(
+        binaries[2],
+        BinaryTarget {
+            name: "toold".into(),
+            package: "toold".into(),
+            version: "1.1.0".into(),
+        }
+    );
+}
+
+#[test]
+fn workspace_binaries_filters_by_package_and_name() {
+    let metadata = workspace_metadata();
+    let binaries = workspace_binaries(&metadata, &["tools".into()], &["all".into()]).unwrap();
+    assert_eq!(
+        binaries.iter().map(|b| b.name.as_str()).collect::<Vec<_>>(),
+        vec!["tool", "tool-admin"]
+    );
+
+    let binaries = workspace_binaries(&metadata, &[], &["toold".into(), "tool".into()]).unwrap();
+    assert_eq!(
+        binaries.iter().map(|b| b.name.as_str()).collect::<Vec<_>>(),
+        vec!["toold", "tool"]
+    );
+}
+
+#[test]
+fn workspace_binaries_rejects_unknown_names() {
+    let metadata = workspace_metadata();
+    assert!(workspace_binaries(&metadata, &[], &["dep-cli".into()]).is_err());
+    assert!(workspace_binaries(&metadata, &["dep".into()], &["all".into()]).is_err());
+    assert!(workspace_binaries(&metadata, &["toold".into()], &["tool".into()]).is_err());
+}
+
+#[test]
+fn binary_packaging_from_str() {
+    assert_eq!(
+        "".parse::<BinaryPackaging>().unwrap(),
+        BinaryPackaging::Combined
+    );
+    assert_eq!(
+        "combined".parse::<BinaryPackaging>().unwrap(),
+        BinaryPackaging::Combined
+    );
+    assert_eq!(
+        "separate".parse::<BinaryPackaging>().unwrap(),
+        BinaryPackaging::Separate
+    );
+    assert!("both".parse::<BinaryPackaging>().is_err());
+}
+
+#[test]
+fn build_tool_from_str() {
+    assert_eq!("".parse::<BuildTool>().unwrap(), BuildTool::Cargo);
+    assert_eq!("cargo".parse::<BuildTool>().unwrap(), BuildTool::Cargo);
+    assert_eq!(
+        "zigbuild".parse::<BuildTool>().unwrap(),
+        BuildTool::Zigbuild
+    );
+    assert_eq!("cross".parse::<BuildTool>().unwrap(), BuildTool::Cross);
+    let err = "docker".parse::<BuildTool>().unwrap_err();
+    assert!(err.to_string().contains("docker"));
+}
+
+#[test]
+fn targets_requiring_cross() {
+    assert!(requires_cross("powerpc64le-unknown-linux-gnu"));
+    assert!(requires_cross("s390x-unknown-linux-gnu"));
+    assert!(requires_cross("riscv64gc-unknown-linux-musl"));
+    assert!(!requires_cross("aarch64-unknown-linux-gnu"));
+    assert!(!requires_cross("x86_64-unknown-linux-musl"));
+    assert!(!requires_cross("riscv64gc-unknown-none-elf"));
+}
+
+#[test]
+fn build_tool_selection() {
+    assert_eq!(
+        build_tool_for("s390x-unknown-linux-gnu", None),
+        BuildTool::Cross
+    );
+    assert_eq!(
+        build_tool_for("x86_64-unknown-linux-gnu", None),
+        BuildTool::Cargo
+    );
+    assert_eq!(
+        build_tool_for("s390x-unknown-linux-gnu", Some(BuildTool::Zigbuild)),
+        BuildTool::Zigbuild
+    );
+    assert_eq!(
+        build_tool_for("x86_64-unknown-linux-gnu", Some(BuildTool::Cross)),
+        BuildTool::Cross
+    );
+    assert_eq!(
+        build_tool_for("aarch64-apple-darwin", Some(BuildTool::Zigbuild)),
+        BuildTool::Zigbuild
+    );
+    assert_eq!(
+        build_tool_for("x86_64-pc-windows-gnu", Some(BuildTool::Cross)),
+        BuildTool::Cross
+    );
+    assert_eq!(
+        build_tool_for("aarch64-apple-darwin", None),
+        BuildTool::Cargo
+    );
+}
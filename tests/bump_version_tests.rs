@@ -0,0 +1,166 @@
+use rust_release_action::bump_version::{
+    Manifest, bump_manifests, update_requirement, workspace_members,
+};
+use rust_release_action::version::{BumpLevel, Version};
+use std::fs;
+use std::path::Path;
+use tempfile::TempDir;
+
+fn write(dir: &Path, path: &str, content: &str) {
+    let path = dir.join(path);
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+fn workspace() -> TempDir {
+    let dir = TempDir::new().unwrap();
+    write(
+        dir.path(),
+        "Cargo.toml",
+        r#"[workspace]
+members = ["crates/*"]
+exclude = ["crates/excluded"]
+
+[workspace.package]
+version = "1.2.3" # shared version
+
+[workspace.dependencies]
+core = { path = "crates/core", version = "=1.2.3" }
+"#,
+    );
+    write(
+        dir.path(),
+        "crates/core/Cargo.toml",
+        "[package]\nname = \"core\"\nversion.workspace = true\n",
+    );
+    write(
+        dir.path(),
+        "crates/cli/Cargo.toml",
+        r#"[package]
+name = "cli"
+version = "1.2.3"
+
+[dependencies]
+core = { workspace = true }
+tool = { path = "../tool", version = "1.2.3" }
+
+[target.'cfg(unix)'.dependencies.core-alias]
+package = "core"
+path = "../core"
+version = "^1.2"   # unix only
+"#,
+    );
+    write(
+        dir.path(),
+        "crates/tool/Cargo.toml",
+        "[package]\nname = \"tool\"\nversion = \"1.2.3\"\n",
+    );
+    write(
+        dir.path(),
+        "crates/other/Cargo.toml",
+        "[package]\nname = \"other\"\nversion = \"0.1.0\"\n",
+    );
+    write(
+        dir.path(),
+        "crates/excluded/Cargo.toml",
+        "[package]\nname = \"excluded\"\nversion = \"1.2.3\"\n",
+    );
+    dir
+}
+
+#[test]
+fn requirement_updates_keep_the_operator() {
+    let v = Version::parse("1.3.0").unwrap();
+    assert_eq!(update_requirement("1.2.3", &v).as_deref(), Some("1.3.0"));
+    assert_eq!(update_requirement("=1.2.3", &v).as_deref(), Some("=1.3.0"));
+    assert_eq!(update_requirement("^1.2", &v).as_deref(), Some("^1.3.0"));
+    assert_eq!(update_requirement("~1.2.3", &v).as_deref(), Some("~1.3.0"));
+    assert_eq!(update_requirement("1.3.0", &v), None);
+    assert_eq!(update_requirement(">=1, <2", &v), None);
+    assert_eq!(update_requirement("1.*", &v), None);
+    assert_eq!(update_requirement("<2", &v), None);
+}
+
+#[test]
+fn members_follow_globs_and_excludes() {
+    let dir = workspace();
+    let root = Manifest::read(&dir.path().join("Cargo.toml")).unwrap();
+    let names: Vec<String> = workspace_members(&root)
+        .iter()
+        .map(|p| {
+            Manifest::read(p)
+                .unwrap()
+                .package_name()
+                .unwrap()
+                .to_string()
+        })
+        .collect();
+    assert_eq!(names, vec!["cli", "core", "other", "tool"]);
+}
+
+#[test]
+fn bump_workspace_version_and_requirements() {
+    let dir = workspace();
+    let bump = bump_manifests(&dir.path().join("Cargo.toml"), &BumpLevel::Minor, None).unwrap();
+    assert_eq!(bump.previous.to_string(), "1.2.3");
+    assert_eq!(bump.version.to_string(), "1.3.0");
+    assert_eq!(bump.packages, vec!["cli", "core", "tool"]);
+
+    let changed: Vec<String> = bump
+        .manifests
+        .iter()
+        .map(|m| {
+            m.path
+                .strip_prefix(dir.path())
+                .unwrap()
+                .display()
+                .to_string()
+        })
+        .collect();
+    assert_eq!(
+        changed,
+        vec![
+            "Cargo.toml",
+            "crates/cli/Cargo.toml",
+            "crates/tool/Cargo.toml"
+        ]
+    );
+
+    let root = bump.manifests[0].doc.to_string();
+    assert!(root.contains("version = \"1.3.0\" # shared version"));
+    assert!(root.contains("core = { path = \"crates/core\", version = \"=1.3.0\" }"));
+
+    let cli = bump.manifests[1].doc.to_string();
+    assert!(cli.contains("[package]\nname = \"cli\"\nversion = \"1.3.0\"\n"));
+    assert!(cli.contains("tool = { path = \"../tool\", version = \"1.3.0\" }"));
+    assert!(cli.contains("version = \"^1.3.0\"   # unix only"));
+    assert!(cli.contains("core = { workspace = true }"));
+}
+
+#[test]
+fn bump_single_package() {
+    let dir = TempDir::new().unwrap();
+    write(
+        dir.path(),
+        "Cargo.toml",
+        "[package]\nname = \"app\"\nversion = \"0.9.0-rc.2\"\nedition = \"2021\"\n",
+    );
+    let bump =
+        bump_manifests(&dir.path().join("Cargo.toml"), &BumpLevel::Prerelease, None).unwrap();
+    assert_eq!(
+        bump.manifests[0].doc.to_string(),
+        "[package]\nname = \"app\"\nversion = \"0.9.0-rc.3\"\nedition = \"2021\"\n"
+    );
+}
+
+#[test]
+fn bump_member_manifest_that_inherits_is_an_error() {
+    let dir = workspace();
+    let err = bump_manifests(
+        &dir.path().join("crates/core/Cargo.toml"),
+        &BumpLevel::Patch,
+        None,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("workspace root"), "{err}");
+}
@@ -1,6 +1,8 @@
 mod test_helpers;
 
 use rust_release_action::cargo_info::get_cargo_info_from_path;
+use std::fs;
+use tempfile::TempDir;
 use test_helpers::create_temp_text_file;
 
 #[test]
@@ -33,6 +35,22 @@ version = "2.0.0"
     assert_eq!(info.version, "2.0.0");
 }
 
+#[test]
+fn reads_inherited_workspace_version() {
+    let f = create_temp_text_file(
+        r#"
+[package]
+name = "my-tool"
+version.workspace = true
+
+[workspace.package]
+version = "2.1.0"
+"#,
+    );
+    let info = get_cargo_info_from_path(f.path().to_str().unwrap()).unwrap();
+    assert_eq!(info.version, "2.1.0");
+}
+
 #[test]
 fn package_version_takes_precedence() {
     let f = create_temp_text_file(
@@ -113,3 +131,107 @@ license = "MIT"
     assert_eq!(info.name, "complex-tool");
     assert_eq!(info.version, "0.5.0");
 }
+
+#[test]
+fn reads_packaging_metadata() {
+    let f = create_temp_text_file(
+        r#"
+[package]
+name = "tool"
+version = "1.0.0"
+description = "A fast CLI tool"
+license = "MIT OR Apache-2.0"
+repository = "https://github.com/org/tool"
+authors = ["Jane Doe <jane@example.com>"]
+keywords = ["cli", "release"]
+readme = "docs/README.md"
+"#,
+    );
+    let info = get_cargo_info_from_path(f.path().to_str().unwrap()).unwrap();
+    assert_eq!(info.description, "A fast CLI tool");
+    assert_eq!(info.license, "MIT OR Apache-2.0");
+    assert_eq!(info.homepage, "");
+    assert_eq!(info.homepage_or_repository(), "https://github.com/org/tool");
+    assert_eq!(info.maintainer_or(""), "Jane Doe <jane@example.com>");
+    assert_eq!(info.keywords, vec!["cli", "release"]);
+    let dir = f.path().parent().unwrap();
+    assert_eq!(info.readme, dir.join("docs/README.md").to_string_lossy());
+}
+
+#[test]
+fn missing_metadata_uses_defaults() {
+    let f = create_temp_text_file("[package]\nname = \"tool\"\nreadme = false\n");
+    let info = get_cargo_info_from_path(f.path().to_str().unwrap()).unwrap();
+    assert_eq!(info.readme, "");
+    assert_eq!(info.license_or("MIT"), "MIT");
+    assert_eq!(info.maintainer_or("Unknown"), "Unknown");
+    assert_eq!(
+        info.description_or_default("tool"),
+        "tool - built with rust-build-package-release-action"
+    );
+}
+
+#[test]
+fn member_inherits_metadata_from_workspace_root() {
+    let dir = TempDir::new().unwrap();
+    fs::write(
+        dir.path().join("Cargo.toml"),
+        r#"
+[workspace]
+members = ["cli"]
+
+[workspace.package]
+version = "3.1.0"
+license = "Apache-2.0"
+homepage = "https://tool.example.com"
+authors = ["Tool Maintainers <team@example.com>"]
+license-file = "LICENSE.txt"
+"#,
+    )
+    .unwrap();
+    fs::create_dir_all(dir.path().join("cli")).unwrap();
+    let member = dir.path().join("cli/Cargo.toml");
+    fs::write(
+        &member,
+        r#"
+[package]
+name = "tool"
+version.workspace = true
+license.workspace = true
+homepage.workspace = true
+authors.workspace = true
+license-file.workspace = true
+description = "Member description"
+"#,
+    )
+    .unwrap();
+
+    let info = get_cargo_info_from_path(member.to_str().unwrap()).unwrap();
+    assert_eq!(info.version, "3.1.0");
+    assert_eq!(info.license, "Apache-2.0");
+    assert_eq!(info.homepage, "https://tool.example.com");
+    assert_eq!(info.authors, vec!["Tool Maintainers <team@example.com>"]);
+    assert_eq!(info.description, "Member description");
+    assert!(info.license_file.ends_with("LICENSE.txt"));
+    assert!(!info.license_file.contains("cli"));
+}
+
+#[test]
+fn virtual_workspace_uses_workspace_package_metadata() {
+    let f = create_temp_text_file(
+        r#"
+[workspace]
+members = ["cli"]
+
+[workspace.package]
+version = "0.4.0"
+description = "Workspace tools"
+keywords = ["tools"]
+"#,
+    );
+    let info = get_cargo_info_from_path(f.path().to_str().unwrap()).unwrap();
+    assert_eq!(info.name, "");
+    assert_eq!(info.version, "0.4.0");
+    assert_eq!(info.description, "Workspace tools");
+    assert_eq!(info.keywords, vec!["tools"]);
+}
@@ -1,4 +1,8 @@
-use rust_release_action::changelog::{extract_changelog_section, validate_changelog_entry};
+use rust_release_action::changelog::{
+    ChangeKind, Changelog, Severity, UNRELEASED, extract_changelog_section, lint_changelog,
+    parse_changelog_date, parse_version_heading, promote_unreleased, release_tag,
+    validate_changelog_entry,
+};
 
 const SAMPLE_CHANGELOG: &str = "\
 # Changelog
@@ -96,3 +100,348 @@ fn extract_changelog_with_only_header() {
     let result = extract_changelog_section(changelog, "1.0.0");
     assert!(result.is_err());
 }
+
+const KEEP_A_CHANGELOG: &str = "\
+# Changelog
+
+All notable changes to this project will be documented in this file.
+
+## [Unreleased]
+
+### Added
+
+- Upcoming feature
+
+## [1.2.0] - 2024-03-01
+
+### Added
+
+- New `--json` flag
+  for machine-readable output
+
+### Fixed
+
+* Crash on empty input (#12)
+
+## [1.1.10] - 2024-01-15
+
+### Security
+
+- Bumped a dependency
+
+## [1.1.1] - 2024-01-01
+
+- Initial fixes
+
+[Unreleased]: https://github.com/org/repo/compare/v1.2.0...HEAD
+[1.2.0]: https://github.com/org/repo/compare/v1.1.10...v1.2.0
+[1.1.10]: https://github.com/org/repo/compare/v1.1.1...v1.1.10
+[1.1.1]: https://github.com/org/repo/releases/tag/v1.1.1
+";
+
+#[test]
+fn version_heading_forms() {
+    assert_eq!(
+        parse_version_heading("[1.2.3] - 2024-01-01"),
+        Some(("1.2.3".into(), Some("2024-01-01".into())))
+    );
+    assert_eq!(
+        parse_version_heading("v1.2.3 (Jul 11, 2026)"),
+        Some(("1.2.3".into(), Some("Jul 11, 2026".into())))
+    );
+    assert_eq!(
+        parse_version_heading("2.0.0-rc.1+build.5"),
+        Some(("2.0.0-rc.1+build.5".into(), None))
+    );
+    assert_eq!(
+        parse_version_heading("[Unreleased]"),
+        Some((UNRELEASED.into(), None))
+    );
+    assert_eq!(parse_version_heading("1.2.3x"), None);
+    assert_eq!(parse_version_heading("Notes"), None);
+}
+
+#[test]
+fn keep_a_changelog_sections() {
+    let changelog = Changelog::parse(KEEP_A_CHANGELOG);
+    let versions: Vec<&str> = changelog
+        .sections
+        .iter()
+        .map(|s| s.version.as_str())
+        .collect();
+    assert_eq!(versions, vec![UNRELEASED, "1.2.0", "1.1.10", "1.1.1"]);
+    assert_eq!(changelog.sections[1].date.as_deref(), Some("2024-03-01"));
+    assert_eq!(changelog.sections[1].line, 11);
+    assert_eq!(changelog.links.len(), 4);
+    assert!(changelog.unreleased().is_some());
+}
+
+#[test]
+fn extract_keep_a_changelog_section() {
+    let section = extract_changelog_section(KEEP_A_CHANGELOG, "1.2.0").unwrap();
+    assert!(section.starts_with("## [1.2.0] - 2024-03-01"));
+    assert!(section.contains("Crash on empty input"));
+    assert!(!section.contains("1.1.10] - "));
+    assert!(!section.contains("Upcoming feature"));
+    // Only the link definition the section refers to is carried over
+    assert!(section.ends_with("[1.2.0]: https://github.com/org/repo/compare/v1.1.10...v1.2.0"));
+    assert!(!section.contains("[1.1.1]:"));
+}
+
+#[test]
+fn extract_does_not_match_version_prefixes() {
+    let section = extract_changelog_section(KEEP_A_CHANGELOG, "1.1.1").unwrap();
+    assert!(section.contains("Initial fixes"));
+    assert!(!section.contains("Bumped a dependency"));
+    assert!(!validate_changelog_entry(KEEP_A_CHANGELOG, "1.1.0"));
+}
+
+#[test]
+fn extract_last_section_excludes_link_definitions() {
+    let section = extract_changelog_section(KEEP_A_CHANGELOG, "v1.1.1").unwrap();
+    assert!(!section.contains("[Unreleased]:"));
+    assert!(!section.contains("[1.2.0]:"));
+}
+
+#[test]
+fn extract_unreleased_section() {
+    let section = extract_changelog_section(KEEP_A_CHANGELOG, "unreleased").unwrap();
+    assert!(section.contains("Upcoming feature"));
+    assert!(section.contains("[Unreleased]: https://github.com/org/repo/compare/v1.2.0...HEAD"));
+}
+
+#[test]
+fn setext_headings() {
+    let changelog = "Changelog\n=========\n\n1.0.1\n-----\n\n- Fix\n\n1.0.0\n-----\n\n- Initial\n";
+    let section = extract_changelog_section(changelog, "1.0.1").unwrap();
+    assert_eq!(section, "1.0.1\n-----\n\n- Fix");
+    assert!(validate_changelog_entry(changelog, "1.0.0"));
+}
+
+#[test]
+fn headings_in_code_blocks_are_ignored() {
+    let changelog = "## 1.0.0\n\n```md\n## 0.9.0\n```\n\n- Change\n";
+    let section = extract_changelog_section(changelog, "1.0.0").unwrap();
+    assert!(section.contains("- Change"));
+    assert!(!validate_changelog_entry(changelog, "0.9.0"));
+}
+
+#[test]
+fn empty_section_is_an_error() {
+    let changelog = "## [Unreleased]\n\n## [1.0.0]\n\n- Change\n";
+    assert!(extract_changelog_section(changelog, "Unreleased").is_err());
+}
+
+#[test]
+fn categories_and_entries() {
+    let changelog = Changelog::parse(KEEP_A_CHANGELOG);
+    let categories = changelog.find("1.2.0").unwrap().categories();
+    assert_eq!(categories.len(), 2);
+    assert_eq!(categories[0].title, "Added");
+    assert_eq!(categories[0].kind, ChangeKind::Added);
+    assert_eq!(
+        categories[0].entries,
+        vec!["New `--json` flag for machine-readable output"]
+    );
+    assert_eq!(categories[1].kind, ChangeKind::Fixed);
+    assert_eq!(categories[1].entries, vec!["Crash on empty input (#12)"]);
+
+    let uncategorized = changelog.find("1.1.1").unwrap().categories();
+    assert_eq!(uncategorized[0].title, "");
+    assert_eq!(uncategorized[0].entries, vec!["Initial fixes"]);
+}
+
+#[test]
+fn change_kinds_from_headings_and_commit_types() {
+    assert_eq!(ChangeKind::from_heading("Bug Fixes"), ChangeKind::Fixed);
+    assert_eq!(ChangeKind::from_heading("Enhancements"), ChangeKind::Added);
+    assert_eq!(
+        ChangeKind::from_heading("Breaking Changes"),
+        ChangeKind::Breaking
+    );
+    assert_eq!(ChangeKind::from_commit_type("feat"), ChangeKind::Added);
+    assert_eq!(ChangeKind::from_commit_type("fix"), ChangeKind::Fixed);
+    assert_eq!(
+        ChangeKind::from_commit_type("perf"),
+        ChangeKind::Performance
+    );
+    assert_eq!(ChangeKind::from_commit_type("chore"), ChangeKind::Other);
+}
+
+#[test]
+fn changelog_dates() {
+    assert_eq!(parse_changelog_date("2024-02-29"), Some((2024, 2, 29)));
+    assert_eq!(parse_changelog_date("Jul 11, 2026"), Some((2026, 7, 11)));
+    assert_eq!(
+        parse_changelog_date("September 3, 2025"),
+        Some((2025, 9, 3))
+    );
+    assert_eq!(
+        parse_changelog_date("2024-01-01 [YANKED]"),
+        Some((2024, 1, 1))
+    );
+    assert_eq!(parse_changelog_date("2023-02-29"), None);
+    assert_eq!(parse_changelog_date("2024-13-01"), None);
+    assert_eq!(parse_changelog_date("Juu 11, 2026"), None);
+    assert_eq!(parse_changelog_date("11/07/2026"), None);
+}
+
+#[test]
+fn lint_well_formed_changelog() {
+    assert!(lint_changelog(KEEP_A_CHANGELOG, None).is_empty());
+    assert!(lint_changelog(SAMPLE_CHANGELOG, None).is_empty());
+}
+
+#[test]
+fn lint_reports_order_dates_and_duplicates() {
+    let content = "\
+# Changelog
+
+## v1.2.0 (Mar 1, 2024)
+
+ * Feature
+
+## v1.10.0 (2024-02-01)
+
+ * Feature
+
+## v1.1.0 (2024-04-01)
+
+ * Feature
+
+## v1.1.0 (2024-02-30)
+
+ * Feature
+
+## v1.0.0 (in development)
+
+## v01.0.0
+
+ * Feature
+";
+    let diagnostics = lint_changelog(content, None);
+    let found: Vec<(usize, Severity)> = diagnostics.iter().map(|d| (d.line, d.severity)).collect();
+    assert_eq!(
+        found,
+        vec![
+            (7, Severity::Error),
+            (11, Severity::Error),
+            (15, Severity::Error),
+            (15, Severity::Error),
+            (19, Severity::Warning),
+            (21, Severity::Error),
+        ]
+    );
+    assert!(diagnostics[0].message.contains("descending order"));
+    assert!(diagnostics[1].message.contains("dates must not decrease"));
+    assert!(diagnostics[2].message.contains("duplicate section"));
+    assert!(diagnostics[2].message.contains("line 11"));
+    assert!(diagnostics[3].message.contains("invalid date '2024-02-30'"));
+    assert!(diagnostics[5].message.contains("leading zeros"));
+    assert_eq!(
+        diagnostics[0].annotation("CHANGELOG.md"),
+        format!(
+            "::error file=CHANGELOG.md,line=7::{}",
+            diagnostics[0].message
+        )
+    );
+}
+
+#[test]
+fn lint_at_release_time() {
+    // Unreleased still lists an entry
+    let diagnostics = lint_changelog(KEEP_A_CHANGELOG, Some("1.2.0"));
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].line, 5);
+    assert!(diagnostics[0].message.contains("Unreleased"));
+
+    let content = "\
+## [Unreleased]
+
+### Added
+
+## [1.3.0] - 2024-04-01
+
+### Added
+
+## [1.2.0] - 2024-03-01
+
+- Feature
+";
+    let diagnostics = lint_changelog(content, Some("1.3.0"));
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].line, 5);
+    assert!(
+        diagnostics[0]
+            .message
+            .contains("released version 1.3.0 is empty")
+    );
+
+    let diagnostics = lint_changelog(content, Some("2.0.0"));
+    assert!(diagnostics.iter().any(|d| d.message.contains("no section")));
+    assert!(
+        lint_changelog(content, Some("1.2.0"))
+            .iter()
+            .any(|d| d.severity == Severity::Warning && d.message.contains("1.3.0 is empty"))
+    );
+}
+
+#[test]
+fn promote_keep_a_changelog_unreleased() {
+    let updated = promote_unreleased(KEEP_A_CHANGELOG, "v1.3.0", "2024-04-02").unwrap();
+    assert!(updated.contains(
+        "## [Unreleased]\n\n## [1.3.0] - 2024-04-02\n\n### Added\n\n- Upcoming feature\n\n## [1.2.0]"
+    ));
+    assert!(updated.contains(
+        "[Unreleased]: https://github.com/org/repo/compare/v1.3.0...HEAD\n\
+         [1.3.0]: https://github.com/org/repo/compare/v1.2.0...v1.3.0\n\
+         [1.2.0]:"
+    ));
+
+    let changelog = Changelog::parse(&updated);
+    assert!(changelog.unreleased().unwrap().is_empty());
+    assert!(lint_changelog(&updated, Some("1.3.0")).is_empty());
+    assert!(
+        extract_changelog_section(&updated, "1.3.0")
+            .unwrap()
+            .ends_with("[1.3.0]: https://github.com/org/repo/compare/v1.2.0...v1.3.0")
+    );
+}
+
+#[test]
+fn promote_unreleased_in_plain_changelog() {
+    let content = "# Changelog\n\nUnreleased\n----------\n\n * Fix\n\n## v1.0.0 (Jan 1, 2024)\n\n * Initial\n";
+    let updated = promote_unreleased(content, "1.0.1", "2024-02-03").unwrap();
+    assert_eq!(
+        updated,
+        "# Changelog\n\n## Unreleased\n\n## v1.0.1 (Feb 3, 2024)\n\n * Fix\n\n## v1.0.0 (Jan 1, 2024)\n\n * Initial\n"
+    );
+}
+
+#[test]
+fn release_tag_follows_previous_tag() {
+    assert_eq!(release_tag("v1.2.0", "1.3.0"), "v1.3.0");
+    assert_eq!(release_tag("1.2.0", "1.3.0"), "1.3.0");
+    assert_eq!(release_tag("tool-v1.2.0-rc.1", "1.3.0"), "tool-v1.3.0");
+    assert_eq!(release_tag("abc1234", "1.3.0"), "v1.3.0");
+}
+
+#[test]
+fn promote_keeps_tag_prefix_in_compare_links() {
+    let content = "## [Unreleased]\n\n- Fix\n\n## [1.2.0] - 2024-01-01\n\n- Initial\n\n\
+        [Unreleased]: https://github.com/org/repo/compare/tool-v1.2.0...HEAD\n";
+    let updated = promote_unreleased(content, "1.3.0", "2024-04-02").unwrap();
+    assert!(updated.contains(
+        "[Unreleased]: https://github.com/org/repo/compare/tool-v1.3.0...HEAD\n\
+         [1.3.0]: https://github.com/org/repo/compare/tool-v1.2.0...tool-v1.3.0\n"
+    ));
+}
+
+#[test]
+fn promote_unreleased_errors() {
+    assert!(promote_unreleased(SAMPLE_CHANGELOG, "3.0.0", "2024-01-01").is_err());
+    assert!(promote_unreleased(KEEP_A_CHANGELOG, "1.2.0", "2024-01-01").is_err());
+    let empty = "## [Unreleased]\n\n### Added\n\n## [1.0.0] - 2024-01-01\n\n- Initial\n";
+    let err = promote_unreleased(empty, "1.1.0", "2024-02-01").unwrap_err();
+    assert!(err.to_string().contains("empty"));
+}
@@ -0,0 +1,306 @@
+use rust_release_action::check_sbom::{
+    LicenseExpr, LicensePolicy, SbomFinding, SbomKind, check_sbom_file, find_sbom_files,
+    findings_summary, parse_license_expression, sbom_components, validate_cyclonedx, validate_sbom,
+    validate_spdx,
+};
+use rust_release_action::sbom::SbomFormat;
+use rust_release_action::sbom::{DependencyGraph, SbomArtifact, SbomPackage, sbom_document};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::fs;
+use tempfile::TempDir;
+
+fn package(name: &str, version: &str, license: Option<&str>) -> SbomPackage {
+    SbomPackage {
+        id: format!("{name}@{version}"),
+        name: name.into(),
+        version: version.into(),
+        license: license.map(String::from),
+        source: Some("registry+https://github.com/rust-lang/crates.io-index".into()),
+        description: None,
+        repository: None,
+        homepage: None,
+        authors: Vec::new(),
+        features: Vec::new(),
+    }
+}
+
+fn graph() -> DependencyGraph {
+    let root = package("app", "1.0.0", Some("MIT"));
+    let packages = vec![
+        package("serde", "1.0.200", Some("MIT OR Apache-2.0")),
+        package("gpl-thing", "0.1.0", Some("GPL-3.0")),
+        package("mystery", "0.2.0", None),
+    ];
+    let mut edges = BTreeMap::new();
+    edges.insert(
+        root.id.clone(),
+        packages.iter().map(|p| p.id.clone()).collect(),
+    );
+    DependencyGraph {
+        root,
+        packages,
+        edges,
+    }
+}
+
+#[test]
+fn generated_documents_are_valid() {
+    let graph = graph();
+    let artifact = SbomArtifact {
+        name: "app-1.0.0-x86_64-unknown-linux-gnu.tar.gz".into(),
+        sha256: "a".repeat(64),
+        sha512: "b".repeat(128),
+        target: "x86_64-unknown-linux-gnu".into(),
+    };
+    for format in [
+        SbomFormat::Spdx23,
+        SbomFormat::CycloneDx15,
+        SbomFormat::CycloneDx16,
+    ] {
+        for artifact in [None, Some(&artifact)] {
+            let doc = sbom_document(&graph, artifact, format, "2026-01-01T00:00:00Z");
+            let (_, errors) = validate_sbom(&doc).unwrap();
+            assert!(errors.is_empty(), "{format:?}: {errors:?}");
+        }
+    }
+}
+
+#[test]
+fn spdx_validation_reports_problems() {
+    let doc = json!({
+        "spdxVersion": "SPDX-2.2",
+        "dataLicense": "CC0-1.0",
+        "SPDXID": "SPDXRef-DOCUMENT",
+        "documentNamespace": "https://example.com/doc#frag",
+        "creationInfo": { "created": "yesterday", "creators": ["Tool: x"] },
+        "packages": [
+            { "SPDXID": "SPDXRef-a", "name": "a", "downloadLocation": "NOASSERTION" },
+            { "SPDXID": "bad id", "downloadLocation": "NOASSERTION",
+              "checksums": [{ "algorithm": "SHA-256", "checksumValue": "ab" }] }
+        ],
+        "relationships": [
+            { "spdxElementId": "SPDXRef-a", "relationshipType": "USES", "relatedSpdxElement": "SPDXRef-z" }
+        ]
+    });
+    let errors = validate_spdx(&doc);
+    let has = |needle: &str| errors.iter().any(|e| e.contains(needle));
+    assert!(has("$.spdxVersion"));
+    assert!(has("missing required property 'name'"));
+    assert!(has("$.documentNamespace"));
+    assert!(has("$.creationInfo.created"));
+    assert!(has("$.packages[1].SPDXID"));
+    assert!(has("$.packages[1].checksums[0].algorithm"));
+    assert!(has("'USES' is not an allowed value"));
+    assert!(has("unknown SPDX element SPDXRef-z"));
+}
+
+#[test]
+fn cyclonedx_validation_reports_problems() {
+    let doc = json!({
+        "bomFormat": "CycloneDX",
+        "specVersion": "1.4",
+        "serialNumber": "urn:uuid:not-a-uuid",
+        "version": 0,
+        "components": [
+            { "type": "library", "name": "a", "bom-ref": "a",
+              "hashes": [{ "alg": "SHA-256", "content": "xyz" }],
+              "licenses": [{ "license": {} }] },
+            { "type": "widget", "name": "b", "bom-ref": "a" }
+        ],
+        "dependencies": [{ "ref": "a", "dependsOn": ["c"] }]
+    });
+    let errors = validate_cyclonedx(&doc);
+    let has = |needle: &str| errors.iter().any(|e| e.contains(needle));
+    assert!(has("$.specVersion"));
+    assert!(has("$.serialNumber"));
+    assert!(has("$.version"));
+    assert!(has("$.components[0].hashes[0].content"));
+    assert!(has("$.components[0].licenses[0].license"));
+    assert!(has("'widget' is not an allowed value"));
+    assert!(has("duplicate bom-ref a"));
+    assert!(has("unknown bom-ref c"));
+}
+
+#[test]
+fn validate_sbom_rejects_non_sbom_json() {
+    assert!(validate_sbom(&json!({ "hello": "world" })).is_err());
+    assert_eq!(
+        validate_sbom(&json!({ "bomFormat": "CycloneDX" }))
+            .unwrap()
+            .0,
+        SbomKind::CycloneDx
+    );
+}
+
+#[test]
+fn license_expression_parsing() {
+    assert_eq!(
+        parse_license_expression("MIT").unwrap(),
+        LicenseExpr::License {
+            id: "MIT".into(),
+            exception: None
+        }
+    );
+    let expr = parse_license_expression("(MIT OR Apache-2.0) AND Unicode-3.0").unwrap();
+    assert!(matches!(expr, LicenseExpr::And(_, _)));
+    let expr = parse_license_expression("MIT/Apache-2.0").unwrap();
+    assert!(matches!(expr, LicenseExpr::Or(_, _)));
+    assert_eq!(
+        parse_license_expression("Apache-2.0 WITH LLVM-exception").unwrap(),
+        LicenseExpr::License {
+            id: "Apache-2.0".into(),
+            exception: Some("LLVM-exception".into())
+        }
+    );
+    assert!(parse_license_expression("MIT OR").is_err());
+    assert!(parse_license_expression("(MIT").is_err());
+    assert!(parse_license_expression("MIT Apache-2.0").is_err());
+}
+
+#[test]
+fn license_policy_with_allow_list() {
+    let policy = LicensePolicy {
+        allow: vec!["MIT".into(), "Apache-2.0".into()],
+        deny: Vec::new(),
+    };
+    assert_eq!(policy.check(Some("MIT OR GPL-3.0")), None);
+    assert_eq!(policy.check(Some("mit")), None);
+    assert!(policy.check(Some("MIT AND GPL-3.0")).is_some());
+    assert!(
+        policy
+            .check(Some("Apache-2.0 WITH LLVM-exception"))
+            .is_none()
+    );
+    assert_eq!(policy.check(None).as_deref(), Some("no license declared"));
+    assert!(policy.check(Some("NOASSERTION")).is_some());
+}
+
+#[test]
+fn license_policy_with_deny_list() {
+    let policy = LicensePolicy {
+        allow: Vec::new(),
+        deny: vec!["GPL-3.0".into()],
+    };
+    assert_eq!(policy.check(Some("MIT OR GPL-3.0")), None);
+    assert!(policy.check(Some("GPL-3.0")).is_some());
+    assert!(policy.check(Some("GPL-3.0+")).is_some());
+    assert!(policy.check(Some("GPL-3.0-only")).is_some());
+    assert!(policy.check(Some("gpl-3.0-or-later")).is_some());
+    assert_eq!(policy.check(Some("LGPL-3.0-only")), None);
+
+    let with_exception = LicensePolicy {
+        allow: vec!["GPL-2.0-or-later WITH Classpath-exception-2.0".into()],
+        deny: Vec::new(),
+    };
+    assert_eq!(
+        with_exception.check(Some("GPL-2.0+ WITH Classpath-exception-2.0")),
+        None
+    );
+    assert!(with_exception.check(Some("GPL-2.0-only")).is_some());
+    assert_eq!(policy.check(None), None);
+    assert_eq!(
+        LicensePolicy::default().check(Some("anything at all")),
+        None
+    );
+}
+
+#[test]
+fn components_skip_root_package_and_artifact_files() {
+    let graph = graph();
+    let artifact = SbomArtifact {
+        name: "app.tar.gz".into(),
+        sha256: "a".repeat(64),
+        sha512: "b".repeat(128),
+        target: String::new(),
+    };
+    for (format, kind) in [
+        (SbomFormat::Spdx23, SbomKind::Spdx),
+        (SbomFormat::CycloneDx16, SbomKind::CycloneDx),
+    ] {
+        let doc = sbom_document(&graph, None, format, "2026-01-01T00:00:00Z");
+        let names: Vec<String> = sbom_components(&doc, kind)
+            .into_iter()
+            .map(|c| c.name)
+            .collect();
+        assert_eq!(names, vec!["serde", "gpl-thing", "mystery"]);
+
+        let doc = sbom_document(&graph, Some(&artifact), format, "2026-01-01T00:00:00Z");
+        let components = sbom_components(&doc, kind);
+        let names: Vec<&str> = components.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, vec!["serde", "gpl-thing", "mystery"]);
+        let serde = components.iter().find(|c| c.name == "serde").unwrap();
+        assert_eq!(serde.license.as_deref(), Some("MIT OR Apache-2.0"));
+        let mystery = components.iter().find(|c| c.name == "mystery").unwrap();
+        assert_eq!(mystery.license, None);
+    }
+}
+
+#[test]
+fn check_sbom_file_reports_offending_crates() {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("app-1.0.0.cdx.json");
+    let doc = sbom_document(
+        &graph(),
+        None,
+        SbomFormat::CycloneDx16,
+        "2026-01-01T00:00:00Z",
+    );
+    fs::write(&path, doc.to_string()).unwrap();
+    fs::write(dir.path().join("notes.json"), "{}").unwrap();
+    assert_eq!(find_sbom_files(dir.path()), vec![path.clone()]);
+
+    let policy = LicensePolicy {
+        allow: vec!["MIT".into(), "Apache-2.0".into()],
+        deny: vec!["GPL-3.0".into()],
+    };
+    let findings = check_sbom_file(&path, &policy).unwrap();
+    let packages: Vec<&str> = findings.iter().map(|f| f.package.as_str()).collect();
+    assert_eq!(packages, vec!["gpl-thing@0.1.0", "mystery@0.2.0"]);
+    assert_eq!(findings[0].license, "GPL-3.0");
+
+    // The released crate itself is not a dependency
+    let policy = LicensePolicy {
+        allow: Vec::new(),
+        deny: vec!["MIT".into()],
+    };
+    let findings = check_sbom_file(&path, &policy).unwrap();
+    assert!(findings.is_empty(), "{findings:?}");
+}
+
+#[test]
+fn summary_table() {
+    assert!(findings_summary(2, &[]).contains("2 SBOM file(s) passed"));
+    let summary = findings_summary(
+        1,
+        &[SbomFinding {
+            file: "app.spdx.json".into(),
+            package: "gpl-thing@0.1.0".into(),
+            license: "GPL-3.0 | MIT".into(),
+            problem: "license not permitted by policy".into(),
+        }],
+    );
+    assert!(summary.contains("| SBOM | Crate | License | Problem |"));
+    assert!(summary.contains(
+        "| app.spdx.json | gpl-thing@0.1.0 | GPL-3.0 \\| MIT | license not permitted by policy |"
+    ));
+}
+
+#[test]
+fn check_sbom_file_reports_unrecognised_documents() {
+    let dir = TempDir::new().unwrap();
+    let policy = LicensePolicy::default();
+
+    let not_sbom = dir.path().join("notes.cdx.json");
+    fs::write(&not_sbom, r#"{"hello": "world"}"#).unwrap();
+    let findings = check_sbom_file(&not_sbom, &policy).unwrap();
+    assert_eq!(findings.len(), 1);
+    assert!(findings[0].package.is_empty());
+    assert!(findings[0].problem.contains("not an SBOM"));
+
+    let not_json = dir.path().join("broken.spdx.json");
+    fs::write(&not_json, "{").unwrap();
+    let findings = check_sbom_file(&not_json, &policy).unwrap();
+    assert_eq!(findings.len(), 1);
+    assert!(findings[0].problem.starts_with("invalid JSON"));
+}
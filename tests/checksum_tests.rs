@@ -1,9 +1,12 @@
 mod test_helpers;
 
 use rust_release_action::checksum::{
-    blake2_file, compute_checksum, detect_checksum_type, generate_checksums, parse_checksum_file,
-    sha256_bytes, sha256_file, sha512_file, verify_checksum,
+    ChecksumAlgorithm, algorithm_for_manifest, blake2_file, blake3_file, compute_checksum,
+    detect_checksum_type, generate_checksums, hash_file, hash_reader, parse_checksum_algorithms,
+    parse_checksum_file, parse_checksum_manifest, sha3_256_file, sha256_bytes, sha256_file,
+    sha512_file, verify_checksum,
 };
+use rust_release_action::config::ReleaseConfig;
 use std::env;
 use std::fs;
 use std::sync::{LazyLock, Mutex};
@@ -49,6 +52,94 @@ fn blake2_known_value() {
     );
 }
 
+#[test]
+fn sha3_256_known_value() {
+    let f = create_temp_file(b"hello world\n");
+    let hash = sha3_256_file(f.path()).unwrap();
+    assert_eq!(
+        hash,
+        "a8009a7a528d87778c356da3a55d964719e818666a04e4f960c9e2439e35f138"
+    );
+}
+
+#[test]
+fn blake3_known_value() {
+    let f = create_temp_file(b"abc");
+    let hash = blake3_file(f.path()).unwrap();
+    assert_eq!(
+        hash,
+        "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"
+    );
+}
+
+#[test]
+fn blake3_empty_file() {
+    let f = create_temp_file(b"");
+    let hash = blake3_file(f.path()).unwrap();
+    assert_eq!(
+        hash,
+        "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262"
+    );
+}
+
+#[test]
+fn hash_file_computes_all_algorithms_in_one_pass() {
+    let f = create_temp_file(b"hello world\n");
+    let hashes = hash_file(f.path(), &ChecksumAlgorithm::ALL).unwrap();
+    assert_eq!(hashes.len(), 5);
+    assert_eq!(
+        hashes[0],
+        (ChecksumAlgorithm::Sha256, sha256_file(f.path()).unwrap())
+    );
+    assert_eq!(
+        hashes[1],
+        (ChecksumAlgorithm::Sha512, sha512_file(f.path()).unwrap())
+    );
+    assert_eq!(
+        hashes[2],
+        (ChecksumAlgorithm::B2, blake2_file(f.path()).unwrap())
+    );
+    assert_eq!(hashes[3].0, ChecksumAlgorithm::Sha3_256);
+    assert_eq!(hashes[4].0, ChecksumAlgorithm::B3);
+}
+
+#[test]
+fn hash_reader_handles_inputs_larger_than_buffer() {
+    let data = vec![0xabu8; 200 * 1024 + 7];
+    let hashes = hash_reader(data.as_slice(), &[ChecksumAlgorithm::Sha256]).unwrap();
+    assert_eq!(hashes[0].1, sha256_bytes(&data));
+}
+
+#[test]
+fn parse_checksum_algorithms_accepts_aliases() {
+    let algorithms = parse_checksum_algorithms("sha256, blake3, SHA3-256, b2, b3").unwrap();
+    assert_eq!(
+        algorithms,
+        vec![
+            ChecksumAlgorithm::Sha256,
+            ChecksumAlgorithm::B3,
+            ChecksumAlgorithm::Sha3_256,
+            ChecksumAlgorithm::B2,
+        ]
+    );
+}
+
+#[test]
+fn parse_checksum_algorithms_defaults_to_sha256() {
+    assert_eq!(
+        parse_checksum_algorithms("").unwrap(),
+        vec![ChecksumAlgorithm::Sha256]
+    );
+}
+
+#[test]
+fn parse_checksum_algorithms_rejects_unknown() {
+    let err = parse_checksum_algorithms("sha256,md5")
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("md5"));
+}
+
 #[test]
 fn sha256_bytes_known_value() {
     let hash = sha256_bytes(b"hello");
@@ -73,6 +164,12 @@ fn detect_checksum_type_b2() {
     assert_eq!(detect_checksum_type("file.b2"), "b2");
 }
 
+#[test]
+fn detect_checksum_type_new_algorithms() {
+    assert_eq!(detect_checksum_type("app.tar.gz.sha3-256"), "sha3-256");
+    assert_eq!(detect_checksum_type("app.tar.gz.b3"), "b3");
+}
+
 #[test]
 fn detect_checksum_type_default() {
     assert_eq!(detect_checksum_type("file.txt"), "sha256");
@@ -163,7 +260,7 @@ fn generate_checksums_sha256_only() {
 
     // Safety: serialised by ENV_LOCK
     unsafe { env::set_var("CHECKSUM", "sha256") };
-    let checksums = generate_checksums(&file_path).unwrap();
+    let checksums = generate_checksums(&ReleaseConfig::default(), &file_path).unwrap();
     unsafe { env::remove_var("CHECKSUM") };
 
     assert!(!checksums.sha256.is_empty());
@@ -185,7 +282,7 @@ fn generate_checksums_all_types() {
     fs::write(&file_path, b"binary content").unwrap();
 
     unsafe { env::set_var("CHECKSUM", "sha256,sha512,b2") };
-    let checksums = generate_checksums(&file_path).unwrap();
+    let checksums = generate_checksums(&ReleaseConfig::default(), &file_path).unwrap();
     unsafe { env::remove_var("CHECKSUM") };
 
     assert!(!checksums.sha256.is_empty());
@@ -196,3 +293,127 @@ fn generate_checksums_all_types() {
     assert!(dir.path().join("binary.sha512").exists());
     assert!(dir.path().join("binary.b2").exists());
 }
+
+#[test]
+fn generate_checksums_new_algorithms() {
+    let _lock = ENV_LOCK.lock().unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let file_path = dir.path().join("app.zip");
+    fs::write(&file_path, b"abc").unwrap();
+
+    unsafe { env::set_var("CHECKSUM", "sha3-256,blake3") };
+    let checksums = generate_checksums(&ReleaseConfig::default(), &file_path).unwrap();
+    unsafe { env::remove_var("CHECKSUM") };
+
+    assert!(checksums.sha256.is_empty());
+    assert_eq!(
+        checksums.b3,
+        "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"
+    );
+    assert_eq!(
+        checksums.get(ChecksumAlgorithm::Sha3_256),
+        checksums.sha3_256
+    );
+    assert!(dir.path().join("app.zip.sha3-256").exists());
+    assert!(dir.path().join("app.zip.b3").exists());
+
+    verify_checksum(&file_path, &dir.path().join("app.zip.b3")).unwrap();
+}
+
+#[test]
+fn parse_manifest_gnu_lines() {
+    let content = format!(
+        "{}  app.tar.gz\n{} *app.zip\n\n# comment\n",
+        "a".repeat(64),
+        "B".repeat(64)
+    );
+    let entries = parse_checksum_manifest(&content, Some(ChecksumAlgorithm::Sha256)).unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0].file, "app.tar.gz");
+    assert_eq!(entries[1].file, "app.zip");
+    assert_eq!(entries[1].hash, "b".repeat(64));
+}
+
+#[test]
+fn parse_manifest_bsd_tags() {
+    let content = format!(
+        "SHA256 (app.tar.gz) = {}\nSHA512 (app.zip) = {}\nBLAKE2b (app.deb) = {}\n",
+        "a".repeat(64),
+        "b".repeat(128),
+        "c".repeat(128)
+    );
+    let entries = parse_checksum_manifest(&content, None).unwrap();
+    assert_eq!(entries[0].algorithm, ChecksumAlgorithm::Sha256);
+    assert_eq!(entries[0].file, "app.tar.gz");
+    assert_eq!(entries[1].algorithm, ChecksumAlgorithm::Sha512);
+    assert_eq!(entries[2].algorithm, ChecksumAlgorithm::B2);
+}
+
+#[test]
+fn parse_manifest_infers_algorithm_from_length() {
+    let content = format!("{}  a\n{}  b\n", "a".repeat(64), "b".repeat(128));
+    let entries = parse_checksum_manifest(&content, None).unwrap();
+    assert_eq!(entries[0].algorithm, ChecksumAlgorithm::Sha256);
+    assert_eq!(entries[1].algorithm, ChecksumAlgorithm::Sha512);
+    assert!(parse_checksum_manifest("abcd  a\n", None).is_err());
+}
+
+#[test]
+fn parse_manifest_unescapes_gnu_names() {
+    let content = format!("\\{}  dir\\\\name\\nwith-newline\n", "a".repeat(64));
+    let entries = parse_checksum_manifest(&content, Some(ChecksumAlgorithm::Sha256)).unwrap();
+    assert_eq!(entries[0].file, "dir\\name\nwith-newline");
+}
+
+#[test]
+fn parse_manifest_rejects_garbage() {
+    assert!(parse_checksum_manifest("not a checksum line\n", None).is_err());
+    assert!(parse_checksum_manifest("MD5 (a) = abcdef\n", None).is_err());
+}
+
+#[test]
+fn algorithm_for_manifest_names() {
+    assert_eq!(
+        algorithm_for_manifest("SHA256SUMS"),
+        Some(ChecksumAlgorithm::Sha256)
+    );
+    assert_eq!(
+        algorithm_for_manifest("sha512sums.txt"),
+        Some(ChecksumAlgorithm::Sha512)
+    );
+    assert_eq!(
+        algorithm_for_manifest("B2SUMS"),
+        Some(ChecksumAlgorithm::B2)
+    );
+    assert_eq!(
+        algorithm_for_manifest("SHA3-256SUMS"),
+        Some(ChecksumAlgorithm::Sha3_256)
+    );
+    assert_eq!(
+        algorithm_for_manifest("app.tar.gz.b3"),
+        Some(ChecksumAlgorithm::B3)
+    );
+    assert_eq!(algorithm_for_manifest("CHECKSUMS"), None);
+}
+
+#[test]
+fn verify_checksum_uses_matching_manifest_entry() {
+    let dir = tempfile::tempdir().unwrap();
+    let artifact = dir.path().join("app.deb");
+    fs::write(&artifact, b"deb").unwrap();
+    fs::write(dir.path().join("other.rpm"), b"rpm").unwrap();
+    let manifest = dir.path().join("SHA256SUMS");
+    fs::write(
+        &manifest,
+        format!(
+            "{}  other.rpm\n{}  app.deb\n",
+            sha256_bytes(b"rpm"),
+            sha256_bytes(b"deb")
+        ),
+    )
+    .unwrap();
+
+    verify_checksum(&artifact, &manifest).unwrap();
+    fs::write(&artifact, b"tampered").unwrap();
+    assert!(verify_checksum(&artifact, &manifest).is_err());
+}
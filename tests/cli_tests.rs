@@ -0,0 +1,158 @@
+use clap::{CommandFactory, Parser};
+use rust_release_action::cli::{Cli, Command, ToSettings};
+
+fn settings_for(args: &[&str]) -> Vec<(&'static str, String)> {
+    let mut argv = vec!["rust-release-action"];
+    argv.extend_from_slice(args);
+    Cli::try_parse_from(argv).unwrap().command.settings()
+}
+
+fn get<'a>(settings: &'a [(&'static str, String)], key: &str) -> Option<&'a str> {
+    settings
+        .iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, v)| v.as_str())
+}
+
+#[test]
+fn cli_definition_is_consistent() {
+    Cli::command().debug_assert();
+}
+
+#[test]
+fn commands_without_flags_produce_no_settings() {
+    assert!(settings_for(&["release-linux"]).is_empty());
+    assert!(settings_for(&["get-version"]).is_empty());
+    assert!(settings_for(&["collect-artifacts"]).is_empty());
+}
+
+#[test]
+fn release_flags_map_to_settings() {
+    let settings = settings_for(&[
+        "release-linux",
+        "--target",
+        "x86_64-unknown-linux-musl",
+        "--binary-name",
+        "tool",
+        "--features",
+        "a,b",
+        "--locked",
+        "--archive",
+        "--checksum",
+        "sha256,sha512",
+        "--include",
+        "README.md",
+    ]);
+    assert_eq!(get(&settings, "TARGET"), Some("x86_64-unknown-linux-musl"));
+    assert_eq!(get(&settings, "BINARY_NAME"), Some("tool"));
+    assert_eq!(get(&settings, "FEATURES"), Some("a,b"));
+    assert_eq!(get(&settings, "LOCKED"), Some("true"));
+    assert_eq!(get(&settings, "ARCHIVE"), Some("true"));
+    assert_eq!(get(&settings, "CHECKSUM"), Some("sha256,sha512"));
+    assert_eq!(get(&settings, "ARCHIVE_INCLUDE"), Some("README.md"));
+    assert_eq!(get(&settings, "NO_DEFAULT_FEATURES"), None);
+}
+
+#[test]
+fn package_flags_map_to_settings() {
+    let settings = settings_for(&[
+        "release-linux-deb",
+        "--pkg-description",
+        "A tool",
+        "--pkg-depends",
+        "libc6",
+        "--pkg-contents",
+        "a.toml:/etc/a.toml",
+    ]);
+    assert_eq!(get(&settings, "PKG_DESCRIPTION"), Some("A tool"));
+    assert_eq!(get(&settings, "PKG_DEPENDS"), Some("libc6"));
+    assert_eq!(get(&settings, "PKG_CONTENTS"), Some("a.toml:/etc/a.toml"));
+}
+
+#[test]
+fn renamed_settings_use_handler_keys() {
+    let settings = settings_for(&[
+        "extract-changelog",
+        "--version",
+        "1.2.3",
+        "--changelog",
+        "docs/CHANGES.md",
+        "--notes-output",
+        "notes.md",
+    ]);
+    assert_eq!(get(&settings, "VERSION"), Some("1.2.3"));
+    assert_eq!(get(&settings, "CHANGELOG_PATH"), Some("docs/CHANGES.md"));
+    assert_eq!(get(&settings, "OUTPUT_PATH"), Some("notes.md"));
+
+    let settings = settings_for(&["get-version", "--manifest", "crates/a/Cargo.toml"]);
+    assert_eq!(get(&settings, "MANIFEST_PATH"), Some("crates/a/Cargo.toml"));
+}
+
+#[test]
+fn format_release_accepts_explicit_booleans() {
+    let settings = settings_for(&["format-release", "--include-checksums", "false"]);
+    assert_eq!(get(&settings, "INCLUDE_CHECKSUMS"), Some("false"));
+    let result = Cli::try_parse_from([
+        "rust-release-action",
+        "format-release",
+        "--include-checksums",
+        "maybe",
+    ]);
+    assert!(result.is_err());
+}
+
+#[test]
+fn unknown_flags_are_rejected() {
+    let result = Cli::try_parse_from(["rust-release-action", "sign-artifact", "--target", "x"]);
+    assert!(result.is_err());
+}
+
+#[test]
+fn subcommand_is_parsed() {
+    let cli = Cli::try_parse_from(["rust-release-action", "test-deb", "--arch", "arm64"]).unwrap();
+    assert!(matches!(cli.command, Command::TestDeb(_)));
+}
+
+#[test]
+fn verify_signature_flags_map_to_settings() {
+    let settings = settings_for(&[
+        "verify-signature",
+        "--artifact",
+        "app.tar.gz",
+        "--certificate-identity-regexp",
+        "^https://github.com/org/",
+        "--certificate-oidc-issuer",
+        "https://token.actions.githubusercontent.com",
+        "--cosign-tlog-upload",
+        "false",
+    ]);
+    assert_eq!(get(&settings, "ARTIFACT_PATH"), Some("app.tar.gz"));
+    assert_eq!(
+        get(&settings, "CERTIFICATE_IDENTITY_REGEXP"),
+        Some("^https://github.com/org/")
+    );
+    assert_eq!(
+        get(&settings, "CERTIFICATE_OIDC_ISSUER"),
+        Some("https://token.actions.githubusercontent.com")
+    );
+    assert_eq!(get(&settings, "COSIGN_TLOG_UPLOAD"), Some("false"));
+    assert_eq!(get(&settings, "GPG_USE_KEYRING"), None);
+
+    let settings = settings_for(&["verify-signature", "--gpg-use-keyring"]);
+    assert_eq!(get(&settings, "GPG_USE_KEYRING"), Some("true"));
+}
+
+#[test]
+fn sign_artifacts_flags_map_to_settings() {
+    let settings = settings_for(&[
+        "sign-artifacts",
+        "--artifacts-dir",
+        "release",
+        "--sign-method",
+        "minisign",
+        "--sign-manifests",
+    ]);
+    assert_eq!(get(&settings, "ARTIFACTS_DIR"), Some("release"));
+    assert_eq!(get(&settings, "SIGN_METHOD"), Some("minisign"));
+    assert_eq!(get(&settings, "SIGN_MANIFESTS"), Some("true"));
+}
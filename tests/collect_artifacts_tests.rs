@@ -1,4 +1,15 @@
+mod test_helpers;
+
+use test_helpers::dir_with_contents;
+
+use rust_release_action::checksum::{ChecksumAlgorithm, sha256_bytes};
+use rust_release_action::collect_artifacts::{
+    collect_artifacts, detect_algorithms, find_artifacts, write_manifests,
+};
 use rust_release_action::platform::detect_platform_short;
+use rust_release_action::verify_checksums::{find_manifests, read_manifests, verify_entries};
+use std::fs;
+use tempfile::TempDir;
 
 #[test]
 fn artifact_classification_tar_gz() {
@@ -37,3 +48,112 @@ fn artifact_classification_unknown() {
     assert_eq!(detect_platform_short("checksums.txt"), "unknown");
     assert_eq!(detect_platform_short("SBOM.spdx.json"), "unknown");
 }
+
+fn artifacts_dir() -> TempDir {
+    dir_with_contents(&[
+        ("app-1.0.0-x86_64-unknown-linux-gnu.tar.gz", b"linux"),
+        ("app-1.0.0-x86_64-pc-windows-msvc.zip", b"win"),
+        ("notes.txt", b"not an artifact"),
+    ])
+}
+
+#[test]
+fn find_artifacts_lists_archives_and_packages() {
+    let dir = artifacts_dir();
+    assert_eq!(
+        find_artifacts(dir.path()),
+        vec![
+            "app-1.0.0-x86_64-pc-windows-msvc.zip",
+            "app-1.0.0-x86_64-unknown-linux-gnu.tar.gz"
+        ]
+    );
+}
+
+#[test]
+fn detect_algorithms_always_includes_sha256() {
+    let dir = artifacts_dir();
+    let artifacts = find_artifacts(dir.path());
+    assert_eq!(
+        detect_algorithms(dir.path(), &artifacts, "b2").unwrap(),
+        vec![ChecksumAlgorithm::Sha256, ChecksumAlgorithm::B2]
+    );
+}
+
+#[test]
+fn detect_algorithms_from_sidecars() {
+    let dir = artifacts_dir();
+    let artifacts = find_artifacts(dir.path());
+    let sidecar = dir
+        .path()
+        .join("app-1.0.0-x86_64-unknown-linux-gnu.tar.gz.sha512");
+    fs::write(&sidecar, "ignored").unwrap();
+    assert_eq!(
+        detect_algorithms(dir.path(), &artifacts, "sha256").unwrap(),
+        vec![ChecksumAlgorithm::Sha256, ChecksumAlgorithm::Sha512]
+    );
+}
+
+#[test]
+fn collection_carries_all_digests_and_manifests_are_written() {
+    let dir = artifacts_dir();
+    let artifacts = find_artifacts(dir.path());
+    let algorithms = [ChecksumAlgorithm::Sha256, ChecksumAlgorithm::Sha512];
+    let collection = collect_artifacts(
+        dir.path(),
+        &artifacts,
+        &algorithms,
+        "https://example.com/v1",
+    )
+    .unwrap();
+    assert_eq!(collection.len(), 2);
+    assert_eq!(collection[1].checksums.sha256, sha256_bytes(b"linux"));
+    assert_eq!(collection[1].platform, "linux-x64");
+    assert_eq!(
+        collection[1].url,
+        "https://example.com/v1/app-1.0.0-x86_64-unknown-linux-gnu.tar.gz"
+    );
+
+    let json: serde_json::Value = serde_json::to_value(&collection).unwrap();
+    assert!(json[0]["sha512"].as_str().unwrap().len() == 128);
+    assert!(json[0].get("b2").is_none());
+
+    let paths = write_manifests(dir.path(), &collection, &algorithms).unwrap();
+    assert_eq!(paths.len(), 2);
+    assert!(paths[0].ends_with("SHA256SUMS"));
+    assert!(paths[1].ends_with("SHA512SUMS"));
+    let sha512sums = fs::read_to_string(dir.path().join("SHA512SUMS")).unwrap();
+    assert!(sha512sums.contains(&format!(
+        "{}  app-1.0.0-x86_64-pc-windows-msvc.zip",
+        collection[0].checksums.sha512
+    )));
+}
+
+#[test]
+fn written_manifests_verify() {
+    let dir = artifacts_dir();
+    let artifacts = find_artifacts(dir.path());
+    let algorithms = [ChecksumAlgorithm::Sha256, ChecksumAlgorithm::B2];
+    let collection = collect_artifacts(dir.path(), &artifacts, &algorithms, "").unwrap();
+    write_manifests(dir.path(), &collection, &algorithms).unwrap();
+
+    let manifests = find_manifests(dir.path());
+    assert_eq!(manifests.len(), 2);
+    let entries = read_manifests(&manifests).unwrap();
+    let report = verify_entries(&entries, dir.path(), &manifests).unwrap();
+    assert!(report.is_ok());
+    assert_eq!(report.extra, vec!["notes.txt"]);
+}
+
+#[test]
+fn mismatched_sidecar_is_rejected() {
+    let dir = artifacts_dir();
+    let artifacts = find_artifacts(dir.path());
+    fs::write(
+        dir.path()
+            .join("app-1.0.0-x86_64-pc-windows-msvc.zip.sha256"),
+        format!("{}  app-1.0.0-x86_64-pc-windows-msvc.zip\n", "0".repeat(64)),
+    )
+    .unwrap();
+    let result = collect_artifacts(dir.path(), &artifacts, &[ChecksumAlgorithm::Sha256], "");
+    assert!(result.is_err());
+}
@@ -0,0 +1,367 @@
+use rust_release_action::build::{BuildTool, build_tool, configured_build_tool};
+use rust_release_action::config::ReleaseConfig;
+use std::env;
+use std::fs;
+use std::sync::{LazyLock, Mutex};
+use tempfile::TempDir;
+
+static ENV_LOCK: LazyLock<Mutex<()>> = LazyLock::new(|| Mutex::new(()));
+
+const SAMPLE_CONFIG: &str = r#"
+checksum = ["sha256", "sha512"]
+
+[build]
+target = "x86_64-unknown-linux-musl"
+features = ["mimalloc", "tls"]
+locked = true
+
+[archive]
+enabled = true
+include = ["completions/*", "man/*.1"]
+
+[pkg]
+description = "A tool"
+depends = ["libc6", "ca-certificates"]
+contents = ["config.toml:/etc/tool/config.toml"]
+
+[winget]
+publisher = "Example Corp"
+"#;
+
+#[test]
+fn parses_release_toml() {
+    let config = ReleaseConfig::from_toml_str(SAMPLE_CONFIG).unwrap();
+    assert_eq!(
+        config.build.target.as_deref(),
+        Some("x86_64-unknown-linux-musl")
+    );
+    assert_eq!(config.build.locked, Some(true));
+    assert_eq!(config.archive.enabled, Some(true));
+    assert_eq!(config.pkg.description.as_deref(), Some("A tool"));
+    config.validate().unwrap();
+}
+
+#[test]
+fn lookup_maps_env_keys() {
+    let config = ReleaseConfig::from_toml_str(SAMPLE_CONFIG).unwrap();
+    assert_eq!(
+        config.lookup("TARGET").as_deref(),
+        Some("x86_64-unknown-linux-musl")
+    );
+    assert_eq!(config.lookup("FEATURES").as_deref(), Some("mimalloc,tls"));
+    assert_eq!(config.lookup("LOCKED").as_deref(), Some("true"));
+    assert_eq!(config.lookup("ARCHIVE").as_deref(), Some("true"));
+    assert_eq!(
+        config.lookup("ARCHIVE_INCLUDE").as_deref(),
+        Some("completions/*,man/*.1")
+    );
+    assert_eq!(config.lookup("CHECKSUM").as_deref(), Some("sha256,sha512"));
+    assert_eq!(
+        config.lookup("PKG_DEPENDS").as_deref(),
+        Some("libc6,ca-certificates")
+    );
+    assert_eq!(
+        config.lookup("WINGET_PUBLISHER").as_deref(),
+        Some("Example Corp")
+    );
+}
+
+#[test]
+fn lookup_unset_values_return_none() {
+    let config = ReleaseConfig::from_toml_str(SAMPLE_CONFIG).unwrap();
+    assert_eq!(config.lookup("PROFILE"), None);
+    assert_eq!(config.lookup("NO_DEFAULT_FEATURES"), None);
+    assert_eq!(config.lookup("UNKNOWN_KEY"), None);
+}
+
+#[test]
+fn empty_config_is_valid() {
+    let config = ReleaseConfig::from_toml_str("").unwrap();
+    config.validate().unwrap();
+    assert_eq!(config.lookup("TARGET"), None);
+}
+
+#[test]
+fn unknown_keys_are_rejected() {
+    let result = ReleaseConfig::from_toml_str("[build]\ntargets = \"x\"\n");
+    assert!(result.is_err());
+}
+
+#[test]
+fn unknown_tables_are_rejected() {
+    let result = ReleaseConfig::from_toml_str("[deploy]\nenabled = true\n");
+    assert!(result.is_err());
+}
+
+#[test]
+fn wrong_value_type_is_rejected() {
+    let result = ReleaseConfig::from_toml_str("[build]\nlocked = \"yes\"\n");
+    assert!(result.is_err());
+}
+
+#[test]
+fn validate_rejects_unknown_checksum_algorithm() {
+    let config = ReleaseConfig::from_toml_str("checksum = [\"md5\"]\n").unwrap();
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("md5"));
+}
+
+#[test]
+fn validate_rejects_unknown_sbom_format() {
+    let config = ReleaseConfig::from_toml_str("[sbom]\nformat = [\"swid\"]\n").unwrap();
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn validate_rejects_unknown_sign_method() {
+    let config = ReleaseConfig::from_toml_str("[sign]\nmethod = \"pkcs7\"\n").unwrap();
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("pkcs7"));
+}
+
+#[test]
+fn sign_table_does_not_accept_keys() {
+    let result = ReleaseConfig::from_toml_str("[sign]\ngpg-private-key = \"...\"\n");
+    assert!(result.is_err());
+}
+
+#[test]
+fn validate_rejects_malformed_contents() {
+    let config = ReleaseConfig::from_toml_str("[pkg]\ncontents = [\"just-a-path\"]\n").unwrap();
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("just-a-path"));
+}
+
+#[test]
+fn validate_accepts_windows_contents_paths() {
+    let config = ReleaseConfig::from_toml_str(
+        "[pkg]\ncontents = ['C:\\build\\tool.toml:/etc/tool/tool.toml']\n",
+    )
+    .unwrap();
+    config.validate().unwrap();
+
+    let config =
+        ReleaseConfig::from_toml_str("[pkg]\ncontents = ['C:\\build\\tool.toml']\n").unwrap();
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn validate_rejects_unknown_binary_packaging() {
+    let config = ReleaseConfig::from_toml_str(
+        "[build]\nbinaries = [\"all\"]\nbinary-packaging = \"merged\"\n",
+    )
+    .unwrap();
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("merged"));
+
+    let config = ReleaseConfig::from_toml_str(
+        "[build]\nbinaries = [\"tool\", \"toold\"]\nbinary-packaging = \"separate\"\n",
+    )
+    .unwrap();
+    config.validate().unwrap();
+    assert_eq!(config.lookup("BINARIES").as_deref(), Some("tool,toold"));
+}
+
+#[test]
+fn validate_rejects_unknown_build_tool() {
+    let config = ReleaseConfig::from_toml_str("[build]\ntool = \"docker\"\n").unwrap();
+    assert!(config.validate().is_err());
+
+    let config = ReleaseConfig::from_toml_str("[build]\ntool = \"cross\"\n").unwrap();
+    config.validate().unwrap();
+    assert_eq!(config.lookup("BUILD_TOOL").as_deref(), Some("cross"));
+}
+
+#[test]
+fn validate_rejects_unknown_plan_format() {
+    let config =
+        ReleaseConfig::from_toml_str("[plan]\nformats = [\"archive\", \"snap\"]\n").unwrap();
+    let err = config.validate().unwrap_err().to_string();
+    assert!(err.contains("snap"));
+
+    let config = ReleaseConfig::from_toml_str(
+        "[plan]\ntargets = [\"x86_64-unknown-linux-gnu\", \"aarch64-apple-darwin\"]\nformats = [\"deb\"]\n",
+    )
+    .unwrap();
+    config.validate().unwrap();
+    assert_eq!(
+        config.lookup("PLAN_TARGETS").as_deref(),
+        Some("x86_64-unknown-linux-gnu,aarch64-apple-darwin")
+    );
+    assert_eq!(config.lookup("PLAN_FORMATS").as_deref(), Some("deb"));
+}
+
+#[test]
+fn validate_rejects_empty_profile() {
+    let config = ReleaseConfig::from_toml_str("[build]\nprofile = \"\"\n").unwrap();
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn reads_package_metadata_release() {
+    let manifest = r#"
+[package]
+name = "tool"
+version = "1.0.0"
+
+[package.metadata.release]
+checksum = ["b2"]
+
+[package.metadata.release.pkg]
+license = "MIT"
+"#;
+    let config = ReleaseConfig::from_cargo_manifest_str(manifest)
+        .unwrap()
+        .unwrap();
+    assert_eq!(config.lookup("CHECKSUM").as_deref(), Some("b2"));
+    assert_eq!(config.lookup("PKG_LICENSE").as_deref(), Some("MIT"));
+}
+
+#[test]
+fn manifest_without_metadata_returns_none() {
+    let manifest = "[package]\nname = \"tool\"\nversion = \"1.0.0\"\n";
+    assert!(
+        ReleaseConfig::from_cargo_manifest_str(manifest)
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn load_prefers_release_toml_over_metadata() {
+    let dir = TempDir::new().unwrap();
+    let manifest = dir.path().join("Cargo.toml");
+    fs::write(
+        &manifest,
+        "[package]\nname = \"tool\"\n\n[package.metadata.release.build]\ntarget = \"from-metadata\"\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("release.toml"),
+        "[build]\ntarget = \"from-file\"\n",
+    )
+    .unwrap();
+
+    let config = ReleaseConfig::load(manifest.to_str().unwrap(), "")
+        .unwrap()
+        .unwrap();
+    assert_eq!(config.lookup("TARGET").as_deref(), Some("from-file"));
+}
+
+#[test]
+fn load_falls_back_to_metadata() {
+    let dir = TempDir::new().unwrap();
+    let manifest = dir.path().join("Cargo.toml");
+    fs::write(
+        &manifest,
+        "[package]\nname = \"tool\"\n\n[package.metadata.release.build]\ntarget = \"from-metadata\"\n",
+    )
+    .unwrap();
+
+    let config = ReleaseConfig::load(manifest.to_str().unwrap(), "")
+        .unwrap()
+        .unwrap();
+    assert_eq!(config.lookup("TARGET").as_deref(), Some("from-metadata"));
+}
+
+#[test]
+fn load_explicit_path_must_exist() {
+    let dir = TempDir::new().unwrap();
+    let manifest = dir.path().join("Cargo.toml");
+    let missing = dir.path().join("missing.toml");
+    let result = ReleaseConfig::load(manifest.to_str().unwrap(), missing.to_str().unwrap());
+    assert!(result.is_err());
+}
+
+#[test]
+fn load_without_any_config_returns_none() {
+    let dir = TempDir::new().unwrap();
+    let manifest = dir.path().join("Cargo.toml");
+    fs::write(&manifest, "[package]\nname = \"tool\"\n").unwrap();
+    assert!(
+        ReleaseConfig::load(manifest.to_str().unwrap(), "")
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn env_overrides_config() {
+    let _lock = ENV_LOCK.lock().unwrap();
+    let config = ReleaseConfig::from_toml_str(SAMPLE_CONFIG).unwrap();
+
+    // Safety: serialised by ENV_LOCK
+    unsafe { env::remove_var("PKG_DESCRIPTION") };
+    assert_eq!(config.get("PKG_DESCRIPTION", "default"), "A tool");
+
+    unsafe { env::set_var("PKG_DESCRIPTION", "From env") };
+    assert_eq!(config.get("PKG_DESCRIPTION", "default"), "From env");
+    unsafe { env::remove_var("PKG_DESCRIPTION") };
+
+    let empty = ReleaseConfig::default();
+    assert_eq!(empty.get("PKG_DESCRIPTION", "default"), "default");
+}
+
+#[test]
+fn settings_override_env_and_config() {
+    let _lock = ENV_LOCK.lock().unwrap();
+    let mut config = ReleaseConfig::from_toml_str(SAMPLE_CONFIG).unwrap();
+    config.set("PKG_DESCRIPTION", "From input");
+
+    // Safety: serialised by ENV_LOCK
+    unsafe { env::set_var("PKG_DESCRIPTION", "From env") };
+    assert_eq!(config.get("PKG_DESCRIPTION", "default"), "From input");
+    unsafe { env::remove_var("PKG_DESCRIPTION") };
+
+    config.set("ARTIFACTS_DIR", "dist");
+    assert_eq!(config.get("ARTIFACTS_DIR", "release"), "dist");
+}
+
+#[test]
+fn from_settings_locates_the_config_file() {
+    let _lock = ENV_LOCK.lock().unwrap();
+    let dir = TempDir::new().unwrap();
+    let release_toml = dir.path().join("release.toml");
+    fs::write(&release_toml, "[build]\ntarget = \"from-file\"\n").unwrap();
+
+    let config = ReleaseConfig::from_settings(&[
+        ("RELEASE_CONFIG", "missing.toml".to_string()),
+        ("RELEASE_CONFIG", release_toml.display().to_string()),
+        ("BINARY_NAME", "tool".to_string()),
+    ])
+    .unwrap();
+    assert_eq!(config.get("TARGET", ""), "from-file");
+    assert_eq!(config.get("BINARY_NAME", ""), "tool");
+    assert!(env::var("BINARY_NAME").is_err());
+}
+
+#[test]
+fn build_tool_from_config_and_env() {
+    let _lock = ENV_LOCK.lock().unwrap();
+    // Safety: serialised by ENV_LOCK
+    unsafe {
+        env::remove_var("BUILD_TOOL");
+        env::remove_var("USE_ZIGBUILD");
+    }
+    let config = ReleaseConfig::from_toml_str("[build]\nuse-zigbuild = true\n").unwrap();
+    assert_eq!(
+        configured_build_tool(&config).unwrap(),
+        Some(BuildTool::Zigbuild)
+    );
+
+    unsafe { env::set_var("BUILD_TOOL", "cross") };
+    let err = configured_build_tool(&config).unwrap_err();
+    assert!(err.to_string().contains("use-zigbuild conflicts"));
+
+    let empty = ReleaseConfig::default();
+    assert_eq!(
+        configured_build_tool(&empty).unwrap(),
+        Some(BuildTool::Cross)
+    );
+    unsafe { env::remove_var("BUILD_TOOL") };
+    assert_eq!(configured_build_tool(&empty).unwrap(), None);
+    assert_eq!(
+        build_tool(&empty, "s390x-unknown-linux-gnu").unwrap(),
+        BuildTool::Cross
+    );
+}
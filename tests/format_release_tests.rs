@@ -1,4 +1,4 @@
-use rust_release_action::format_release::format_size;
+use rust_release_action::format_release::{format_signatures_section, format_size};
 
 #[test]
 fn format_size_bytes() {
@@ -27,3 +27,21 @@ fn format_size_boundary_values() {
     assert_eq!(format_size(1024 * 1024 - 1), "1024.0 KB");
     assert_eq!(format_size(100 * 1024 * 1024), "100.0 MB");
 }
+
+#[test]
+fn signatures_section_lists_each_method() {
+    let dir = tempfile::TempDir::new().unwrap();
+    std::fs::write(dir.path().join("app.tar.gz.asc"), "sig").unwrap();
+    std::fs::write(dir.path().join("app.tar.gz.minisig"), "sig").unwrap();
+    let section = format_signatures_section(dir.path());
+    assert!(section.contains("gpg --verify <artifact>.asc <artifact>"));
+    assert!(section.contains("minisign -Vm <artifact>"));
+    assert!(!section.contains("cosign"));
+}
+
+#[test]
+fn signatures_section_empty_without_signatures() {
+    let dir = tempfile::TempDir::new().unwrap();
+    std::fs::write(dir.path().join("app.tar.gz"), "data").unwrap();
+    assert_eq!(format_signatures_section(dir.path()), "");
+}
@@ -0,0 +1,197 @@
+use rust_release_action::changelog::{ChangeKind, Changelog, ChangelogStyle, long_date};
+use rust_release_action::generate_changelog::{
+    Commit, ConventionalCommit, group_commits, insert_section, link_pull_requests, render_section,
+};
+
+fn commit(hash: &str, subject: &str, body: &str) -> Commit {
+    Commit {
+        hash: hash.into(),
+        subject: subject.into(),
+        body: body.into(),
+    }
+}
+
+const KEEP_A_CHANGELOG: &str = "\
+# Changelog
+
+## [Unreleased]
+
+## [1.2.0] - 2024-03-01
+
+### Added
+
+- New `--json` flag
+
+[Unreleased]: https://github.com/org/repo/compare/v1.2.0...HEAD
+[1.2.0]: https://github.com/org/repo/releases/tag/v1.2.0
+";
+
+#[test]
+fn parse_conventional_commits() {
+    let cc = ConventionalCommit::parse("feat(cli): add --json flag", "").unwrap();
+    assert_eq!(cc.kind, "feat");
+    assert_eq!(cc.scope.as_deref(), Some("cli"));
+    assert!(!cc.breaking);
+    assert_eq!(cc.description, "add --json flag");
+
+    assert!(
+        ConventionalCommit::parse("refactor!: drop the v1 API", "")
+            .unwrap()
+            .breaking
+    );
+    let cc =
+        ConventionalCommit::parse("Fix: crash", "BREAKING CHANGE: input format changed").unwrap();
+    assert_eq!(cc.kind, "fix");
+    assert!(cc.breaking);
+
+    assert!(ConventionalCommit::parse("Update README", "").is_none());
+    assert!(ConventionalCommit::parse("feat:missing space", "").is_none());
+}
+
+#[test]
+fn commits_are_grouped_by_kind() {
+    let commits = vec![
+        commit("a1", "feat: add --json flag (#12)", ""),
+        commit("b2", "fix(parser): handle empty input", ""),
+        commit("c3", "chore: bump dependencies", ""),
+        commit("d4", "ci: cache cargo registry", ""),
+        commit("e5", "feat!: remove the legacy config format", ""),
+        commit("f6", "Merge some things by hand", ""),
+        commit("g7", "perf: stream checksums", ""),
+    ];
+    let groups = group_commits(&commits);
+    let kinds: Vec<ChangeKind> = groups.keys().copied().collect();
+    assert_eq!(
+        kinds,
+        vec![
+            ChangeKind::Breaking,
+            ChangeKind::Added,
+            ChangeKind::Fixed,
+            ChangeKind::Performance,
+            ChangeKind::Other,
+        ]
+    );
+    assert_eq!(
+        groups[&ChangeKind::Fixed][0].scope.as_deref(),
+        Some("parser")
+    );
+    assert_eq!(
+        groups[&ChangeKind::Other][0].description,
+        "Merge some things by hand"
+    );
+}
+
+#[test]
+fn pull_request_references_are_linked() {
+    let url = "https://github.com/org/repo";
+    assert_eq!(
+        link_pull_requests("add a flag (#12)", url),
+        "add a flag ([#12](https://github.com/org/repo/pull/12))"
+    );
+    assert_eq!(
+        link_pull_requests("closes #7 and #8", url),
+        "closes [#7](https://github.com/org/repo/pull/7) and [#8](https://github.com/org/repo/pull/8)"
+    );
+    assert_eq!(link_pull_requests("issue#7", url), "issue#7");
+    assert_eq!(
+        link_pull_requests("add a flag (#12)", ""),
+        "add a flag (#12)"
+    );
+}
+
+#[test]
+fn style_follows_existing_changelog() {
+    let style = ChangelogStyle::detect(KEEP_A_CHANGELOG);
+    assert!(style.keep_a_changelog);
+    assert_eq!(style.bullet, "- ");
+    assert_eq!(
+        style.heading("1.3.0", "2024-04-02"),
+        "## [1.3.0] - 2024-04-02"
+    );
+
+    let style =
+        ChangelogStyle::detect("# Changelog\n\n## v1.0.0 (Jan 1, 2024)\n\n * Initial release\n");
+    assert!(!style.keep_a_changelog);
+    assert_eq!(style.bullet, " * ");
+    assert_eq!(
+        style.heading("1.3.0", "2024-04-02"),
+        "## v1.3.0 (Apr 2, 2024)"
+    );
+
+    assert_eq!(ChangelogStyle::detect(""), ChangelogStyle::default());
+    assert_eq!(long_date("2026-12-31"), "Dec 31, 2026");
+    assert_eq!(long_date("not a date"), "not a date");
+}
+
+#[test]
+fn render_section_with_categories() {
+    let commits = vec![
+        commit("a1", "feat(cli): add --json flag (#12)", ""),
+        commit("b2", "fix: handle empty input", ""),
+    ];
+    let style = ChangelogStyle {
+        keep_a_changelog: true,
+        bullet: "- ".into(),
+    };
+    let section = render_section(
+        "1.3.0",
+        "2024-04-02",
+        &group_commits(&commits),
+        &style,
+        "https://github.com/org/repo",
+    );
+    assert_eq!(
+        section,
+        "\
+## [1.3.0] - 2024-04-02
+
+### Added
+
+- **cli:** add --json flag ([#12](https://github.com/org/repo/pull/12))
+
+### Fixed
+
+- handle empty input"
+    );
+
+    let empty = render_section("1.3.0", "2024-04-02", &group_commits(&[]), &style, "");
+    assert!(empty.ends_with("- No notable changes."));
+}
+
+#[test]
+fn insert_section_below_unreleased() {
+    let section = "## [1.3.0] - 2024-04-02\n\n### Fixed\n\n- handle empty input";
+    let link = "[1.3.0]: https://github.com/org/repo/compare/v1.2.0...v1.3.0";
+    let updated = insert_section(KEEP_A_CHANGELOG, "1.3.0", section, Some(link)).unwrap();
+
+    let changelog = Changelog::parse(&updated);
+    let versions: Vec<&str> = changelog
+        .sections
+        .iter()
+        .map(|s| s.version.as_str())
+        .collect();
+    assert_eq!(versions, vec!["Unreleased", "1.3.0", "1.2.0"]);
+    assert!(updated.contains("## [Unreleased]\n\n## [1.3.0] - 2024-04-02\n"));
+    assert!(updated.contains("- handle empty input\n\n## [1.2.0]"));
+    assert!(updated.contains(&format!(
+        "...HEAD\n{link}\n[1.2.0]: https://github.com/org/repo/releases/tag/v1.2.0"
+    )));
+
+    assert!(insert_section(&updated, "1.3.0", section, None).is_err());
+}
+
+#[test]
+fn insert_section_into_new_changelog() {
+    let updated = insert_section(
+        "# Changelog\n",
+        "0.1.0",
+        "## v0.1.0 (Jan 1, 2024)\n\n * Initial release",
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        updated,
+        "# Changelog\n\n## v0.1.0 (Jan 1, 2024)\n\n * Initial release\n"
+    );
+    assert_eq!(Changelog::parse(&updated).sections[0].version, "0.1.0");
+}
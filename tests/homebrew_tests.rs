@@ -39,6 +39,8 @@ fn formula_all_platforms() {
         macos_arm64_sha256: "abc123".into(),
         macos_x64_url: "https://example.com/macos-x64.tar.gz".into(),
         macos_x64_sha256: "def456".into(),
+        macos_universal_url: String::new(),
+        macos_universal_sha256: String::new(),
         linux_arm64_url: "https://example.com/linux-arm64.tar.gz".into(),
         linux_arm64_sha256: "ghi789".into(),
         linux_x64_url: "https://example.com/linux-x64.tar.gz".into(),
@@ -77,6 +79,8 @@ fn formula_dual_license() {
         macos_arm64_sha256: String::new(),
         macos_x64_url: String::new(),
         macos_x64_sha256: String::new(),
+        macos_universal_url: String::new(),
+        macos_universal_sha256: String::new(),
         linux_arm64_url: String::new(),
         linux_arm64_sha256: String::new(),
         linux_x64_url: String::new(),
@@ -101,6 +105,8 @@ fn formula_with_copyright_header() {
         macos_arm64_sha256: String::new(),
         macos_x64_url: String::new(),
         macos_x64_sha256: String::new(),
+        macos_universal_url: String::new(),
+        macos_universal_sha256: String::new(),
         linux_arm64_url: String::new(),
         linux_arm64_sha256: String::new(),
         linux_x64_url: String::new(),
@@ -127,6 +133,8 @@ fn formula_macos_arm64_only() {
         macos_arm64_sha256: "abc123".into(),
         macos_x64_url: String::new(),
         macos_x64_sha256: String::new(),
+        macos_universal_url: String::new(),
+        macos_universal_sha256: String::new(),
         linux_arm64_url: String::new(),
         linux_arm64_sha256: String::new(),
         linux_x64_url: String::new(),
@@ -143,6 +151,37 @@ fn formula_macos_arm64_only() {
     assert!(!formula.contains("license"));
 }
 
+#[test]
+fn formula_macos_universal() {
+    let config = FormulaConfig {
+        class: "Tool".into(),
+        binary_name: "tool".into(),
+        version: "0.1.0".into(),
+        description: "desc".into(),
+        homepage: String::new(),
+        license: String::new(),
+        copyright: String::new(),
+        macos_arm64_url: "https://example.com/arm64.tar.gz".into(),
+        macos_arm64_sha256: "abc123".into(),
+        macos_x64_url: String::new(),
+        macos_x64_sha256: String::new(),
+        macos_universal_url: "https://example.com/universal.tar.gz".into(),
+        macos_universal_sha256: "fff000".into(),
+        linux_arm64_url: String::new(),
+        linux_arm64_sha256: String::new(),
+        linux_x64_url: String::new(),
+        linux_x64_sha256: String::new(),
+    };
+
+    let formula = generate_formula(&config);
+
+    assert!(formula.contains(
+        "  on_macos do\n    url \"https://example.com/universal.tar.gz\"\n    sha256 \"fff000\"\n  end\n"
+    ));
+    assert!(!formula.contains("on_arm do"));
+    assert!(!formula.contains("arm64.tar.gz"));
+}
+
 #[test]
 fn formula_linux_x64_only() {
     let config = FormulaConfig {
@@ -157,6 +196,8 @@ fn formula_linux_x64_only() {
         macos_arm64_sha256: String::new(),
         macos_x64_url: String::new(),
         macos_x64_sha256: String::new(),
+        macos_universal_url: String::new(),
+        macos_universal_sha256: String::new(),
         linux_arm64_url: String::new(),
         linux_arm64_sha256: String::new(),
         linux_x64_url: "https://example.com/linux-x64.tar.gz".into(),
@@ -185,6 +226,8 @@ fn formula_no_platforms() {
         macos_arm64_sha256: String::new(),
         macos_x64_url: String::new(),
         macos_x64_sha256: String::new(),
+        macos_universal_url: String::new(),
+        macos_universal_sha256: String::new(),
         linux_arm64_url: String::new(),
         linux_arm64_sha256: String::new(),
         linux_x64_url: String::new(),
@@ -197,3 +240,29 @@ fn formula_no_platforms() {
     assert!(!formula.contains("on_linux"));
     assert!(formula.contains("bin.install \"tool\""));
 }
+
+#[test]
+fn formula_escapes_ruby_strings() {
+    let config = FormulaConfig {
+        class: "Tool".into(),
+        binary_name: "tool".into(),
+        version: "1.0.0".into(),
+        description: "Says \"hi\" to #{user}\nand \\more".into(),
+        homepage: String::new(),
+        license: "MIT".into(),
+        copyright: String::new(),
+        macos_arm64_url: String::new(),
+        macos_arm64_sha256: String::new(),
+        macos_x64_url: String::new(),
+        macos_x64_sha256: String::new(),
+        macos_universal_url: String::new(),
+        macos_universal_sha256: String::new(),
+        linux_arm64_url: String::new(),
+        linux_arm64_sha256: String::new(),
+        linux_x64_url: String::new(),
+        linux_x64_sha256: String::new(),
+    };
+
+    let formula = generate_formula(&config);
+    assert!(formula.contains("desc \"Says \\\"hi\\\" to \\#{user} and \\\\more\"\n"));
+}
@@ -0,0 +1,168 @@
+use rust_release_action::config::ReleaseConfig;
+use rust_release_action::macho::{
+    CPU_TYPE_ARM64, CPU_TYPE_X86_64, FAT_MAGIC, FatArch, cpu_type_name, create_universal,
+    parse_universal, thin_arch,
+};
+use rust_release_action::release::run_release_macos_universal;
+
+const MH_MAGIC_64: u32 = 0xfeed_facf;
+const MH_EXECUTE: u32 = 0x2;
+
+/// A minimal thin 64-bit Mach-O: a little-endian `mach_header_64` followed by `body`.
+fn thin_binary(cputype: u32, cpusubtype: u32, body: &[u8]) -> Vec<u8> {
+    let mut data = Vec::new();
+    for field in [MH_MAGIC_64, cputype, cpusubtype, MH_EXECUTE, 0, 0, 0, 0] {
+        data.extend_from_slice(&field.to_le_bytes());
+    }
+    data.extend_from_slice(body);
+    data
+}
+
+fn arm64_binary() -> Vec<u8> {
+    thin_binary(CPU_TYPE_ARM64, 0, b"arm64 code")
+}
+
+fn x86_64_binary() -> Vec<u8> {
+    thin_binary(CPU_TYPE_X86_64, 3, b"x86_64 code and more")
+}
+
+#[test]
+fn thin_arch_reads_little_endian_header() {
+    assert_eq!(thin_arch(&arm64_binary()).unwrap(), (CPU_TYPE_ARM64, 0));
+    assert_eq!(thin_arch(&x86_64_binary()).unwrap(), (CPU_TYPE_X86_64, 3));
+}
+
+#[test]
+fn thin_arch_reads_big_endian_header() {
+    let mut data = Vec::new();
+    for field in [0xfeed_face, 0x0000_0012, 0, MH_EXECUTE] {
+        data.extend_from_slice(&u32::to_be_bytes(field));
+    }
+    assert_eq!(thin_arch(&data).unwrap(), (0x0000_0012, 0));
+}
+
+#[test]
+fn thin_arch_rejects_other_formats() {
+    let err = thin_arch(b"\x7fELF\x02\x01\x01\x00").unwrap_err();
+    assert!(err.to_string().contains("not a Mach-O binary"));
+    assert!(thin_arch(b"").is_err());
+    assert!(thin_arch(&MH_MAGIC_64.to_le_bytes()).is_err());
+}
+
+#[test]
+fn universal_header_and_slices() {
+    let arm64 = arm64_binary();
+    let x86_64 = x86_64_binary();
+    let universal = create_universal(&[&arm64, &x86_64]).unwrap();
+
+    assert_eq!(universal[..4], FAT_MAGIC.to_be_bytes());
+    let archs = parse_universal(&universal).unwrap();
+    // Ordered by alignment: x86_64 (4 KiB) before arm64 (16 KiB)
+    assert_eq!(
+        archs,
+        vec![
+            FatArch {
+                cputype: CPU_TYPE_X86_64,
+                cpusubtype: 3,
+                offset: 0x1000,
+                size: x86_64.len() as u32,
+                align: 12,
+            },
+            FatArch {
+                cputype: CPU_TYPE_ARM64,
+                cpusubtype: 0,
+                offset: 0x4000,
+                size: arm64.len() as u32,
+                align: 14,
+            },
+        ]
+    );
+    assert_eq!(universal.len(), 0x4000 + arm64.len());
+
+    for (arch, thin) in archs.iter().zip([&x86_64, &arm64]) {
+        let start = arch.offset as usize;
+        assert_eq!(&universal[start..start + arch.size as usize], &thin[..]);
+    }
+}
+
+#[test]
+fn universal_is_independent_of_input_order() {
+    let arm64 = arm64_binary();
+    let x86_64 = x86_64_binary();
+    assert_eq!(
+        create_universal(&[&arm64, &x86_64]).unwrap(),
+        create_universal(&[&x86_64, &arm64]).unwrap()
+    );
+}
+
+#[test]
+fn universal_pads_large_slices_to_alignment() {
+    let x86_64 = thin_binary(CPU_TYPE_X86_64, 3, &vec![0x90; 0x5000]);
+    let arm64 = arm64_binary();
+    let universal = create_universal(&[&arm64, &x86_64]).unwrap();
+    let archs = parse_universal(&universal).unwrap();
+
+    assert_eq!(archs[0].offset, 0x1000);
+    // The x86_64 slice ends past 0x6000, so arm64 starts at the next 16 KiB boundary
+    assert_eq!(archs[1].offset, 0x8000);
+    assert!(
+        universal[archs[0].offset as usize + x86_64.len()..0x8000]
+            .iter()
+            .all(|b| *b == 0)
+    );
+}
+
+#[test]
+fn universal_with_single_slice() {
+    let arm64 = arm64_binary();
+    let archs = parse_universal(&create_universal(&[&arm64]).unwrap()).unwrap();
+    assert_eq!(archs.len(), 1);
+    assert_eq!(cpu_type_name(archs[0].cputype), "arm64");
+}
+
+#[test]
+fn universal_rejects_duplicate_architectures() {
+    let a = arm64_binary();
+    let b = thin_binary(CPU_TYPE_ARM64, 2, b"arm64e");
+    let err = create_universal(&[&a, &b]).unwrap_err();
+    assert!(err.to_string().contains("more than one arm64 binary"));
+}
+
+#[test]
+fn universal_rejects_fat_and_empty_input() {
+    let arm64 = arm64_binary();
+    let x86_64 = x86_64_binary();
+    let fat = create_universal(&[&arm64]).unwrap();
+
+    let err = create_universal(&[&fat, &x86_64]).unwrap_err();
+    assert!(err.to_string().contains("already universal"));
+    let err = create_universal(&[]).unwrap_err();
+    assert!(err.to_string().contains("no binaries to merge"));
+}
+
+#[test]
+fn parse_universal_rejects_truncated_input() {
+    let arm64 = arm64_binary();
+    let x86_64 = x86_64_binary();
+    let universal = create_universal(&[&arm64, &x86_64]).unwrap();
+
+    assert!(parse_universal(&universal[..universal.len() - 1]).is_err());
+    assert!(parse_universal(&universal[..20]).is_err());
+    let err = parse_universal(&arm64).unwrap_err();
+    assert!(err.to_string().contains("not a universal binary"));
+}
+
+#[test]
+fn cpu_type_names() {
+    assert_eq!(cpu_type_name(CPU_TYPE_ARM64), "arm64");
+    assert_eq!(cpu_type_name(CPU_TYPE_X86_64), "x86_64");
+    assert_eq!(cpu_type_name(0x42), "unknown");
+}
+
+#[test]
+fn universal_release_rejects_a_conflicting_target() {
+    let config =
+        ReleaseConfig::from_toml_str("[build]\ntarget = \"x86_64-apple-darwin\"\n").unwrap();
+    let err = run_release_macos_universal(&config).unwrap_err();
+    assert!(err.to_string().contains("unset target"), "{err}");
+}
@@ -1,6 +1,10 @@
+use rust_release_action::cargo_info::CargoInfo;
+use rust_release_action::config::ReleaseConfig;
 use rust_release_action::nfpm::{
-    format_dependency_list, nfpm_base_config, nfpm_contents_section, nfpm_dependencies_section,
+    format_dependency_list, nfpm_base_config, nfpm_binaries_contents_section,
+    nfpm_contents_section, nfpm_dependencies_section,
 };
+use rust_release_action::split_src_dst;
 use std::env;
 use std::fs;
 use std::sync::{LazyLock, Mutex};
@@ -55,23 +59,87 @@ fn format_dependency_list_different_keys() {
 
 #[test]
 fn nfpm_base_config_required_fields() {
-    let config = nfpm_base_config("myapp", "1.2.3", "amd64");
+    let config = nfpm_base_config(
+        &ReleaseConfig::default(),
+        &CargoInfo::default(),
+        "myapp",
+        "1.2.3",
+        "amd64",
+    );
     assert!(config.contains("name: \"myapp\""));
     assert!(config.contains("version: \"1.2.3\""));
     assert!(config.contains("arch: \"amd64\""));
     assert!(config.contains("platform: linux"));
 }
 
+#[test]
+fn nfpm_base_config_defaults_to_cargo_metadata() {
+    let info = CargoInfo {
+        description: "A fast CLI tool".into(),
+        license: "MIT OR Apache-2.0".into(),
+        repository: "https://github.com/org/tool".into(),
+        authors: vec!["Jane Doe <jane@example.com>".into(), "John Roe".into()],
+        ..CargoInfo::default()
+    };
+    let config = nfpm_base_config(&ReleaseConfig::default(), &info, "tool", "1.0.0", "amd64");
+    assert!(config.contains("description: \"A fast CLI tool\""));
+    assert!(config.contains("maintainer: \"Jane Doe <jane@example.com>\""));
+    assert!(config.contains("homepage: \"https://github.com/org/tool\""));
+    assert!(config.contains("license: \"MIT OR Apache-2.0\""));
+
+    let config = nfpm_base_config(
+        &ReleaseConfig::default(),
+        &CargoInfo::default(),
+        "tool",
+        "1.0.0",
+        "amd64",
+    );
+    assert!(config.contains("maintainer: \"Unknown <unknown@example.com>\""));
+    assert!(
+        config.contains("description: \"tool - built with rust-build-package-release-action\"")
+    );
+    assert!(!config.contains("homepage:"));
+}
+
+#[test]
+fn nfpm_base_config_escapes_cargo_metadata() {
+    let info = CargoInfo {
+        description: "Says \"hi\": a\n  \\tool".into(),
+        authors: vec!["Jane: Doe <jane@example.com>".into()],
+        ..CargoInfo::default()
+    };
+    let config = nfpm_base_config(&ReleaseConfig::default(), &info, "tool", "1.0.0", "amd64");
+    assert!(config.contains("description: \"Says \\\"hi\\\": a \\\\tool\"\n"));
+    assert!(config.contains("maintainer: \"Jane: Doe <jane@example.com>\"\n"));
+}
+
+#[test]
+fn nfpm_base_config_reads_release_config() {
+    let config = ReleaseConfig::from_toml_str(
+        "[pkg]\ndescription = \"From release.toml\"\nvendor = \"Acme\"\n",
+    )
+    .unwrap();
+    let yaml = nfpm_base_config(&config, &CargoInfo::default(), "tool", "1.0.0", "amd64");
+    assert!(yaml.contains("description: \"From release.toml\""));
+    assert!(yaml.contains("vendor: \"Acme\""));
+}
+
 #[test]
 fn nfpm_base_config_arm64_arch() {
-    let config = nfpm_base_config("tool", "0.1.0", "arm64");
+    let config = nfpm_base_config(
+        &ReleaseConfig::default(),
+        &CargoInfo::default(),
+        "tool",
+        "0.1.0",
+        "arm64",
+    );
     assert!(config.contains("arch: \"arm64\""));
 }
 
 #[test]
 fn nfpm_dependencies_section_no_env_vars() {
     // With no PKG_* env vars set, this should return an empty collection
-    let result = nfpm_dependencies_section();
+    let result = nfpm_dependencies_section(&ReleaseConfig::default());
     assert!(result.is_empty());
 }
 
@@ -86,7 +154,12 @@ fn nfpm_contents_section_includes_binary() {
 
     // Safety: serialised by CWD_LOCK
     unsafe { env::remove_var("PKG_CONTENTS") };
-    let result = nfpm_contents_section("myapp", "/usr/src/myapp");
+    let result = nfpm_contents_section(
+        &ReleaseConfig::default(),
+        &CargoInfo::default(),
+        "myapp",
+        "/usr/src/myapp",
+    );
     env::set_current_dir(original_dir).unwrap();
 
     assert!(result.contains("src: \"/usr/src/myapp\""));
@@ -105,10 +178,103 @@ fn nfpm_contents_section_includes_license_and_readme() {
     fs::write(dir.path().join("README.md"), "# Readme").unwrap();
 
     unsafe { env::remove_var("PKG_CONTENTS") };
-    let result = nfpm_contents_section("myapp", "/usr/src/myapp");
+    let result = nfpm_contents_section(
+        &ReleaseConfig::default(),
+        &CargoInfo::default(),
+        "myapp",
+        "/usr/src/myapp",
+    );
     env::set_current_dir(original_dir).unwrap();
 
     assert!(result.contains("LICENSE"));
     assert!(result.contains("README.md"));
     assert!(result.contains("/usr/share/doc/myapp/"));
 }
+
+#[test]
+fn nfpm_binaries_contents_section_installs_every_binary() {
+    let _lock = CWD_LOCK.lock().unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let original_dir = env::current_dir().unwrap();
+    env::set_current_dir(dir.path()).unwrap();
+
+    fs::write(dir.path().join("README.md"), "# Readme").unwrap();
+
+    unsafe { env::remove_var("PKG_CONTENTS") };
+    let result = nfpm_binaries_contents_section(
+        &ReleaseConfig::default(),
+        &CargoInfo::default(),
+        "tools",
+        &[("tool", "/src/tool"), ("toold", "/src/toold")],
+    );
+    env::set_current_dir(original_dir).unwrap();
+
+    assert_eq!(result.matches("contents:").count(), 1);
+    assert!(result.contains("src: \"/src/tool\"\n    dst: \"/usr/bin/tool\""));
+    assert!(result.contains("src: \"/src/toold\"\n    dst: \"/usr/bin/toold\""));
+    assert!(result.contains("/usr/share/doc/tools/README.md"));
+}
+
+#[test]
+fn nfpm_contents_section_installs_manifest_readme_and_license_file() {
+    let _lock = CWD_LOCK.lock().unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let original_dir = env::current_dir().unwrap();
+    env::set_current_dir(dir.path()).unwrap();
+
+    fs::create_dir_all(dir.path().join("docs")).unwrap();
+    fs::write(dir.path().join("docs/USAGE.md"), "# Usage").unwrap();
+    fs::write(dir.path().join("COPYING"), "GPL").unwrap();
+    let info = CargoInfo {
+        readme: "docs/USAGE.md".into(),
+        license_file: "COPYING".into(),
+        ..CargoInfo::default()
+    };
+
+    unsafe { env::remove_var("PKG_CONTENTS") };
+    let result = nfpm_contents_section(&ReleaseConfig::default(), &info, "tool", "/src/tool");
+    env::set_current_dir(original_dir).unwrap();
+
+    assert!(result.contains("dst: \"/usr/share/doc/tool/USAGE.md\""));
+    assert!(result.contains("dst: \"/usr/share/doc/tool/COPYING\""));
+    assert!(!result.contains("README.md"));
+}
+
+#[test]
+fn nfpm_contents_section_escapes_paths() {
+    let _lock = CWD_LOCK.lock().unwrap();
+    let dir = tempfile::tempdir().unwrap();
+    let original_dir = env::current_dir().unwrap();
+    env::set_current_dir(dir.path()).unwrap();
+
+    let mut config = ReleaseConfig::default();
+    config.set("PKG_CONTENTS", r#"conf "a".toml:/etc/my"app/a.toml"#);
+    let result = nfpm_contents_section(
+        &config,
+        &CargoInfo::default(),
+        "myapp",
+        r#"C:\build\my"app"#,
+    );
+    env::set_current_dir(original_dir).unwrap();
+
+    assert!(result.contains(r#"src: "C:\\build\\my\"app""#));
+    assert!(result.contains(r#"dst: "/etc/my\"app/a.toml""#));
+    assert!(result.contains(r#"conf \"a\".toml""#));
+}
+
+#[test]
+fn split_src_dst_keeps_drive_letters() {
+    assert_eq!(
+        split_src_dst("a.toml:/etc/a.toml"),
+        ("a.toml", Some("/etc/a.toml"))
+    );
+    assert_eq!(
+        split_src_dst(r"C:\build\a.toml:/etc/a.toml"),
+        (r"C:\build\a.toml", Some("/etc/a.toml"))
+    );
+    assert_eq!(
+        split_src_dst(r"C:\build\a.toml"),
+        (r"C:\build\a.toml", None)
+    );
+    assert_eq!(split_src_dst("a.toml"), ("a.toml", None));
+}
@@ -1,4 +1,4 @@
-use rust_release_action::output::{output, output_multiline};
+use rust_release_action::output::{annotation, output, output_multiline};
 use std::env;
 use std::fs;
 use std::sync::{LazyLock, Mutex};
@@ -68,3 +68,11 @@ fn output_noop_with_empty_env() {
     output_multiline("key", "value");
     unsafe { env::remove_var("GITHUB_OUTPUT") };
 }
+
+#[test]
+fn annotation_escapes_message_and_properties() {
+    assert_eq!(
+        annotation("warning", "docs/CHANGE,LOG.md", 3, "50% done\nnext: line"),
+        "::warning file=docs/CHANGE%2CLOG.md,line=3::50%25 done%0Anext: line"
+    );
+}
@@ -0,0 +1,251 @@
+use rust_release_action::build::BuildTool;
+use rust_release_action::plan::{PlanFormat, plan_matrix, release_command, runner_for_target};
+use rust_release_action::tools::{linux_cross_linker, linux_cross_packages};
+
+fn targets(list: &[&str]) -> Vec<String> {
+    list.iter().map(|t| t.to_string()).collect()
+}
+
+#[test]
+fn format_parsing() {
+    assert_eq!(
+        "archive".parse::<PlanFormat>().unwrap(),
+        PlanFormat::Archive
+    );
+    assert_eq!(" DEB ".parse::<PlanFormat>().unwrap(), PlanFormat::Deb);
+    let err = "snap".parse::<PlanFormat>().unwrap_err();
+    assert!(err.to_string().contains("unsupported plan format: snap"));
+}
+
+#[test]
+fn runners_by_target() {
+    assert_eq!(
+        runner_for_target("aarch64-unknown-linux-musl").unwrap(),
+        "ubuntu-22.04"
+    );
+    assert_eq!(
+        runner_for_target("aarch64-apple-darwin").unwrap(),
+        "macos-14"
+    );
+    assert_eq!(
+        runner_for_target("x86_64-apple-darwin").unwrap(),
+        "macos-15-intel"
+    );
+    assert_eq!(
+        runner_for_target("universal-apple-darwin").unwrap(),
+        "macos-14"
+    );
+    assert_eq!(
+        runner_for_target("x86_64-pc-windows-msvc").unwrap(),
+        "windows-2022"
+    );
+    assert!(runner_for_target("wasm32-unknown-unknown").is_err());
+}
+
+#[test]
+fn commands_by_target_and_format() {
+    let cases = [
+        (
+            "x86_64-unknown-linux-gnu",
+            PlanFormat::Archive,
+            Some("release-linux"),
+        ),
+        (
+            "x86_64-unknown-linux-gnu",
+            PlanFormat::Deb,
+            Some("release-linux-deb"),
+        ),
+        (
+            "x86_64-unknown-linux-musl",
+            PlanFormat::Apk,
+            Some("release-linux-apk"),
+        ),
+        (
+            "aarch64-apple-darwin",
+            PlanFormat::Binary,
+            Some("release-macos"),
+        ),
+        (
+            "aarch64-apple-darwin",
+            PlanFormat::Dmg,
+            Some("release-macos-dmg"),
+        ),
+        (
+            "universal-apple-darwin",
+            PlanFormat::Archive,
+            Some("release-macos-universal"),
+        ),
+        ("universal-apple-darwin", PlanFormat::Dmg, None),
+        (
+            "x86_64-pc-windows-msvc",
+            PlanFormat::Msi,
+            Some("release-windows-msi"),
+        ),
+        ("x86_64-pc-windows-msvc", PlanFormat::Rpm, None),
+        ("aarch64-apple-darwin", PlanFormat::Deb, None),
+        ("mips-unknown-linux-gnu", PlanFormat::Deb, None),
+    ];
+    for (target, format, expected) in cases {
+        assert_eq!(
+            release_command(target, format).unwrap(),
+            expected,
+            "{target} {format:?}"
+        );
+    }
+}
+
+#[test]
+fn plan_crosses_targets_and_formats() {
+    let entries = plan_matrix(
+        &targets(&[
+            "x86_64-unknown-linux-gnu",
+            "aarch64-apple-darwin",
+            "x86_64-pc-windows-msvc",
+        ]),
+        &[PlanFormat::Archive, PlanFormat::Deb, PlanFormat::Msi],
+        None,
+    )
+    .unwrap();
+
+    let jobs: Vec<(&str, &str, &str)> = entries
+        .iter()
+        .map(|e| (e.target.as_str(), e.command.as_str(), e.os.as_str()))
+        .collect();
+    assert_eq!(
+        jobs,
+        vec![
+            ("x86_64-unknown-linux-gnu", "release-linux", "ubuntu-22.04"),
+            (
+                "x86_64-unknown-linux-gnu",
+                "release-linux-deb",
+                "ubuntu-22.04"
+            ),
+            ("aarch64-apple-darwin", "release-macos", "macos-14"),
+            ("x86_64-pc-windows-msvc", "release-windows", "windows-2022"),
+            (
+                "x86_64-pc-windows-msvc",
+                "release-windows-msi",
+                "windows-2022"
+            ),
+        ]
+    );
+    assert!(entries[0].archive);
+    assert!(!entries[1].archive);
+}
+
+#[test]
+fn plan_cross_deps_and_zigbuild() {
+    let entries = plan_matrix(
+        &targets(&[
+            "aarch64-unknown-linux-gnu",
+            "x86_64-unknown-linux-musl",
+            "x86_64-unknown-linux-gnu",
+            "aarch64-apple-darwin",
+        ]),
+        &[PlanFormat::Binary],
+        Some(BuildTool::Zigbuild),
+    )
+    .unwrap();
+
+    assert_eq!(entries[0].cross_deps, vec!["gcc-aarch64-linux-gnu"]);
+    assert_eq!(entries[1].cross_deps, vec!["musl-tools"]);
+    assert!(entries[2].cross_deps.is_empty());
+    assert!(entries[3].cross_deps.is_empty());
+    assert!(entries.iter().all(|e| e.build_tool == "zigbuild"));
+}
+
+#[test]
+fn plan_uses_cross_where_required() {
+    let entries = plan_matrix(
+        &targets(&["s390x-unknown-linux-gnu", "aarch64-unknown-linux-gnu"]),
+        &[PlanFormat::Deb],
+        None,
+    )
+    .unwrap();
+
+    assert_eq!(entries[0].command, "release-linux-deb");
+    assert_eq!(entries[0].build_tool, "cross");
+    assert!(entries[0].cross_deps.is_empty());
+    assert_eq!(entries[1].build_tool, "cargo");
+
+    let entries = plan_matrix(
+        &targets(&["aarch64-unknown-linux-gnu"]),
+        &[PlanFormat::Binary],
+        Some(BuildTool::Cross),
+    )
+    .unwrap();
+    assert_eq!(entries[0].build_tool, "cross");
+    assert!(entries[0].cross_deps.is_empty());
+}
+
+#[test]
+fn plan_skips_duplicate_targets() {
+    let entries = plan_matrix(
+        &targets(&["aarch64-apple-darwin", "aarch64-apple-darwin"]),
+        &[PlanFormat::Archive],
+        None,
+    )
+    .unwrap();
+    assert_eq!(entries.len(), 1);
+}
+
+#[test]
+fn plan_rejects_formats_without_targets() {
+    let err = plan_matrix(
+        &targets(&["x86_64-unknown-linux-gnu"]),
+        &[PlanFormat::Archive, PlanFormat::Dmg],
+        None,
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("format dmg"));
+
+    assert!(plan_matrix(&[], &[PlanFormat::Archive], None).is_err());
+    assert!(
+        plan_matrix(
+            &targets(&["mips-unknown-none"]),
+            &[PlanFormat::Archive],
+            None
+        )
+        .is_err()
+    );
+}
+
+#[test]
+fn plan_entries_serialize_for_github_matrix() {
+    let entries = plan_matrix(
+        &targets(&["armv7-unknown-linux-gnueabihf"]),
+        &[PlanFormat::Deb],
+        None,
+    )
+    .unwrap();
+    let json = serde_json::to_value(&entries[0]).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "target": "armv7-unknown-linux-gnueabihf",
+            "os": "ubuntu-22.04",
+            "command": "release-linux-deb",
+            "format": "deb",
+            "archive": false,
+            "build_tool": "cargo",
+            "cross_deps": ["pkg-config", "gcc-arm-linux-gnueabihf"],
+        })
+    );
+}
+
+#[test]
+fn cross_toolchains_depend_on_host() {
+    assert!(linux_cross_packages("aarch64-unknown-linux-gnu", "aarch64").is_empty());
+    assert_eq!(
+        linux_cross_linker("aarch64-unknown-linux-gnu", "aarch64"),
+        None
+    );
+    assert_eq!(
+        linux_cross_linker("aarch64-unknown-linux-gnu", "x86_64"),
+        Some((
+            "CARGO_TARGET_AARCH64_UNKNOWN_LINUX_GNU_LINKER",
+            "aarch64-linux-gnu-gcc"
+        ))
+    );
+    assert!(linux_cross_packages("x86_64-unknown-linux-gnu", "x86_64").is_empty());
+}
@@ -21,6 +21,18 @@ fn short_platform_macos_x64() {
     assert_eq!(detect_platform_short("myapp-macos-x64.tar.gz"), "macos-x64");
 }
 
+#[test]
+fn short_platform_macos_universal() {
+    assert_eq!(
+        detect_platform_short("myapp-1.0.0-universal-apple-darwin.tar.gz"),
+        "macos-universal"
+    );
+    assert_eq!(
+        detect_platform_short("myapp-macos-universal.tar.gz"),
+        "macos-universal"
+    );
+}
+
 #[test]
 fn short_platform_linux_arm64() {
     assert_eq!(
@@ -94,6 +106,10 @@ fn display_platform_macos() {
         detect_platform_display("myapp-x86_64-apple-darwin.tar.gz"),
         "macOS (Intel)"
     );
+    assert_eq!(
+        detect_platform_display("myapp-universal-apple-darwin.tar.gz"),
+        "macOS (Universal)"
+    );
 }
 
 #[test]
@@ -0,0 +1,194 @@
+use rust_release_action::checksum::sha256_bytes;
+use rust_release_action::config::ReleaseConfig;
+use rust_release_action::provenance::{
+    ArtifactBuild, BuildParameters, GitHubContext, PREDICATE_TYPE, STATEMENT_TYPE, Subject,
+    build_statement, subjects_from_collection, subjects_from_dir,
+};
+use std::collections::BTreeMap;
+use std::fs;
+use tempfile::TempDir;
+
+fn github() -> GitHubContext {
+    GitHubContext {
+        server_url: "https://github.com".into(),
+        repository: "org/tool".into(),
+        repository_id: "123".into(),
+        repository_owner_id: "456".into(),
+        sha: "0123456789abcdef0123456789abcdef01234567".into(),
+        git_ref: "refs/tags/v1.2.0".into(),
+        workflow_ref: "org/tool/.github/workflows/release.yml@refs/tags/v1.2.0".into(),
+        event_name: "push".into(),
+        run_id: "987".into(),
+        run_attempt: "2".into(),
+        runner_environment: "github-hosted".into(),
+    }
+}
+
+fn subject(name: &str) -> Subject {
+    Subject {
+        name: name.into(),
+        digest: BTreeMap::from([("sha256".to_string(), "ab".repeat(32))]),
+    }
+}
+
+#[test]
+fn github_context_identifiers() {
+    let gh = github();
+    assert_eq!(gh.workflow_path(), ".github/workflows/release.yml");
+    assert_eq!(
+        gh.builder_id(),
+        "https://github.com/org/tool/.github/workflows/release.yml@refs/tags/v1.2.0"
+    );
+    assert_eq!(
+        gh.invocation_id(),
+        "https://github.com/org/tool/actions/runs/987/attempts/2"
+    );
+    assert_eq!(
+        gh.source_uri(),
+        "git+https://github.com/org/tool@refs/tags/v1.2.0"
+    );
+}
+
+#[test]
+fn statement_structure() {
+    let name = "tool-1.2.0-x86_64-unknown-linux-musl.tar.gz";
+    let params = BuildParameters {
+        features: vec!["mimalloc".into()],
+        profile: "release".into(),
+        rustflags: "-C target-cpu=native".into(),
+        locked: true,
+        artifacts: BTreeMap::from([(
+            name.to_string(),
+            ArtifactBuild {
+                target: "x86_64-unknown-linux-musl".into(),
+                build_tool: "cross".into(),
+            },
+        )]),
+        ..Default::default()
+    };
+    let statement = build_statement(&[subject(name)], &params, &github()).unwrap();
+
+    assert_eq!(statement["_type"], STATEMENT_TYPE);
+    assert_eq!(statement["predicateType"], PREDICATE_TYPE);
+    assert_eq!(statement["subject"][0]["name"], name);
+    assert_eq!(statement["subject"][0]["digest"]["sha256"], "ab".repeat(32));
+
+    let definition = &statement["predicate"]["buildDefinition"];
+    let build = &definition["externalParameters"]["build"];
+    assert_eq!(build["features"][0], "mimalloc");
+    assert_eq!(build["rustflags"], "-C target-cpu=native");
+    assert_eq!(build["locked"], true);
+    assert_eq!(
+        build["artifacts"][name]["target"],
+        "x86_64-unknown-linux-musl"
+    );
+    assert_eq!(build["artifacts"][name]["buildTool"], "cross");
+    assert_eq!(
+        definition["externalParameters"]["workflow"]["path"],
+        ".github/workflows/release.yml"
+    );
+    assert_eq!(
+        definition["resolvedDependencies"][0]["digest"]["gitCommit"],
+        "0123456789abcdef0123456789abcdef01234567"
+    );
+    assert_eq!(
+        statement["predicate"]["runDetails"]["builder"]["id"],
+        "https://github.com/org/tool/.github/workflows/release.yml@refs/tags/v1.2.0"
+    );
+}
+
+#[test]
+fn statement_requires_subjects() {
+    let result = build_statement(&[], &BuildParameters::default(), &github());
+    assert!(result.is_err());
+}
+
+#[test]
+fn statement_requires_github_context() {
+    let gh = GitHubContext {
+        sha: String::new(),
+        ..github()
+    };
+    let err = build_statement(&[subject("a.zip")], &BuildParameters::default(), &gh)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("GITHUB_SHA"));
+}
+
+#[test]
+fn subjects_from_collection_json() {
+    let json = r#"[
+        {"artifact": "tool.zip", "path": "release/tool.zip", "sha256": "aa", "sha512": "bb", "b2": "cc", "platform": "windows-x64", "url": ""},
+        {"artifact": "tool.tar.gz", "path": "release/tool.tar.gz", "sha256": "dd", "platform": "linux-x64", "url": ""}
+    ]"#;
+    let subjects = subjects_from_collection(json).unwrap();
+    assert_eq!(subjects.len(), 2);
+    assert_eq!(subjects[0].name, "tool.zip");
+    assert_eq!(subjects[0].digest["sha512"], "bb");
+    assert_eq!(subjects[0].digest["blake2b"], "cc");
+    assert_eq!(subjects[1].digest.len(), 1);
+}
+
+#[test]
+fn subjects_from_collection_require_sha256() {
+    assert!(subjects_from_collection(r#"[{"artifact": "tool.zip"}]"#).is_err());
+    assert!(subjects_from_collection("not json").is_err());
+}
+
+#[test]
+fn subjects_from_dir_hash_artifacts() {
+    let dir = TempDir::new().unwrap();
+    fs::write(
+        dir.path()
+            .join("tool-1.0.0-x86_64-unknown-linux-gnu.tar.gz"),
+        b"linux",
+    )
+    .unwrap();
+    fs::write(dir.path().join("provenance.intoto.json"), b"{}").unwrap();
+    let subjects = subjects_from_dir(dir.path(), "sha512").unwrap();
+    assert_eq!(subjects.len(), 1);
+    assert_eq!(subjects[0].digest["sha256"], sha256_bytes(b"linux"));
+    assert_eq!(subjects[0].digest["sha512"].len(), 128);
+}
+
+#[test]
+fn build_parameters_take_each_target_from_the_artifact_name() {
+    let subjects = [
+        subject("tool-1.2.0-x86_64-unknown-linux-musl.tar.gz"),
+        subject("tool-1.2.0-aarch64-apple-darwin.tar.gz"),
+        subject("tool-1.2.0.tar.gz"),
+    ];
+    let params = BuildParameters::from_env(&ReleaseConfig::default(), &subjects, "tool", "1.2.0");
+
+    let targets: Vec<(&str, &str)> = params
+        .artifacts
+        .iter()
+        .map(|(name, build)| (name.as_str(), build.target.as_str()))
+        .collect();
+    assert_eq!(
+        targets,
+        vec![
+            (
+                "tool-1.2.0-aarch64-apple-darwin.tar.gz",
+                "aarch64-apple-darwin"
+            ),
+            (
+                "tool-1.2.0-x86_64-unknown-linux-musl.tar.gz",
+                "x86_64-unknown-linux-musl"
+            ),
+        ]
+    );
+    assert_eq!(
+        params.artifacts["tool-1.2.0-aarch64-apple-darwin.tar.gz"].build_tool,
+        "cargo"
+    );
+
+    // TARGET covers artifacts whose name carries none
+    let mut config = ReleaseConfig::default();
+    config.set("TARGET", "x86_64-pc-windows-msvc");
+    let params = BuildParameters::from_env(&config, &subjects[2..], "tool", "1.2.0");
+    assert_eq!(
+        params.artifacts["tool-1.2.0.tar.gz"].target,
+        "x86_64-pc-windows-msvc"
+    );
+}
@@ -1,3 +1,4 @@
+use rust_release_action::config::ReleaseConfig;
 use rust_release_action::publish::{build_publish_args, is_version_tag};
 use std::sync::{LazyLock, Mutex};
 
@@ -23,7 +24,10 @@ fn publish_args_minimal() {
     let _guard = ENV_LOCK.lock().unwrap();
     clear_publish_env();
 
-    assert_eq!(build_publish_args(false), vec!["publish"]);
+    assert_eq!(
+        build_publish_args(&ReleaseConfig::default(), false),
+        vec!["publish"]
+    );
 }
 
 #[test]
@@ -31,7 +35,10 @@ fn publish_args_dry_run() {
     let _guard = ENV_LOCK.lock().unwrap();
     clear_publish_env();
 
-    assert_eq!(build_publish_args(true), vec!["publish", "--dry-run"]);
+    assert_eq!(
+        build_publish_args(&ReleaseConfig::default(), true),
+        vec!["publish", "--dry-run"]
+    );
 }
 
 #[test]
@@ -42,7 +49,7 @@ fn publish_args_with_package() {
     unsafe { std::env::set_var("PACKAGE", "my-crate") };
 
     assert_eq!(
-        build_publish_args(false),
+        build_publish_args(&ReleaseConfig::default(), false),
         vec!["publish", "--package", "my-crate"]
     );
 }
@@ -55,7 +62,7 @@ fn publish_args_with_features() {
     unsafe { std::env::set_var("FEATURES", "foo,bar") };
 
     assert_eq!(
-        build_publish_args(false),
+        build_publish_args(&ReleaseConfig::default(), false),
         vec!["publish", "--features", "foo,bar"]
     );
 }
@@ -68,7 +75,7 @@ fn publish_args_no_default_features() {
     unsafe { std::env::set_var("NO_DEFAULT_FEATURES", "true") };
 
     assert_eq!(
-        build_publish_args(false),
+        build_publish_args(&ReleaseConfig::default(), false),
         vec!["publish", "--no-default-features"]
     );
 }
@@ -80,7 +87,10 @@ fn publish_args_locked() {
     // Safety: serialised by ENV_LOCK
     unsafe { std::env::set_var("LOCKED", "true") };
 
-    assert_eq!(build_publish_args(false), vec!["publish", "--locked"]);
+    assert_eq!(
+        build_publish_args(&ReleaseConfig::default(), false),
+        vec!["publish", "--locked"]
+    );
 }
 
 #[test]
@@ -91,7 +101,7 @@ fn publish_args_custom_manifest() {
     unsafe { std::env::set_var("MANIFEST_PATH", "subcrate/Cargo.toml") };
 
     assert_eq!(
-        build_publish_args(false),
+        build_publish_args(&ReleaseConfig::default(), false),
         vec!["publish", "--manifest-path", "subcrate/Cargo.toml"]
     );
 }
@@ -103,7 +113,7 @@ fn publish_args_default_manifest_omitted() {
     // Safety: serialised by ENV_LOCK
     unsafe { std::env::set_var("MANIFEST_PATH", "Cargo.toml") };
 
-    let args = build_publish_args(false);
+    let args = build_publish_args(&ReleaseConfig::default(), false);
     assert!(!args.contains(&"--manifest-path".to_string()));
 }
 
@@ -120,7 +130,7 @@ fn publish_args_all_options() {
         std::env::set_var("MANIFEST_PATH", "libs/Cargo.toml");
     }
 
-    let args = build_publish_args(true);
+    let args = build_publish_args(&ReleaseConfig::default(), true);
     assert_eq!(
         args,
         vec![
@@ -145,7 +155,10 @@ fn publish_args_no_default_features_false_is_ignored() {
     // Safety: serialised by ENV_LOCK
     unsafe { std::env::set_var("NO_DEFAULT_FEATURES", "false") };
 
-    assert_eq!(build_publish_args(false), vec!["publish"]);
+    assert_eq!(
+        build_publish_args(&ReleaseConfig::default(), false),
+        vec!["publish"]
+    );
 }
 
 #[test]
@@ -155,7 +168,10 @@ fn publish_args_locked_false_is_ignored() {
     // Safety: serialised by ENV_LOCK
     unsafe { std::env::set_var("LOCKED", "false") };
 
-    assert_eq!(build_publish_args(false), vec!["publish"]);
+    assert_eq!(
+        build_publish_args(&ReleaseConfig::default(), false),
+        vec!["publish"]
+    );
 }
 
 #[test]
@@ -0,0 +1,341 @@
+use rust_release_action::sbom::{
+    Metadata, SbomArtifact, SbomFormat, artifact_target, cyclonedx_document, parse_sbom_formats,
+    resolve_dependencies, sbom_document, spdx_document,
+};
+use rust_release_action::timestamp::{format_timestamp, now_secs, today};
+use std::fs;
+use tempfile::TempDir;
+
+const REGISTRY: &str = "registry+https://github.com/rust-lang/crates.io-index";
+
+fn metadata() -> Metadata {
+    let json = serde_json::json!({
+        "packages": [
+            {
+                "id": "path+file:///work/app#0.4.0",
+                "name": "app",
+                "version": "0.4.0",
+                "license": "MIT/Apache-2.0",
+                "source": null,
+                "description": "An example app\n",
+                "repository": "https://github.com/org/app",
+                "homepage": null,
+                "authors": ["Jane Doe"]
+            },
+            {
+                "id": format!("{REGISTRY}#serde@1.0.200"),
+                "name": "serde",
+                "version": "1.0.200",
+                "license": "MIT OR Apache-2.0",
+                "source": REGISTRY,
+                "description": null,
+                "repository": null,
+                "homepage": "https://serde.rs"
+            },
+            {
+                "id": format!("{REGISTRY}#serde_derive@1.0.200"),
+                "name": "serde_derive",
+                "version": "1.0.200",
+                "license": "MIT OR Apache-2.0",
+                "source": REGISTRY
+            },
+            {
+                "id": format!("{REGISTRY}#cc@1.1.0"),
+                "name": "cc",
+                "version": "1.1.0",
+                "license": "MIT",
+                "source": REGISTRY
+            },
+            {
+                "id": format!("{REGISTRY}#proptest@1.5.0"),
+                "name": "proptest",
+                "version": "1.5.0",
+                "license": "MIT",
+                "source": REGISTRY
+            }
+        ],
+        "workspace_members": ["path+file:///work/app#0.4.0"],
+        "resolve": {
+            "root": "path+file:///work/app#0.4.0",
+            "nodes": [
+                {
+                    "id": "path+file:///work/app#0.4.0",
+                    "deps": [
+                        {"pkg": format!("{REGISTRY}#serde@1.0.200"), "dep_kinds": [{"kind": null, "target": null}]},
+                        {"pkg": format!("{REGISTRY}#cc@1.1.0"), "dep_kinds": [{"kind": "build", "target": null}]},
+                        {"pkg": format!("{REGISTRY}#proptest@1.5.0"), "dep_kinds": [{"kind": "dev", "target": null}]}
+                    ],
+                    "features": ["default", "tls"]
+                },
+                {
+                    "id": format!("{REGISTRY}#serde@1.0.200"),
+                    "deps": [
+                        {"pkg": format!("{REGISTRY}#serde_derive@1.0.200"), "dep_kinds": [{"kind": null, "target": null}]}
+                    ],
+                    "features": ["derive", "std"]
+                },
+                {"id": format!("{REGISTRY}#serde_derive@1.0.200"), "deps": [], "features": []},
+                {"id": format!("{REGISTRY}#cc@1.1.0"), "deps": [], "features": []},
+                {"id": format!("{REGISTRY}#proptest@1.5.0"), "deps": [], "features": []}
+            ]
+        }
+    });
+    serde_json::from_value(json).unwrap()
+}
+
+#[test]
+fn sbom_format_parsing() {
+    assert_eq!(
+        parse_sbom_formats("spdx, cyclonedx").unwrap(),
+        vec![SbomFormat::Spdx23, SbomFormat::CycloneDx16]
+    );
+    assert_eq!(
+        parse_sbom_formats("cyclonedx-1.6,cyclonedx").unwrap(),
+        vec![SbomFormat::CycloneDx16]
+    );
+    assert!(parse_sbom_formats("cyclonedx-1.5,cyclonedx").is_err());
+    assert!(parse_sbom_formats("swid").is_err());
+    assert_eq!(SbomFormat::Spdx23.extension(), "spdx.json");
+    assert_eq!(SbomFormat::CycloneDx15.extension(), "cdx.json");
+}
+
+#[test]
+fn resolve_skips_dev_and_build_dependencies() {
+    let graph = resolve_dependencies(&metadata(), "").unwrap();
+    assert_eq!(graph.root.name, "app");
+    assert_eq!(graph.root.features, vec!["default", "tls"]);
+    let names: Vec<&str> = graph.packages.iter().map(|p| p.name.as_str()).collect();
+    assert_eq!(names, vec!["serde", "serde_derive"]);
+    assert_eq!(graph.packages[0].features, vec!["derive", "std"]);
+}
+
+#[test]
+fn resolve_by_package_name() {
+    assert_eq!(
+        resolve_dependencies(&metadata(), "app")
+            .unwrap()
+            .root
+            .version,
+        "0.4.0"
+    );
+    assert!(resolve_dependencies(&metadata(), "missing").is_err());
+}
+
+#[test]
+fn license_expression_and_purl() {
+    let graph = resolve_dependencies(&metadata(), "").unwrap();
+    assert_eq!(
+        graph.root.license_expression().unwrap(),
+        "MIT OR Apache-2.0"
+    );
+    assert_eq!(graph.packages[0].purl(), "pkg:cargo/serde@1.0.200");
+    assert!(graph.packages[0].is_crates_io());
+    assert!(!graph.root.is_crates_io());
+}
+
+#[test]
+fn spdx_document_structure() {
+    let graph = resolve_dependencies(&metadata(), "").unwrap();
+    let doc = spdx_document(&graph, None, "2026-01-01T00:00:00Z");
+    assert_eq!(doc["spdxVersion"], "SPDX-2.3");
+    assert_eq!(doc["creationInfo"]["created"], "2026-01-01T00:00:00Z");
+    let packages = doc["packages"].as_array().unwrap();
+    assert_eq!(packages.len(), 3);
+    assert_eq!(packages[0]["SPDXID"], "SPDXRef-Package-app-0.4.0");
+    assert_eq!(packages[0]["licenseDeclared"], "MIT OR Apache-2.0");
+    assert_eq!(packages[0]["downloadLocation"], "NOASSERTION");
+    assert_eq!(
+        packages[1]["downloadLocation"],
+        "https://crates.io/api/v1/crates/serde/1.0.200/download"
+    );
+    assert_eq!(
+        packages[1]["externalRefs"][0]["referenceLocator"],
+        "pkg:cargo/serde@1.0.200"
+    );
+
+    let relationships: Vec<(String, String, String)> = doc["relationships"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| {
+            (
+                r["spdxElementId"].as_str().unwrap().to_string(),
+                r["relationshipType"].as_str().unwrap().to_string(),
+                r["relatedSpdxElement"].as_str().unwrap().to_string(),
+            )
+        })
+        .collect();
+    assert!(relationships.contains(&(
+        "SPDXRef-DOCUMENT".into(),
+        "DESCRIBES".into(),
+        "SPDXRef-Package-app-0.4.0".into()
+    )));
+    assert!(relationships.contains(&(
+        "SPDXRef-Package-serde-1.0.200".into(),
+        "DEPENDS_ON".into(),
+        "SPDXRef-Package-serde-derive-1.0.200".into()
+    )));
+    assert_eq!(relationships.len(), 3);
+}
+
+#[test]
+fn cyclonedx_document_structure() {
+    let graph = resolve_dependencies(&metadata(), "").unwrap();
+    let doc = cyclonedx_document(&graph, None, "1.5", "2026-01-01T00:00:00Z");
+    assert_eq!(doc["bomFormat"], "CycloneDX");
+    assert_eq!(doc["specVersion"], "1.5");
+    assert!(
+        doc["serialNumber"]
+            .as_str()
+            .unwrap()
+            .starts_with("urn:uuid:")
+    );
+    assert_eq!(doc["metadata"]["component"]["name"], "app");
+    assert_eq!(
+        doc["metadata"]["component"]["properties"][0]["value"],
+        "default,tls"
+    );
+    let components = doc["components"].as_array().unwrap();
+    assert_eq!(components.len(), 2);
+    assert_eq!(components[0]["bom-ref"], "pkg:cargo/serde@1.0.200");
+    assert_eq!(
+        components[0]["licenses"][0]["expression"],
+        "MIT OR Apache-2.0"
+    );
+    let app_deps = doc["dependencies"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|d| d["ref"] == "pkg:cargo/app@0.4.0")
+        .unwrap();
+    assert_eq!(
+        app_deps["dependsOn"],
+        serde_json::json!(["pkg:cargo/serde@1.0.200"])
+    );
+}
+
+#[test]
+fn documents_are_deterministic() {
+    let graph = resolve_dependencies(&metadata(), "").unwrap();
+    let created = "2026-01-01T00:00:00Z";
+    assert_eq!(
+        sbom_document(&graph, None, SbomFormat::CycloneDx16, created),
+        sbom_document(&graph, None, SbomFormat::CycloneDx16, created)
+    );
+    let serial = sbom_document(&graph, None, SbomFormat::CycloneDx16, created)["serialNumber"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    // urn:uuid:xxxxxxxx-xxxx-4xxx-...
+    assert_eq!(&serial[23..24], "4");
+    assert_eq!(
+        sbom_document(&graph, None, SbomFormat::CycloneDx16, created)["specVersion"],
+        "1.6"
+    );
+}
+
+#[test]
+fn timestamps_are_rfc3339() {
+    assert_eq!(format_timestamp(0), "1970-01-01T00:00:00Z");
+    assert_eq!(format_timestamp(951_782_400), "2000-02-29T00:00:00Z");
+    assert_eq!(format_timestamp(1_767_225_599), "2025-12-31T23:59:59Z");
+    assert!(format_timestamp(now_secs()).starts_with(&today()));
+}
+
+#[test]
+fn artifact_target_from_name() {
+    assert_eq!(
+        artifact_target("app-0.4.0-x86_64-unknown-linux-gnu.tar.gz", "app", "0.4.0").as_deref(),
+        Some("x86_64-unknown-linux-gnu")
+    );
+    assert_eq!(
+        artifact_target("app-0.4.0-x86_64-pc-windows-msvc.msi", "app", "0.4.0").as_deref(),
+        Some("x86_64-pc-windows-msvc")
+    );
+    assert_eq!(
+        artifact_target("app-0.4.0-aarch64-apple-darwin", "app", "0.4.0").as_deref(),
+        Some("aarch64-apple-darwin")
+    );
+    assert_eq!(artifact_target("app_0.4.0_amd64.deb", "app", "0.4.0"), None);
+    assert_eq!(
+        artifact_target("app-0.4.0-1.x86_64.rpm", "app", "0.4.0"),
+        None
+    );
+}
+
+fn artifact() -> SbomArtifact {
+    let dir = TempDir::new().unwrap();
+    let path = dir.path().join("app-0.4.0-x86_64-unknown-linux-gnu.tar.gz");
+    fs::write(&path, b"release bytes").unwrap();
+    SbomArtifact::from_file(&path, "x86_64-unknown-linux-gnu").unwrap()
+}
+
+#[test]
+fn artifact_hashes() {
+    let artifact = artifact();
+    assert_eq!(artifact.name, "app-0.4.0-x86_64-unknown-linux-gnu.tar.gz");
+    assert_eq!(artifact.sha256.len(), 64);
+    assert_eq!(artifact.sha512.len(), 128);
+}
+
+#[test]
+fn spdx_document_describes_artifact() {
+    let graph = resolve_dependencies(&metadata(), "").unwrap();
+    let artifact = artifact();
+    let doc = spdx_document(&graph, Some(&artifact), "2026-01-01T00:00:00Z");
+    assert_eq!(doc["name"], artifact.name.as_str());
+    let subject = &doc["packages"][0];
+    assert_eq!(
+        subject["SPDXID"],
+        "SPDXRef-Artifact-app-0.4.0-x86-64-unknown-linux-gnu.tar.gz"
+    );
+    assert_eq!(subject["packageFileName"], artifact.name.as_str());
+    assert_eq!(subject["primaryPackagePurpose"], "ARCHIVE");
+    assert_eq!(subject["checksums"][0]["algorithm"], "SHA256");
+    assert_eq!(
+        subject["checksums"][0]["checksumValue"],
+        artifact.sha256.as_str()
+    );
+    assert_eq!(
+        subject["checksums"][1]["checksumValue"],
+        artifact.sha512.as_str()
+    );
+    assert_eq!(subject["comment"], "Target: x86_64-unknown-linux-gnu");
+    assert_eq!(
+        doc["relationships"][0]["relatedSpdxElement"],
+        subject["SPDXID"]
+    );
+    assert_eq!(doc["relationships"][1]["relationshipType"], "CONTAINS");
+    assert_eq!(
+        doc["relationships"][1]["relatedSpdxElement"],
+        "SPDXRef-Package-app-0.4.0"
+    );
+    assert_ne!(
+        doc["documentNamespace"],
+        spdx_document(&graph, None, "2026-01-01T00:00:00Z")["documentNamespace"]
+    );
+}
+
+#[test]
+fn cyclonedx_document_describes_artifact() {
+    let graph = resolve_dependencies(&metadata(), "").unwrap();
+    let artifact = artifact();
+    let doc = cyclonedx_document(&graph, Some(&artifact), "1.6", "2026-01-01T00:00:00Z");
+    let subject = &doc["metadata"]["component"];
+    assert_eq!(subject["type"], "file");
+    assert_eq!(subject["name"], artifact.name.as_str());
+    assert_eq!(subject["hashes"][0]["alg"], "SHA-256");
+    assert_eq!(subject["hashes"][0]["content"], artifact.sha256.as_str());
+    assert_eq!(subject["hashes"][1]["alg"], "SHA-512");
+    assert_eq!(
+        subject["properties"][0]["value"],
+        "x86_64-unknown-linux-gnu"
+    );
+    assert_eq!(doc["components"][0]["name"], "app");
+    assert_eq!(doc["components"].as_array().unwrap().len(), 3);
+    assert_eq!(doc["dependencies"][0]["ref"], artifact.name.as_str());
+    assert_eq!(
+        doc["dependencies"][0]["dependsOn"],
+        serde_json::json!(["pkg:cargo/app@0.4.0"])
+    );
+}
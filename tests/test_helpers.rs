@@ -1,7 +1,8 @@
 #![allow(dead_code)]
 
+use std::fs;
 use std::io::Write;
-use tempfile::NamedTempFile;
+use tempfile::{NamedTempFile, TempDir};
 
 /// Create a temp file with the given byte content.
 pub fn create_temp_file(content: &[u8]) -> NamedTempFile {
@@ -15,3 +16,21 @@ pub fn create_temp_file(content: &[u8]) -> NamedTempFile {
 pub fn create_temp_text_file(content: &str) -> NamedTempFile {
     create_temp_file(content.as_bytes())
 }
+
+/// Create a temp directory holding `files`, each containing its own name.
+pub fn dir_with_files(files: &[&str]) -> TempDir {
+    let dir = TempDir::new().unwrap();
+    for name in files {
+        fs::write(dir.path().join(name), name).unwrap();
+    }
+    dir
+}
+
+/// Create a temp directory holding `files` with the given contents.
+pub fn dir_with_contents(files: &[(&str, &[u8])]) -> TempDir {
+    let dir = TempDir::new().unwrap();
+    for (name, content) in files {
+        fs::write(dir.path().join(name), content).unwrap();
+    }
+    dir
+}
@@ -0,0 +1,117 @@
+mod test_helpers;
+
+use test_helpers::dir_with_contents;
+
+use rust_release_action::checksum::{ChecksumAlgorithm, sha256_bytes};
+use rust_release_action::verify_checksums::{find_manifests, read_manifests, verify_entries};
+use std::fs;
+use tempfile::TempDir;
+
+fn artifacts_dir() -> TempDir {
+    dir_with_contents(&[("app.tar.gz", b"tarball"), ("app.deb", b"deb")])
+}
+
+#[test]
+fn all_listed_files_verify() {
+    let dir = artifacts_dir();
+    fs::write(
+        dir.path().join("SHA256SUMS"),
+        format!(
+            "{}  app.tar.gz\n{}  ./app.deb\n",
+            sha256_bytes(b"tarball"),
+            sha256_bytes(b"deb")
+        ),
+    )
+    .unwrap();
+
+    let manifests = find_manifests(dir.path());
+    let entries = read_manifests(&manifests).unwrap();
+    let report = verify_entries(&entries, dir.path(), &manifests).unwrap();
+    assert!(report.is_ok());
+    assert_eq!(report.verified, vec!["app.deb", "app.tar.gz"]);
+    assert!(report.extra.is_empty());
+}
+
+#[test]
+fn reports_mismatched_missing_and_extra() {
+    let dir = artifacts_dir();
+    fs::write(dir.path().join("notes.txt"), b"notes").unwrap();
+    fs::write(dir.path().join("app.deb.sha256"), b"sidecar").unwrap();
+    fs::write(
+        dir.path().join("SHA256SUMS"),
+        format!(
+            "{}  app.tar.gz\n{}  app.deb\n{}  app.rpm\n",
+            sha256_bytes(b"tarball"),
+            sha256_bytes(b"not the deb"),
+            sha256_bytes(b"rpm")
+        ),
+    )
+    .unwrap();
+
+    let manifests = find_manifests(dir.path());
+    let entries = read_manifests(&manifests).unwrap();
+    let report = verify_entries(&entries, dir.path(), &manifests).unwrap();
+    assert!(!report.is_ok());
+    assert_eq!(report.verified, vec!["app.tar.gz"]);
+    assert_eq!(report.mismatched.len(), 1);
+    assert_eq!(report.mismatched[0].file, "app.deb");
+    assert_eq!(report.mismatched[0].actual, sha256_bytes(b"deb"));
+    assert_eq!(report.missing, vec!["app.rpm"]);
+    assert_eq!(report.extra, vec!["notes.txt"]);
+}
+
+#[test]
+fn verifies_bsd_tagged_and_multiple_manifests() {
+    let dir = artifacts_dir();
+    let sha512 = rust_release_action::checksum::sha512_file(&dir.path().join("app.deb")).unwrap();
+    fs::write(
+        dir.path().join("SHA256SUMS"),
+        format!("SHA256 (app.tar.gz) = {}\n", sha256_bytes(b"tarball")),
+    )
+    .unwrap();
+    fs::write(
+        dir.path().join("SHA512SUMS"),
+        format!("{sha512}  app.deb\n"),
+    )
+    .unwrap();
+
+    let manifests = find_manifests(dir.path());
+    assert_eq!(manifests.len(), 2);
+    let entries = read_manifests(&manifests).unwrap();
+    assert_eq!(entries[1].algorithm, ChecksumAlgorithm::Sha512);
+    let report = verify_entries(&entries, dir.path(), &manifests).unwrap();
+    assert!(report.is_ok());
+    assert_eq!(report.verified.len(), 2);
+}
+
+#[test]
+fn unreadable_manifest_is_an_error() {
+    let dir = artifacts_dir();
+    let result = read_manifests(&[dir.path().join("SHA256SUMS")]);
+    assert!(result.is_err());
+}
+
+#[test]
+fn rejects_entries_outside_the_directory() {
+    let dir = artifacts_dir();
+    for name in [
+        "../secret",
+        "/etc/passwd",
+        "sub/app.deb",
+        "..\\secret",
+        "C:secret",
+        "..",
+    ] {
+        fs::write(
+            dir.path().join("SHA256SUMS"),
+            format!("{}  {name}\n", sha256_bytes(b"deb")),
+        )
+        .unwrap();
+        let manifests = find_manifests(dir.path());
+        let entries = read_manifests(&manifests).unwrap();
+        assert!(
+            verify_entries(&entries, dir.path(), &manifests).is_err(),
+            "{name} should be rejected"
+        );
+    }
+}
@@ -1,5 +1,5 @@
 use proptest::prelude::*;
-use rust_release_action::version::is_valid_semver;
+use rust_release_action::version::{Version, is_valid_semver};
 
 proptest! {
     #[test]
@@ -17,7 +17,7 @@ proptest! {
         major in 0u32..100,
         minor in 0u32..100,
         patch in 0u32..100,
-        pre in "[a-zA-Z0-9]{1,10}",
+        pre in prop_oneof!["0|[1-9][0-9]{0,5}", "[a-zA-Z-][a-zA-Z0-9-]{0,9}"],
     ) {
         let v = format!("{major}.{minor}.{patch}-{pre}");
         prop_assert!(is_valid_semver(&v), "expected valid: {v}");
@@ -39,7 +39,7 @@ proptest! {
         major in 0u32..100,
         minor in 0u32..100,
         patch in 0u32..100,
-        pre in "[a-zA-Z0-9]{1,10}",
+        pre in prop_oneof!["0|[1-9][0-9]{0,5}", "[a-zA-Z-][a-zA-Z0-9-]{0,9}"],
         build in "[a-zA-Z0-9]{1,10}",
     ) {
         let v = format!("{major}.{minor}.{patch}-{pre}+{build}");
@@ -57,4 +57,29 @@ proptest! {
         let v = format!("v{major}.{minor}.{patch}");
         prop_assert!(!is_valid_semver(&v), "expected invalid: {v}");
     }
+
+    #[test]
+    fn display_round_trips(
+        major in 0u64..1000,
+        minor in 0u64..1000,
+        patch in 0u64..1000,
+        pre in "(0|[1-9][0-9]{0,3}|[a-zA-Z-][a-zA-Z0-9-]{0,5})(\\.(0|[1-9][0-9]{0,3}|[a-zA-Z-][a-zA-Z0-9-]{0,5})){0,2}",
+        build in "[a-zA-Z0-9-]{1,8}",
+    ) {
+        let v = format!("{major}.{minor}.{patch}-{pre}+{build}");
+        let parsed = Version::parse(&v).unwrap();
+        prop_assert_eq!(parsed.to_string(), v);
+    }
+
+    #[test]
+    fn release_has_higher_precedence_than_its_prereleases(
+        major in 0u64..100,
+        minor in 0u64..100,
+        patch in 0u64..100,
+        pre in "[a-zA-Z-][a-zA-Z0-9-]{0,9}",
+    ) {
+        let release = Version::new(major, minor, patch);
+        let prerelease = Version::parse(&format!("{release}-{pre}")).unwrap();
+        prop_assert!(prerelease < release);
+    }
 }
@@ -1,4 +1,7 @@
-use rust_release_action::version::{is_valid_semver, semver_pattern, version_from_tag};
+use rust_release_action::version::{
+    BumpLevel, Identifier, Version, is_valid_semver, version_from_tag,
+};
+use std::cmp::Ordering;
 
 #[test]
 fn valid_semver_versions() {
@@ -18,6 +21,11 @@ fn valid_semver_versions() {
         "1.0.0-0",
         "1.0.0-alpha0",
         "1.0.0+20240101",
+        "1.0.0-rc-1",
+        "1.0.0--double",
+        "1.0.0-x-y-z.--",
+        "1.0.0+001",
+        "1.0.0-alpha-a.b-c-somethinglong+build.1-aef.1-its-okay",
     ];
     for v in &cases {
         assert!(is_valid_semver(v), "expected valid: {v}");
@@ -36,11 +44,17 @@ fn invalid_semver_versions() {
         "1.0.0-",
         "1.0.0+",
         "a.b.c",
-        "1.0.0--double",
         "",
         " 1.0.0",
         "1.0.0 ",
         "1.0.0-alpha..1",
+        "01.0.0",
+        "1.02.0",
+        "1.0.00",
+        "1.0.0-01",
+        "1.0.0-alpha_1",
+        "1.0.0+build+1",
+        "99999999999999999999.0.0",
     ];
     for v in &cases {
         assert!(!is_valid_semver(v), "expected invalid: {v}");
@@ -48,8 +62,68 @@ fn invalid_semver_versions() {
 }
 
 #[test]
-fn semver_pattern_compiles() {
-    let _re = semver_pattern();
+fn version_parts_and_accessors() {
+    let v = Version::parse("1.2.3-rc.1+build.5").unwrap();
+    assert_eq!((v.major, v.minor, v.patch), (1, 2, 3));
+    assert_eq!(
+        v.pre,
+        vec![
+            Identifier::AlphaNumeric("rc".into()),
+            Identifier::Numeric(1)
+        ]
+    );
+    assert!(v.is_prerelease());
+    assert_eq!(v.pre_release().as_deref(), Some("rc.1"));
+    assert_eq!(v.build_metadata().as_deref(), Some("build.5"));
+    assert_eq!(v.to_string(), "1.2.3-rc.1+build.5");
+
+    let v: Version = "2.0.0".parse().unwrap();
+    assert_eq!(v, Version::new(2, 0, 0));
+    assert!(!v.is_prerelease());
+    assert_eq!(v.pre_release(), None);
+    assert_eq!(v.build_metadata(), None);
+}
+
+#[test]
+fn version_parse_errors_explain_the_problem() {
+    let err = Version::parse("1.0.0-01").unwrap_err().to_string();
+    assert!(err.contains("leading zeros"), "{err}");
+    let err = Version::parse("1.0").unwrap_err().to_string();
+    assert!(err.contains("MAJOR.MINOR.PATCH"), "{err}");
+}
+
+#[test]
+fn version_precedence() {
+    // The ordering example from the Semantic Versioning specification
+    let ordered = [
+        "1.0.0-alpha",
+        "1.0.0-alpha.1",
+        "1.0.0-alpha.beta",
+        "1.0.0-beta",
+        "1.0.0-beta.2",
+        "1.0.0-beta.11",
+        "1.0.0-rc.1",
+        "1.0.0",
+        "1.9.0",
+        "1.10.0",
+        "2.0.0",
+    ];
+    let versions: Vec<Version> = ordered.iter().map(|v| v.parse().unwrap()).collect();
+    for pair in versions.windows(2) {
+        assert_eq!(pair[0].precedence(&pair[1]), Ordering::Less, "{pair:?}");
+        assert!(pair[0] < pair[1]);
+    }
+
+    let plain = Version::parse("1.0.0").unwrap();
+    let with_build = Version::parse("1.0.0+build.1").unwrap();
+    assert_eq!(plain.precedence(&with_build), Ordering::Equal);
+    assert_ne!(plain, with_build);
+    assert_eq!(
+        Version::parse("1.0.0-1")
+            .unwrap()
+            .precedence(&Version::parse("1.0.0-alpha").unwrap()),
+        Ordering::Less
+    );
 }
 
 #[test]
@@ -68,3 +142,55 @@ fn version_from_tag_without_prefix_returns_none() {
 fn version_from_tag_only_v_returns_empty() {
     assert_eq!(version_from_tag("v"), Some(""));
 }
+
+#[test]
+fn bump_levels() {
+    let bump = |v: &str, level: &str| {
+        Version::parse(v)
+            .unwrap()
+            .bump(&level.parse().unwrap(), None)
+            .unwrap()
+            .to_string()
+    };
+    assert_eq!(bump("1.2.3", "major"), "2.0.0");
+    assert_eq!(bump("1.2.3", "minor"), "1.3.0");
+    assert_eq!(bump("1.2.3+build.7", "patch"), "1.2.4");
+    assert_eq!(bump("2.0.0-rc.1", "major"), "2.0.0");
+    assert_eq!(bump("2.1.0-rc.1", "major"), "3.0.0");
+    assert_eq!(bump("1.3.0-rc.1", "minor"), "1.3.0");
+    assert_eq!(bump("1.3.1-rc.1", "minor"), "1.4.0");
+    assert_eq!(bump("1.2.4-rc.1", "patch"), "1.2.4");
+    assert_eq!(bump("1.2.3", "prerelease"), "1.2.4-rc.1");
+    assert_eq!(bump("1.2.4-rc.1", "prerelease"), "1.2.4-rc.2");
+    assert_eq!(bump("1.2.4-alpha", "prerelease"), "1.2.4-alpha.1");
+    assert_eq!(bump("1.2.3", "v2.0.0-beta.1"), "2.0.0-beta.1");
+}
+
+#[test]
+fn prerelease_bumps_with_identifier() {
+    let bump = |v: &str, id: &str| {
+        Version::parse(v)
+            .unwrap()
+            .bump(&BumpLevel::Prerelease, Some(id))
+    };
+    assert_eq!(bump("1.2.3", "alpha").unwrap().to_string(), "1.2.4-alpha.1");
+    assert_eq!(
+        bump("1.2.4-alpha.3", "alpha").unwrap().to_string(),
+        "1.2.4-alpha.4"
+    );
+    assert_eq!(
+        bump("1.2.4-alpha.3", "beta").unwrap().to_string(),
+        "1.2.4-beta.1"
+    );
+    assert!(bump("1.2.3", "01").is_err());
+    assert!(bump("1.2.3", "b_1").is_err());
+}
+
+#[test]
+fn explicit_bump_must_increase_version() {
+    let current = Version::parse("1.2.3").unwrap();
+    assert!(current.bump(&"1.2.3".parse().unwrap(), None).is_err());
+    assert!(current.bump(&"1.2.3-rc.1".parse().unwrap(), None).is_err());
+    assert!("sideways".parse::<BumpLevel>().is_err());
+    assert_eq!("Major".parse::<BumpLevel>().unwrap(), BumpLevel::Major);
+}